@@ -0,0 +1,41 @@
+//! Throughput of `convertx series` on a large file, read into memory and
+//! memory-mapped. Run with `cargo bench --bench series`.
+
+use std::process::Command;
+use std::time::Instant;
+
+/// Data rows in the generated file.
+const ROWS: usize = 2_000_000;
+
+fn main() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("convertx_bench_series.csv");
+    let output = dir.join("convertx_bench_series_out.csv");
+    let csv: String = std::iter::once("t,bytes\n".to_string())
+        .chain((0..ROWS).map(|i| format!("{},{}\n", i, i * 4096)))
+        .collect();
+    std::fs::write(&input, &csv).unwrap();
+    let megabytes = csv.len() as f64 / 1e6;
+
+    for mmap in [false, true] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_convertx"));
+        cmd.args(["series", input.to_str().unwrap(), "--y-from", "bytes"]);
+        cmd.args(["--y-to", "mib", "--output", output.to_str().unwrap()]);
+        if mmap {
+            cmd.arg("--mmap");
+        }
+        let start = Instant::now();
+        let status = cmd.status().unwrap();
+        let seconds = start.elapsed().as_secs_f64();
+        assert!(status.success());
+        println!(
+            "series {:>8}: {:.1} MB in {:.2}s = {:.1} MB/s",
+            if mmap { "--mmap" } else { "read" },
+            megabytes,
+            seconds,
+            megabytes / seconds
+        );
+    }
+    let _ = std::fs::remove_file(&input);
+    let _ = std::fs::remove_file(&output);
+}
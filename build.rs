@@ -0,0 +1,17 @@
+//! Generates the gRPC service of the `grpc` feature from `proto/`.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/convertx.proto");
+    #[cfg(feature = "grpc")]
+    {
+        // A protoc built for the host comes with the build, so none needs to
+        // be installed.
+        let protoc =
+            protoc_bin_vendored::protoc_bin_path().expect("protoc is vendored for the host");
+        std::env::set_var("PROTOC", protoc);
+        tonic_prost_build::configure()
+            .compile_protos(&["proto/convertx.proto"], &["proto"])
+            .expect("proto/convertx.proto compiles");
+    }
+}
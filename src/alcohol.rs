@@ -0,0 +1,82 @@
+//! Standard drink sizes for `convertx alcohol`.
+//!
+//! A standard drink is a country-specific amount of pure ethanol; drinks are
+//! counted by converting a volume and ABV into grams of ethanol.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Density of ethanol in grams per milliliter.
+const ETHANOL_DENSITY: f64 = 0.789;
+
+/// Grams of ethanol in one standard drink, by country code.
+const STANDARD_DRINK_GRAMS: &[(&str, f64)] = &[
+    ("us", 14.0),
+    // The UK unit is defined as 10 ml of ethanol.
+    ("uk", 7.89),
+    ("au", 10.0),
+    ("nz", 10.0),
+    ("ie", 10.0),
+    ("ca", 13.45),
+];
+
+/// A country whose standard drink definition is used, e.g. `us` or `uk`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Country {
+    code: &'static str,
+    pub grams: f64,
+}
+
+impl FromStr for Country {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        STANDARD_DRINK_GRAMS
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(s.trim()))
+            .map(|(code, grams)| Country {
+                code,
+                grams: *grams,
+            })
+            .ok_or_else(|| {
+                let codes: Vec<&str> = STANDARD_DRINK_GRAMS.iter().map(|(code, _)| *code).collect();
+                format!(
+                    "unknown country '{}', expected one of: {}",
+                    s,
+                    codes.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code.to_uppercase())
+    }
+}
+
+/// Returns the number of standard drinks in `ml` milliliters at `abv` percent.
+///
+/// # Example
+/// ```
+/// # use convertx::alcohol::*;
+/// let uk: Country = "uk".parse().unwrap();
+/// assert!((standard_drinks(500.0, 5.0, &uk) - 2.5).abs() < 1e-9);
+/// ```
+pub fn standard_drinks(ml: f64, abv: f64, country: &Country) -> f64 {
+    ml * abv / 100.0 * ETHANOL_DENSITY / country.grams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_drinks() {
+        let us: Country = "US".parse().unwrap();
+        let uk: Country = "uk".parse().unwrap();
+        assert!((standard_drinks(500.0, 5.0, &us) - 1.408929).abs() < 1e-6);
+        assert!((standard_drinks(500.0, 5.0, &uk) - 2.5).abs() < 1e-9);
+        assert_eq!(us.to_string(), "US");
+        assert!("xx".parse::<Country>().is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Oven temperature and time adjustments for `convertx bake`.
+//!
+//! The heuristics follow common baking guidance:
+//! + convection (fan) ovens bake hotter, so lower the temperature by 25°F;
+//! + above 3000 ft (914.4 m) batters set more slowly, so raise the temperature
+//!   by 15°F and shorten the time by a fifth.
+
+/// Temperature change for convection ovens, in °F.
+pub const CONVECTION_DELTA_F: f64 = -25.0;
+/// Altitude above which the high-altitude adjustment applies, in meters.
+pub const HIGH_ALTITUDE_M: f64 = 914.4;
+/// Temperature change at high altitude, in °F.
+pub const ALTITUDE_DELTA_F: f64 = 15.0;
+/// Baking time multiplier at high altitude.
+pub const ALTITUDE_TIME_FACTOR: f64 = 0.8;
+
+/// Returns the adjusted temperature in °F and the baking time multiplier.
+///
+/// # Example
+/// ```
+/// # use convertx::bake::*;
+/// assert_eq!(adjust(350.0, true, 0.0), (325.0, 1.0));
+/// ```
+pub fn adjust(temp_f: f64, convection: bool, altitude_m: f64) -> (f64, f64) {
+    let mut temp_f = temp_f;
+    let mut time_factor = 1.0;
+    if convection {
+        temp_f += CONVECTION_DELTA_F;
+    }
+    if altitude_m >= HIGH_ALTITUDE_M {
+        temp_f += ALTITUDE_DELTA_F;
+        time_factor *= ALTITUDE_TIME_FACTOR;
+    }
+    (temp_f, time_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust() {
+        assert_eq!(adjust(350.0, false, 0.0), (350.0, 1.0));
+        assert_eq!(adjust(350.0, true, 0.0), (325.0, 1.0));
+        assert_eq!(adjust(350.0, false, 2000.0), (365.0, 0.8));
+        assert_eq!(adjust(350.0, true, 2000.0), (340.0, 0.8));
+        assert_eq!(adjust(350.0, false, 900.0), (350.0, 1.0));
+    }
+}
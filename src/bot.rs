@@ -0,0 +1,352 @@
+//! Slack and Discord slash commands for `convertx bot`.
+//!
+//! Both platforms send a command such as `/convert 5 km to mi` to the bot as
+//! an HTTP `POST`: Slack as a form whose `text` is the command's text, Discord
+//! as a JSON interaction whose first option holds it. Each signs its requests,
+//! Slack with an HMAC keyed by the app's signing secret and Discord with the
+//! application's Ed25519 key, and a [`Bot`] answers no request without a valid
+//! signature. Answers are posted to the channel; when a question cannot be
+//! answered, only the user who asked sees why.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::json::Value::{self, Number, Object, Text};
+use crate::serve::{Request, Response};
+
+/// How far the timestamp of a Slack request may be from the time it arrives,
+/// so that a captured request cannot be replayed later.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Phrases around a question that are not part of its expression.
+const PREFIXES: &[&str] = &["what is", "what's", "how much is", "convert"];
+
+/// The answer to a command without a question.
+const HELP: &str = "Ask for a conversion, e.g. `5 km to mi` or `(3 km + 250 m) / 2 in feet`.";
+
+/// The Discord interaction sent to check that an endpoint answers.
+const PING: u64 = 1;
+/// The Discord interaction of a slash command.
+const APPLICATION_COMMAND: u64 = 2;
+/// The Discord response type that posts a message.
+const CHANNEL_MESSAGE: f64 = 4.0;
+/// The Discord message flag that shows a message only to the user who asked.
+const EPHEMERAL: f64 = 64.0;
+
+/// A bot answering the slash commands of one platform, and what it checks the
+/// signatures of their requests against; `None` answers unsigned requests too,
+/// for trying a bot locally.
+#[derive(Debug, Clone)]
+pub enum Bot {
+    Slack { signing_secret: Option<String> },
+    Discord { public_key: Option<VerifyingKey> },
+}
+
+/// Decodes hexadecimal `text`, such as a signature.
+fn hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
+
+/// Reads a Discord application's public key, written in hexadecimal.
+pub fn public_key(text: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = hex(text.trim())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("a public key is 64 hexadecimal digits")?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| "not an Ed25519 public key".to_string())
+}
+
+/// Returns the question asked in `text` without the phrases around it.
+///
+/// # Example
+/// ```
+/// # use convertx::bot::*;
+/// assert_eq!(question("What is 5 km in miles?"), "5 km in miles");
+/// ```
+pub fn question(text: &str) -> &str {
+    let text = text.trim().trim_end_matches('?').trim_end();
+    PREFIXES
+        .iter()
+        .find_map(|prefix| {
+            let rest = text.get(prefix.len()..)?;
+            let asks = text[..prefix.len()].eq_ignore_ascii_case(prefix)
+                && rest.starts_with(char::is_whitespace);
+            asks.then(|| rest.trim_start())
+        })
+        .unwrap_or(text)
+}
+
+/// Reads a Discord interaction: the text of a command's first option, or
+/// `None` for a [`PING`].
+fn interaction_text(body: &str) -> Result<Option<String>, &'static str> {
+    let interaction: serde_json::Value =
+        serde_json::from_str(body).map_err(|_| "the body is not a JSON interaction")?;
+    match interaction["type"].as_u64() {
+        Some(PING) => Ok(None),
+        Some(APPLICATION_COMMAND) => {
+            let text = interaction["data"]["options"]
+                .as_array()
+                .and_then(|options| options.iter().find_map(|option| option["value"].as_str()));
+            Ok(Some(text.unwrap_or_default().to_string()))
+        }
+        _ => Err("only commands are answered"),
+    }
+}
+
+impl Bot {
+    /// Checks that `request`, arriving at `now`, is signed by the platform.
+    fn verify(&self, request: &Request, now: SystemTime) -> Result<(), &'static str> {
+        match self {
+            Bot::Slack {
+                signing_secret: Some(secret),
+            } => {
+                let timestamp = request
+                    .header("x-slack-request-timestamp")
+                    .ok_or("missing the request timestamp")?;
+                let sent = timestamp
+                    .parse()
+                    .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+                    .map_err(|_| "malformed request timestamp")?;
+                let skew = now
+                    .duration_since(sent)
+                    .unwrap_or_else(|early| early.duration());
+                if skew > MAX_CLOCK_SKEW {
+                    return Err("the request timestamp is more than five minutes off");
+                }
+                let signature = request
+                    .header("x-slack-signature")
+                    .and_then(|signature| signature.strip_prefix("v0="))
+                    .and_then(hex)
+                    .ok_or("missing or malformed signature")?;
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+                    .expect("HMAC takes keys of any length");
+                mac.update(format!("v0:{}:{}", timestamp, request.body).as_bytes());
+                mac.verify_slice(&signature)
+                    .map_err(|_| "invalid signature")
+            }
+            Bot::Discord {
+                public_key: Some(key),
+            } => {
+                let timestamp = request
+                    .header("x-signature-timestamp")
+                    .ok_or("missing the request timestamp")?;
+                let signature = request
+                    .header("x-signature-ed25519")
+                    .and_then(hex)
+                    .and_then(|bytes| Signature::from_slice(&bytes).ok())
+                    .ok_or("missing or malformed signature")?;
+                let message = format!("{}{}", timestamp, request.body);
+                key.verify_strict(message.as_bytes(), &signature)
+                    .map_err(|_| "invalid signature")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The body posting `text` to the channel if `public`, or else only to
+    /// the user who asked.
+    fn reply(&self, text: &str, public: bool) -> Value {
+        match self {
+            Bot::Slack { .. } => {
+                let audience = if public { "in_channel" } else { "ephemeral" };
+                Object(vec![
+                    ("response_type", Text(audience.to_string())),
+                    ("text", Text(text.to_string())),
+                ])
+            }
+            Bot::Discord { .. } => {
+                let mut data = vec![("content", Text(text.to_string()))];
+                if !public {
+                    data.push(("flags", Number(EPHEMERAL)));
+                }
+                Object(vec![
+                    ("type", Number(CHANNEL_MESSAGE)),
+                    ("data", Object(data)),
+                ])
+            }
+        }
+    }
+
+    /// Answers the slash command of `request`, which arrived at `now`, with
+    /// what `convert` answers to its question, e.g. `3.107 miles` to
+    /// `5 km to mi`.
+    pub fn answer<F>(&self, request: &Request, now: SystemTime, convert: F) -> Response
+    where
+        F: Fn(&str) -> Result<String, String>,
+    {
+        if let Err(message) = self.verify(request, now) {
+            return Response::error(401, message);
+        }
+        let text = match self {
+            Bot::Slack { .. } => request
+                .form()
+                .into_iter()
+                .find(|(name, _)| name == "text")
+                .map(|(_, text)| text)
+                .unwrap_or_default(),
+            Bot::Discord { .. } => match interaction_text(&request.body) {
+                Ok(Some(text)) => text,
+                Ok(None) => {
+                    return Response::json(Object(vec![("type", Number(PING as f64))]).to_string())
+                }
+                Err(message) => return Response::error(400, message),
+            },
+        };
+        let reply = match question(&text) {
+            "" => self.reply(HELP, false),
+            question => match convert(question) {
+                Ok(answer) => self.reply(&format!("{} = {}", question, answer), true),
+                Err(error) => self.reply(&error, false),
+            },
+        };
+        Response::json(reply.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serve::read_request;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn request(headers: &[(&str, &str)], body: &str) -> Request {
+        let mut text = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n", body.len());
+        for (name, value) in headers {
+            text.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        text.push_str("\r\n");
+        text.push_str(body);
+        read_request(&mut text.as_bytes()).unwrap()
+    }
+
+    fn convert(question: &str) -> Result<String, String> {
+        match question {
+            "5 km to mi" => Ok("3.107 miles".to_string()),
+            _ => Err(format!("cannot read '{}'", question)),
+        }
+    }
+
+    #[test]
+    fn test_question() {
+        assert_eq!(question("5 km to mi"), "5 km to mi");
+        assert_eq!(question("  What is 5 km in miles? "), "5 km in miles");
+        assert_eq!(question("what's 72kg in lb"), "72kg in lb");
+        assert_eq!(question("convert 3 ft to m"), "3 ft to m");
+        assert_eq!(question("converted"), "converted");
+        assert_eq!(question("?"), "");
+    }
+
+    #[test]
+    fn test_slack() {
+        let secret = "8f742231b10e8888abcd99yyyzzz85a5";
+        let bot = Bot::Slack {
+            signing_secret: Some(secret.to_string()),
+        };
+        let body = "command=%2Fconvert&text=what+is+5+km+to+mi%3F";
+        let sign = |timestamp: &str, body: &str| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+            mac.update(format!("v0:{}:{}", timestamp, body).as_bytes());
+            let bytes = mac.finalize().into_bytes();
+            let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            format!("v0={}", hex)
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(1_531_420_618);
+        let signed = |timestamp: &str, body: &str| {
+            let signature = sign(timestamp, body);
+            request(
+                &[
+                    ("X-Slack-Request-Timestamp", timestamp),
+                    ("X-Slack-Signature", &signature),
+                ],
+                body,
+            )
+        };
+        let response = bot.answer(&signed("1531420618", body), now, convert);
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.body,
+            r#"{"response_type":"in_channel","text":"5 km to mi = 3.107 miles"}"#
+        );
+        let response = bot.answer(&signed("1531420618", "text=5+kg+to+mi"), now, convert);
+        assert_eq!(
+            response.body,
+            r#"{"response_type":"ephemeral","text":"cannot read '5 kg to mi'"}"#
+        );
+        // A tampered body, a replayed request and an unsigned one are refused.
+        let mut tampered = signed("1531420618", body);
+        tampered.body = body.replace("5+km", "6+km");
+        assert_eq!(bot.answer(&tampered, now, convert).status, 401);
+        let late = now + MAX_CLOCK_SKEW + Duration::from_secs(1);
+        assert_eq!(
+            bot.answer(&signed("1531420618", body), late, convert)
+                .status,
+            401
+        );
+        assert_eq!(bot.answer(&request(&[], body), now, convert).status, 401);
+        let unverified = Bot::Slack {
+            signing_secret: None,
+        };
+        let response = unverified.answer(&request(&[], "text="), now, convert);
+        assert!(response.body.contains(r#""response_type":"ephemeral""#));
+    }
+
+    #[test]
+    fn test_discord() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let public: String = key
+            .verifying_key()
+            .to_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let bot = Bot::Discord {
+            public_key: Some(public_key(&public).unwrap()),
+        };
+        let signed = |body: &str| {
+            let signature = key.sign(format!("1700000000{}", body).as_bytes());
+            let hex: String = signature
+                .to_bytes()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            request(
+                &[
+                    ("X-Signature-Ed25519", &hex),
+                    ("X-Signature-Timestamp", "1700000000"),
+                ],
+                body,
+            )
+        };
+        let now = SystemTime::now();
+        assert_eq!(
+            bot.answer(&signed(r#"{"type":1}"#), now, convert).body,
+            r#"{"type":1}"#
+        );
+        let command = r#"{"type":2,"data":{"name":"convert","options":[{"name":"question","type":3,"value":"5 km to mi"}]}}"#;
+        assert_eq!(
+            bot.answer(&signed(command), now, convert).body,
+            r#"{"type":4,"data":{"content":"5 km to mi = 3.107 miles"}}"#
+        );
+        let command = r#"{"type":2,"data":{"name":"convert"}}"#;
+        assert_eq!(
+            bot.answer(&signed(command), now, convert).body,
+            format!(r#"{{"type":4,"data":{{"content":"{}","flags":64}}}}"#, HELP)
+        );
+        let mut tampered = signed(r#"{"type":1}"#);
+        tampered.body = r#"{"type":1} "#.to_string();
+        assert_eq!(bot.answer(&tampered, now, convert).status, 401);
+        assert_eq!(bot.answer(&signed("{}"), now, convert).status, 400);
+        assert!(public_key("abc").is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! A least-recently-used cache with hit and miss counters.
+//!
+//! `convertx serve` keeps the unit pairs it has resolved here, so repeated
+//! queries skip the unit lookup and the search for a conversion path.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// How a cache has been used since it was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub capacity: usize,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// A map of at most `capacity` entries that drops the least recently used
+/// entry to make room for a new one.
+#[derive(Debug)]
+pub struct LruCache<K, V> {
+    /// Each value with the tick of its last use.
+    entries: HashMap<K, (V, u64)>,
+    /// The key last used at each tick, oldest first.
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    /// Creates an empty cache; a `capacity` of 0 caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            stats: CacheStats {
+                capacity,
+                ..CacheStats::default()
+            },
+        }
+    }
+
+    /// Returns the value cached for `key`, computing and caching it with
+    /// `compute` if there is none.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::cache::*;
+    /// let mut cache = LruCache::new(16);
+    /// assert_eq!(cache.get_or_insert_with("km", || 1000.0), 1000.0);
+    /// assert_eq!(cache.stats().misses, 1);
+    /// ```
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce() -> V) -> V {
+        self.tick += 1;
+        if let Some((value, used)) = self.entries.get_mut(&key) {
+            self.stats.hits += 1;
+            self.recency.remove(used);
+            *used = self.tick;
+            self.recency.insert(self.tick, key);
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute();
+        if self.stats.capacity == 0 {
+            return value;
+        }
+        if self.entries.len() == self.stats.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+        }
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, (value.clone(), self.tick));
+        value
+    }
+
+    /// The counters of the cache and its current size.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        let mut computed = 0;
+        let mut get = |cache: &mut LruCache<&str, usize>, key| {
+            cache.get_or_insert_with(key, || {
+                computed += 1;
+                key.len()
+            })
+        };
+        assert_eq!(get(&mut cache, "km"), 2);
+        assert_eq!(get(&mut cache, "mile"), 4);
+        assert_eq!(get(&mut cache, "km"), 2);
+        // "mile" is now the least recently used, so it makes room for "feet".
+        assert_eq!(get(&mut cache, "feet"), 4);
+        assert_eq!(get(&mut cache, "km"), 2);
+        assert_eq!(get(&mut cache, "mile"), 4);
+        assert_eq!(computed, 4);
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                capacity: 2,
+                entries: 2,
+                hits: 2,
+                misses: 4,
+                evictions: 2,
+            }
+        );
+
+        let mut uncached = LruCache::new(0);
+        assert_eq!(uncached.get_or_insert_with("km", || 2), 2);
+        assert_eq!(uncached.get_or_insert_with("km", || 3), 3);
+        assert_eq!(uncached.stats().entries, 0);
+    }
+}
@@ -0,0 +1,575 @@
+//! Lunar and lunisolar calendars for `convertx calendar`.
+//!
+//! The algorithms follow Reingold and Dershowitz, *Calendrical Calculations*:
+//! + Hijri dates use the arithmetical (tabular) Islamic calendar, which can
+//!   differ by a day or two from calendars based on moon sightings;
+//! + Hebrew dates use the arithmetical Hebrew calendar;
+//! + Chinese dates are computed astronomically for Beijing time (UTC+8), with
+//!   new moons and the sun's longitude from Meeus, *Astronomical Algorithms*,
+//!   so dates a few minutes from a new moon or solar term may be off by one.
+//!
+//! Days are counted as fixed day numbers (Rata Die), where 0001-01-01 is day 1.
+
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+
+use crate::portable::{powi, sin};
+
+/// Fixed day number of 1 Muharram 1 AH (16 July 622, Julian).
+const ISLAMIC_EPOCH: i64 = 227_015;
+/// Fixed day number of 1 Tishrei 1 AM (7 October 3761 BCE, Julian).
+const HEBREW_EPOCH: i64 = -1_373_427;
+/// Fixed day number of the start of the Chinese calendar (15 February 2637 BCE).
+const CHINESE_EPOCH: i64 = -963_099;
+/// Julian day number of the midnight starting fixed day 0.
+const JD_OF_FIXED_ZERO: f64 = 1_721_424.5;
+/// Beijing time, UTC+8, as a fraction of a day.
+const CHINA_OFFSET: f64 = 8.0 / 24.0;
+/// Mean length of a lunar month, in days.
+const SYNODIC_MONTH: f64 = 29.530_588_861;
+/// Mean length of a tropical year, in days.
+const TROPICAL_YEAR: f64 = 365.242_189;
+
+const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi' al-Awwal",
+    "Rabi' al-Thani",
+    "Jumada al-Ula",
+    "Jumada al-Akhirah",
+    "Rajab",
+    "Sha'ban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qi'dah",
+    "Dhu al-Hijjah",
+];
+/// Hebrew months, numbered from Nisan as in the calendar's own reckoning.
+const HEBREW_MONTHS: [&str; 13] = [
+    "Nisan",
+    "Iyyar",
+    "Sivan",
+    "Tammuz",
+    "Av",
+    "Elul",
+    "Tishrei",
+    "Marheshvan",
+    "Kislev",
+    "Tevet",
+    "Shevat",
+    "Adar",
+    "Adar II",
+];
+const STEMS: [(&str, &str); 10] = [
+    ("甲", "Wood"),
+    ("乙", "Wood"),
+    ("丙", "Fire"),
+    ("丁", "Fire"),
+    ("戊", "Earth"),
+    ("己", "Earth"),
+    ("庚", "Metal"),
+    ("辛", "Metal"),
+    ("壬", "Water"),
+    ("癸", "Water"),
+];
+const BRANCHES: [(&str, &str); 12] = [
+    ("子", "Rat"),
+    ("丑", "Ox"),
+    ("寅", "Tiger"),
+    ("卯", "Rabbit"),
+    ("辰", "Dragon"),
+    ("巳", "Snake"),
+    ("午", "Horse"),
+    ("未", "Goat"),
+    ("申", "Monkey"),
+    ("酉", "Rooster"),
+    ("戌", "Dog"),
+    ("亥", "Pig"),
+];
+
+/// A date in the arithmetical Islamic calendar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HijriDate {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+}
+
+/// A date in the Hebrew calendar; month 1 is Nisan and month 7 Tishrei.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HebrewDate {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+}
+
+/// A date in the Chinese calendar: the year within its sexagenary cycle, and
+/// the month, which may be a leap month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChineseDate {
+    pub cycle: i64,
+    pub year: i64,
+    pub month: i64,
+    pub leap: bool,
+    pub day: i64,
+}
+
+/// Returns `x` modulo `n` in the range `1..=n`.
+fn amod(x: i64, n: i64) -> i64 {
+    1 + (x - 1).rem_euclid(n)
+}
+
+fn fixed(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce())
+}
+
+fn fixed_from_hijri(year: i64, month: i64, day: i64) -> i64 {
+    day + 29 * (month - 1)
+        + (6 * month - 1).div_euclid(11)
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + ISLAMIC_EPOCH
+        - 1
+}
+
+/// Converts a Gregorian date to the arithmetical Islamic calendar.
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveDate;
+/// # use convertx::calendar::*;
+/// let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+/// assert_eq!(hijri(date).to_string(), "1 Ramadan 1446 AH");
+/// ```
+pub fn hijri(date: NaiveDate) -> HijriDate {
+    let day = fixed(date);
+    let year = (30 * (day - ISLAMIC_EPOCH) + 10_646).div_euclid(10_631);
+    let prior_days = day - fixed_from_hijri(year, 1, 1);
+    let month = (11 * prior_days + 330).div_euclid(325);
+    HijriDate {
+        year,
+        month,
+        day: day - fixed_from_hijri(year, month, 1) + 1,
+    }
+}
+
+impl fmt::Display for HijriDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let month = HIJRI_MONTHS[(self.month - 1) as usize];
+        write!(f, "{} {} {} AH", self.day, month, self.year)
+    }
+}
+
+fn hebrew_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn last_hebrew_month(year: i64) -> i64 {
+    if hebrew_leap_year(year) {
+        13
+    } else {
+        12
+    }
+}
+
+/// Days from the epoch to the molad of Tishrei of `year`, postponed so that
+/// Rosh Hashanah does not fall on a Sunday, Wednesday or Friday.
+fn hebrew_elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12_084 + 13_753 * months;
+    let days = 29 * months + parts.div_euclid(25_920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+/// Further postponement that keeps year lengths within the allowed values.
+fn hebrew_year_length_correction(year: i64) -> i64 {
+    let ny0 = hebrew_elapsed_days(year - 1);
+    let ny1 = hebrew_elapsed_days(year);
+    let ny2 = hebrew_elapsed_days(year + 1);
+    if ny2 - ny1 == 356 {
+        2
+    } else if ny1 - ny0 == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    HEBREW_EPOCH + hebrew_elapsed_days(year) + hebrew_year_length_correction(year)
+}
+
+fn last_day_of_hebrew_month(year: i64, month: i64) -> i64 {
+    let year_length = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = matches!(month, 2 | 4 | 6 | 10 | 13)
+        || (month == 12 && !hebrew_leap_year(year))
+        || (month == 8 && !matches!(year_length, 355 | 385))
+        || (month == 9 && matches!(year_length, 353 | 383));
+    if short {
+        29
+    } else {
+        30
+    }
+}
+
+fn fixed_from_hebrew(year: i64, month: i64, day: i64) -> i64 {
+    let month_days = |months: &mut dyn Iterator<Item = i64>| -> i64 {
+        months.map(|m| last_day_of_hebrew_month(year, m)).sum()
+    };
+    // The year starts in Tishrei (month 7) and wraps around to Nisan.
+    let before = if month < 7 {
+        month_days(&mut (7..=last_hebrew_month(year)).chain(1..month))
+    } else {
+        month_days(&mut (7..month))
+    };
+    hebrew_new_year(year) + day - 1 + before
+}
+
+/// Converts a Gregorian date to the Hebrew calendar.
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveDate;
+/// # use convertx::calendar::*;
+/// let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+/// assert_eq!(hebrew(date).to_string(), "1 Adar 5785");
+/// ```
+pub fn hebrew(date: NaiveDate) -> HebrewDate {
+    let day = fixed(date);
+    let approx = ((day - HEBREW_EPOCH) as f64 / (35_975_351.0 / 98_496.0)).floor() as i64 + 1;
+    // The estimate can be a year early or late around Tishrei 1.
+    let year = (approx - 1..=approx + 1)
+        .rev()
+        .find(|&year| hebrew_new_year(year) <= day)
+        .unwrap_or(approx - 1);
+    let start = if day < fixed_from_hebrew(year, 1, 1) {
+        7
+    } else {
+        1
+    };
+    let month = (start..=last_hebrew_month(year))
+        .find(|&m| day <= fixed_from_hebrew(year, m, last_day_of_hebrew_month(year, m)))
+        .unwrap_or(start);
+    HebrewDate {
+        year,
+        month,
+        day: day - fixed_from_hebrew(year, month, 1) + 1,
+    }
+}
+
+impl fmt::Display for HebrewDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let month = match self.month {
+            12 if hebrew_leap_year(self.year) => "Adar I",
+            month => HEBREW_MONTHS[(month - 1) as usize],
+        };
+        write!(f, "{} {} {}", self.day, month, self.year)
+    }
+}
+
+/// Returns ΔT, the difference between terrestrial and universal time, in days
+/// (Espenak and Meeus polynomials, with a long-term parabola elsewhere).
+fn delta_t(jd: f64) -> f64 {
+    let year = 2000.0 + (jd - 2_451_545.0) / 365.25;
+    let t = year - 2000.0;
+    let seconds = if (1986.0..2005.0).contains(&year) {
+        63.86 + 0.3345 * t - 0.060_374 * powi(t, 2)
+            + 0.001_727_5 * powi(t, 3)
+            + 0.000_651_814 * powi(t, 4)
+            + 0.000_023_735_99 * powi(t, 5)
+    } else if (2005.0..2050.0).contains(&year) {
+        62.92 + 0.322_17 * t + 0.005_589 * powi(t, 2)
+    } else {
+        let u = (year - 1820.0) / 100.0;
+        -20.0 + 32.0 * powi(u, 2)
+    };
+    seconds / 86_400.0
+}
+
+fn sin_deg(degrees: f64) -> f64 {
+    sin(degrees.to_radians())
+}
+
+/// Returns the apparent longitude of the sun in degrees at Julian day `jd`
+/// (universal time), accurate to about 0.01°.
+fn solar_longitude(jd: f64) -> f64 {
+    let t = (jd + delta_t(jd) - 2_451_545.0) / 36_525.0;
+    let l0 = 280.466_46 + 36_000.769_83 * t + 0.000_303_2 * powi(t, 2);
+    let m = 357.529_11 + 35_999.050_29 * t - 0.000_153_7 * powi(t, 2);
+    let c = (1.914_602 - 0.004_817 * t - 0.000_014 * powi(t, 2)) * sin_deg(m)
+        + (0.019_993 - 0.000_101 * t) * sin_deg(2.0 * m)
+        + 0.000_289 * sin_deg(3.0 * m);
+    let omega = 125.04 - 1934.136 * t;
+    (l0 + c - 0.005_69 - 0.004_78 * sin_deg(omega)).rem_euclid(360.0)
+}
+
+/// Returns the first moment at or after `jd` when the sun's longitude is
+/// `target` degrees.
+fn solar_longitude_after(target: f64, jd: f64) -> f64 {
+    let rate = 360.0 / TROPICAL_YEAR;
+    let estimate = jd + (target - solar_longitude(jd)).rem_euclid(360.0) / rate;
+    let (mut low, mut high) = ((estimate - 5.0).max(jd), estimate + 5.0);
+    for _ in 0..50 {
+        let mid = (low + high) / 2.0;
+        // The sun has not reached `target` yet if it is less than 180° short.
+        if (solar_longitude(mid) - target + 180.0).rem_euclid(360.0) < 180.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Returns the moment (universal time) of the `k`th new moon after January
+/// 2000, from Meeus chapter 49.
+fn new_moon(k: i64) -> f64 {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let jde = 2_451_550.097_66 + SYNODIC_MONTH * k + 0.000_154_37 * powi(t, 2)
+        - 0.000_000_150 * powi(t, 3)
+        + 0.000_000_000_73 * powi(t, 4);
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * powi(t, 2);
+    let m = 2.5534 + 29.105_356_70 * k - 0.000_001_4 * powi(t, 2) - 0.000_000_11 * powi(t, 3);
+    let mp = 201.5643 + 385.816_935_28 * k + 0.010_758_2 * powi(t, 2) + 0.000_012_38 * powi(t, 3)
+        - 0.000_000_058 * powi(t, 4);
+    let f = 160.7108 + 390.670_502_84 * k - 0.001_611_8 * powi(t, 2) - 0.000_002_27 * powi(t, 3)
+        + 0.000_000_011 * powi(t, 4);
+    let omega = 124.7746 - 1.563_755_88 * k + 0.002_067_2 * powi(t, 2) + 0.000_002_15 * powi(t, 3);
+    let periodic = [
+        (-0.40720, 0.0, 1.0, 0.0, 0.0),
+        (0.17241 * e, 1.0, 0.0, 0.0, 0.0),
+        (0.01608, 0.0, 2.0, 0.0, 0.0),
+        (0.01039, 0.0, 0.0, 2.0, 0.0),
+        (0.00739 * e, -1.0, 1.0, 0.0, 0.0),
+        (-0.00514 * e, 1.0, 1.0, 0.0, 0.0),
+        (0.00208 * e * e, 2.0, 0.0, 0.0, 0.0),
+        (-0.00111, 0.0, 1.0, -2.0, 0.0),
+        (-0.00057, 0.0, 1.0, 2.0, 0.0),
+        (0.00056 * e, 1.0, 2.0, 0.0, 0.0),
+        (-0.00042, 0.0, 3.0, 0.0, 0.0),
+        (0.00042 * e, 1.0, 0.0, 2.0, 0.0),
+        (0.00038 * e, 1.0, 0.0, -2.0, 0.0),
+        (-0.00024 * e, -1.0, 2.0, 0.0, 0.0),
+        (-0.00017, 0.0, 0.0, 0.0, 1.0),
+        (-0.00007, 2.0, 1.0, 0.0, 0.0),
+        (0.00004, 0.0, 2.0, -2.0, 0.0),
+        (0.00004, 3.0, 0.0, 0.0, 0.0),
+        (0.00003, 1.0, 1.0, -2.0, 0.0),
+        (0.00003, 0.0, 2.0, 2.0, 0.0),
+        (-0.00003, 1.0, 1.0, 2.0, 0.0),
+        (0.00003, -1.0, 1.0, 2.0, 0.0),
+        (-0.00002, -1.0, 1.0, -2.0, 0.0),
+        (-0.00002, 1.0, 3.0, 0.0, 0.0),
+        (0.00002, 0.0, 4.0, 0.0, 0.0),
+    ]
+    .iter()
+    .map(|(coefficient, x, y, z, w)| coefficient * sin_deg(x * m + y * mp + z * f + w * omega))
+    .sum::<f64>();
+    let planetary = [
+        (0.000_325, 299.77 + 0.107_408 * k - 0.009_173 * powi(t, 2)),
+        (0.000_165, 251.88 + 0.016_321 * k),
+        (0.000_164, 251.83 + 26.651_886 * k),
+        (0.000_126, 349.42 + 36.412_478 * k),
+        (0.000_110, 84.66 + 18.206_239 * k),
+        (0.000_062, 141.74 + 53.303_771 * k),
+        (0.000_060, 207.14 + 2.453_732 * k),
+        (0.000_056, 154.84 + 7.306_860 * k),
+        (0.000_047, 34.52 + 27.261_239 * k),
+        (0.000_042, 207.19 + 0.121_824 * k),
+        (0.000_040, 291.34 + 1.844_379 * k),
+        (0.000_037, 161.72 + 24.198_154 * k),
+        (0.000_035, 239.56 + 25.513_099 * k),
+        (0.000_023, 331.55 + 3.592_518 * k),
+    ]
+    .iter()
+    .map(|(coefficient, angle)| coefficient * sin_deg(*angle))
+    .sum::<f64>();
+    let jde = jde + periodic + planetary;
+    jde - delta_t(jde)
+}
+
+/// Returns the index of the first new moon at or after `jd`.
+fn new_moon_index_at_or_after(jd: f64) -> i64 {
+    let mut k = ((jd - 2_451_550.097_66) / SYNODIC_MONTH).floor() as i64 - 1;
+    while new_moon(k) < jd {
+        k += 1;
+    }
+    k
+}
+
+/// Returns the Julian day of midnight at the start of `day` in Beijing.
+fn china_midnight(day: i64) -> f64 {
+    day as f64 + JD_OF_FIXED_ZERO - CHINA_OFFSET
+}
+
+/// Returns the Beijing calendar day containing the moment `jd`.
+fn china_day(jd: f64) -> i64 {
+    (jd + CHINA_OFFSET - JD_OF_FIXED_ZERO).floor() as i64
+}
+
+/// Returns the first day, at or after `day`, on which a new moon falls.
+fn chinese_new_moon_on_or_after(day: i64) -> i64 {
+    china_day(new_moon(new_moon_index_at_or_after(china_midnight(day))))
+}
+
+/// Returns the last day before `day` on which a new moon falls.
+fn chinese_new_moon_before(day: i64) -> i64 {
+    china_day(new_moon(
+        new_moon_index_at_or_after(china_midnight(day)) - 1,
+    ))
+}
+
+/// Returns the day of the last winter solstice on or before `day`.
+fn chinese_winter_solstice_on_or_before(day: i64) -> i64 {
+    let limit = china_midnight(day + 1);
+    let solstice = solar_longitude_after(270.0, limit - 370.0);
+    let next = solar_longitude_after(270.0, solstice + 1.0);
+    china_day(if next < limit { next } else { solstice })
+}
+
+/// Returns the number (1 to 12) of the last major solar term at the start of
+/// `day`; term 1 begins at a solar longitude of 330°.
+fn current_major_solar_term(day: i64) -> i64 {
+    let longitude = solar_longitude(china_midnight(day));
+    amod(2 + (longitude / 30.0).floor() as i64, 12)
+}
+
+/// Returns whether the month starting on `day` has no major solar term.
+fn no_major_solar_term(day: i64) -> bool {
+    current_major_solar_term(day) == current_major_solar_term(chinese_new_moon_on_or_after(day + 1))
+}
+
+/// Returns whether there is a leap month between the months starting on
+/// `start` and on `month`, inclusive.
+fn prior_leap_month(start: i64, month: i64) -> bool {
+    month >= start
+        && (no_major_solar_term(month) || prior_leap_month(start, chinese_new_moon_before(month)))
+}
+
+/// Converts a Gregorian date to the Chinese calendar.
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveDate;
+/// # use convertx::calendar::*;
+/// let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+/// assert_eq!(chinese(date).to_string(), "month 2, day 2 of the Wood Snake year (乙巳, year 42 of cycle 78)");
+/// ```
+pub fn chinese(date: NaiveDate) -> ChineseDate {
+    let day = fixed(date);
+    let s1 = chinese_winter_solstice_on_or_before(day);
+    let s2 = chinese_winter_solstice_on_or_before(s1 + 370);
+    // The month containing the winter solstice is always month 11, so months
+    // are counted from the first new moon after it.
+    let m12 = chinese_new_moon_on_or_after(s1 + 1);
+    let next_m11 = chinese_new_moon_before(s2 + 1);
+    let m = chinese_new_moon_before(day + 1);
+    let leap_year = ((next_m11 - m12) as f64 / SYNODIC_MONTH).round() as i64 == 12;
+    let months = ((m - m12) as f64 / SYNODIC_MONTH).round() as i64;
+    let month = amod(
+        months - i64::from(leap_year && prior_leap_month(m12, m)),
+        12,
+    );
+    let leap =
+        leap_year && no_major_solar_term(m) && !prior_leap_month(m12, chinese_new_moon_before(m));
+    let elapsed_years =
+        (1.5 - month as f64 / 12.0 + (day - CHINESE_EPOCH) as f64 / TROPICAL_YEAR).floor() as i64;
+    ChineseDate {
+        cycle: (elapsed_years - 1).div_euclid(60) + 1,
+        year: amod(elapsed_years, 60),
+        month,
+        leap,
+        day: day - m + 1,
+    }
+}
+
+impl fmt::Display for ChineseDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (stem, element) = STEMS[(amod(self.year, 10) - 1) as usize];
+        let (branch, animal) = BRANCHES[(amod(self.year, 12) - 1) as usize];
+        let leap = if self.leap { "leap " } else { "" };
+        write!(
+            f,
+            "{}month {}, day {} of the {} {} year ({}{}, year {} of cycle {})",
+            leap, self.month, self.day, element, animal, stem, branch, self.year, self.cycle
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_epochs() {
+        assert_eq!(fixed(date(-2636, 2, 15)), CHINESE_EPOCH);
+        assert_eq!(fixed_from_hijri(1, 1, 1), ISLAMIC_EPOCH);
+        assert_eq!(fixed_from_hebrew(1, 7, 1), HEBREW_EPOCH);
+    }
+
+    #[test]
+    fn test_hijri() {
+        assert_eq!(hijri(date(2025, 3, 1)).to_string(), "1 Ramadan 1446 AH");
+        // The arithmetical calendar starts 1446 a day after Saudi Arabia did.
+        let new_year = hijri(date(2024, 7, 8));
+        assert_eq!((new_year.year, new_year.month, new_year.day), (1446, 1, 1));
+    }
+
+    #[test]
+    fn test_hebrew() {
+        assert_eq!(hebrew(date(2025, 3, 1)).to_string(), "1 Adar 5785");
+        assert_eq!(hebrew(date(2024, 10, 3)).to_string(), "1 Tishrei 5785");
+        assert_eq!(hebrew(date(2024, 3, 11)).to_string(), "1 Adar II 5784");
+        assert_eq!(hebrew(date(2024, 2, 10)).to_string(), "1 Adar I 5784");
+    }
+
+    #[test]
+    fn test_hebrew_new_year() {
+        // 5784 is a leap year and 5785 a common one.
+        assert_eq!(hebrew(date(2023, 9, 15)).to_string(), "29 Elul 5783");
+        assert_eq!(hebrew(date(2023, 9, 16)).to_string(), "1 Tishrei 5784");
+        assert_eq!(hebrew(date(2024, 10, 2)).to_string(), "29 Elul 5784");
+        assert_eq!(hebrew(date(2024, 10, 3)).to_string(), "1 Tishrei 5785");
+        assert_eq!(hebrew(date(2019, 9, 29)).to_string(), "29 Elul 5779");
+        assert_eq!(hebrew(date(2019, 9, 30)).to_string(), "1 Tishrei 5780");
+        assert_eq!(hebrew(date(2020, 9, 19)).to_string(), "1 Tishrei 5781");
+    }
+
+    #[test]
+    fn test_chinese() {
+        let new_year = chinese(date(2024, 2, 10));
+        assert_eq!(
+            new_year,
+            ChineseDate {
+                cycle: 78,
+                year: 41,
+                month: 1,
+                leap: false,
+                day: 1
+            }
+        );
+        assert_eq!(
+            chinese(date(2025, 3, 1)).to_string(),
+            "month 2, day 2 of the Wood Snake year (乙巳, year 42 of cycle 78)"
+        );
+        // 2023 had a leap second month, from 22 March to 19 April.
+        let leap = chinese(date(2023, 3, 22));
+        assert_eq!((leap.month, leap.leap, leap.day), (2, true, 1));
+        let after = chinese(date(2023, 4, 20));
+        assert_eq!((after.month, after.leap, after.day), (3, false, 1));
+        assert_eq!(chinese(date(2025, 1, 28)).month, 12);
+        assert_eq!(
+            chinese(date(2025, 1, 29)).to_string().split(' ').nth(1),
+            Some("1,")
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Checkpoints that let long `convertx series` conversions resume.
+//!
+//! A checkpoint records how far into the input the conversion got and how
+//! many bytes of output that produced, together with a hash of the whole
+//! input and the conversion applied. A resumed run refuses to continue if the
+//! input or the conversion changed, and cuts the output back to the recorded
+//! length so a chunk that was written but not checkpointed is not duplicated.
+
+use std::path::Path;
+
+/// Progress of a conversion, saved after every chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Hash of the complete input, see [`hash`].
+    pub input_hash: u64,
+    /// The columns and units converted, e.g. `x-col=0 y-col=1 y-from=f y-to=c`,
+    /// so that the rest of the input is not converted differently.
+    pub conversion: String,
+    /// Byte offset in the input of the first line not yet converted.
+    pub offset: usize,
+    /// Zero-based number of that line, for error messages.
+    pub line: usize,
+    /// Length of the output written up to `offset`.
+    pub output_bytes: u64,
+}
+
+impl Checkpoint {
+    /// A checkpoint at the start of converting `input` with `conversion`.
+    pub fn start(input: &[u8], conversion: &str) -> Checkpoint {
+        Checkpoint {
+            input_hash: hash(input),
+            conversion: conversion.to_string(),
+            offset: 0,
+            line: 0,
+            output_bytes: 0,
+        }
+    }
+
+    /// Reads the checkpoint at `path`, or `None` if there is none yet.
+    pub fn load(path: &Path) -> Result<Option<Checkpoint>, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("could not read {}: {}", path.display(), e)),
+        };
+        let field = |key: &str| {
+            text.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .ok_or_else(|| format!("{} is not a checkpoint: no {}", path.display(), key))
+        };
+        let number = |key: &str| {
+            field(key)?
+                .parse::<u64>()
+                .map_err(|_| format!("{} is not a checkpoint: bad {}", path.display(), key))
+        };
+        let input_hash = u64::from_str_radix(field("input-hash")?, 16)
+            .map_err(|_| format!("{} is not a checkpoint: bad input-hash", path.display()))?;
+        Ok(Some(Checkpoint {
+            input_hash,
+            conversion: field("conversion")?.to_string(),
+            offset: number("offset")? as usize,
+            line: number("line")? as usize,
+            output_bytes: number("output-bytes")?,
+        }))
+    }
+
+    /// Writes the checkpoint to `path`, replacing any earlier one.
+    ///
+    /// The file is written next to `path` and renamed over it, so an
+    /// interruption leaves either the old or the new checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = format!(
+            "input-hash={:016x}\nconversion={}\noffset={}\nline={}\noutput-bytes={}\n",
+            self.input_hash, self.conversion, self.offset, self.line, self.output_bytes
+        );
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, text)
+            .and_then(|()| std::fs::rename(&partial, path))
+            .map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+}
+
+/// Hashes `bytes` with 64-bit FNV-1a, which is stable across builds and
+/// platforms unlike the standard library's hasher.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash(b"0,1\n"), hash(b"0,2\n"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("convertx_unit_checkpoint");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Checkpoint::load(&path), Ok(None));
+        let checkpoint = Checkpoint {
+            offset: 120,
+            line: 10,
+            output_bytes: 80,
+            ..Checkpoint::start(b"input", "x-col=0 y-col=1 y-from=f y-to=c")
+        };
+        checkpoint.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path), Ok(Some(checkpoint)));
+        std::fs::write(&path, "offset=1\n").unwrap();
+        assert!(Checkpoint::load(&path)
+            .unwrap_err()
+            .contains("no input-hash"));
+        std::fs::write(&path, "input-hash=1\noffset=1\nline=1\noutput-bytes=1\n").unwrap();
+        assert!(Checkpoint::load(&path)
+            .unwrap_err()
+            .contains("no conversion"));
+    }
+}
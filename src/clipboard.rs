@@ -0,0 +1,146 @@
+//! Copying text to the system clipboard for `--emit clipboard`.
+//!
+//! The text is piped into the platform's clipboard command rather than a
+//! windowing library linked into every build: `pbcopy` on macOS, `clip` on
+//! Windows, and `wl-copy`, `xclip` or `xsel`, whichever is installed, on
+//! other systems. The `CONVERTX_CLIPBOARD` environment variable names another
+//! command, e.g. `tmux load-buffer -`, which is tried first.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The commands that may copy their input to the clipboard, in the order
+/// they are tried, each as a program and its arguments.
+fn commands() -> Vec<Vec<String>> {
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    if let Ok(command) = std::env::var("CONVERTX_CLIPBOARD") {
+        commands.push(command.split_whitespace().map(str::to_string).collect());
+    }
+    let platform: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else if cfg!(windows) {
+        &[&["clip"]]
+    } else {
+        &[
+            &["wl-copy"],
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+    commands.extend(
+        platform
+            .iter()
+            .map(|command| command.iter().map(|arg| arg.to_string()).collect()),
+    );
+    commands.retain(|command| !command.is_empty());
+    commands
+}
+
+/// Ignores SIGPIPE while it lives, so writing to a clipboard command that has
+/// already exited fails with a broken pipe instead of killing convertx, which
+/// restores the default handler for its own stdout.
+struct IgnoreSigpipe {
+    #[cfg(unix)]
+    previous: usize,
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn signal(signum: i32, handler: usize) -> usize;
+}
+
+#[cfg(unix)]
+const SIGPIPE: i32 = 13;
+
+impl IgnoreSigpipe {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            const SIG_IGN: usize = 1;
+            // SAFETY: `signal` only swaps the handler.
+            let previous = unsafe { signal(SIGPIPE, SIG_IGN) };
+            IgnoreSigpipe { previous }
+        }
+        #[cfg(not(unix))]
+        IgnoreSigpipe {}
+    }
+}
+
+impl Drop for IgnoreSigpipe {
+    fn drop(&mut self) {
+        // SAFETY: as in `new`, restoring the handler it replaced.
+        #[cfg(unix)]
+        unsafe {
+            signal(SIGPIPE, self.previous);
+        }
+    }
+}
+
+/// Copies `text` to the clipboard with the first of the clipboard commands
+/// that is installed.
+pub fn copy(text: &str) -> Result<(), String> {
+    for command in commands() {
+        let spawned = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            // `xclip` stays behind to serve the selection, so nothing may
+            // wait for its output.
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("could not run {}: {}", command[0], e)),
+        };
+        let written = {
+            let _ignored = IgnoreSigpipe::new();
+            child
+                .stdin
+                .take()
+                .expect("stdin is piped")
+                .write_all(text.as_bytes())
+        };
+        let status = child
+            .wait()
+            .map_err(|e| format!("could not run {}: {}", command[0], e))?;
+        // A command that fails at once breaks the pipe, so its status is
+        // the error to report.
+        return match (written, status.success()) {
+            (Ok(()), true) => Ok(()),
+            (_, false) => Err(format!("{} failed with {}", command[0], status)),
+            (Err(e), true) => Err(format!("could not copy to {}: {}", command[0], e)),
+        };
+    }
+    Err(
+        "no clipboard command found, install wl-copy, xclip or xsel, or set CONVERTX_CLIPBOARD"
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        std::env::set_var("CONVERTX_CLIPBOARD", "tmux load-buffer -");
+        let tried = commands();
+        assert_eq!(tried[0], ["tmux", "load-buffer", "-"]);
+        assert!(tried.len() > 1);
+        std::env::set_var("CONVERTX_CLIPBOARD", " ");
+        assert!(!commands().iter().any(Vec::is_empty));
+
+        // More than a pipe holds, so the write breaks the pipe whenever
+        // `false` exits first; with the default SIGPIPE handler convertx
+        // sets, that would end the test process.
+        #[cfg(unix)]
+        unsafe {
+            signal(SIGPIPE, 0);
+        }
+        std::env::set_var("CONVERTX_CLIPBOARD", "false");
+        let copied = copy(&"1".repeat(1 << 20));
+        assert!(copied.unwrap_err().starts_with("false failed"));
+        std::env::remove_var("CONVERTX_CLIPBOARD");
+    }
+}
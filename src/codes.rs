@@ -0,0 +1,204 @@
+//! Morse code and the NATO phonetic alphabet for `convertx morse` and
+//! `convertx phonetic`.
+//!
+//! Morse letters are separated by spaces and words by ` / `; phonetic code
+//! words are separated by spaces and words by ` / ` too.
+
+/// International Morse code for letters, digits and punctuation.
+const MORSE: &[(char, &str)] = &[
+    ('A', ".-"),
+    ('B', "-..."),
+    ('C', "-.-."),
+    ('D', "-.."),
+    ('E', "."),
+    ('F', "..-."),
+    ('G', "--."),
+    ('H', "...."),
+    ('I', ".."),
+    ('J', ".---"),
+    ('K', "-.-"),
+    ('L', ".-.."),
+    ('M', "--"),
+    ('N', "-."),
+    ('O', "---"),
+    ('P', ".--."),
+    ('Q', "--.-"),
+    ('R', ".-."),
+    ('S', "..."),
+    ('T', "-"),
+    ('U', "..-"),
+    ('V', "...-"),
+    ('W', ".--"),
+    ('X', "-..-"),
+    ('Y', "-.--"),
+    ('Z', "--.."),
+    ('0', "-----"),
+    ('1', ".----"),
+    ('2', "..---"),
+    ('3', "...--"),
+    ('4', "....-"),
+    ('5', "....."),
+    ('6', "-...."),
+    ('7', "--..."),
+    ('8', "---.."),
+    ('9', "----."),
+    ('.', ".-.-.-"),
+    (',', "--..--"),
+    ('?', "..--.."),
+    ('\'', ".----."),
+    ('!', "-.-.--"),
+    ('/', "-..-."),
+    ('(', "-.--."),
+    (')', "-.--.-"),
+    ('&', ".-..."),
+    (':', "---..."),
+    (';', "-.-.-."),
+    ('=', "-...-"),
+    ('+', ".-.-."),
+    ('-', "-....-"),
+    ('_', "..--.-"),
+    ('"', ".-..-."),
+    ('$', "...-..-"),
+    ('@', ".--.-."),
+];
+
+/// NATO phonetic alphabet code words for letters and digits.
+const PHONETIC: &[(char, &str)] = &[
+    ('A', "Alfa"),
+    ('B', "Bravo"),
+    ('C', "Charlie"),
+    ('D', "Delta"),
+    ('E', "Echo"),
+    ('F', "Foxtrot"),
+    ('G', "Golf"),
+    ('H', "Hotel"),
+    ('I', "India"),
+    ('J', "Juliett"),
+    ('K', "Kilo"),
+    ('L', "Lima"),
+    ('M', "Mike"),
+    ('N', "November"),
+    ('O', "Oscar"),
+    ('P', "Papa"),
+    ('Q', "Quebec"),
+    ('R', "Romeo"),
+    ('S', "Sierra"),
+    ('T', "Tango"),
+    ('U', "Uniform"),
+    ('V', "Victor"),
+    ('W', "Whiskey"),
+    ('X', "X-ray"),
+    ('Y', "Yankee"),
+    ('Z', "Zulu"),
+    ('0', "Zero"),
+    ('1', "One"),
+    ('2', "Two"),
+    ('3', "Three"),
+    ('4', "Four"),
+    ('5', "Five"),
+    ('6', "Six"),
+    ('7', "Seven"),
+    ('8', "Eight"),
+    ('9', "Nine"),
+];
+
+/// Encodes each word of `text` with `table`, separating symbols by spaces and
+/// words by ` / `.
+fn encode(text: &str, table: &[(char, &str)], what: &str) -> Result<String, String> {
+    let words = text.split_whitespace().map(|word| {
+        let symbols = word.chars().map(|c| {
+            table
+                .iter()
+                .find(|(key, _)| *key == c.to_ascii_uppercase())
+                .map(|(_, code)| *code)
+                .ok_or_else(|| format!("'{}' has no {} code", c, what))
+        });
+        symbols
+            .collect::<Result<Vec<_>, _>>()
+            .map(|codes| codes.join(" "))
+    });
+    Ok(words.collect::<Result<Vec<_>, _>>()?.join(" / "))
+}
+
+/// Decodes text encoded by [`encode`], matching code words case-insensitively.
+fn decode(code: &str, table: &[(char, &str)], what: &str) -> Result<String, String> {
+    let words = code.split('/').map(|word| {
+        word.split_whitespace()
+            .map(|symbol| {
+                table
+                    .iter()
+                    .find(|(_, code)| code.eq_ignore_ascii_case(symbol))
+                    .map(|(c, _)| *c)
+                    .ok_or_else(|| format!("'{}' is not {}", symbol, what))
+            })
+            .collect::<Result<String, _>>()
+    });
+    Ok(words.collect::<Result<Vec<_>, _>>()?.join(" "))
+}
+
+/// Encodes text as Morse code.
+///
+/// # Example
+/// ```
+/// # use convertx::codes::*;
+/// assert_eq!(to_morse("SOS"), Ok("... --- ...".to_string()));
+/// ```
+pub fn to_morse(text: &str) -> Result<String, String> {
+    encode(text, MORSE, "Morse")
+}
+
+/// Decodes Morse code into upper-case text.
+pub fn from_morse(code: &str) -> Result<String, String> {
+    decode(code, MORSE, "Morse code")
+}
+
+/// Returns whether `s` looks like Morse code rather than text.
+pub fn is_morse(s: &str) -> bool {
+    s.contains(['.', '-']) && s.chars().all(|c| matches!(c, '.' | '-' | '/' | ' '))
+}
+
+/// Spells text with the NATO phonetic alphabet.
+///
+/// # Example
+/// ```
+/// # use convertx::codes::*;
+/// assert_eq!(to_phonetic("R2D4"), Ok("Romeo Two Delta Four".to_string()));
+/// ```
+pub fn to_phonetic(text: &str) -> Result<String, String> {
+    encode(text, PHONETIC, "phonetic")
+}
+
+/// Reads NATO phonetic code words back into upper-case text.
+pub fn from_phonetic(words: &str) -> Result<String, String> {
+    decode(words, PHONETIC, "a phonetic code word")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morse() {
+        assert_eq!(to_morse("sos"), Ok("... --- ...".to_string()));
+        assert_eq!(
+            to_morse("Hi there"),
+            Ok(".... .. / - .... . .-. .".to_string())
+        );
+        assert_eq!(
+            from_morse(".... .. / - .... . .-. ."),
+            Ok("HI THERE".to_string())
+        );
+        assert!(to_morse("naïve").is_err());
+        assert!(from_morse("......").is_err());
+        assert!(is_morse("... --- ..."));
+        assert!(!is_morse("SOS"));
+    }
+
+    #[test]
+    fn test_phonetic() {
+        assert_eq!(to_phonetic("R2D4"), Ok("Romeo Two Delta Four".to_string()));
+        assert_eq!(to_phonetic("ab 1"), Ok("Alfa Bravo / One".to_string()));
+        assert_eq!(from_phonetic("romeo two / x-ray"), Ok("R2 X".to_string()));
+        assert!(from_phonetic("Roger").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Colored terminal output for `--color`.
+//!
+//! Sentences such as `5 kilometers = 3.1069 miles` are written the same way
+//! by every subcommand, so [`highlight`] finds the result after their `=` and
+//! renders it in bold green with ANSI escape codes. Machine readable output,
+//! such as `--output json` or `--quiet`, is never colored.
+//!
+//! Color is off until [`set_enabled`], which the command line turns on for a
+//! terminal unless the `NO_COLOR` environment variable is set, see [`auto`].
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether output is colored, see [`set_enabled`].
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Starts bold green text.
+const START: &str = "\x1b[1;32m";
+/// Resets the text attributes.
+const RESET: &str = "\x1b[0m";
+
+/// Sets whether [`highlight`] and [`paint`] add escape codes. They don't by
+/// default.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns whether `--color auto` colors output: only on a `terminal`, and
+/// not when `no_color`, the value of `NO_COLOR`, is set and not empty, as
+/// <https://no-color.org> asks.
+pub fn auto(terminal: bool, no_color: Option<&OsStr>) -> bool {
+    terminal && no_color.is_none_or(OsStr::is_empty)
+}
+
+/// Renders all of `text` in color, if enabled.
+pub fn paint(text: &str) -> Cow<'_, str> {
+    if enabled() && !text.is_empty() {
+        Cow::Owned(format!("{}{}{}", START, text, RESET))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Renders the result of a `value = result` or `value ≈ result` sentence in
+/// color, if enabled: the text after the last `=` or `≈`, up to a note in
+/// parentheses. Other lines are returned as they are.
+///
+/// # Example
+/// ```
+/// # use convertx::color::*;
+/// set_enabled(true);
+/// assert_eq!(
+///     highlight("1 in = 25.4 mm (exact)"),
+///     "1 in = \x1b[1;32m25.4 mm\x1b[0m (exact)"
+/// );
+/// ```
+pub fn highlight(line: &str) -> Cow<'_, str> {
+    if !enabled() {
+        return Cow::Borrowed(line);
+    }
+    let Some(start) = [" = ", " ≈ "]
+        .iter()
+        .filter_map(|sign| line.rfind(sign).map(|at| at + sign.len()))
+        .max()
+    else {
+        return Cow::Borrowed(line);
+    };
+    let end = line[start..].find(" (").map_or(line.len(), |at| start + at);
+    Cow::Owned(format!(
+        "{}{}{}",
+        &line[..start],
+        paint(&line[start..end]),
+        &line[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto() {
+        assert!(auto(true, None));
+        assert!(auto(true, Some(OsStr::new(""))));
+        assert!(!auto(true, Some(OsStr::new("1"))));
+        assert!(!auto(false, None));
+    }
+
+    // One test, as the setting is global and tests run on several threads.
+    #[test]
+    fn test_highlight() {
+        assert_eq!(highlight("5 km = 3.1069 mi"), "5 km = 3.1069 mi");
+        set_enabled(true);
+        assert_eq!(
+            highlight("5 km = 3.1069 mi"),
+            "5 km = \x1b[1;32m3.1069 mi\x1b[0m"
+        );
+        assert_eq!(
+            highlight("0.2 mm ≈ 32 AWG (0.2019 mm)"),
+            "0.2 mm ≈ \x1b[1;32m32 AWG\x1b[0m (0.2019 mm)"
+        );
+        assert_eq!(
+            highlight("a = b = 2 (two)"),
+            "a = b = \x1b[1;32m2\x1b[0m (two)"
+        );
+        assert_eq!(highlight("count 5"), "count 5");
+        assert_eq!(paint("7"), "\x1b[1;32m7\x1b[0m");
+        assert_eq!(paint(""), "");
+        set_enabled(false);
+        assert_eq!(paint("7"), "7");
+    }
+}
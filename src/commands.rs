@@ -0,0 +1,26 @@
+//! The subcommands of convertx, grouped into families. Each family module has
+//! a `Command` enum of its subcommands, flattened into the top-level `Cli`,
+//! and a `run` that carries the parsed subcommand out.
+//!
+//! [`output`] and [`resolve`] hold what the families share: how results are
+//! printed, and how unit names are looked up across categories.
+
+pub mod category;
+pub mod computing;
+pub mod convert;
+#[cfg(feature = "data")]
+pub mod data;
+#[cfg(feature = "dates")]
+pub mod dates;
+pub mod farm;
+#[cfg(feature = "finance")]
+pub mod finance;
+pub mod kitchen;
+#[cfg(feature = "media")]
+pub mod media;
+pub mod output;
+pub mod resolve;
+#[cfg(feature = "serve")]
+pub mod server;
+pub mod workshop;
+pub mod writing;
@@ -0,0 +1,1127 @@
+//! The unit-to-unit conversion subcommands, one per category of units, e.g.
+//! `convertx length 5 --from km --to mi`, and the [`Category`] each converts
+//! with.
+
+use std::fmt;
+use structopt::StructOpt;
+
+use convertx::conversion::{Amount, ConversionResult, Measurement};
+use convertx::error::ConvertError;
+use convertx::quantity::Duration;
+use convertx::registry::{UnitDef, UnitRegistry};
+use convertx::unit::convert_units;
+use convertx::units::{
+    AreaUnit, CountUnit, DataRateUnit, HardnessUnit, HashrateUnit, InformationUnit, LengthUnit,
+    MassUnit, PressureUnit, SpeedUnit, TempUnit, UnitNames, VolumeUnit,
+};
+use convertx::{color, json, pack, quantity, registry, sql, unit};
+
+use super::output::{
+    conversion_json, conversion_sql, conversion_table, output_format, print_citations,
+    render_conversion, spoken_conversion, table_row, EmitOpts, Figure, NumberFormat, OutputMode,
+};
+use super::resolve::{warn_ambiguous, Converter};
+use crate::fail;
+
+// The unit-to-unit conversion subcommands, one per category.
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert length units.
+    Length(UnitOpts<LengthUnit>),
+    /// Convert temperature units.
+    Temperature(UnitOpts<TempUnit>),
+    /// Convert mass/weight units.
+    Mass(UnitOpts<MassUnit>),
+    /// Convert data rate units.
+    Datarate(UnitOpts<DataRateUnit>),
+    /// Convert area units.
+    Area(UnitOpts<AreaUnit>),
+    /// Convert volume units.
+    Volume(UnitOpts<VolumeUnit>),
+    /// Convert speed units.
+    Speed(UnitOpts<SpeedUnit>),
+    /// Convert pressure units.
+    Pressure(UnitOpts<PressureUnit>),
+    /// Convert counts of items (dozens, gross, scores, reams).
+    Count(UnitOpts<CountUnit>),
+    /// Convert water hardness units.
+    Hardness(UnitOpts<HardnessUnit>),
+    /// Convert amounts of information between bit- and byte-based units.
+    Information(UnitOpts<InformationUnit>),
+    /// Convert hash rates, optionally totalling the work done over a duration.
+    Hashrate(Hashrate),
+}
+
+// The units of a unit-to-unit conversion subcommand, and its other options.
+// This and the other flattened option structs take plain comments, as structopt
+// would show a doc comment as the about text of every subcommand that flattens
+// them.
+#[derive(StructOpt, Debug)]
+pub struct UnitOpts<U: std::str::FromStr<Err = String> + 'static> {
+    /// Unit to convert from, unless written with the value.
+    #[structopt(short = "f", long)]
+    from: Option<Written<U>>,
+    /// Intermediate unit to convert through (repeatable, applied in order).
+    #[structopt(long, number_of_values = 1)]
+    via: Vec<Written<U>>,
+    /// Unit to convert to, else the `--system` preset or the category's default.
+    #[structopt(short = "t", long)]
+    to: Option<Written<U>>,
+    #[structopt(flatten)]
+    opts: ConversionOpts,
+}
+
+impl<U> UnitOpts<U>
+where
+    U: Clone + PartialEq + fmt::Display + UnitNames + std::str::FromStr<Err = String>,
+{
+    /// Converts between units of `category`, returning the unit converted from.
+    fn run(self, category: &Category<U>) -> U {
+        let written = self.from.iter().chain(&self.via).chain(&self.to);
+        let value_unit = self
+            .opts
+            .value
+            .as_ref()
+            .and_then(|value| value.unit.as_deref());
+        // Only a whole unit can be ambiguous, not the gallon of "US gallon".
+        let words: Vec<&str> = written
+            .map(|unit| unit.text.as_str())
+            .chain(value_unit)
+            .filter(|&word| registry::ambiguity(word).is_some())
+            .collect();
+        warn_ambiguous(&words.join(" "));
+        let from = source_unit(&self.opts, category, self.from.map(|from| from.unit));
+        let via: Vec<U> = self.via.into_iter().map(|via| via.unit).collect();
+        print_chain(
+            &self.opts,
+            category,
+            from.clone(),
+            &via,
+            self.to.map(|to| to.unit),
+        );
+        from
+    }
+}
+
+/// A unit as it was written, kept to name it that way in warnings.
+#[derive(Debug, Clone)]
+pub struct Written<U> {
+    unit: U,
+    text: String,
+}
+
+impl<U: std::str::FromStr<Err = String>> std::str::FromStr for Written<U> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Written {
+            unit: s.parse()?,
+            text: s.to_string(),
+        })
+    }
+}
+
+// The hash rate subcommand, which can also total the hashes over a duration.
+#[derive(StructOpt, Debug)]
+pub struct Hashrate {
+    #[structopt(flatten)]
+    units: UnitOpts<HashrateUnit>,
+    /// Also print the total hashes computed over this time, e.g. 24h.
+    #[structopt(long)]
+    duration: Option<Duration>,
+}
+
+impl Hashrate {
+    /// Converts the hash rate, then prints the hashes over `--duration`.
+    fn run(self, category: &Category<HashrateUnit>) {
+        let value = self.units.opts.value.as_ref().map(|value| value.amount);
+        let from = self.units.run(category);
+        if let Some(duration) = self.duration {
+            let Some(value) = value else {
+                fail(ConvertError::Parse(
+                    "--duration needs a hash rate value, not --result".to_string(),
+                ));
+            };
+            let hashes: Vec<f64> = value
+                .points()
+                .into_iter()
+                .map(|rate| {
+                    convert_units(rate, from.clone(), HashrateUnit::Hashes).unwrap_or(f64::NAN)
+                        * duration.seconds
+                })
+                .collect();
+            let total = value.with_points(&hashes);
+            let shown = total.render(|v| format!("{:.4e}", v));
+            let result = match total {
+                Amount::Exact(hashes) => Figure::number(hashes, 0, "hashes").shown(&shown),
+                _ => Figure::text(&shown, "hashes"),
+            };
+            say!(
+                Figure::number(duration.seconds, 2, "s") => result,
+                "over {} = {} hashes",
+                duration,
+                shown
+            );
+        }
+    }
+}
+
+// Options shared by every unit-to-unit conversion subcommand.
+#[derive(StructOpt, Debug)]
+pub struct ConversionOpts {
+    /// Value to convert.
+    /// Accepts a plain number, a fraction such as `1/2` or `1 1/2`, a tolerance such as `10±0.2` (or `10+-0.2`), or a
+    /// range such as `5..7`, optionally with the unit attached instead of `--from`, e.g. `5km` or "3.5 lb".
+    #[structopt(required_unless = "result")]
+    value: Option<Measurement>,
+    /// Desired result; solves for the input value that converts to it.
+    #[structopt(long, alias = "solve-for-input", conflicts_with = "value")]
+    result: Option<Amount>,
+    /// Print every intermediate value of the conversion chain.
+    #[structopt(long = "show-steps")]
+    show_steps: bool,
+    /// Also convert the same value in the opposite direction (e.g. lb to kg).
+    #[structopt(long)]
+    both: bool,
+    /// Convert into every other unit of the category instead of `--to`, one
+    /// per line, skipping those the value is out of range of.
+    #[structopt(long, conflicts_with_all = &["to", "via", "both", "result"])]
+    all: bool,
+    /// Fail instead of rounding when a result is not a whole number, e.g.
+    /// 1500 bytes in KiB, for scripts that count items or provision storage.
+    #[structopt(long)]
+    strict_int: bool,
+    /// Enable an optional unit pack compiled into this build (repeatable).
+    #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+    units_pack: Vec<&'static str>,
+    /// Unit system whose preset target unit is used when `--to` is omitted.
+    #[structopt(long, env = "CONVERTX_SYSTEM", possible_values = &UnitSystem::variants(), case_insensitive = true)]
+    system: Option<UnitSystem>,
+    /// Show unit symbols (e.g. "km") instead of spelled-out names.
+    #[structopt(long)]
+    symbols: bool,
+    /// Output mode; `sql` prints an INSERT statement per conversion, `json` a
+    /// JSON object per line with unrounded numbers, in a stable format, `raw`
+    /// only the converted number, for scripts, `script-filter` the results as
+    /// launchers such as Alfred read them, `markdown` and `html` a table, and
+    /// `spoken` a sentence without symbols, for screen readers.
+    #[structopt(long, conflicts_with = "format", possible_values = &OutputMode::variants(), case_insensitive = true)]
+    output: Option<OutputMode>,
+    /// Print only the converted number (or `--raw`), as `--output raw` does.
+    #[structopt(short, long, alias = "raw", conflicts_with_all = &["format", "output"])]
+    quiet: bool,
+    /// Spell the conversion out for screen readers and speech, e.g. "10
+    /// kilometers equals 6.2137 miles", as `--output spoken` does.
+    #[structopt(long, conflicts_with_all = &["format", "output", "quiet"])]
+    spoken: bool,
+    /// Follow the conversion with where the definition of each unit is
+    /// published, e.g. the SI Brochure or NIST SP 811, for reports.
+    #[structopt(long, conflicts_with_all = &["format", "output", "quiet", "spoken"])]
+    cite: bool,
+    /// Table the `--output sql` statements insert into.
+    #[structopt(long, default_value = "conversions")]
+    table: sql::Table,
+    #[structopt(flatten)]
+    emit: EmitOpts,
+    #[structopt(flatten)]
+    number: NumberFormat,
+}
+
+// Supported unit systems for `--system` presets.
+enum_with_variants!(pub UnitSystem {
+    Metric => "metric",
+    Imperial => "imperial",
+    UsCustomary => "us-customary",
+});
+
+/// How a unit is written next to a number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitStyle {
+    /// Spelled-out name, singular or plural, e.g. "3.2808 feet".
+    Name { singular: bool },
+    /// Unit symbol, e.g. "3.2808 ft".
+    Symbol,
+}
+
+/// Join a formatted number with its unit name or symbol, e.g. "3.2808 feet".
+fn label_plain<U: UnitNames>(number: &str, unit: &U, style: UnitStyle) -> String {
+    match style {
+        UnitStyle::Name { singular } => format!("{} {}", number, unit.name(singular)),
+        UnitStyle::Symbol => format!("{} {}", number, unit.symbol()),
+    }
+}
+
+/// Join a formatted temperature with a degree sign and uppercase unit, e.g. "32.00°F".
+///
+/// With symbols, kelvin is written without a degree sign, e.g. "273.15 K".
+pub fn label_temp(number: &str, unit: &TempUnit, style: UnitStyle) -> String {
+    match (style, unit) {
+        (UnitStyle::Symbol, TempUnit::K) | (_, TempUnit::Prefixed(_)) => {
+            format!("{} {}", number, unit.symbol())
+        }
+        _ => format!("{}°{}", number, unit.to_string().to_uppercase()),
+    }
+}
+
+/// How a unit-to-unit conversion category is converted and displayed.
+pub struct Category<U: 'static> {
+    /// Category name, e.g. "length".
+    name: &'static str,
+    /// Converts a value between two units of the category.
+    convert: fn(f64, U, U) -> Result<f64, ConvertError>,
+    /// Joins an already formatted number with its unit name or symbol.
+    label: fn(&str, &U, UnitStyle) -> String,
+    /// Default number of decimal places in output.
+    precision: usize,
+    /// Show whole numbers without decimal places, e.g. "144 items".
+    whole_numbers: bool,
+    /// Source unit used when neither `--from` nor the value gives one.
+    default_from: Option<U>,
+    /// Target unit used when neither `--to` nor `--system` is given.
+    default_to: Option<U>,
+    /// Target unit picked by each `--system` preset, if the category has one.
+    preset: fn(&UnitSystem) -> Option<U>,
+}
+
+impl<U> Category<U> {
+    /// The category's default decimal places for `amount`.
+    fn precision(&self, amount: &Amount) -> usize {
+        let whole = amount.points().iter().all(|v| v.fract() == 0.0);
+        if self.whole_numbers && whole {
+            0
+        } else {
+            self.precision
+        }
+    }
+
+    /// Formats an amount without its unit using the category's defaults, and
+    /// whole numbers without decimal places with `--strict-int`.
+    fn number(&self, amount: &Amount, opts: &ConversionOpts) -> String {
+        let whole = amount.points().iter().all(|v| v.fract() == 0.0);
+        let places = match opts.strict_int && whole {
+            true => 0,
+            false => self.precision(amount),
+        };
+        opts.number.amount(amount, places)
+    }
+
+    /// Formats an amount with its unit using the category's defaults.
+    fn show(&self, amount: &Amount, unit: &U, opts: &ConversionOpts) -> String {
+        let number = self.number(amount, opts);
+        let style = if opts.symbols {
+            UnitStyle::Symbol
+        } else {
+            UnitStyle::Name {
+                singular: amount.is_singular(&number),
+            }
+        };
+        (self.label)(&number, unit, style)
+    }
+}
+
+const LENGTH: Category<LengthUnit> = Category {
+    name: "length",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: Some(LengthUnit::Meters),
+    default_to: Some(LengthUnit::Feet),
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => LengthUnit::Meters,
+            Imperial | UsCustomary => LengthUnit::Feet,
+        })
+    },
+};
+
+const TEMPERATURE: Category<TempUnit> = Category {
+    name: "temperature",
+    convert: convert_units,
+    label: label_temp,
+    precision: 2,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => TempUnit::C,
+            Imperial | UsCustomary => TempUnit::F,
+        })
+    },
+};
+
+const MASS: Category<MassUnit> = Category {
+    name: "mass",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => MassUnit::Kg,
+            Imperial | UsCustomary => MassUnit::Lb,
+        })
+    },
+};
+
+const DATARATE: Category<DataRateUnit> = Category {
+    name: "datarate",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |_| None,
+};
+
+const AREA: Category<AreaUnit> = Category {
+    name: "area",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => AreaUnit::SquareMeters,
+            Imperial | UsCustomary => AreaUnit::SquareFeet,
+        })
+    },
+};
+
+const VOLUME: Category<VolumeUnit> = Category {
+    name: "volume",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => VolumeUnit::Liters,
+            Imperial => VolumeUnit::ImperialGallons,
+            UsCustomary => VolumeUnit::Gallons,
+        })
+    },
+};
+
+const SPEED: Category<SpeedUnit> = Category {
+    name: "speed",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => SpeedUnit::Kph,
+            Imperial | UsCustomary => SpeedUnit::Mph,
+        })
+    },
+};
+
+const PRESSURE: Category<PressureUnit> = Category {
+    name: "pressure",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |system| {
+        use UnitSystem::*;
+        Some(match system {
+            Metric => PressureUnit::Bar,
+            Imperial | UsCustomary => PressureUnit::Psi,
+        })
+    },
+};
+
+const COUNT: Category<CountUnit> = Category {
+    name: "count",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: true,
+    default_from: None,
+    default_to: None,
+    preset: |_| None,
+};
+
+const HARDNESS: Category<HardnessUnit> = Category {
+    name: "hardness",
+    convert: convert_units,
+    label: label_plain,
+    precision: 2,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |_| None,
+};
+
+const INFORMATION: Category<InformationUnit> = Category {
+    name: "information",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |_| None,
+};
+
+const HASHRATE: Category<HashrateUnit> = Category {
+    name: "hashrate",
+    convert: convert_units,
+    label: label_plain,
+    precision: 4,
+    whole_numbers: false,
+    default_from: None,
+    default_to: None,
+    preset: |_| None,
+};
+
+/// Declares every unit-to-unit conversion category once, as the [`Command`] variant
+/// and the [`Category`] that converts it, and generates from that list:
+/// + `CATEGORIES`, the category names;
+/// + `unit_registry`, the [`UnitRegistry`] of every unit in every category;
+/// + `category_precision`, how many decimal places each category shows;
+/// + `category_converter`, the conversion of each category's subcommand;
+/// + `Command::run`, the dispatch of a category subcommand.
+macro_rules! categories {
+    ($($variant:ident => $category:ident),* $(,)?) => {
+        /// Names of the unit-to-unit conversion categories.
+        pub const CATEGORIES: &[&str] = &[$($category.name),*];
+
+        /// Returns the registry of every unit, built on first use.
+        pub fn unit_registry() -> &'static UnitRegistry {
+            static REGISTRY: std::sync::OnceLock<UnitRegistry> = std::sync::OnceLock::new();
+            REGISTRY.get_or_init(|| {
+                let mut registry = UnitRegistry::default();
+                $(register_category(&mut registry, &$category);)*
+                registry.with_relative_units()
+            })
+        }
+
+        /// Returns the default number of decimal places of the category `name`,
+        /// and whether it shows whole numbers without them.
+        pub fn category_precision(name: &str) -> (usize, bool) {
+            [$(($category.name, ($category.precision, $category.whole_numbers))),*]
+                .into_iter()
+                .find(|(category, _)| *category == name)
+                .map_or((4, false), |(_, precision)| precision)
+        }
+
+        /// Returns a converter between the units named `from` and `to` of the
+        /// category `name` that converts as its subcommand does, if `name` is
+        /// one of these categories and both units parse in it.
+        pub fn category_converter(name: &str, from: &str, to: &str) -> Option<Converter> {
+            $(if name == $category.name {
+                return units_converter(&$category, from, to);
+            })*
+            None
+        }
+
+        impl Command {
+            /// Runs the unit-to-unit conversion requested by a category subcommand.
+            pub fn run(self) {
+                match self {
+                    $(Command::$variant(command) => {
+                        command.run(&$category);
+                    })*
+                }
+            }
+        }
+    };
+}
+
+categories! {
+    Length => LENGTH,
+    Temperature => TEMPERATURE,
+    Mass => MASS,
+    Datarate => DATARATE,
+    Area => AREA,
+    Volume => VOLUME,
+    Speed => SPEED,
+    Pressure => PRESSURE,
+    Count => COUNT,
+    Hardness => HARDNESS,
+    Information => INFORMATION,
+    Hashrate => HASHRATE,
+}
+
+/// Adds every unit of `category` to `registry`, linked to the category's first
+/// unit by the affine map its conversion implies.
+fn register_category<U>(registry: &mut UnitRegistry, category: &Category<U>)
+where
+    U: unit::Unit + fmt::Display + UnitNames,
+{
+    let units = U::units();
+    let Some(first) = units.first().cloned() else {
+        return;
+    };
+    let base = registry.add(unit_def(&first, category.name));
+    for unit in units.into_iter().skip(1) {
+        let to_first = |v: f64| first.from_base(unit.to_base(v));
+        let offset = to_first(0.0);
+        let factor = to_first(1.0) - offset;
+        let index = registry.add(unit_def(&unit, category.name));
+        registry.link(index, base, factor, offset);
+    }
+}
+
+/// Returns a converter between the units of `category` named `from` and `to`
+/// with the category's own conversion, if both parse.
+fn units_converter<U>(category: &Category<U>, from: &str, to: &str) -> Option<Converter>
+where
+    U: Clone + std::str::FromStr + Send + Sync + 'static,
+{
+    let (from, to): (U, U) = (from.parse().ok()?, to.parse().ok()?);
+    let convert = category.convert;
+    Some(std::sync::Arc::new(move |value| {
+        convert(value, from.clone(), to.clone())
+    }))
+}
+
+/// Describes `unit` of the category named `category` for the registry.
+fn unit_def<U: fmt::Display + UnitNames>(unit: &U, category: &str) -> UnitDef {
+    UnitDef {
+        plural: unit.name(false).to_string(),
+        pack: unit.pack_unit().map(|unit| unit.pack),
+        ..UnitDef::new(&unit.to_string(), unit.name(true), unit.symbol(), category)
+    }
+}
+
+/// Convert a value through a chain of units: `from`, each unit in `via` in order, then `to`.
+///
+/// Returns every hop as a `(value, unit)` pair starting with the input, or the
+/// error of the first hop that fails.
+///
+/// # Example
+/// ```
+/// use crate::LengthUnit::*;
+/// let steps = convert_chain(1.0, Kilometers, &[Meters], Feet, convert_units).unwrap();
+/// assert_eq!(steps.len(), 3);
+/// ```
+fn convert_chain<U: Clone + PartialEq>(
+    value: f64,
+    from: U,
+    via: &[U],
+    to: U,
+    convert: fn(f64, U, U) -> Result<f64, ConvertError>,
+) -> Result<Vec<(f64, U)>, ConvertError> {
+    let mut steps = vec![(value, from)];
+    for unit in via.iter().cloned().chain(std::iter::once(to)) {
+        let (current, current_unit) = steps[steps.len() - 1].clone();
+        let next = if current_unit == unit {
+            current
+        } else {
+            convert(current, current_unit, unit.clone())?
+        };
+        steps.push((next, unit));
+    }
+    Ok(steps)
+}
+
+/// Convert an [`Amount`] through a chain of units, see [`convert_chain`].
+///
+/// Each number making up the amount is converted independently and the results
+/// are reassembled per hop, so tolerances and ranges stay correct through affine
+/// conversions such as temperature.
+fn convert_amount_chain<U: Clone + PartialEq>(
+    amount: &Amount,
+    from: U,
+    via: &[U],
+    to: U,
+    convert: fn(f64, U, U) -> Result<f64, ConvertError>,
+) -> Result<Vec<(Amount, U)>, ConvertError> {
+    let chains = amount
+        .points()
+        .into_iter()
+        .map(|point| convert_chain(point, from.clone(), via, to.clone(), convert))
+        .collect::<Result<Vec<_>, _>>()?;
+    let steps = (0..chains[0].len())
+        .map(|i| {
+            let points: Vec<f64> = chains.iter().map(|chain| chain[i].0).collect();
+            (amount.with_points(&points), chains[0][i].1.clone())
+        })
+        .collect();
+    Ok(steps)
+}
+
+/// Solve for the input value that converts to `result` through the chain.
+///
+/// The chain is walked backwards from `to` to `from`, and the answer is checked by
+/// converting it forward again, so a conversion that cannot be inverted fails
+/// with [`ConvertError::OutOfRange`] rather than giving a wrong value.
+///
+/// # Example
+/// ```
+/// use crate::TempUnit::*;
+/// assert!((solve_chain_input(212.0, C, &[], F, convert_units).unwrap() - 100.0).abs() < 1e-9);
+/// ```
+fn solve_chain_input<U: Clone + PartialEq>(
+    result: f64,
+    from: U,
+    via: &[U],
+    to: U,
+    convert: fn(f64, U, U) -> Result<f64, ConvertError>,
+) -> Result<f64, ConvertError> {
+    let reversed: Vec<U> = via.iter().rev().cloned().collect();
+    let backward = convert_chain(result, to.clone(), &reversed, from.clone(), convert)?;
+    let input = backward[backward.len() - 1].0;
+    let forward = convert_chain(input, from, via, to, convert)?;
+    let check = forward[forward.len() - 1].0;
+    if (check - result).abs() <= 1e-9 * result.abs().max(1.0) {
+        Ok(input)
+    } else {
+        Err(ConvertError::OutOfRange {
+            value: result,
+            reason: "no input value converts to it".to_string(),
+        })
+    }
+}
+
+/// Returns the unit to convert from: the one written with the value, e.g.
+/// `5km`, else `--from`, else the category's default.
+fn source_unit<U>(opts: &ConversionOpts, category: &Category<U>, from: Option<U>) -> U
+where
+    U: Clone + PartialEq + fmt::Display + std::str::FromStr<Err = String>,
+{
+    let written = opts.value.as_ref().and_then(|value| value.unit.as_deref());
+    let written = written.map(|unit| {
+        unit.parse().unwrap_or_else(|_| {
+            fail(ConvertError::Parse(format!(
+                "unknown {} unit '{}'",
+                category.name, unit
+            )))
+        })
+    });
+    match (written, from) {
+        (Some(written), Some(from)) if written != from => fail(ConvertError::Parse(format!(
+            "the value is in {} but --from is {}",
+            written, from
+        ))),
+        (Some(unit), _) | (None, Some(unit)) => unit,
+        (None, None) => category.default_from.clone().unwrap_or_else(|| {
+            fail(ConvertError::Parse(format!(
+                "pass --from or write the {} unit with the value",
+                category.name
+            )))
+        }),
+    }
+}
+
+/// Run a conversion chain and print the result.
+///
+/// Without `--to`, the target is the `--system` preset or the category default.
+/// When `--result` is given, the input value is solved for first. With
+/// `--show-steps`, every hop is printed on its own line; otherwise only the input
+/// and the final value are shown. `--both` also converts the same value in the
+/// opposite direction. `--format` replaces the default `a = b` line.
+fn print_chain<U: Clone + PartialEq + fmt::Display + UnitNames + std::str::FromStr>(
+    opts: &ConversionOpts,
+    category: &Category<U>,
+    from: U,
+    via: &[U],
+    to: Option<U>,
+) {
+    let convert = category.convert;
+    let preset = opts.system.as_ref().and_then(category.preset);
+    let targets: Vec<U> = if opts.all {
+        // The registry also holds the units defined relative to others, e.g. miles.
+        let registered = unit_registry()
+            .units()
+            .iter()
+            .filter(|unit| unit.dimension == category.name)
+            .filter_map(|unit| unit.name.parse::<U>().ok());
+        let mut targets: Vec<U> = Vec::new();
+        for unit in registered {
+            let pack = unit.pack_unit();
+            // Packs may share a unit, e.g. fathoms, which is listed once.
+            let listed = std::iter::once(&from)
+                .chain(&targets)
+                .any(|other| other.name(false) == unit.name(false));
+            if !listed && pack.is_none_or(|pack| pack.is_enabled(&opts.units_pack)) {
+                targets.push(unit);
+            }
+        }
+        targets
+    } else {
+        let Some(to) = to.or(preset).or_else(|| category.default_to.clone()) else {
+            fail(ConvertError::Parse(match &opts.system {
+                Some(system) => format!(
+                    "the {} system has no {} preset, pass --to",
+                    system, category.name
+                ),
+                None => format!("pass --to, or --system for a preset {} unit", category.name),
+            }));
+        };
+        vec![to]
+    };
+    let units = std::iter::once(&from).chain(via).chain(&targets);
+    for unit in units.filter_map(UnitNames::pack_unit) {
+        if !unit.is_enabled(&opts.units_pack) {
+            fail(ConvertError::Parse(format!(
+                "'{}' is part of the {} unit pack, enable it with --units-pack {}",
+                unit.name, unit.pack, unit.pack
+            )));
+        }
+    }
+    let value = match (&opts.value, opts.result) {
+        (Some(value), _) => value.amount,
+        (None, Some(result)) => {
+            // `--result` conflicts with `--all`, so there is a single target.
+            let to = &targets[0];
+            let solved = result
+                .points()
+                .into_iter()
+                .map(|point| solve_chain_input(point, from.clone(), via, to.clone(), convert))
+                .collect::<Result<Vec<_>, _>>();
+            match solved {
+                Ok(points) => result.with_points(&points),
+                Err(error) => fail(error),
+            }
+        }
+        (None, None) => unreachable!("structopt requires a value or --result"),
+    };
+    // With `--strict-int` a result that is not whole fails its chain, so
+    // `--all` skips the units it is not whole in.
+    let whole = |mut steps: Vec<(Amount, U)>| {
+        if opts.strict_int {
+            let (result, to) = steps.last_mut().expect("a chain ends in its result");
+            let points = result
+                .points()
+                .into_iter()
+                .map(|point| quantity::check_whole(point, to.symbol()))
+                .collect::<Result<Vec<_>, _>>()?;
+            *result = result.with_points(&points);
+        }
+        Ok(steps)
+    };
+    let mut chains: Vec<_> = targets
+        .iter()
+        .map(|to| convert_amount_chain(&value, from.clone(), via, to.clone(), convert))
+        .map(|chain| chain.and_then(whole))
+        .collect();
+    if opts.both {
+        let reversed: Vec<U> = via.iter().rev().cloned().collect();
+        let chain =
+            convert_amount_chain(&value, targets[0].clone(), &reversed, from.clone(), convert);
+        chains.push(chain.and_then(whole));
+    }
+    let chains = if opts.all {
+        // A value is often out of range of some scales, e.g. of hardness.
+        let first_error = chains.iter().find_map(|chain| chain.clone().err());
+        let chains: Vec<_> = chains.into_iter().filter_map(Result::ok).collect();
+        match first_error {
+            Some(error) if chains.is_empty() => fail(error),
+            _ => chains,
+        }
+    } else {
+        match chains.into_iter().collect::<Result<Vec<_>, _>>() {
+            Ok(chains) => chains,
+            Err(error) => fail(error),
+        }
+    };
+    let text = |(value, from): &(Amount, U), (result, to): &(Amount, U)| {
+        format!(
+            "{} = {}",
+            category.show(value, from, opts),
+            category.show(result, to, opts)
+        )
+    };
+    let line = |start: &(Amount, U), end: &(Amount, U)| {
+        let ((value, from), (result, to)) = (start, end);
+        let shown = |amount: &Amount| category.number(amount, opts);
+        let conversion = ConversionResult::new(category.name, *value, from, *result, to, shown);
+        match output_format(&opts.output, opts.quiet, opts.spoken) {
+            (Some(template), _) => {
+                render_conversion(&conversion, template, &opts.number, category.precision)
+            }
+            (None, Some(OutputMode::Sql)) => conversion_sql(&conversion, &opts.table),
+            (None, Some(OutputMode::Json)) => conversion_json(&conversion),
+            (None, Some(OutputMode::Raw)) => category.number(result, opts),
+            (None, Some(OutputMode::Spoken)) => spoken_conversion(&conversion, |amount| {
+                opts.number.spoken(amount, category.precision(amount))
+            }),
+            (None, _) => color::highlight(&text(start, end)).into_owned(),
+        }
+    };
+    let pairs: Vec<_> = chains
+        .iter()
+        .flat_map(|steps| match opts.show_steps {
+            true => steps.windows(2).map(|pair| (&pair[0], &pair[1])).collect(),
+            false => vec![(&steps[0], &steps[steps.len() - 1])],
+        })
+        .collect();
+    let json = || {
+        let objects: Vec<String> = pairs
+            .iter()
+            .map(|((value, from), (result, to))| {
+                conversion_json(&ConversionResult::new(
+                    category.name,
+                    *value,
+                    from,
+                    *result,
+                    to,
+                    |amount| category.number(amount, opts),
+                ))
+            })
+            .collect();
+        objects.join("\n")
+    };
+    let print = || match output_format(&opts.output, opts.quiet, opts.spoken) {
+        (None, Some(OutputMode::ScriptFilter)) => {
+            let items: Vec<_> = pairs
+                .iter()
+                .map(|(start, end)| {
+                    let (result, to) = end;
+                    let title = category.show(result, to, opts);
+                    (title, text(start, end), category.number(result, opts))
+                })
+                .collect();
+            println!("{}", json::script_filter(&items));
+        }
+        (None, Some(mode @ (OutputMode::Markdown | OutputMode::Html))) => {
+            let rows = pairs
+                .iter()
+                .map(|((value, from), (result, to))| {
+                    let shown = |amount: &Amount| category.number(amount, opts);
+                    let conversion =
+                        ConversionResult::new(category.name, *value, from, *result, to, shown);
+                    let value = category.number(value, opts);
+                    let result = category.number(result, opts);
+                    table_row(conversion, value, result, opts.symbols)
+                })
+                .collect();
+            print!("{}", conversion_table(rows, mode));
+        }
+        _ => {
+            for (start, end) in &pairs {
+                println!("{}", line(start, end));
+            }
+            if opts.cite {
+                let units = std::iter::once(&from).chain(via).chain(&targets);
+                print_citations(units.map(|unit| {
+                    let source = match unit.pack_unit() {
+                        Some(_) => None,
+                        None => registry::source(&unit.to_string(), category.name),
+                    };
+                    (unit.name(false).to_string(), source)
+                }));
+            }
+        }
+    };
+    let raw: Vec<String> = pairs
+        .iter()
+        .map(|(_, (result, _))| category.number(result, opts))
+        .collect();
+    opts.emit.emit(print, &json(), &raw.join("\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_count() {
+        use CountUnit::*;
+        assert_eq!(convert_units(2.0, Gross, Items), Ok(288.0));
+        assert_eq!(convert_units(1.0, Gross, Dozens), Ok(12.0));
+        assert_eq!(convert_units(3.0, Scores, Items), Ok(60.0));
+        assert_eq!(convert_units(1000.0, Items, Reams), Ok(2.0));
+    }
+
+    #[test]
+    fn test_convert_hardness() {
+        use HardnessUnit::*;
+        assert!((convert_units(120.0, Ppm, Dgh).unwrap() - 6.7234).abs() < 1e-4);
+        assert!((convert_units(1.0, Dgh, French).unwrap() - 1.7848).abs() < 1e-9);
+        assert!((convert_units(1.0, Mmol, Ppm).unwrap() - 100.09).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert_information() {
+        use InformationUnit::*;
+        assert_eq!(convert_units(3.0, Bytes, Nibbles), Ok(6.0));
+        assert_eq!(convert_units(1.0, Mebibits, Kibibytes), Ok(128.0));
+        assert_eq!(convert_units(1.0, Gigabytes, Megabits), Ok(8000.0));
+    }
+
+    #[test]
+    fn test_convert_hashrate() {
+        use HashrateUnit::*;
+        assert_eq!(convert_units(250.0, Megahashes, Gigahashes), Ok(0.25));
+        assert_eq!(convert_units(1.0, Exahashes, Hashes), Ok(1e18));
+    }
+
+    #[test]
+    fn test_convert_length() {
+        use LengthUnit::*;
+        assert!((convert_units(1.0, Meters, Feet).unwrap() - 3.28084).abs() < 1e-5);
+        assert!((convert_units(3.28084, Feet, Meters).unwrap() - 1.0).abs() < 1e-5);
+        assert!((convert_units(1.0, Kilometers, Meters).unwrap() - 1000.0).abs() < 1e-5);
+        assert!((convert_units(12.0, Inches, Feet).unwrap() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_convert_temp() {
+        use TempUnit::*;
+        assert!((convert_units(0.0, C, F).unwrap() - 32.0).abs() < 1e-6);
+        assert!((convert_units(32.0, F, C).unwrap() - 0.0).abs() < 1e-6);
+        assert!((convert_units(100.0, C, K).unwrap() - 373.15).abs() < 1e-2);
+        assert!((convert_units(0.0, K, C).unwrap() - -273.15).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_convert_mass() {
+        use MassUnit::*;
+        assert!((convert_units(1.0, Kg, Lb).unwrap() - 2.20462).abs() < 1e-5);
+        assert!((convert_units(2.20462, Lb, Kg).unwrap() - 1.0).abs() < 1e-5);
+        assert!((convert_units(1.0, Kg, Oz).unwrap() - 35.274).abs() < 1e-3);
+        assert!((convert_units(35.274, Oz, Kg).unwrap() - 1.0).abs() < 1e-3);
+        assert!((convert_units(1.0, TroyOunces, Kg).unwrap() - 0.0311034768).abs() < 1e-9);
+        assert!((convert_units(1.0, TroyPounds, TroyOunces).unwrap() - 12.0).abs() < 1e-9);
+        assert!((convert_units(1.0, TroyOunces, Pennyweights).unwrap() - 20.0).abs() < 1e-9);
+        assert!((convert_units(1.0, ApothecariesDrams, Scruples).unwrap() - 3.0).abs() < 1e-9);
+        assert!((convert_units(1.0, ShortTons, Kg).unwrap() - 907.18474).abs() < 1e-9);
+        assert!((convert_units(1.0, LongTons, Kg).unwrap() - 1016.0469088).abs() < 1e-9);
+        assert!((convert_units(1.0, Tonnes, ShortTons).unwrap() - 1.1023113).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_convert_datarate() {
+        use DataRateUnit::*;
+        assert!((convert_units(1_000_000.0, Bps, Mbps).unwrap() - 1.0).abs() < 1e-8);
+        assert!((convert_units(1.0, Mbps, Bps).unwrap() - 1_000_000.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_convert_area() {
+        use AreaUnit::*;
+        assert!((convert_units(1.0, Acres, SquareMeters).unwrap() - 4046.85642).abs() < 1e-4);
+        assert!((convert_units(1.0, SquareMeters, Acres).unwrap() - 0.000247105).abs() < 1e-7);
+        assert!((convert_units(1.0, Hectares, Acres).unwrap() - 2.47105).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_convert_volume() {
+        use VolumeUnit::*;
+        assert!((convert_units(1.0, Gallons, Liters).unwrap() - 3.78541).abs() < 1e-5);
+        assert!((convert_units(1.0, Liters, Gallons).unwrap() - 0.264172).abs() < 1e-6);
+        assert!((convert_units(1000.0, Milliliters, Liters).unwrap() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_convert_speed() {
+        use SpeedUnit::*;
+        assert!((convert_units(1.0, Mps, Kph).unwrap() - 3.6).abs() < 1e-6);
+        assert!((convert_units(3.6, Kph, Mps).unwrap() - 1.0).abs() < 1e-6);
+        assert!((convert_units(1.0, Knots, Mph).unwrap() - 1.15078).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_convert_pressure() {
+        use PressureUnit::*;
+        assert!((convert_units(1.0, Atm, Pascal).unwrap() - 101325.0).abs() < 1e-3);
+        assert!((convert_units(1.0, Psi, Bar).unwrap() - 0.0689476).abs() < 1e-6);
+        assert!((convert_units(1.0, Bar, Psi).unwrap() - 14.5038).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_convert_chain() {
+        use LengthUnit::*;
+        let steps = convert_chain(1.0, Kilometers, &[Meters, Inches], Feet, convert_units).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert!((steps[1].0 - 1000.0).abs() < 1e-6);
+        assert!((steps[2].0 - 39370.1).abs() < 1e-3);
+        assert!((steps[3].0 - 3280.84).abs() < 1e-2);
+        let direct = convert_chain(5.0, Meters, &[], Meters, convert_units).unwrap();
+        assert_eq!(direct, vec![(5.0, Meters), (5.0, Meters)]);
+    }
+
+    #[test]
+    fn test_solve_chain_input() {
+        use TempUnit::*;
+        assert!(
+            (solve_chain_input(100.0, C, &[], F, convert_units).unwrap() - 37.7778).abs() < 1e-4
+        );
+        assert!((solve_chain_input(0.0, F, &[K], C, convert_units).unwrap() - 32.0).abs() < 1e-9);
+        use LengthUnit::*;
+        assert!(
+            (solve_chain_input(1.0, Feet, &[], Meters, convert_units).unwrap() - 3.28084).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_amount_parse_and_display() {
+        assert_eq!("2.5".parse::<Amount>(), Ok(Amount::Exact(2.5)));
+        assert_eq!("10±0.2".parse::<Amount>(), Ok(Amount::Tolerance(10.0, 0.2)));
+        assert_eq!(
+            "10 +- 0.2".parse::<Amount>(),
+            Ok(Amount::Tolerance(10.0, 0.2))
+        );
+        assert_eq!("5..7".parse::<Amount>(), Ok(Amount::Range(5.0, 7.0)));
+        assert!("5..x".parse::<Amount>().is_err());
+        assert_eq!(format!("{:.2}", Amount::Tolerance(10.0, 0.2)), "10.00±0.20");
+        assert_eq!(format!("{:.1}", Amount::Range(5.0, 7.0)), "5.0..7.0");
+    }
+
+    #[test]
+    fn test_convert_amount_chain() {
+        use LengthUnit::*;
+        let steps =
+            convert_amount_chain(&Amount::Range(1.0, 2.0), Meters, &[], Feet, convert_units)
+                .unwrap();
+        assert_eq!(steps[1].0, Amount::Range(3.28084, 6.56168));
+        use TempUnit::*;
+        let steps =
+            convert_amount_chain(&Amount::Tolerance(100.0, 1.0), C, &[], F, convert_units).unwrap();
+        match steps[1].0 {
+            Amount::Tolerance(n, t) => assert!((n - 212.0).abs() < 1e-9 && (t - 1.8).abs() < 1e-9),
+            other => panic!("unexpected amount {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unit_symbol_aliases() {
+        assert_eq!("km".parse::<LengthUnit>(), Ok(LengthUnit::Kilometers));
+        assert_eq!("KM".parse::<LengthUnit>(), Ok(LengthUnit::Kilometers));
+        assert_eq!("°F".parse::<TempUnit>(), Ok(TempUnit::F));
+        assert_eq!(
+            "furlong".parse::<LengthUnit>().unwrap().to_string(),
+            "furlongs"
+        );
+        assert!("parsec".parse::<LengthUnit>().is_err());
+        assert_eq!(
+            TempUnit::accepted(),
+            vec![
+                "c",
+                "f",
+                "k",
+                "°C",
+                "°F",
+                "degree Celsius",
+                "degrees Celsius",
+                "degree Fahrenheit",
+                "degrees Fahrenheit",
+                "kelvin",
+                "kelvins"
+            ]
+        );
+    }
+}
@@ -0,0 +1,547 @@
+//! Subcommands for computing and networking figures: byte and time
+//! conversions, IOPS, latency, CPU cycles, availability nines and request
+//! rates.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::{self, ByteRate, ByteSize, Distance, Duration, Frequency, RequestRate};
+
+use super::output::{
+    bytes_to_human_readable, fixed, human_bytes, money_amount, seconds_to_human_readable, Figure,
+};
+use crate::{check_positive, fail, finite_result};
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert byte values (e.g., bytes to MB or human readable).
+    Bytes {
+        /// Number of bytes to convert.
+        num: u64,
+        /// Convert bytes to megabytes.
+        #[structopt(short, long)]
+        megabytes: bool,
+        /// Convert bytes to a human-readable string (e.g., "1.00 MB").
+        #[structopt(short = "H", long = "human-readable", visible_alias = "human")]
+        human_readable: bool,
+        /// Fail instead of rounding when the result is not a whole number.
+        #[structopt(long)]
+        strict_int: bool,
+    },
+    /// Convert time (seconds) to a human-readable format.
+    Time {
+        /// Seconds to convert.
+        seconds: u64,
+        /// Convert to human-readable format (e.g., "1h 13m 5s")
+        #[structopt(short = "H", long = "human-readable", visible_alias = "human")]
+        human_readable: bool,
+        /// Fail instead of rounding when the result is not a whole number,
+        /// which whole seconds always break down into.
+        #[structopt(long)]
+        strict_int: bool,
+    },
+    /// Solve for throughput, IOPS or block size given the other two.
+    Iops {
+        /// Throughput, e.g. 500MB/s or 4Gbps.
+        #[structopt(long)]
+        throughput: Option<ByteRate>,
+        /// Block (I/O) size, e.g. 4KiB.
+        #[structopt(long)]
+        block: Option<ByteSize>,
+        /// I/O operations per second.
+        #[structopt(long, parse(try_from_str = quantity::parse_number))]
+        iops: Option<f64>,
+    },
+    /// Convert a latency into the distance light travels, or a distance into latency.
+    Latency {
+        /// A time such as 20ms for the km-in-* targets, or a distance such as
+        /// 4000km for the ms-in-* targets.
+        value: String,
+        /// What to convert to, and through which medium.
+        #[structopt(short = "t", long, possible_values = &LatencyTarget::variants(), case_insensitive = true)]
+        to: LatencyTarget,
+        /// Treat the time as a round trip, so the distance is covered twice.
+        #[structopt(long)]
+        rtt: bool,
+        /// Propagation speed as a fraction of the speed of light (default: 1 in
+        /// vacuum, 2/3 in fiber).
+        #[structopt(long = "velocity-factor", parse(try_from_str = quantity::parse_number))]
+        velocity_factor: Option<f64>,
+    },
+    /// Convert clock cycles to time at a given frequency, or time to cycles.
+    Cycles {
+        /// A cycle count such as 2500, or a time such as 780ns with `--to cycles`.
+        value: String,
+        /// Clock frequency, e.g. 3.2GHz.
+        #[structopt(long)]
+        freq: Frequency,
+        /// Time unit to convert to, or cycles.
+        #[structopt(short = "t", long, possible_values = &CycleTarget::variants(), case_insensitive = true)]
+        to: CycleTarget,
+    },
+    /// Convert an availability percentage into allowed downtime.
+    Nines {
+        /// Availability in percent, e.g. 99.95.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        availability: f64,
+        /// Period the downtime budget is measured over.
+        #[structopt(short = "t", long, possible_values = &DowntimePeriod::variants(), case_insensitive = true)]
+        to: DowntimePeriod,
+    },
+    /// Convert request rates between per-second, per-minute, per-day and per-month.
+    Rps {
+        /// Request rate, e.g. 250, 250/s or "1M requests/day". A bare number is per second.
+        rate: RequestRate,
+        /// Period to express the rate per.
+        #[structopt(short = "t", long, possible_values = &RpsTarget::variants(), case_insensitive = true)]
+        to: RpsTarget,
+    },
+    /// Solve Little's law (L = λW) for requests in flight, arrival rate or latency.
+    LittlesLaw {
+        /// Arrival rate, e.g. 120/s or "3k req/min".
+        #[structopt(long)]
+        arrival: Option<RequestRate>,
+        /// Time each request spends in the system, e.g. 250ms.
+        #[structopt(long)]
+        latency: Option<Duration>,
+        /// Average number of requests in the system at once.
+        #[structopt(long, parse(try_from_str = quantity::parse_number))]
+        in_flight: Option<f64>,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &LittlesLawTarget::variants(), case_insensitive = true)]
+        to: LittlesLawTarget,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Bytes {
+                num,
+                megabytes,
+                human_readable,
+                strict_int,
+            } => {
+                let (value, unit) = match (megabytes, human_readable) {
+                    (true, _) => (bytes_to_mb(num), "MB"),
+                    (false, true) => human_bytes(num),
+                    (false, false) => (0.0, ""),
+                };
+                if strict_int {
+                    quantity::check_whole(value, unit).unwrap_or_else(|error| fail(error));
+                }
+                let bytes = Figure::number(num as f64, 0, "bytes");
+                if strict_int && (megabytes || human_readable) {
+                    let result = Figure::number(value, 0, unit);
+                    say!(bytes => result, "{} bytes = {} {}", num, value, unit);
+                } else if megabytes {
+                    let mb = Figure::number(bytes_to_mb(num), 2, "MB");
+                    say!(bytes => mb, "{} bytes = {} MB", num, fixed(bytes_to_mb(num), 2));
+                } else if human_readable {
+                    let result = Figure::number(value, 2, unit);
+                    say!(bytes => result, "{} bytes = {}", num, bytes_to_human_readable(num));
+                } else {
+                    println!("Please specify --megabytes or --human-readable. See --help.");
+                }
+            }
+            Command::Time {
+                seconds,
+                human_readable,
+                strict_int,
+            } => {
+                // Whole seconds break down into whole days, hours and minutes, so
+                // only the seconds given need to be whole.
+                if strict_int {
+                    quantity::check_whole(seconds as f64, "s").unwrap_or_else(|error| fail(error));
+                }
+                if human_readable {
+                    let shown = seconds_to_human_readable(seconds);
+                    let value = Figure::number(seconds as f64, 0, "s");
+                    say!(value => Figure::text(&shown, "duration"), "{} seconds = {}", seconds, shown);
+                } else {
+                    println!("Please specify --human-readable. See --help.");
+                }
+            }
+            Command::Iops {
+                throughput,
+                block,
+                iops,
+            } => {
+                let given = [
+                    throughput.as_ref().map(|t| t.bytes_per_second),
+                    block.as_ref().map(|b| b.bytes),
+                    iops,
+                ];
+                check_positive(
+                    given.iter().flatten(),
+                    "--throughput, --block and --iops must be greater than zero",
+                );
+                let Some((t, b, i)) = solve_iops(given[0], given[1], given[2]) else {
+                    fail(ConvertError::Parse(
+                        "pass exactly two of --throughput, --block and --iops".to_string(),
+                    ));
+                };
+                match (throughput, block) {
+                    (Some(throughput), Some(block)) => say!(
+                        Figure::number(t, 2, "B/s") => Figure::number(i, 2, "IOPS"),
+                        "{} at {} per I/O = {} IOPS",
+                        throughput,
+                        block,
+                        fixed(i, 2)
+                    ),
+                    (None, Some(block)) => {
+                        let (rate, unit) = human_bytes(t.round() as u64);
+                        say!(
+                            Figure::number(i, 2, "IOPS") => Figure::number(rate, 2, &format!("{}/s", unit)),
+                            "{} IOPS at {} per I/O = {}/s",
+                            i,
+                            block,
+                            bytes_to_human_readable(t.round() as u64)
+                        )
+                    }
+                    (Some(throughput), None) => {
+                        let (size, unit) = human_bytes(b.round() as u64);
+                        say!(
+                            Figure::number(t, 2, "B/s") => Figure::number(size, 2, unit),
+                            "{} at {} IOPS = {} per I/O",
+                            throughput,
+                            i,
+                            bytes_to_human_readable(b.round() as u64)
+                        )
+                    }
+                    (None, None) => unreachable!("two of the three values are given"),
+                }
+            }
+            Command::Latency {
+                value,
+                to,
+                rtt,
+                velocity_factor,
+            } => {
+                use LatencyTarget::*;
+                let (medium, default_factor) = match to {
+                    KmInFiber | MsInFiber => ("fiber", FIBER_VELOCITY_FACTOR),
+                    KmInVacuum | MsInVacuum => ("vacuum", 1.0),
+                };
+                let factor = velocity_factor.unwrap_or(default_factor);
+                if !(factor > 0.0 && factor <= 1.0) {
+                    fail(ConvertError::OutOfRange {
+                        value: factor,
+                        reason: "--velocity-factor must be greater than 0 and at most 1"
+                            .to_string(),
+                    });
+                }
+                let trips = if rtt { 2.0 } else { 1.0 };
+                let round_trip = if rtt { " round trip" } else { "" };
+                let parsed = match to {
+                    KmInFiber | KmInVacuum => value.parse::<Duration>().map(|time| {
+                        let km = finite_result(
+                            time.seconds,
+                            light_distance_km(time.seconds, factor) / trips,
+                        );
+                        let line =
+                            format!("{}{} = {} km in {}", time, round_trip, fixed(km, 1), medium);
+                        let value = Figure::number(time.seconds, 3, "s");
+                        (value, Figure::number(km, 1, "km"), line)
+                    }),
+                    MsInFiber | MsInVacuum => value.parse::<Distance>().map(|distance| {
+                        let ms = finite_result(
+                            distance.km,
+                            distance.km * trips / light_distance_km(1e-3, factor),
+                        );
+                        let line = format!(
+                            "{} = {} ms{} in {}",
+                            distance,
+                            fixed(ms, 3),
+                            round_trip,
+                            medium
+                        );
+                        let value = Figure::number(distance.km, 1, "km");
+                        (value, Figure::number(ms, 3, "ms"), line)
+                    }),
+                };
+                match parsed {
+                    Ok((value, result, line)) => say!(value => result, "{}", line),
+                    Err(message) => fail(ConvertError::Parse(message)),
+                }
+            }
+            Command::Cycles { value, freq, to } => {
+                check_positive([&freq.hertz], "--freq must be greater than zero");
+                let scale = match to {
+                    CycleTarget::Cycles => None,
+                    CycleTarget::Ns => Some(1e-9),
+                    CycleTarget::Us => Some(1e-6),
+                    CycleTarget::Ms => Some(1e-3),
+                    CycleTarget::S => Some(1.0),
+                };
+                let parsed = match scale {
+                    None => value.parse::<Duration>().map(|time| {
+                        let cycles = time.seconds * freq.hertz;
+                        let line = format!("{} at {} = {} cycles", time, freq, fixed(cycles, 0));
+                        let value = Figure::number(time.seconds, 3, "s");
+                        (value, Figure::number(cycles, 0, "cycles"), line)
+                    }),
+                    Some(scale) => quantity::parse_number(&value)
+                        .map_err(|_| format!("'{}' is not a number of cycles", value))
+                        .map(|cycles| {
+                            let time = cycles / freq.hertz / scale;
+                            let line = format!(
+                                "{} cycles at {} = {} {}",
+                                cycles,
+                                freq,
+                                fixed(time, 2),
+                                to
+                            );
+                            let value = Figure::number(cycles, 0, "cycles");
+                            (value, Figure::number(time, 2, &to.to_string()), line)
+                        }),
+                };
+                match parsed {
+                    Ok((value, result, line)) => say!(value => result, "{}", line),
+                    Err(message) => fail(ConvertError::Parse(message)),
+                }
+            }
+            Command::Nines { availability, to } => {
+                if !(0.0..=100.0).contains(&availability) {
+                    fail(ConvertError::OutOfRange {
+                        value: availability,
+                        reason: "availability must be a percentage between 0 and 100".to_string(),
+                    });
+                }
+                let downtime = allowed_downtime(availability, &to).round() as u64;
+                let period = to.to_string();
+                say!(
+                    Figure::number(availability, 2, "%") => Figure::number(downtime as f64, 0, "s"),
+                    "{}% availability = {} {}",
+                    availability,
+                    seconds_to_human_readable(downtime),
+                    period.replace('-', " ")
+                );
+            }
+            Command::Rps { rate, to } => {
+                let (seconds, period) = match to {
+                    RpsTarget::Rps => (1.0, "s"),
+                    RpsTarget::PerMinute => (60.0, "min"),
+                    RpsTarget::PerHour => (3600.0, "hour"),
+                    RpsTarget::PerDay => (86_400.0, "day"),
+                    // An average Gregorian month, matching `/month` in the input.
+                    RpsTarget::PerMonth => (2_629_746.0, "month"),
+                };
+                let requests = rate.per_second * seconds;
+                say!(
+                    Figure::number(rate.per_second, 2, "requests/s")
+                        => Figure::money(requests, &format!("requests/{}", period)),
+                    "{} = {} requests/{}",
+                    rate,
+                    money_amount(rate.per_second * seconds),
+                    period
+                );
+            }
+            Command::LittlesLaw {
+                arrival,
+                latency,
+                in_flight,
+                to,
+            } => {
+                let given = [
+                    arrival.as_ref().map(|a| a.per_second),
+                    latency.as_ref().map(|l| l.seconds),
+                    in_flight,
+                ];
+                check_positive(
+                    given.iter().flatten(),
+                    "--arrival, --latency and --in-flight must be greater than zero",
+                );
+                match (to, arrival, latency, in_flight) {
+                    (LittlesLawTarget::InFlight, Some(arrival), Some(latency), _) => {
+                        let in_flight = arrival.per_second * latency.seconds;
+                        say!(
+                            Figure::number(arrival.per_second, 2, "requests/s")
+                                => Figure::number(in_flight, 2, "requests in flight"),
+                            "{} × {} = {} requests in flight",
+                            arrival,
+                            latency,
+                            fixed(in_flight, 2)
+                        )
+                    }
+                    (LittlesLawTarget::Arrival, _, Some(latency), Some(in_flight)) => {
+                        let arrival = in_flight / latency.seconds;
+                        say!(
+                            Figure::number(in_flight, 2, "requests in flight")
+                                => Figure::number(arrival, 2, "requests/s"),
+                            "{} in flight / {} = {} requests/s",
+                            in_flight,
+                            latency,
+                            fixed(arrival, 2)
+                        )
+                    }
+                    (LittlesLawTarget::Latency, Some(arrival), _, Some(in_flight)) => {
+                        let ms = in_flight / arrival.per_second * 1000.0;
+                        say!(
+                            Figure::number(in_flight, 2, "requests in flight")
+                                => Figure::number(ms, 2, "ms"),
+                            "{} in flight / {} = {} ms latency",
+                            in_flight,
+                            arrival,
+                            fixed(ms, 2)
+                        )
+                    }
+                    (to, ..) => {
+                        let needs = match to {
+                            LittlesLawTarget::InFlight => "--arrival and --latency",
+                            LittlesLawTarget::Arrival => "--latency and --in-flight",
+                            LittlesLawTarget::Latency => "--arrival and --in-flight",
+                        };
+                        fail(ConvertError::Parse(format!("--to {} needs {}", to, needs)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Constant: Speed of light in vacuum, in kilometers per second.
+const LIGHT_KM_PER_S: f64 = 299_792.458;
+
+/// Constant: Typical speed of light in optical fiber as a fraction of `c`.
+const FIBER_VELOCITY_FACTOR: f64 = 2.0 / 3.0;
+
+// Supported targets of the `latency` subcommand.
+enum_with_variants!(pub LatencyTarget {
+    KmInFiber => "km-in-fiber",
+    KmInVacuum => "km-in-vacuum",
+    MsInFiber => "ms-in-fiber",
+    MsInVacuum => "ms-in-vacuum",
+});
+
+// Supported targets of the `cycles` subcommand.
+enum_with_variants!(pub CycleTarget {
+    Cycles => "cycles",
+    Ns => "ns",
+    Us => "us",
+    Ms => "ms",
+    S => "s",
+});
+
+// Supported targets of the `rps` subcommand.
+enum_with_variants!(pub RpsTarget {
+    Rps => "rps",
+    PerMinute => "per-minute",
+    PerHour => "per-hour",
+    PerDay => "per-day",
+    PerMonth => "per-month",
+});
+
+// Supported targets of the `littles-law` subcommand.
+enum_with_variants!(pub LittlesLawTarget {
+    InFlight => "in-flight",
+    Arrival => "arrival",
+    Latency => "latency",
+});
+
+// Supported periods of the `nines` subcommand.
+enum_with_variants!(pub DowntimePeriod {
+    Year => "downtime-per-year",
+    Month => "downtime-per-month",
+    Week => "downtime-per-week",
+    Day => "downtime-per-day",
+});
+
+/// Convert bytes to megabytes.
+///
+/// # Example
+/// ```
+/// assert_eq!(bytes_to_mb(1048576), 1.0);
+/// ```
+fn bytes_to_mb(num_bytes: u64) -> f64 {
+    num_bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// Completes a `(throughput, block size, IOPS)` triple from any two of them,
+/// with throughput in bytes per second and block size in bytes.
+///
+/// Returns `None` unless exactly two values are given.
+///
+/// # Example
+/// ```
+/// assert_eq!(solve_iops(Some(4096.0e3), Some(4096.0), None), Some((4096.0e3, 4096.0, 1000.0)));
+/// ```
+fn solve_iops(
+    throughput: Option<f64>,
+    block: Option<f64>,
+    iops: Option<f64>,
+) -> Option<(f64, f64, f64)> {
+    match (throughput, block, iops) {
+        (Some(t), Some(b), None) => Some((t, b, t / b)),
+        (None, Some(b), Some(i)) => Some((i * b, b, i)),
+        (Some(t), None, Some(i)) => Some((t, t / i, i)),
+        _ => None,
+    }
+}
+
+/// Returns the distance in kilometers that light covers in `seconds` at
+/// `velocity_factor` times the speed of light.
+///
+/// # Example
+/// ```
+/// assert!((light_distance_km(0.001, 1.0) - 299.792458).abs() < 1e-9);
+/// ```
+fn light_distance_km(seconds: f64, velocity_factor: f64) -> f64 {
+    seconds * LIGHT_KM_PER_S * velocity_factor
+}
+
+/// Returns the allowed downtime in seconds for `availability` percent over `period`.
+///
+/// A year is 365.25 days and a month is a twelfth of that.
+///
+/// # Example
+/// ```
+/// assert_eq!(allowed_downtime(99.9, &DowntimePeriod::Day), 86.4);
+/// ```
+fn allowed_downtime(availability: f64, period: &DowntimePeriod) -> f64 {
+    const YEAR: f64 = 365.25 * 86_400.0;
+    let seconds = match period {
+        DowntimePeriod::Year => YEAR,
+        DowntimePeriod::Month => YEAR / 12.0,
+        DowntimePeriod::Week => 7.0 * 86_400.0,
+        DowntimePeriod::Day => 86_400.0,
+    };
+    seconds * (100.0 - availability) / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_to_mb() {
+        assert_eq!(bytes_to_mb(1048576), 1.0);
+        assert!((bytes_to_mb(2097152) - 2.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_solve_iops() {
+        let (t, b, i) = (4096.0e3, 4096.0, 1000.0);
+        assert_eq!(solve_iops(Some(t), Some(b), None), Some((t, b, i)));
+        assert_eq!(solve_iops(None, Some(b), Some(i)), Some((t, b, i)));
+        assert_eq!(solve_iops(Some(t), None, Some(i)), Some((t, b, i)));
+        assert_eq!(solve_iops(Some(t), Some(b), Some(i)), None);
+        assert_eq!(solve_iops(Some(t), None, None), None);
+    }
+
+    #[test]
+    fn test_light_distance_km() {
+        assert!((light_distance_km(0.001, 1.0) - 299.792458).abs() < 1e-9);
+        assert!((light_distance_km(0.02, FIBER_VELOCITY_FACTOR) - 3997.2328).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_allowed_downtime() {
+        use DowntimePeriod::*;
+        assert!((allowed_downtime(99.9, &Day) - 86.4).abs() < 1e-9);
+        assert!((allowed_downtime(99.95, &Year) - 15778.8).abs() < 1e-6);
+        assert!((allowed_downtime(99.99, &Month) - 262.98).abs() < 1e-6);
+        assert_eq!(allowed_downtime(100.0, &Week), 0.0);
+    }
+}
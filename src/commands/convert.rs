@@ -0,0 +1,590 @@
+//! Subcommands that convert between any units, finding the category from the
+//! units themselves: `convert`, `batch`, `eval`, `lookup`, `units`, `quiz`
+//! and `change`.
+
+use structopt::StructOpt;
+
+use convertx::conversion::{Amount, ConversionResult, Measurement};
+use convertx::error::ConvertError;
+use convertx::registry::{UnitDef, UnitRegistry};
+use convertx::{color, compound, expr, json, pack, quantity, rng, sql};
+
+use super::category::{category_precision, unit_registry, CATEGORIES};
+use super::output::{
+    conversion_json, conversion_sql, conversion_table, fixed, output_format, print_citations,
+    render_conversion, spoken_conversion, table_row, EmitOpts, Figure, Format, NumberFormat,
+    OutputMode, FORMAT,
+};
+use super::resolve::{find_unit, registered, resolve_conversion, resolve_unit, warn_ambiguous};
+use crate::fail;
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert between any two units, detecting the category from the units,
+    /// e.g. `convertx convert 5 km mi`.
+    #[structopt(setting = structopt::clap::AppSettings::AllowNegativeNumbers)]
+    Convert {
+        /// Value to convert; accepts the same forms as the category subcommands,
+        /// including a unit attached to it, e.g. `5km`.
+        value: Measurement,
+        /// Units to convert from and to, by name or symbol, e.g. "km mi"; just the
+        /// unit to convert to if the value has its unit attached.
+        #[structopt(max_values = 2)]
+        units: Vec<String>,
+        /// Unit to convert to, instead of the last of the units.
+        #[structopt(short = "t", long)]
+        to: Option<String>,
+        /// Category to convert in when a unit could belong to several.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Enable an optional unit pack compiled into this build (repeatable).
+        #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+        units_pack: Vec<&'static str>,
+        /// Show unit symbols (e.g. "km") instead of spelled-out names.
+        #[structopt(long)]
+        symbols: bool,
+        /// Output mode: text, sql, json, raw, script-filter, markdown, html or
+        /// spoken, as for the category subcommands.
+        #[structopt(long, conflicts_with = "format", possible_values = &OutputMode::variants(), case_insensitive = true)]
+        output: Option<OutputMode>,
+        /// Print only the converted number (or `--raw`), as `--output raw` does.
+        #[structopt(short, long, alias = "raw", conflicts_with_all = &["format", "output"])]
+        quiet: bool,
+        /// Spell the conversion out for screen readers and speech, as
+        /// `--output spoken` does.
+        #[structopt(long, conflicts_with_all = &["format", "output", "quiet"])]
+        spoken: bool,
+        /// Follow the conversion with where the definition of each unit is
+        /// published, as for the category subcommands.
+        #[structopt(long, conflicts_with_all = &["format", "output", "quiet", "spoken"])]
+        cite: bool,
+        /// Table the `--output sql` statements insert into.
+        #[structopt(long, default_value = "conversions")]
+        table: sql::Table,
+        #[structopt(flatten)]
+        emit: EmitOpts,
+        #[structopt(flatten)]
+        number: NumberFormat,
+    },
+    /// Run the conversions listed in a file, one per line, e.g.
+    /// `length 5 km ft` or `temperature 100 f c`.
+    Batch {
+        /// File of conversions, or `-` for stdin. Each line has an optional
+        /// category, a value and the units to convert from and to, as for
+        /// `convert`; blank lines and lines starting with `#` are skipped.
+        #[structopt(short, long, parse(from_os_str))]
+        file: std::path::PathBuf,
+        /// Enable an optional unit pack compiled into this build (repeatable).
+        #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+        units_pack: Vec<&'static str>,
+        /// Show unit symbols (e.g. "km") instead of spelled-out names.
+        #[structopt(long)]
+        symbols: bool,
+        #[structopt(flatten)]
+        number: NumberFormat,
+    },
+    /// Evaluate arithmetic on quantities, e.g.
+    /// `convertx eval "(3 km + 250 m) / 2 in feet"`.
+    Eval {
+        /// Expression with numbers, units, + - * / and parentheses, optionally
+        /// ending in `in <unit>` or `to <unit>`; several arguments are joined.
+        #[structopt(required = true)]
+        expression: Vec<String>,
+        /// Category to read a unit in when it could belong to several.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Enable an optional unit pack compiled into this build (repeatable).
+        #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+        units_pack: Vec<&'static str>,
+        /// Show the unit symbol (e.g. "km") instead of its spelled-out name.
+        #[structopt(long)]
+        symbols: bool,
+        #[structopt(flatten)]
+        number: NumberFormat,
+    },
+    /// Look up which category and unit a name or symbol refers to.
+    Lookup {
+        /// Unit name or symbol, e.g. "km".
+        unit: String,
+        /// Restrict the lookup to one category when a name is ambiguous.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+    },
+    /// List known units, optionally only those matching a search.
+    Units {
+        /// Only list units whose name, symbol or category contains this text.
+        #[structopt(long)]
+        search: Option<String>,
+        /// Only list units of one category.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+    },
+    /// Print a worksheet of random unit conversion questions.
+    Quiz {
+        /// Only ask about units of this category (default: any category).
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Number of questions.
+        #[structopt(long, default_value = "10")]
+        count: usize,
+        /// Seed for the questions; the same seed prints the same worksheet.
+        #[structopt(long)]
+        seed: Option<u64>,
+        /// Fill in the answers.
+        #[structopt(long)]
+        answers: bool,
+    },
+    /// Compare two quantities of the same kind, e.g. 45MB and 62MB.
+    Change {
+        /// The original quantity, e.g. 45MB or 1.2s.
+        from: String,
+        /// The new quantity, in any unit of the same kind.
+        to: String,
+        /// How to express the relative change.
+        #[structopt(short = "t", long = "to", possible_values = &ChangeTarget::variants(), case_insensitive = true)]
+        target: ChangeTarget,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Convert {
+                value,
+                units,
+                to,
+                category,
+                units_pack,
+                symbols,
+                output,
+                quiet,
+                spoken,
+                cite,
+                table,
+                emit,
+                number,
+            } => {
+                let mut units = value.unit.into_iter().chain(units).chain(to);
+                let (Some(from), Some(to), None) = (units.next(), units.next(), units.next())
+                else {
+                    fail(ConvertError::Parse("pass one unit to convert from and one to convert to, e.g. `convert 5 km mi` or `convert 5km --to mi`".to_string()));
+                };
+                let value = value.amount;
+                let resolved = resolve_conversion(&from, &to, category.as_deref(), &units_pack)
+                    .unwrap_or_else(|error| fail(error));
+                let places = |amount: &Amount| category_places(&resolved.category, amount);
+                let show_number = |amount: &Amount| number.amount(amount, places(amount));
+                let conversion = resolved
+                    .convert(&value, show_number)
+                    .unwrap_or_else(|error| fail(error));
+                warn_ambiguous(&format!("{} {}", from, to));
+                let (precision, _) = category_precision(&conversion.category);
+                let show = |amount: &Amount, name: &str, symbol: &str| {
+                    let unit = if symbols { symbol } else { name };
+                    format!("{} {}", show_number(amount), unit)
+                };
+                let result = show(&conversion.result, &conversion.to, &conversion.to_symbol);
+                let (json, raw) = (
+                    conversion_json(&conversion),
+                    show_number(&conversion.result),
+                );
+                let text = conversion_text(&value, &conversion, &number, symbols);
+                let line = match output_format(&output, quiet, spoken) {
+                    (Some(template), _) => {
+                        render_conversion(&conversion, template, &number, precision)
+                    }
+                    (None, Some(OutputMode::Sql)) => conversion_sql(&conversion, &table),
+                    (None, Some(OutputMode::Json)) => conversion_json(&conversion),
+                    (None, Some(OutputMode::Raw)) => show_number(&conversion.result),
+                    (None, Some(OutputMode::Spoken)) => spoken_conversion(&conversion, |amount| {
+                        number.spoken(amount, places(amount))
+                    }),
+                    (None, Some(OutputMode::ScriptFilter)) => {
+                        json::script_filter(&[(result, text, show_number(&conversion.result))])
+                    }
+                    (None, Some(mode @ (OutputMode::Markdown | OutputMode::Html))) => {
+                        let (value, result) =
+                            (show_number(&value), show_number(&conversion.result));
+                        let row = table_row(conversion, value, result, symbols);
+                        conversion_table(vec![row], mode).trim_end().to_string()
+                    }
+                    (None, _) => color::highlight(&text).into_owned(),
+                };
+                let print = || {
+                    println!("{}", line);
+                    if cite {
+                        print_citations(
+                            [&resolved.from, &resolved.to]
+                                .map(|unit| (unit.plural.clone(), unit.source)),
+                        );
+                    }
+                };
+                emit.emit(print, &json, &raw);
+            }
+            Command::Batch {
+                file,
+                units_pack,
+                symbols,
+                number,
+            } => {
+                use std::io::BufRead;
+                let reader: Box<dyn BufRead> = if file.as_os_str() == "-" {
+                    Box::new(std::io::stdin().lock())
+                } else {
+                    match std::fs::File::open(&file) {
+                        Ok(opened) => Box::new(std::io::BufReader::new(opened)),
+                        Err(e) => fail(ConvertError::Io(format!(
+                            "cannot read {}: {}",
+                            file.display(),
+                            e
+                        ))),
+                    }
+                };
+                // Lines that fail are reported and skipped; the exit code is that
+                // of the first failure.
+                let mut failed = None;
+                let mut converted = String::new();
+                for (i, line) in reader.lines().enumerate() {
+                    let line = line.unwrap_or_else(|e| {
+                        fail(ConvertError::Io(format!(
+                            "cannot read {}: {}",
+                            file.display(),
+                            e
+                        )))
+                    });
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    match batch_job(line, &units_pack, &number) {
+                        Ok((value, conversion)) => {
+                            let shown = match FORMAT.get() {
+                                None | Some(Format::Mode(OutputMode::Text)) => {
+                                    let text = conversion_text(&value, &conversion, &number, symbols);
+                                    color::highlight(&text).into_owned()
+                                }
+                                Some(Format::Mode(OutputMode::Json)) => conversion_json(&conversion),
+                                Some(Format::Mode(OutputMode::Raw)) => {
+                                    category_amount(&conversion.category, &conversion.result, &number)
+                                }
+                                Some(Format::Template(template)) => {
+                                    let (precision, _) = category_precision(&conversion.category);
+                                    render_conversion(&conversion, template, &number, precision)
+                                }
+                                Some(Format::Mode(mode)) => fail(ConvertError::Parse(format!(
+                                    "--format {} lays out a table or a single conversion; batch prints text, json, raw or a template",
+                                    mode
+                                ))),
+                            };
+                            println!("{}", shown);
+                            converted.push_str(line);
+                            converted.push('\n');
+                        }
+                        Err(error) => {
+                            eprintln!("error: line {}: {}", i + 1, error);
+                            failed.get_or_insert(error.exit_code());
+                        }
+                    }
+                }
+                // Once for the whole file rather than on every line.
+                warn_ambiguous(&converted);
+                if let Some(code) = failed {
+                    std::process::exit(code);
+                }
+            }
+            Command::Eval {
+                expression,
+                category,
+                units_pack,
+                symbols,
+                number,
+            } => {
+                let expression = expression.join(" ");
+                let value = evaluate(&expression, category.as_deref(), &units_pack)
+                    .unwrap_or_else(|error| fail(error));
+                warn_ambiguous(&expression);
+                let shown = show_value(&value, &number, symbols);
+                let unit = value.unit.map_or("", |unit| unit.symbol.as_str());
+                let (places, _) =
+                    category_precision(value.unit.map_or("", |unit| unit.dimension.as_str()));
+                let result = Figure::number(value.number, places, unit);
+                match FORMAT.get() {
+                    Some(_) => say!(result, "{}", shown),
+                    None => println!("{}", color::paint(&shown)),
+                }
+            }
+            Command::Lookup { unit, category } => {
+                match resolve_unit(&unit, category.as_deref(), find_unit(&unit)) {
+                    Ok(found) => {
+                        say!(
+                            Figure::text(&unit, "unit") => Figure::text(&found.name, found.category),
+                            "{} = {} ({}, symbol {})",
+                            unit,
+                            found.name,
+                            found.category,
+                            found.symbol
+                        );
+                        warn_ambiguous(&unit);
+                    }
+                    Err(message) => fail(ConvertError::Parse(message)),
+                }
+            }
+            Command::Units { search, category } => {
+                let registry = unit_registry();
+                // Units that only convert within compound units, e.g. joules.
+                let mut compound = UnitRegistry::default();
+                for unit in compound::unit_defs() {
+                    if registry.lookup(&unit.name).is_empty() {
+                        compound.add(unit);
+                    }
+                }
+                let units: Vec<&UnitDef> = match &search {
+                    Some(query) => registry
+                        .search(query)
+                        .into_iter()
+                        .chain(compound.search(query))
+                        .collect(),
+                    None => registry.units().iter().chain(compound.units()).collect(),
+                };
+                for unit in units.into_iter().filter(|unit| {
+                    category
+                        .as_ref()
+                        .is_none_or(|c| unit.dimension.eq_ignore_ascii_case(c))
+                }) {
+                    let pack = unit
+                        .pack
+                        .map(|pack| format!(" [{}]", pack))
+                        .unwrap_or_default();
+                    println!(
+                        "{:<24} {:<8} {}{}",
+                        unit.name, unit.symbol, unit.dimension, pack
+                    );
+                }
+            }
+            Command::Quiz {
+                category,
+                count,
+                seed,
+                answers,
+            } => {
+                let mut rng = seed.map_or_else(rng::Rng::from_clock, rng::Rng::seeded);
+                println!("Unit conversion quiz (seed {})", rng.seed());
+                for number in 1..=count {
+                    let question = quiz_question(&mut rng, category.as_deref(), answers);
+                    println!("{:>3}. {}", number, question);
+                }
+            }
+            Command::Change { from, to, target } => {
+                let change =
+                    quantity::change(&from, &to).unwrap_or_else(|e| fail(ConvertError::Parse(e)));
+                if !change.factor.is_finite() {
+                    let what = match target {
+                        ChangeTarget::Percent => "percent change",
+                        ChangeTarget::Factor => "the factor of change",
+                    };
+                    fail(ConvertError::OutOfRange {
+                        value: 0.0,
+                        reason: format!("{} from zero is undefined", what),
+                    });
+                }
+                let (result, relative) = match target {
+                    ChangeTarget::Percent => {
+                        let percent = (change.factor - 1.0) * 100.0;
+                        let shown = format!("{:+.2}", percent);
+                        (
+                            Figure::number(percent, 2, "%").shown(&shown),
+                            format!("{}%", shown),
+                        )
+                    }
+                    ChangeTarget::Factor => (
+                        Figure::number(change.factor, 3, "x"),
+                        format!("{}x", fixed(change.factor, 3)),
+                    ),
+                };
+                let delta = format!("{:+.2}", change.delta);
+                let delta = Figure::number(change.delta, 2, change.unit).shown(delta);
+                say!(
+                    delta => result,
+                    "{} -> {} = {:+.2}{} ({})",
+                    from,
+                    to,
+                    change.delta,
+                    change.unit,
+                    relative
+                );
+            }
+        }
+    }
+}
+
+// Supported targets of the `change` subcommand.
+enum_with_variants!(pub ChangeTarget {
+    Percent => "percent",
+    Factor => "factor",
+});
+
+/// Evaluates an `eval` expression, reading units that could belong to several
+/// categories in `category`, and those of unit packs only if enabled.
+pub fn evaluate(
+    expression: &str,
+    category: Option<&str>,
+    units_pack: &[&'static str],
+) -> Result<expr::Value<'static>, ConvertError> {
+    let expression: expr::Expression = expression.parse().map_err(ConvertError::from)?;
+    let evaluator = expr::Evaluator {
+        registry: unit_registry(),
+        resolve: |token: &str| -> Result<&'static UnitDef, ConvertError> {
+            // --category only settles units that are ambiguous on their own.
+            let matches = find_unit(token);
+            let category = category.filter(|_| matches.len() > 1);
+            let unit = registered(&resolve_unit(token, category, matches)?);
+            match unit.pack.filter(|pack| !units_pack.contains(pack)) {
+                Some(pack) => Err(ConvertError::Parse(format!(
+                    "'{}' is part of the {} unit pack, enable it with --units-pack {}",
+                    unit.name, pack, pack
+                ))),
+                None => Ok(unit),
+            }
+        },
+    };
+    evaluator.evaluate(&expression)
+}
+
+/// Shows the value of an `eval` expression, e.g. `3.107 miles`, with the
+/// precision of its category.
+pub fn show_value(value: &expr::Value, number: &NumberFormat, symbols: bool) -> String {
+    let dimension = value.unit.map_or("", |unit| unit.dimension.as_str());
+    let (precision, whole_numbers) = category_precision(dimension);
+    let places = match whole_numbers && value.number.fract() == 0.0 {
+        true => 0,
+        false => precision,
+    };
+    let shown = number.amount(&Amount::Exact(value.number), places);
+    match value.unit {
+        None => shown,
+        Some(unit) if symbols => format!("{} {}", shown, unit.symbol),
+        Some(unit) if Amount::Exact(value.number).is_singular(&shown) => {
+            format!("{} {}", shown, unit.singular)
+        }
+        Some(unit) => format!("{} {}", shown, unit.plural),
+    }
+}
+
+/// Returns the decimal places to show `amount` of `category` with: none for
+/// whole numbers of a category counted in whole numbers, else its precision.
+fn category_places(category: &str, amount: &Amount) -> usize {
+    let (precision, whole_numbers) = category_precision(category);
+    let whole = amount.points().iter().all(|v| v.fract() == 0.0);
+    match whole_numbers && whole {
+        true => 0,
+        false => precision,
+    }
+}
+
+/// Formats `amount` of `category` without its unit as `convert` prints it.
+fn category_amount(category: &str, amount: &Amount, number: &NumberFormat) -> String {
+    number.amount(amount, category_places(category, amount))
+}
+
+/// Shows a conversion of `value` as `convert` prints it, e.g.
+/// `5 kilometers = 3.1069 miles`.
+fn conversion_text(
+    value: &Amount,
+    conversion: &ConversionResult,
+    number: &NumberFormat,
+    symbols: bool,
+) -> String {
+    let show = |amount: &Amount, name: &str, symbol: &str| {
+        let shown = category_amount(&conversion.category, amount, number);
+        format!("{} {}", shown, if symbols { symbol } else { name })
+    };
+    format!(
+        "{} = {}",
+        show(value, &conversion.from, &conversion.from_symbol),
+        show(&conversion.result, &conversion.to, &conversion.to_symbol)
+    )
+}
+
+/// Runs one line of a `batch` file: an optional category, then a value and
+/// the units to convert from and to as `convert` reads them, e.g.
+/// `length 5 km ft`, `temperature 100 f c` or `5km ft`.
+///
+/// # Example
+/// ```
+/// let (value, conversion) = batch_job("length 5 km m", &[], &NumberFormat::default()).unwrap();
+/// assert_eq!(conversion.result, Amount::Exact(5000.0));
+/// ```
+fn batch_job(
+    line: &str,
+    units_pack: &[&str],
+    number: &NumberFormat,
+) -> Result<(Amount, ConversionResult), ConvertError> {
+    let mut words = line.split_whitespace().peekable();
+    let category = CATEGORIES.iter().copied().find(|name| {
+        words
+            .peek()
+            .is_some_and(|word| name.eq_ignore_ascii_case(word))
+    });
+    if category.is_some() {
+        words.next();
+    } else if let [first, second, ..] = line.split_whitespace().collect::<Vec<_>>()[..] {
+        // A word that is not a value, followed by one, was meant as a category.
+        if first.parse::<Measurement>().is_err() && second.parse::<Measurement>().is_ok() {
+            return Err(ConvertError::Parse(format!(
+                "unknown category '{}', expected one of {}",
+                first,
+                CATEGORIES.join(", ")
+            )));
+        }
+    }
+    let value: Measurement = words
+        .next()
+        .ok_or_else(|| ConvertError::Parse("missing the value to convert".to_string()))?
+        .parse()?;
+    let mut units = value.unit.into_iter().chain(words.map(str::to_string));
+    let (Some(from), Some(to), None) = (units.next(), units.next(), units.next()) else {
+        return Err(ConvertError::Parse(
+            "expected a value, a unit to convert from and one to convert to, e.g. `length 5 km ft`"
+                .to_string(),
+        ));
+    };
+    let resolved = resolve_conversion(&from, &to, category, units_pack)?;
+    let conversion = resolved.convert(&value.amount, |amount| {
+        category_amount(&resolved.category, amount, number)
+    })?;
+    Ok((value.amount, conversion))
+}
+
+/// Returns a random conversion between two built-in units, of `category` if
+/// given, as "37 feet = ? meters" or with the answer filled in.
+fn quiz_question(rng: &mut rng::Rng, category: Option<&str>, answer: bool) -> String {
+    let category =
+        category.unwrap_or_else(|| rng.choose(CATEGORIES).expect("there are categories"));
+    let units: Vec<&UnitDef> = unit_registry()
+        .units()
+        .iter()
+        .filter(|unit| unit.pack.is_none() && unit.dimension.eq_ignore_ascii_case(category))
+        .collect();
+    let from = *rng.choose(&units).expect("every category has units");
+    let others: Vec<&UnitDef> = units.into_iter().filter(|unit| unit != &from).collect();
+    let to = *rng.choose(&others).expect("every category has two units");
+    let value = rng.between(1, 100) as f64;
+    let name = |unit: &UnitDef, value: f64| {
+        if value == 1.0 {
+            unit.singular.clone()
+        } else {
+            unit.plural.clone()
+        }
+    };
+    let result = match answer {
+        true => {
+            let convert = unit_registry()
+                .converter(&from.name, &to.name, &from.dimension)
+                .expect("registered units convert within their category");
+            fixed(convert(value), 4)
+        }
+        false => "?".to_string(),
+    };
+    format!("{} {} = {} {}", value, name(from, value), result, to.plural)
+}
@@ -0,0 +1,478 @@
+//! Subcommands that convert values read in bulk, from stdin, CSV, JSON and
+//! Parquet files, and summarize or resume series of them.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::Dimension;
+#[cfg(feature = "arrow")]
+use convertx::sheet;
+use convertx::{checkpoint, csv, jsonpath, series, stats};
+
+use super::category::CATEGORIES;
+use super::output::{fixed, heading, Figure, PRECISION};
+use super::resolve::{pair_converter, resolve_pair, Converter};
+use crate::fail;
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Summarize values read from stdin, one per line, e.g. 343ms or 1.2s.
+    Stats {
+        /// Kind of values to read.
+        #[structopt(short = "c", long, possible_values = &StatsCategory::variants(), case_insensitive = true)]
+        category: StatsCategory,
+        /// Unit to report in, e.g. ms. Defaults to the category's base unit.
+        #[structopt(short = "t", long)]
+        to: Option<String>,
+        /// Also draw a histogram of the values.
+        #[structopt(long)]
+        histogram: bool,
+        /// Number of histogram buckets.
+        #[structopt(long, default_value = "10", parse(try_from_str = parse_count))]
+        buckets: usize,
+    },
+    /// Convert one column of a CSV measurement series for plotting.
+    Series {
+        /// CSV file to read.
+        #[structopt(parse(from_os_str))]
+        input: std::path::PathBuf,
+        /// Zero-based index of the x column, copied as is.
+        #[structopt(long, default_value = "0")]
+        x_col: usize,
+        /// Zero-based index of the y column to convert.
+        #[structopt(long, default_value = "1")]
+        y_col: usize,
+        /// Unit of the y values, e.g. bytes.
+        #[structopt(long)]
+        y_from: String,
+        /// Unit to convert the y values to, e.g. gib.
+        #[structopt(long)]
+        y_to: String,
+        /// File to write the two-column CSV to, instead of stdout.
+        #[structopt(short = "o", long, parse(from_os_str))]
+        output: Option<std::path::PathBuf>,
+        /// Worker threads for large inputs (default: one per CPU).
+        #[structopt(long)]
+        jobs: Option<usize>,
+        /// Memory-map the input instead of reading it into memory.
+        #[structopt(long)]
+        mmap: bool,
+        /// Record progress in this file, and resume from it if it exists.
+        #[structopt(long, parse(from_os_str), requires = "output")]
+        checkpoint: Option<std::path::PathBuf>,
+    },
+    /// Convert columns of a CSV file in place, keeping the other columns.
+    Csv {
+        /// CSV file to read, or `-` for stdin.
+        #[structopt(parse(from_os_str))]
+        input: std::path::PathBuf,
+        /// Column to convert, by zero-based index or header name (repeatable).
+        #[structopt(long, required = true, number_of_values = 1)]
+        column: Vec<csv::Column>,
+        /// Category to read the units in when they could belong to several.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Unit of the columns, e.g. lb.
+        #[structopt(short = "f", long)]
+        from: String,
+        /// Unit to convert the columns to, e.g. kg.
+        #[structopt(short = "t", long)]
+        to: String,
+        /// Field delimiter: a single character, or `tab`.
+        #[structopt(long, default_value = ",", parse(try_from_str = csv::parse_delimiter))]
+        delimiter: char,
+        /// File to write the converted CSV to, instead of stdout.
+        #[structopt(short = "o", long, parse(from_os_str))]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Convert the numbers at a path of JSON read from stdin, keeping the rest.
+    Json {
+        /// Where the numbers are, as in jq, e.g. .readings[].temp_f.
+        #[structopt(long)]
+        path: jsonpath::Path,
+        /// Category to read the units in when they could belong to several.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Unit of the numbers, e.g. f.
+        #[structopt(short = "f", long)]
+        from: String,
+        /// Unit to convert the numbers to, e.g. c.
+        #[structopt(short = "t", long)]
+        to: String,
+    },
+    /// Convert one column of a Parquet file, streaming it in batches.
+    #[cfg(feature = "arrow")]
+    Sheet {
+        /// Parquet file to read.
+        #[structopt(parse(from_os_str))]
+        input: std::path::PathBuf,
+        /// Name of the column to convert.
+        #[structopt(long)]
+        column: String,
+        /// Unit of the column, e.g. f.
+        #[structopt(short = "f", long)]
+        from: String,
+        /// Unit to convert the column to, e.g. c.
+        #[structopt(short = "t", long)]
+        to: String,
+        /// Parquet file to write.
+        #[structopt(short = "o", long, parse(from_os_str))]
+        output: std::path::PathBuf,
+        /// Rows read and converted at a time.
+        #[structopt(long, default_value = "65536")]
+        batch_size: usize,
+        /// Worker threads for large batches (default: one per CPU).
+        #[structopt(long)]
+        jobs: Option<usize>,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Stats {
+                category,
+                to,
+                histogram,
+                buckets,
+            } => {
+                use std::io::BufRead;
+                let what = match category {
+                    StatsCategory::Number => "number",
+                    StatsCategory::Time => "duration",
+                    StatsCategory::Data => "byte size",
+                    StatsCategory::Distance => "distance",
+                    StatsCategory::Volume => "volume",
+                    StatsCategory::Area => "area",
+                    StatsCategory::Weight => "weight",
+                    StatsCategory::Frequency => "frequency",
+                };
+                let dimension = Dimension::named(what).expect("stats categories are dimensions");
+                let unit = to.unwrap_or_else(|| dimension.base_unit().to_string());
+                let factor = dimension
+                    .factor(&unit)
+                    .unwrap_or_else(|e| fail(ConvertError::Parse(e)));
+                let mut values = Vec::new();
+                for (i, line) in std::io::stdin().lock().lines().enumerate() {
+                    let line = line.unwrap_or_else(|e| {
+                        fail(ConvertError::Io(format!("could not read stdin: {}", e)))
+                    });
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match dimension.parse(&line) {
+                        Ok(value) => values.push(value / factor),
+                        Err(e) => fail(ConvertError::Parse(format!("line {}: {}", i + 1, e))),
+                    }
+                }
+                let Some(summary) = stats::summarize(&values) else {
+                    fail(ConvertError::Parse("no values on stdin".to_string()));
+                };
+                let statistic = |label: &str| Figure::text(label, "statistic");
+                say!(
+                    statistic("count") => Figure::number(summary.count as f64, 0, "values"),
+                    "count  {}",
+                    summary.count
+                );
+                for (label, value) in [
+                    ("min", summary.min),
+                    ("median", summary.median),
+                    ("p95", summary.p95),
+                    ("max", summary.max),
+                ] {
+                    say!(
+                        statistic(label) => Figure::number(value, 2, &unit),
+                        "{:<6} {}",
+                        label,
+                        format!("{} {}", fixed(value, 2), unit).trim_end()
+                    );
+                }
+                if histogram {
+                    let buckets = stats::histogram(&values, buckets);
+                    let labels: Vec<String> = buckets
+                        .iter()
+                        .map(|b| {
+                            format!("{}-{} {}", fixed(b.low, 2), fixed(b.high, 2), unit)
+                                .trim_end()
+                                .to_string()
+                        })
+                        .collect();
+                    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+                    let tallest = buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+                    heading("");
+                    for (label, bucket) in labels.iter().zip(&buckets) {
+                        // Scale bars to at most 40 columns.
+                        let bar = "#".repeat((bucket.count * 40).div_ceil(tallest));
+                        say!(
+                            Figure::text(label, "bucket") => Figure::number(bucket.count as f64, 0, "values"),
+                            "{:>width$} | {} {}",
+                            label,
+                            bar,
+                            bucket.count,
+                            width = label_width
+                        );
+                    }
+                }
+            }
+            Command::Series {
+                input,
+                x_col,
+                y_col,
+                y_from,
+                y_to,
+                output,
+                jobs,
+                mmap,
+                checkpoint,
+            } => {
+                set_jobs(jobs);
+                let convert =
+                    unit_converter(&y_from, &y_to, None).unwrap_or_else(|error| fail(error));
+                let input = series::Input::open(&input, mmap)
+                    .unwrap_or_else(|message| fail(ConvertError::Io(message)));
+                let text = input
+                    .text()
+                    .unwrap_or_else(|message| fail(ConvertError::Parse(message)));
+                match (&checkpoint, &output) {
+                    (Some(checkpoint), Some(output)) => {
+                        let conversion = format!(
+                            "x-col={} y-col={} y-from={} y-to={}",
+                            x_col, y_col, y_from, y_to
+                        );
+                        let columns = (x_col, y_col, conversion.as_str());
+                        resume_series(text, columns, &*convert, output, checkpoint)
+                            .unwrap_or_else(|error| fail(error));
+                    }
+                    _ => {
+                        let converted = series::convert(text, x_col, y_col, &*convert)
+                            .unwrap_or_else(|message| fail(ConvertError::Parse(message)));
+                        write_output(output.as_deref(), &converted);
+                    }
+                }
+            }
+            Command::Csv {
+                input,
+                column,
+                category,
+                from,
+                to,
+                delimiter,
+                output,
+            } => {
+                let convert = unit_converter(&from, &to, category.as_deref())
+                    .unwrap_or_else(|error| fail(error));
+                let text = if input.as_os_str() == "-" {
+                    let mut text = String::new();
+                    std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)
+                        .map(|_| text)
+                        .map_err(|e| format!("could not read stdin: {}", e))
+                } else {
+                    std::fs::read_to_string(&input)
+                        .map_err(|e| format!("could not read {}: {}", input.display(), e))
+                };
+                let text = text.unwrap_or_else(|message| fail(ConvertError::Io(message)));
+                let places = PRECISION.load(std::sync::atomic::Ordering::Relaxed);
+                let places = Some(places).filter(|&places| places != usize::MAX);
+                let converted = csv::convert(&text, delimiter, &column, places, &*convert)
+                    .unwrap_or_else(|message| fail(ConvertError::Parse(message)));
+                write_output(output.as_deref(), &converted);
+            }
+            Command::Json {
+                path,
+                category,
+                from,
+                to,
+            } => {
+                let convert = unit_converter(&from, &to, category.as_deref())
+                    .unwrap_or_else(|error| fail(error));
+                let mut text = String::new();
+                if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut text) {
+                    fail(ConvertError::Io(format!("could not read stdin: {}", e)));
+                }
+                let places = PRECISION.load(std::sync::atomic::Ordering::Relaxed);
+                let places = Some(places).filter(|&places| places != usize::MAX);
+                let (converted, count) = jsonpath::convert(&text, &path, places, &*convert)
+                    .unwrap_or_else(|message| fail(ConvertError::Parse(message)));
+                if count == 0 {
+                    eprintln!("warning: no numbers found at the path");
+                }
+                print!("{}", converted);
+            }
+            #[cfg(feature = "arrow")]
+            Command::Sheet {
+                input,
+                column,
+                from,
+                to,
+                output,
+                batch_size,
+                jobs,
+            } => {
+                set_jobs(jobs);
+                let convert = unit_converter(&from, &to, None).unwrap_or_else(|error| fail(error));
+                let result = sheet::convert_file(&input, &output, &column, batch_size, &*convert);
+                match result {
+                    Ok(rows) => say!(
+                        Figure::number(rows as f64, 0, "rows"),
+                        "converted {} rows of {} from {} to {} into {}",
+                        rows,
+                        column,
+                        from,
+                        to,
+                        output.display()
+                    ),
+                    Err(message) => fail(ConvertError::Parse(message)),
+                }
+            }
+        }
+    }
+}
+
+// Supported categories of the `stats` subcommand.
+enum_with_variants!(pub StatsCategory {
+    Number => "number",
+    Time => "time",
+    Data => "data",
+    Distance => "distance",
+    Volume => "volume",
+    Area => "area",
+    Weight => "weight",
+    Frequency => "frequency",
+});
+
+/// Parses a count that must be at least 1, e.g. a number of histogram buckets.
+fn parse_count(s: &str) -> Result<usize, String> {
+    match s.trim().parse::<usize>() {
+        Ok(0) => Err("it must be at least 1".to_string()),
+        Ok(count) => Ok(count),
+        Err(_) => Err(format!("'{}' is not a whole number", s)),
+    }
+}
+
+/// Resolves `from` and `to` to units of one category, `category` if given,
+/// see [`resolve_pair`], and returns a function converting values between them.
+///
+/// # Example
+/// ```
+/// let to_gib = unit_converter("bytes", "gib", None).unwrap();
+/// assert_eq!(to_gib(1073741824.0), Ok(1.0));
+/// ```
+fn unit_converter(from: &str, to: &str, category: Option<&str>) -> Result<Converter, ConvertError> {
+    let (from, to) = resolve_pair(from, to, category)?;
+    pair_converter(&from, &to)
+}
+
+/// Limits the thread pool used for large inputs to `jobs` threads.
+fn set_jobs(jobs: Option<usize>) {
+    if let Some(jobs) = jobs {
+        if jobs == 0 {
+            fail(ConvertError::OutOfRange {
+                value: 0.0,
+                reason: "--jobs must be at least 1".to_string(),
+            });
+        }
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .expect("the thread pool is configured once, before any parallel work");
+    }
+}
+
+/// Converts the series `text` into `output` like `series::convert`, saving
+/// progress to the `checkpoint` file after every chunk and resuming from it if
+/// it exists. `conversion` describes the columns and units converted, which a
+/// resumed run must share.
+///
+/// The checkpoint is removed once the whole input is converted.
+fn resume_series(
+    text: &str,
+    (x_col, y_col, conversion): (usize, usize, &str),
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+    output: &std::path::Path,
+    path: &std::path::Path,
+) -> Result<(), ConvertError> {
+    use std::io::{Seek, Write};
+    let mut state = match checkpoint::Checkpoint::load(path).map_err(ConvertError::Io)? {
+        Some(saved) if saved.input_hash != checkpoint::hash(text.as_bytes()) => {
+            return Err(ConvertError::Io(format!(
+                "the input changed since {} was written; delete it to start over",
+                path.display()
+            )))
+        }
+        Some(saved) if saved.conversion != conversion => {
+            return Err(ConvertError::Io(format!(
+                "{} was written converting {}, not {}; delete it to start over",
+                path.display(),
+                saved.conversion,
+                conversion
+            )))
+        }
+        Some(saved) => saved,
+        None => checkpoint::Checkpoint::start(text.as_bytes(), conversion),
+    };
+    let write_error = |e: std::io::Error| format!("could not write {}: {}", output.display(), e);
+    let io_error = |e| ConvertError::Io(write_error(e));
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(output)
+        .map_err(io_error)?;
+    // Drop output written after the last checkpoint, it is converted again.
+    file.set_len(state.output_bytes).map_err(io_error)?;
+    file.seek(std::io::SeekFrom::End(0)).map_err(io_error)?;
+    let (offset, line) = (state.offset, state.line);
+    series::convert_from(
+        text,
+        offset,
+        line,
+        x_col,
+        y_col,
+        convert,
+        &mut |chunk, offset, line| {
+            file.write_all(chunk.as_bytes())
+                .and_then(|()| file.sync_data())
+                .map_err(write_error)?;
+            state.offset = offset;
+            state.line = line;
+            state.output_bytes += chunk.len() as u64;
+            state.save(path)
+        },
+    )?;
+    std::fs::remove_file(path)
+        .map_err(|e| ConvertError::Io(format!("could not remove {}: {}", path.display(), e)))
+}
+
+/// Writes the converted `text` to the file `output`, or to stdout without one.
+fn write_output(output: Option<&std::path::Path>, text: &str) {
+    match output {
+        Some(path) => std::fs::write(path, text).unwrap_or_else(|e| {
+            fail(ConvertError::Io(format!(
+                "could not write {}: {}",
+                path.display(),
+                e
+            )))
+        }),
+        None => print!("{}", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_converter() {
+        let to_gib = unit_converter("bytes", "gib", None).unwrap();
+        assert_eq!(to_gib(1073741824.0), Ok(1.0));
+        let to_f = unit_converter("c", "f", None).unwrap();
+        assert!((to_f(100.0).unwrap() - 212.0).abs() < 1e-9);
+        assert!(unit_converter("km", "kg", None).is_err());
+        assert!(unit_converter("parsecs", "km", None).is_err());
+        assert!(unit_converter("oz", "g", Some("mass")).is_ok());
+        assert!(unit_converter("oz", "ml", Some("mass")).is_err());
+        let to_km = unit_converter("furlongs", "km", None).unwrap();
+        assert!((to_km(5.0).unwrap() - 1.00584).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,247 @@
+//! Date subcommands: other calendars, meeting times across time zones,
+//! workdays, date formats and ISO week dates.
+
+use structopt::StructOpt;
+
+#[cfg(feature = "calendars")]
+use convertx::calendar;
+use convertx::error::ConvertError;
+use convertx::quantity::Duration;
+use convertx::{date, meeting, quantity, workdays};
+
+use super::output::{fixed, heading, Figure};
+use crate::fail;
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert a date to the Hijri, Hebrew or Chinese calendar.
+    #[cfg(feature = "calendars")]
+    Calendar {
+        /// Gregorian date, e.g. 2025-03-01.
+        date: chrono::NaiveDate,
+        /// Calendar to convert to.
+        #[structopt(short = "t", long, possible_values = &CalendarSystem::variants(), case_insensitive = true)]
+        to: CalendarSystem,
+    },
+    /// Show a meeting time in the local time of several time zones.
+    Meeting {
+        /// Date, time and zone, e.g. "2025-03-01 15:00 UTC" (default zone: UTC).
+        #[structopt(parse(try_from_str = meeting::parse_time))]
+        time: chrono::DateTime<chrono_tz::Tz>,
+        /// Comma-separated zones or cities, e.g. berlin,tokyo,new_york.
+        #[structopt(long = "in", required = true, use_delimiter = true, parse(try_from_str = meeting::find_zone))]
+        zones: Vec<chrono_tz::Tz>,
+    },
+    /// Convert an amount of work into business days, weeks or an end date.
+    Workdays {
+        /// Working time, e.g. 120h.
+        duration: Duration,
+        /// What to convert to; end-date needs --start.
+        #[structopt(short = "t", long, possible_values = &WorkdaysTarget::variants(), case_insensitive = true)]
+        to: WorkdaysTarget,
+        /// Working hours in a business day.
+        #[structopt(long, default_value = "8", parse(try_from_str = quantity::parse_number))]
+        hours_per_day: f64,
+        /// First day of work, e.g. 2025-03-03.
+        #[structopt(long)]
+        start: Option<chrono::NaiveDate>,
+        /// Count Saturdays and Sundays as working days, which are skipped by
+        /// default.
+        #[structopt(long)]
+        count_weekends: bool,
+        // Weekends are skipped by default now; the flag is still accepted.
+        #[structopt(long, hidden = true, conflicts_with = "count-weekends")]
+        skip_weekends: bool,
+        /// File of holidays to skip, one YYYY-MM-DD date per line.
+        #[structopt(long)]
+        holidays: Option<String>,
+    },
+    /// Convert a date between formats such as mdY, ISO 8601, RFC 2822 and Unix time.
+    Date {
+        /// Date in the `--from` format, e.g. 03/04/2025.
+        value: String,
+        /// Format of the value: iso, rfc2822, unix or a pattern such as mdY.
+        #[structopt(short = "f", long, default_value = "iso")]
+        from: date::DateFormat,
+        /// Format to convert to: iso, rfc2822, unix or a pattern such as dmY.
+        #[structopt(short = "t", long)]
+        to: date::DateFormat,
+    },
+    /// Convert between calendar dates, ISO week dates and ordinal (day-of-year) dates.
+    Week {
+        /// A date such as 2025-04-02, 2025-W14-3 or 2025-092.
+        #[structopt(parse(try_from_str = date::parse_day))]
+        day: chrono::NaiveDate,
+        /// Form to convert to.
+        #[structopt(short = "t", long, possible_values = &DayForm::variants(), case_insensitive = true)]
+        to: DayForm,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            #[cfg(feature = "calendars")]
+            Command::Calendar { date, to } => {
+                let converted = match to {
+                    CalendarSystem::Hijri => calendar::hijri(date).to_string(),
+                    CalendarSystem::Hebrew => calendar::hebrew(date).to_string(),
+                    CalendarSystem::Chinese => calendar::chinese(date).to_string(),
+                };
+                say!(
+                    Figure::text(date, "gregorian") => Figure::text(&converted, &to.to_string()),
+                    "{} = {}",
+                    date,
+                    converted
+                );
+            }
+            Command::Meeting { time, zones } => {
+                let zones: Vec<_> = std::iter::once(time.timezone()).chain(zones).collect();
+                let width = zones
+                    .iter()
+                    .map(|zone| zone.name().len())
+                    .max()
+                    .unwrap_or(4);
+                heading(&format!("{:<width$}  Local time", "Zone", width = width));
+                let given =
+                    || Figure::text(time.format("%Y-%m-%dT%H:%M%:z"), time.timezone().name());
+                for zone in zones {
+                    let local = time.with_timezone(&zone);
+                    say!(
+                        given() => Figure::text(local.format("%Y-%m-%dT%H:%M%:z"), zone.name()),
+                        "{:<width$}  {} (UTC{})",
+                        zone.name(),
+                        local.format("%a %Y-%m-%d %H:%M"),
+                        local.format("%:z"),
+                        width = width
+                    );
+                }
+            }
+            Command::Workdays {
+                duration,
+                to,
+                hours_per_day,
+                start,
+                count_weekends,
+                skip_weekends,
+                holidays,
+            } => {
+                let calendar =
+                    start.is_some() || count_weekends || skip_weekends || holidays.is_some();
+                if calendar && to != WorkdaysTarget::EndDate {
+                    fail(ConvertError::Parse(format!(
+                        "--start, --holidays and the weekend flags only apply to --to end-date, not {}",
+                        to
+                    )));
+                }
+                if duration.seconds <= 0.0 || hours_per_day <= 0.0 || hours_per_day > 24.0 {
+                    fail(ConvertError::OutOfRange {
+                        value: match duration.seconds <= 0.0 {
+                            true => duration.seconds,
+                            false => hours_per_day,
+                        },
+                        reason:
+                            "the duration must be positive and --hours-per-day between 0 and 24"
+                                .to_string(),
+                    });
+                }
+                let days = duration.seconds / 3600.0 / hours_per_day;
+                match to {
+                    WorkdaysTarget::BusinessDays => say!(
+                        Figure::number(duration.seconds / 3600.0, 2, "h") => Figure::number(days, 2, "business days"),
+                        "{} = {} business days ({}h/day)",
+                        duration,
+                        fixed(days, 2),
+                        hours_per_day
+                    ),
+                    WorkdaysTarget::BusinessWeeks => say!(
+                        Figure::number(duration.seconds / 3600.0, 2, "h") => Figure::number(days / 5.0, 2, "business weeks"),
+                        "{} = {} business weeks ({}h/day, 5 days/week)",
+                        duration,
+                        fixed(days / 5.0, 2),
+                        hours_per_day
+                    ),
+                    WorkdaysTarget::EndDate => {
+                        let Some(start) = start else {
+                            fail(ConvertError::Parse(
+                                "--to end-date needs --start".to_string(),
+                            ));
+                        };
+                        let holidays = match holidays.as_deref().map(workdays::load_holidays) {
+                            Some(Ok(holidays)) => holidays,
+                            Some(Err(e)) => fail(ConvertError::Parse(e)),
+                            None => Vec::new(),
+                        };
+                        // A part-day still takes up the day it falls on.
+                        let end = workdays::last_working_day(
+                            start,
+                            days.ceil() as u64,
+                            !count_weekends,
+                            &holidays,
+                        );
+                        say!(
+                            Figure::number(days, 2, "business days") => Figure::text(end.format("%Y-%m-%d"), "end date"),
+                            "{} = {} business days from {}, ending {} ({} calendar days)",
+                            duration,
+                            fixed(days, 2),
+                            start,
+                            end.format("%a %Y-%m-%d"),
+                            (end - start).num_days() + 1
+                        );
+                    }
+                }
+            }
+            Command::Date { value, from, to } => match from.parse(&value) {
+                Ok(moment) => {
+                    let converted = to.format(&moment);
+                    say!(
+                        Figure::text(&value, &from.to_string()) => Figure::text(&converted, &to.to_string()),
+                        "{} ({}) = {} ({})",
+                        value,
+                        from,
+                        converted,
+                        to
+                    )
+                }
+                Err(e) => fail(ConvertError::Parse(e)),
+            },
+            Command::Week { day, to } => {
+                let pattern = match to {
+                    DayForm::Date => "%Y-%m-%d",
+                    DayForm::Week => "%G-W%V-%u",
+                    DayForm::Ordinal => "%Y-%j",
+                };
+                let shown = day.format(pattern).to_string();
+                say!(
+                    Figure::text(day.format("%Y-%m-%d"), "date") => Figure::text(&shown, &to.to_string()),
+                    "{} ({})",
+                    shown,
+                    day.format("%A")
+                );
+            }
+        }
+    }
+}
+
+// Supported targets of the `workdays` subcommand.
+enum_with_variants!(pub WorkdaysTarget {
+    BusinessDays => "business-days",
+    BusinessWeeks => "business-weeks",
+    EndDate => "end-date",
+});
+
+// Supported targets of the `calendar` subcommand.
+#[cfg(feature = "calendars")]
+enum_with_variants!(pub CalendarSystem {
+    Hijri => "hijri",
+    Hebrew => "hebrew",
+    Chinese => "chinese",
+});
+
+// Supported targets of the `week` subcommand.
+enum_with_variants!(pub DayForm {
+    Date => "date",
+    Week => "week",
+    Ordinal => "ordinal",
+});
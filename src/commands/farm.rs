@@ -0,0 +1,249 @@
+//! Subcommands for the garden and the field: rainfall, irrigation and crop
+//! yields.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::{Area, Distance, Duration, FlowRate, Liquid};
+use convertx::unit::convert_units;
+use convertx::units::{VolumeUnit, KG_IN_LB};
+use convertx::{crop, quantity};
+
+use super::output::{fixed, seconds_to_human_readable, Figure};
+use crate::{check_positive, fail};
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert a rainfall depth over an area into a collected volume.
+    Rain {
+        /// Rainfall depth, e.g. 25mm or 1in.
+        depth: Distance,
+        /// Catchment area, e.g. 120m2 or 1000ft2.
+        #[structopt(long)]
+        area: Area,
+        /// Unit of the collected volume.
+        #[structopt(short = "t", long, possible_values = &RainUnit::variants(), case_insensitive = true)]
+        to: RainUnit,
+    },
+    /// Solve for the duration, flow or volume of watering from the other two.
+    Irrigation {
+        /// Flow rate, e.g. 12L/min or 2gal/h.
+        #[structopt(long)]
+        flow: Option<FlowRate>,
+        /// Volume to deliver, e.g. 500L.
+        #[structopt(long)]
+        target: Option<Liquid>,
+        /// Watering time, e.g. 45m or 2h.
+        #[structopt(long)]
+        time: Option<Duration>,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &IrrigationTarget::variants(), case_insensitive = true)]
+        to: IrrigationTarget,
+    },
+    /// Convert crop yields between per-hectare and per-acre units.
+    Yield {
+        /// Yield in the `--from` unit.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        value: f64,
+        /// Unit of the value.
+        #[structopt(short = "f", long, possible_values = &YieldUnit::variants(), case_insensitive = true)]
+        from: YieldUnit,
+        /// Unit to convert to.
+        #[structopt(short = "t", long, possible_values = &YieldUnit::variants(), case_insensitive = true)]
+        to: YieldUnit,
+        /// Crop, which sets the bushel weight for bu/acre.
+        #[structopt(long)]
+        crop: Option<crop::Crop>,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Rain { depth, area, to } => {
+                let (unit, symbol) = match to {
+                    RainUnit::Liters => (VolumeUnit::Liters, "L"),
+                    RainUnit::Gallons => (VolumeUnit::Gallons, "gal"),
+                    RainUnit::CubicMeters => (VolumeUnit::CubicMeters, "m³"),
+                };
+                let m3 = depth.km * 1000.0 * area.m2;
+                let volume = convert_units(m3, VolumeUnit::CubicMeters, unit).unwrap_or(f64::NAN);
+                say!(
+                    Figure::number(depth.km * 1e6, 2, "mm") => Figure::number(volume, 2, symbol),
+                    "{} over {} = {} {}",
+                    depth,
+                    area,
+                    fixed(volume, 2),
+                    symbol
+                );
+            }
+            Command::Irrigation {
+                flow,
+                target,
+                time,
+                to,
+            } => {
+                let given = [
+                    flow.as_ref().map(|f| f.ml_per_second),
+                    target.as_ref().map(|t| t.ml),
+                    time.as_ref().map(|t| t.seconds),
+                ];
+                check_positive(
+                    given.iter().flatten(),
+                    "--flow, --target and --time must be greater than zero",
+                );
+                let (value, result, line) = match (to, flow, target, time) {
+                    (IrrigationTarget::Duration, Some(flow), Some(target), _) => {
+                        let seconds = (target.ml / flow.ml_per_second).round();
+                        let shown = seconds_to_human_readable(seconds as u64);
+                        (
+                            Figure::number(target.ml / 1000.0, 2, "L"),
+                            Figure::number(seconds, 0, "s").shown(&shown),
+                            format!("{} at {} = {}", target, flow, shown),
+                        )
+                    }
+                    (IrrigationTarget::Flow, _, Some(target), Some(time)) => {
+                        let rate = target.ml / time.seconds * 60.0 / 1000.0;
+                        (
+                            Figure::number(target.ml / 1000.0, 2, "L"),
+                            Figure::number(rate, 2, "L/min"),
+                            format!("{} in {} = {} L/min", target, time, fixed(rate, 2)),
+                        )
+                    }
+                    (IrrigationTarget::Volume, Some(flow), _, Some(time)) => {
+                        let liters = flow.ml_per_second * time.seconds / 1000.0;
+                        (
+                            Figure::number(time.seconds, 0, "s"),
+                            Figure::number(liters, 2, "L"),
+                            format!("{} for {} = {} L", flow, time, fixed(liters, 2)),
+                        )
+                    }
+                    (to, ..) => {
+                        let needs = match to {
+                            IrrigationTarget::Duration => "--flow and --target",
+                            IrrigationTarget::Flow => "--target and --time",
+                            IrrigationTarget::Volume => "--flow and --time",
+                        };
+                        fail(ConvertError::Parse(format!("--to {} needs {}", to, needs)));
+                    }
+                };
+                say!(value => result, "{}", line);
+            }
+            Command::Yield {
+                value,
+                from,
+                to,
+                crop,
+            } => {
+                let bushel_lb = crop.as_ref().map(|crop| crop.bushel_lb);
+                let result = match convert_yield(value, from.clone(), to.clone(), bushel_lb) {
+                    Ok(result) => result,
+                    Err(error) => fail(error),
+                };
+                let figures = || {
+                    (
+                        Figure::number(value, 2, &from.to_string()),
+                        Figure::number(result, 2, &to.to_string()),
+                    )
+                };
+                let (given, converted) = figures();
+                match crop {
+                    Some(crop) => say!(
+                        given => converted,
+                        "{} {} = {} {} ({})",
+                        value,
+                        from,
+                        fixed(result, 2),
+                        to,
+                        crop
+                    ),
+                    None => {
+                        say!(given => converted, "{} {} = {} {}", value, from, fixed(result, 2), to)
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Supported targets of the `rain` subcommand.
+enum_with_variants!(pub RainUnit {
+    Liters => "liters",
+    Gallons => "gallons",
+    CubicMeters => "m3",
+});
+
+// Supported targets of the `irrigation` subcommand.
+enum_with_variants!(pub IrrigationTarget {
+    Duration => "duration",
+    Flow => "flow",
+    Volume => "volume",
+});
+
+// Supported units of the `yield` subcommand.
+enum_with_variants!(pub YieldUnit {
+    TonnesPerHectare => "t/ha",
+    KgPerHectare => "kg/ha",
+    LbPerAcre => "lb/acre",
+    BushelsPerAcre => "bu/acre",
+});
+
+/// Convert a crop yield between mass per area units, given the crop's bushel
+/// weight in pounds.
+///
+/// Fails when bushels are involved and no bushel weight is given.
+///
+/// # Example
+/// ```
+/// use crate::YieldUnit::*;
+/// assert!((convert_yield(1.0, TonnesPerHectare, LbPerAcre, None).unwrap() - 892.18).abs() < 0.01);
+/// ```
+fn convert_yield(
+    value: f64,
+    from: YieldUnit,
+    to: YieldUnit,
+    bushel_lb: Option<f64>,
+) -> Result<f64, ConvertError> {
+    use YieldUnit::*;
+    let bushel_lb = || {
+        bushel_lb.ok_or_else(|| ConvertError::Unsupported {
+            from: from.to_string(),
+            to: to.to_string(),
+            reason: "bu/acre needs --crop to know the bushel weight".to_string(),
+        })
+    };
+    let acres_in_hectare = 10_000.0 / 4046.85642;
+    let kg_per_ha = match from {
+        TonnesPerHectare => value * 1000.0,
+        KgPerHectare => value,
+        LbPerAcre => value / KG_IN_LB * acres_in_hectare,
+        BushelsPerAcre => value * bushel_lb()? / KG_IN_LB * acres_in_hectare,
+    };
+    let result = match to {
+        TonnesPerHectare => kg_per_ha / 1000.0,
+        KgPerHectare => kg_per_ha,
+        LbPerAcre => kg_per_ha * KG_IN_LB / acres_in_hectare,
+        BushelsPerAcre => kg_per_ha * KG_IN_LB / acres_in_hectare / bushel_lb()?,
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_yield() {
+        use YieldUnit::*;
+        let wheat = convert_yield(3.5, TonnesPerHectare, BushelsPerAcre, Some(60.0)).unwrap();
+        assert!((wheat - 52.04).abs() < 0.01);
+        let back = convert_yield(wheat, BushelsPerAcre, KgPerHectare, Some(60.0)).unwrap();
+        assert!((back - 3500.0).abs() < 1e-6);
+        let missing = convert_yield(3.5, TonnesPerHectare, BushelsPerAcre, None).unwrap_err();
+        assert!(missing
+            .to_string()
+            .ends_with("bu/acre needs --crop to know the bushel weight"));
+        assert_eq!(missing.exit_code(), 3);
+    }
+}
@@ -0,0 +1,295 @@
+//! Money subcommands: unit prices, VAT, tips and salaries.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity;
+
+use super::category::CATEGORIES;
+use super::output::{money_amount, places, Figure, NumberFormat, RoundMode};
+use super::resolve::{pair_converter, resolve_pair_ignoring_case, warn_ambiguous};
+use crate::{check_positive, fail};
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Normalize a price to another unit, e.g. per lb to per kg.
+    Price {
+        /// Price per `--per` unit.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        price: f64,
+        /// Unit the price is for, e.g. lb, L or GB.
+        #[structopt(long, parse(from_str = price_unit))]
+        per: String,
+        /// Unit to normalize to, e.g. per-kg, per-gal or per-TB.
+        #[structopt(short = "t", long, parse(from_str = price_unit))]
+        to: String,
+        /// Category of the units when one could belong to several, e.g. mass for oz.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+    },
+    /// Convert between net, gross and tax amounts at a VAT or sales tax rate.
+    Vat {
+        /// Amount in the `--from` form.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        amount: f64,
+        /// Tax rate in percent.
+        #[structopt(long, parse(try_from_str = quantity::parse_number))]
+        rate: f64,
+        /// What the amount is.
+        #[structopt(short = "f", long, default_value = "net", possible_values = &VatAmount::variants(), case_insensitive = true)]
+        from: VatAmount,
+        /// What to convert to.
+        #[structopt(short = "t", long, possible_values = &VatAmount::variants(), case_insensitive = true)]
+        to: VatAmount,
+    },
+    /// Add a tip to a bill and split the total between people.
+    Tip {
+        /// Bill amount before the tip.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        bill: f64,
+        /// Tip in percent of the bill.
+        #[structopt(long, default_value = "15", parse(try_from_str = quantity::parse_number))]
+        percent: f64,
+        /// Number of people sharing the bill.
+        #[structopt(long, default_value = "1")]
+        split: u32,
+        /// Rounding policy for each person's share (default: round to nearest).
+        #[structopt(long, possible_values = &RoundMode::variants(), case_insensitive = true)]
+        round: Option<RoundMode>,
+    },
+    /// Convert pay between hourly, daily, weekly, monthly and annual rates.
+    Salary {
+        /// Pay per `--from` period.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        amount: f64,
+        /// Period of the amount.
+        #[structopt(short = "f", long, possible_values = &PayPeriod::variants(), case_insensitive = true)]
+        from: PayPeriod,
+        /// Period to convert to.
+        #[structopt(short = "t", long, possible_values = &PayPeriod::variants(), case_insensitive = true)]
+        to: PayPeriod,
+        /// Working hours in a week.
+        #[structopt(long, default_value = "40", parse(try_from_str = quantity::parse_number))]
+        hours_per_week: f64,
+        /// Working days in a week.
+        #[structopt(long, default_value = "5", parse(try_from_str = quantity::parse_number))]
+        days_per_week: f64,
+        /// Paid weeks in a year.
+        #[structopt(long, default_value = "52", parse(try_from_str = quantity::parse_number))]
+        weeks_per_year: f64,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Price {
+                price,
+                per,
+                to,
+                category,
+            } => {
+                warn_ambiguous(&format!("{} {}", per, to));
+                let (per_unit, to_unit) =
+                    resolve_pair_ignoring_case(&per, &to, category.as_deref())
+                        .unwrap_or_else(|error| fail(error));
+                // The price of one `to` is that of the `per`s in it.
+                let per_to = pair_converter(&to_unit, &per_unit)
+                    .and_then(|convert| convert(1.0))
+                    .unwrap_or_else(|error| fail(error));
+                say!(
+                    Figure::money(price, &format!("per {}", per)) => Figure::money(price * per_to, &format!("per {}", to)),
+                    "{} per {} = {} per {}",
+                    money_amount(price),
+                    per,
+                    money_amount(price * per_to),
+                    to
+                );
+            }
+            Command::Vat {
+                amount,
+                rate,
+                from,
+                to,
+            } => {
+                if rate < 0.0 || (rate == 0.0 && from == VatAmount::Tax) {
+                    fail(ConvertError::OutOfRange {
+                        value: rate,
+                        reason: "--rate must be greater than zero".to_string(),
+                    });
+                }
+                let result = convert_vat(amount, rate, from.clone(), to.clone());
+                say!(
+                    Figure::money(amount, &from.to_string()) => Figure::money(result, &to.to_string()),
+                    "{} {} at {}% = {} {}",
+                    money_amount(amount),
+                    from,
+                    rate,
+                    money_amount(result),
+                    to
+                );
+            }
+            Command::Tip {
+                bill,
+                percent,
+                split,
+                round,
+            } => {
+                if bill < 0.0 || percent < 0.0 || split == 0 {
+                    fail(ConvertError::OutOfRange {
+                        value: [bill, percent]
+                            .into_iter()
+                            .find(|v| *v < 0.0)
+                            .unwrap_or(0.0),
+                        reason:
+                            "the bill and --percent cannot be negative, and --split must be at least 1"
+                                .to_string(),
+                    });
+                }
+                let tip = bill * percent / 100.0;
+                let total = bill + tip;
+                let cents = NumberFormat {
+                    round,
+                    engineering: false,
+                };
+                say!(
+                    Figure::money(bill, "bill") => Figure::money(tip, "tip"),
+                    "{}% tip on {} = {}",
+                    percent,
+                    money_amount(bill),
+                    money_amount(tip)
+                );
+                say!(
+                    Figure::money(total, "total"),
+                    "Total = {}",
+                    money_amount(total)
+                );
+                if split > 1 {
+                    let share = cents.round(total / f64::from(split), places(&[], 2));
+                    say!(
+                        Figure::money(total, "total") => Figure::money(share, "per person"),
+                        "Per person ({}) = {}",
+                        split,
+                        money_amount(share)
+                    );
+                }
+            }
+            Command::Salary {
+                amount,
+                from,
+                to,
+                hours_per_week,
+                days_per_week,
+                weeks_per_year,
+            } => {
+                check_positive(
+                    &[hours_per_week, days_per_week, weeks_per_year],
+                    "--hours-per-week, --days-per-week and --weeks-per-year must be greater than zero",
+                );
+                let per_year = |period| {
+                    periods_per_year(period, hours_per_week, days_per_week, weeks_per_year)
+                };
+                let result = amount * per_year(&from) / per_year(&to);
+                say!(
+                    Figure::money(amount, &from.to_string()) => Figure::money(result, &to.to_string()),
+                    "{} {} = {} {} ({}h/week, {} weeks/year)",
+                    money_amount(amount),
+                    from,
+                    money_amount(result),
+                    to,
+                    hours_per_week,
+                    weeks_per_year
+                );
+            }
+        }
+    }
+}
+
+// Supported amounts of the `vat` subcommand.
+enum_with_variants!(pub VatAmount {
+    Net => "net",
+    Gross => "gross",
+    Tax => "tax",
+});
+
+// Supported periods of the `salary` subcommand.
+enum_with_variants!(pub PayPeriod {
+    Hourly => "hourly",
+    Daily => "daily",
+    Weekly => "weekly",
+    Monthly => "monthly",
+    Annual => "annual",
+});
+
+/// Convert between the net price, gross price and tax amount at `rate` percent.
+///
+/// # Example
+/// ```
+/// use crate::VatAmount::*;
+/// assert!((convert_vat(119.0, 19.0, Gross, Net) - 100.0).abs() < 1e-9);
+/// ```
+fn convert_vat(amount: f64, rate: f64, from: VatAmount, to: VatAmount) -> f64 {
+    use VatAmount::*;
+    let rate = rate / 100.0;
+    let net = match from {
+        Net => amount,
+        Gross => amount / (1.0 + rate),
+        Tax => amount / rate,
+    };
+    match to {
+        Net => net,
+        Gross => net * (1.0 + rate),
+        Tax => net * rate,
+    }
+}
+
+/// Returns how many of each pay period make up a year, given the working
+/// hours and days in a week and the paid weeks in a year.
+///
+/// # Example
+/// ```
+/// assert_eq!(periods_per_year(&PayPeriod::Hourly, 40.0, 5.0, 52.0), 2080.0);
+/// ```
+fn periods_per_year(
+    period: &PayPeriod,
+    hours_per_week: f64,
+    days_per_week: f64,
+    weeks_per_year: f64,
+) -> f64 {
+    match period {
+        PayPeriod::Hourly => hours_per_week * weeks_per_year,
+        PayPeriod::Daily => days_per_week * weeks_per_year,
+        PayPeriod::Weekly => weeks_per_year,
+        PayPeriod::Monthly => 12.0,
+        PayPeriod::Annual => 1.0,
+    }
+}
+
+/// Reads the unit a price is for, e.g. `kg` from `per-kg`.
+fn price_unit(s: &str) -> String {
+    let s = s.trim();
+    s.strip_prefix("per-").unwrap_or(s).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_vat() {
+        use VatAmount::*;
+        assert!((convert_vat(100.0, 19.0, Net, Gross) - 119.0).abs() < 1e-9);
+        assert!((convert_vat(119.0, 19.0, Gross, Tax) - 19.0).abs() < 1e-9);
+        assert!((convert_vat(19.0, 19.0, Tax, Net) - 100.0).abs() < 1e-9);
+        assert_eq!(convert_vat(50.0, 0.0, Net, Gross), 50.0);
+    }
+
+    #[test]
+    fn test_periods_per_year() {
+        use PayPeriod::*;
+        assert_eq!(periods_per_year(&Hourly, 40.0, 5.0, 52.0), 2080.0);
+        assert_eq!(periods_per_year(&Daily, 40.0, 5.0, 48.0), 240.0);
+        assert_eq!(periods_per_year(&Monthly, 40.0, 5.0, 52.0), 12.0);
+    }
+}
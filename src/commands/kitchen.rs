@@ -0,0 +1,440 @@
+//! Kitchen subcommands: alcohol content, brewing gravity, oven adjustments
+//! and coffee brew ratios.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::{Distance, Duration, Liquid, Ratio, Weight};
+use convertx::unit::convert_units;
+use convertx::units::TempUnit;
+use convertx::{alcohol, bake, gravity, quantity};
+
+use super::category::{label_temp, UnitStyle};
+use super::output::{fixed, seconds_to_human_readable, Figure};
+use crate::fail;
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert alcohol content between ABV and proof, or count standard drinks.
+    Alcohol {
+        /// Alcohol content in the `--from` scale.
+        #[structopt(required_unless = "abv", parse(try_from_str = quantity::parse_number))]
+        value: Option<f64>,
+        /// Scale of the value.
+        #[structopt(short = "f", long, default_value = "percent", possible_values = &AlcoholScale::variants(), case_insensitive = true)]
+        from: AlcoholScale,
+        /// Scale to convert to, or standard-drinks (requires --volume).
+        #[structopt(short = "t", long, possible_values = &AlcoholScale::variants(), case_insensitive = true)]
+        to: AlcoholScale,
+        /// Alcohol by volume in percent, instead of a value.
+        #[structopt(long, conflicts_with = "value", parse(try_from_str = quantity::parse_number))]
+        abv: Option<f64>,
+        /// Volume of the drink, e.g. 500ml or 12floz.
+        #[structopt(long)]
+        volume: Option<Liquid>,
+        /// Country whose standard drink size is used.
+        #[structopt(long, default_value = "us")]
+        country: alcohol::Country,
+    },
+    /// Convert brewing gravity between SG, Plato and Brix, or estimate ABV.
+    Gravity {
+        /// Gravity reading in the `--from` scale.
+        #[structopt(required_unless = "og", parse(try_from_str = quantity::parse_number))]
+        value: Option<f64>,
+        /// Scale of the readings.
+        #[structopt(short = "f", long, default_value = "sg", possible_values = &GravityScale::variants(), case_insensitive = true)]
+        from: GravityScale,
+        /// Scale to convert to.
+        #[structopt(short = "t", long, required_unless = "og", possible_values = &GravityScale::variants(), case_insensitive = true)]
+        to: Option<GravityScale>,
+        /// Original gravity; with --fg, estimates alcohol by volume.
+        #[structopt(long, requires = "fg", conflicts_with = "value", parse(try_from_str = quantity::parse_number))]
+        og: Option<f64>,
+        /// Final gravity.
+        #[structopt(long, requires = "og", parse(try_from_str = quantity::parse_number))]
+        fg: Option<f64>,
+    },
+    /// Adjust a recipe's oven temperature and time for convection or altitude.
+    Bake {
+        /// Recipe temperature, e.g. 180C or 350F.
+        #[structopt(long, parse(try_from_str = parse_temperature))]
+        temp: (f64, TempUnit),
+        /// Recipe baking time, e.g. 40m.
+        #[structopt(long)]
+        time: Option<Duration>,
+        /// Baking in a convection (fan) oven.
+        #[structopt(long)]
+        convection: bool,
+        /// Altitude of the kitchen, e.g. 2000m or 6500ft.
+        #[structopt(long)]
+        altitude: Option<Distance>,
+    },
+    /// Work out coffee and water amounts for a brew ratio.
+    Brew {
+        /// Coffee to water ratio by weight.
+        #[structopt(long, default_value = "1:16")]
+        ratio: Ratio,
+        /// Amount of water, e.g. 500ml.
+        #[structopt(long, required_unless = "coffee", conflicts_with = "coffee")]
+        water: Option<Liquid>,
+        /// Amount of coffee, e.g. 30g.
+        #[structopt(long)]
+        coffee: Option<Weight>,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &BrewTarget::variants(), case_insensitive = true)]
+        to: BrewTarget,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Alcohol {
+                value,
+                from,
+                to,
+                abv,
+                volume,
+                country,
+            } => {
+                let (value, from) = match abv {
+                    Some(abv) => (abv, AlcoholScale::Percent),
+                    None => (value.unwrap_or_default(), from),
+                };
+                let scale = |scale: &AlcoholScale| match scale {
+                    AlcoholScale::Percent => "% ABV",
+                    AlcoholScale::Proof => " proof",
+                    AlcoholScale::UkProof => " UK proof",
+                    AlcoholScale::StandardDrinks => " standard drinks",
+                };
+                let line = match (&to, volume) {
+                    (AlcoholScale::StandardDrinks, Some(volume)) => {
+                        convert_alcohol(value, from.clone(), AlcoholScale::Percent).map(|percent| {
+                            let drinks = alcohol::standard_drinks(volume.ml, percent, &country);
+                            let line = format!(
+                                "{} at {}% ABV = {} standard drinks ({})",
+                                volume,
+                                percent,
+                                fixed(drinks, 2),
+                                country
+                            );
+                            let value = Figure::number(volume.ml, 0, "ml");
+                            (value, Figure::number(drinks, 2, "standard drinks"), line)
+                        })
+                    }
+                    (AlcoholScale::StandardDrinks, None) => fail(ConvertError::Parse(
+                        "--to standard-drinks needs --volume".to_string(),
+                    )),
+                    _ => convert_alcohol(value, from.clone(), to.clone()).map(|result| {
+                        let line = format!(
+                            "{}{} = {}{}",
+                            value,
+                            scale(&from),
+                            fixed(result, 1),
+                            scale(&to)
+                        );
+                        let value = Figure::number(value, 1, scale(&from).trim_start());
+                        (
+                            value,
+                            Figure::number(result, 1, scale(&to).trim_start()),
+                            line,
+                        )
+                    }),
+                };
+                match line {
+                    Ok((value, result, line)) => say!(value => result, "{}", line),
+                    Err(error) => fail(error),
+                }
+            }
+            Command::Gravity {
+                value,
+                from,
+                to,
+                og,
+                fg,
+            } => {
+                let show = |value: f64, scale: &GravityScale| match scale {
+                    GravityScale::Sg => format!("{} SG", fixed(value, 3)),
+                    GravityScale::Plato => format!("{}°P", fixed(value, 1)),
+                    GravityScale::Brix => format!("{}°Bx", fixed(value, 1)),
+                };
+                let figure = |value: f64, scale: &GravityScale| match scale {
+                    GravityScale::Sg => Figure::number(value, 3, "SG"),
+                    GravityScale::Plato => Figure::number(value, 1, "°P"),
+                    GravityScale::Brix => Figure::number(value, 1, "°Bx"),
+                };
+                let sg = |value| {
+                    convert_gravity(value, from.clone(), GravityScale::Sg)
+                        .unwrap_or_else(|error| fail(error))
+                };
+                match (value, to, og.map(sg), fg.map(sg)) {
+                    (Some(value), Some(to), _, _) => {
+                        let result = convert_gravity(value, from.clone(), to.clone())
+                            .unwrap_or_else(|error| fail(error));
+                        say!(
+                            figure(value, &from) => figure(result, &to),
+                            "{} = {}",
+                            show(value, &from),
+                            show(result, &to)
+                        );
+                    }
+                    (None, _, Some(og), Some(fg)) => {
+                        let abv = gravity::abv(og, fg)
+                            .unwrap_or_else(|message| fail(ConvertError::Parse(message)));
+                        say!(
+                            figure(og, &GravityScale::Sg) => Figure::number(abv, 2, "% ABV"),
+                            "OG {} and FG {} = {}% ABV",
+                            show(og, &GravityScale::Sg),
+                            show(fg, &GravityScale::Sg),
+                            fixed(abv, 2)
+                        )
+                    }
+                    _ => unreachable!("structopt requires a value with --to, or --og with --fg"),
+                }
+            }
+            Command::Bake {
+                temp: (value, unit),
+                time,
+                convection,
+                altitude,
+            } => {
+                let altitude_m = altitude.as_ref().map_or(0.0, |a| a.km * 1000.0);
+                let to_f = |v| convert_units(v, unit.clone(), TempUnit::F).unwrap_or(f64::NAN);
+                let from_f = |v| convert_units(v, TempUnit::F, unit.clone()).unwrap_or(f64::NAN);
+                let (adjusted_f, time_factor) = bake::adjust(to_f(value), convection, altitude_m);
+                let show = |v: f64| label_temp(&fixed(v, 0), &unit, UnitStyle::Symbol);
+                let mut notes = Vec::new();
+                if convection {
+                    notes.push(format!("convection {}°F", bake::CONVECTION_DELTA_F));
+                }
+                if altitude_m >= bake::HIGH_ALTITUDE_M {
+                    notes.push(format!(
+                        "altitude {} +{}°F and {}% less time",
+                        altitude
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                        bake::ALTITUDE_DELTA_F,
+                        fixed((1.0 - bake::ALTITUDE_TIME_FACTOR) * 100.0, 0)
+                    ));
+                }
+                let times = time.map(|time| {
+                    let adjusted = (time.seconds * time_factor).round() as u64;
+                    (
+                        format!(" for {}", time),
+                        format!(" for {}", seconds_to_human_readable(adjusted)),
+                    )
+                });
+                let (before, after) = times.unwrap_or_default();
+                let degrees = label_temp("", &unit, UnitStyle::Symbol);
+                say!(
+                    Figure::number(value, 0, degrees.trim_start()) => Figure::number(from_f(adjusted_f), 0, degrees.trim_start()),
+                    "{}{} = {}{} ({})",
+                    show(value),
+                    before,
+                    show(from_f(adjusted_f)),
+                    after,
+                    if notes.is_empty() {
+                        "no adjustment needed".to_string()
+                    } else {
+                        notes.join("; ")
+                    }
+                );
+            }
+            Command::Brew {
+                ratio,
+                water,
+                coffee,
+                to,
+            } => {
+                // A milliliter of water weighs a gram, so the ratio applies to either.
+                let (value, result, line) = match (to, water, coffee) {
+                    (BrewTarget::CoffeeGrams, Some(water), _) => {
+                        let grams = water.ml / ratio.per_left();
+                        (
+                            Figure::number(water.ml, 0, "ml"),
+                            Figure::number(grams, 1, "g"),
+                            format!(
+                                "{} water at {} = {} g coffee",
+                                water,
+                                ratio,
+                                fixed(grams, 1)
+                            ),
+                        )
+                    }
+                    (BrewTarget::WaterMl, _, Some(coffee)) => {
+                        let ml = coffee.grams * ratio.per_left();
+                        (
+                            Figure::number(coffee.grams, 1, "g"),
+                            Figure::number(ml, 0, "ml"),
+                            format!("{} coffee at {} = {} ml water", coffee, ratio, fixed(ml, 0)),
+                        )
+                    }
+                    (BrewTarget::CoffeeGrams, None, _) => fail(ConvertError::Parse(
+                        "--to coffee-grams needs --water".to_string(),
+                    )),
+                    (BrewTarget::WaterMl, _, None) => fail(ConvertError::Parse(
+                        "--to water-ml needs --coffee".to_string(),
+                    )),
+                };
+                say!(value => result, "{}", line);
+            }
+        }
+    }
+}
+
+// Supported scales of the `alcohol` subcommand.
+enum_with_variants!(pub AlcoholScale {
+    Percent => "percent",
+    Proof => "proof",
+    UkProof => "uk-proof",
+    StandardDrinks => "standard-drinks",
+});
+
+// Supported scales of the `gravity` subcommand.
+enum_with_variants!(pub GravityScale {
+    Sg => "sg",
+    Plato => "plato",
+    Brix => "brix",
+});
+
+// Supported targets of the `brew` subcommand.
+enum_with_variants!(pub BrewTarget {
+    CoffeeGrams => "coffee-grams",
+    WaterMl => "water-ml",
+});
+
+/// Convert alcohol content between ABV percent, US proof and UK proof.
+///
+/// Fails for standard drinks, which also depend on the volume, and for more
+/// alcohol than pure ethanol or less than none.
+///
+/// # Example
+/// ```
+/// use crate::AlcoholScale::*;
+/// assert_eq!(convert_alcohol(40.0, Percent, Proof), Ok(80.0));
+/// ```
+fn convert_alcohol(value: f64, from: AlcoholScale, to: AlcoholScale) -> Result<f64, ConvertError> {
+    use AlcoholScale::*;
+    let unsupported = || ConvertError::Unsupported {
+        from: from.to_string(),
+        to: to.to_string(),
+        reason: "standard drinks also depend on the volume".to_string(),
+    };
+    let (percent, pure) = match from {
+        Percent => (value, 100.0),
+        Proof => (value / 2.0, 200.0),
+        UkProof => (value / 1.75, 175.0),
+        StandardDrinks => return Err(unsupported()),
+    };
+    if !percent.is_nan() && !(0.0..=100.0).contains(&percent) {
+        return Err(ConvertError::OutOfRange {
+            value,
+            reason: format!("{} must be between 0 and {}", from, pure),
+        });
+    }
+    match to {
+        Percent => Ok(percent),
+        Proof => Ok(percent * 2.0),
+        UkProof => Ok(percent * 1.75),
+        StandardDrinks => Err(unsupported()),
+    }
+}
+
+/// Convert a gravity reading between specific gravity, Plato and Brix.
+///
+/// Fails for readings outside about 0.980 to 1.200 SG, where the
+/// polynomials no longer hold.
+///
+/// # Example
+/// ```
+/// use crate::GravityScale::*;
+/// assert!((convert_gravity(1.050, Sg, Plato).unwrap() - 12.39).abs() < 0.01);
+/// ```
+fn convert_gravity(value: f64, from: GravityScale, to: GravityScale) -> Result<f64, ConvertError> {
+    use GravityScale::*;
+    let (low, high) = match from {
+        Sg => (0.98, 1.2),
+        Plato | Brix => (-5.0, 44.0),
+    };
+    if !value.is_nan() && !(low..=high).contains(&value) {
+        return Err(ConvertError::OutOfRange {
+            value,
+            reason: format!("{} must be between {} and {}", from, low, high),
+        });
+    }
+    let sg = match from {
+        Sg => value,
+        Plato => gravity::plato_to_sg(value),
+        Brix => gravity::brix_to_sg(value),
+    };
+    let result = match to {
+        Sg => sg,
+        Plato => gravity::sg_to_plato(sg),
+        Brix => gravity::sg_to_brix(sg),
+    };
+    Ok(result)
+}
+
+/// Parses a temperature with its unit, e.g. `180C`, `350°F` or `300 K`.
+fn parse_temperature(s: &str) -> Result<(f64, TempUnit), String> {
+    let s = s.trim();
+    let split = s
+        .rfind(|c: char| c.is_ascii_digit() || c == '.')
+        .map_or(0, |i| i + 1);
+    let (number, unit) = s.split_at(split);
+    let unit = unit
+        .trim()
+        .trim_start_matches('°')
+        .parse::<TempUnit>()
+        .map_err(|_| format!("'{}' is not a temperature like 180C or 350F", s))?;
+    let number = quantity::parse_number(number).map_err(|error| error.to_string())?;
+    Ok((number, unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_alcohol() {
+        use AlcoholScale::*;
+        assert_eq!(convert_alcohol(40.0, Percent, Proof), Ok(80.0));
+        assert_eq!(convert_alcohol(100.0, Proof, UkProof), Ok(87.5));
+        assert_eq!(convert_alcohol(200.0, Proof, Percent), Ok(100.0));
+        for (value, from) in [(150.0, Percent), (-1.0, Percent), (201.0, Proof)] {
+            assert!(matches!(
+                convert_alcohol(value, from, Proof),
+                Err(ConvertError::OutOfRange { .. })
+            ));
+        }
+        assert!(matches!(
+            convert_alcohol(5.0, Percent, StandardDrinks),
+            Err(ConvertError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_convert_gravity() {
+        use GravityScale::*;
+        assert!((convert_gravity(1.050, Sg, Plato).unwrap() - 12.39).abs() < 0.01);
+        assert!((convert_gravity(12.0, Brix, Sg).unwrap() - 1.0484).abs() < 1e-3);
+        assert_eq!(convert_gravity(1.050, Sg, Sg), Ok(1.050));
+        assert!(convert_gravity(0.990, Sg, Plato).unwrap() < 0.0);
+        for (value, from) in [(0.5, Sg), (1.5, Sg), (60.0, Plato), (-10.0, Brix)] {
+            assert!(matches!(
+                convert_gravity(value, from, Sg),
+                Err(ConvertError::OutOfRange { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_temperature() {
+        assert_eq!(parse_temperature("180C"), Ok((180.0, TempUnit::C)));
+        assert_eq!(parse_temperature("350 °F"), Ok((350.0, TempUnit::F)));
+        assert!(parse_temperature("180").is_err());
+        assert!(parse_temperature("hotC").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Media subcommands: audio file sizes, screen density, refresh rates, shutter
+//! speeds and video bitrates.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::{ByteRate, ByteUnit, Distance, Duration, Frequency};
+use convertx::{quantity, screen, video};
+
+use super::output::{bytes_to_human_readable, fixed, human_bytes, Figure};
+use crate::{check_positive, fail};
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Compute the size of uncompressed PCM audio.
+    #[structopt(name = "audio-size")]
+    AudioSize {
+        /// Sample rate, e.g. 48kHz.
+        #[structopt(long)]
+        rate: Frequency,
+        /// Bits per sample.
+        #[structopt(long = "bit-depth", default_value = "16")]
+        bit_depth: u32,
+        /// Number of channels.
+        #[structopt(long, default_value = "2")]
+        channels: u32,
+        /// Length of the recording, e.g. 3m or 90s.
+        #[structopt(long)]
+        duration: Duration,
+        /// Byte unit of the result, e.g. MiB or GB.
+        #[structopt(short = "t", long)]
+        to: ByteUnit,
+    },
+    /// Convert pixel density to dot pitch and back, or compute it for a screen.
+    Ppi {
+        /// Density in ppi for `--to dot-pitch-mm`, or dot pitch in mm for `--to ppi`.
+        #[structopt(required_unless = "resolution", parse(try_from_str = quantity::parse_number))]
+        value: Option<f64>,
+        /// Screen resolution, e.g. 2556x1179 (requires --diagonal).
+        #[structopt(long, conflicts_with = "value", requires = "diagonal")]
+        resolution: Option<screen::Resolution>,
+        /// Screen diagonal, e.g. 6.1in or 15.6in.
+        #[structopt(long)]
+        diagonal: Option<Distance>,
+        /// What to convert to.
+        #[structopt(short = "t", long, possible_values = &PpiTarget::variants(), case_insensitive = true)]
+        to: PpiTarget,
+    },
+    /// Convert a refresh rate to frame time, or frame time to a refresh rate.
+    Refresh {
+        /// Refresh rate in Hz for `--to frame-time-ms`, or frame time in ms for `--to hz`.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        value: f64,
+        /// What to convert to.
+        #[structopt(short = "t", long, possible_values = &RefreshTarget::variants(), case_insensitive = true)]
+        to: RefreshTarget,
+    },
+    /// Convert a shutter speed or exposure time, e.g. 1/250, into ms or µs.
+    Shutter {
+        /// Exposure time in seconds, as a fraction like 1/250 or a number like 0.5.
+        #[structopt(parse(try_from_str = quantity::parse_number))]
+        seconds: f64,
+        /// Time unit to convert to.
+        #[structopt(short = "t", long, possible_values = &ExposureUnit::variants(), case_insensitive = true)]
+        to: ExposureUnit,
+    },
+    /// Estimate the bitrate or storage per hour of a video preset.
+    Video {
+        /// Resolution and frame rate, e.g. 4k60 or 1080p (30 fps).
+        preset: video::Preset,
+        /// Video codec.
+        #[structopt(long, default_value = "h264", possible_values = &VideoCodec::variants(), case_insensitive = true)]
+        codec: VideoCodec,
+        /// What to estimate.
+        #[structopt(short = "t", long, possible_values = &VideoTarget::variants(), case_insensitive = true)]
+        to: VideoTarget,
+        /// Frame rate, overriding the one in the preset.
+        #[structopt(long)]
+        fps: Option<u32>,
+        /// Bitrate, e.g. 20Mbps, overriding the preset estimate.
+        #[structopt(long)]
+        bitrate: Option<ByteRate>,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::AudioSize {
+                rate,
+                bit_depth,
+                channels,
+                duration,
+                to,
+            } => {
+                check_positive(
+                    &[rate.hertz, f64::from(bit_depth), f64::from(channels)],
+                    "--rate, --bit-depth and --channels must be greater than zero",
+                );
+                let bytes = audio_size_bytes(rate.hertz, bit_depth, channels, duration.seconds);
+                let size = Figure::number(bytes / to.bytes, 2, &to.to_string());
+                say!(
+                    Figure::number(duration.seconds, 2, "s") => size,
+                    "{} at {}, {}-bit, {} channel{} = {} {}",
+                    duration,
+                    rate,
+                    bit_depth,
+                    channels,
+                    if channels == 1 { "" } else { "s" },
+                    fixed(bytes / to.bytes, 2),
+                    to
+                );
+            }
+            Command::Ppi {
+                value,
+                resolution,
+                diagonal,
+                to,
+            } => {
+                let (label, ppi, given) = match (value, resolution, diagonal) {
+                    (Some(ppi), _, _) if to == PpiTarget::DotPitchMm => {
+                        (format!("{} ppi", ppi), ppi, Figure::number(ppi, 2, "ppi"))
+                    }
+                    (Some(pitch), _, _) => (
+                        format!("{} mm dot pitch", pitch),
+                        screen::dot_pitch_mm(pitch),
+                        Figure::number(pitch, 4, "mm"),
+                    ),
+                    (None, Some(resolution), Some(diagonal)) => {
+                        let label = format!("{} at {}", resolution, diagonal);
+                        let screen = Figure::text(&label, "screen");
+                        (
+                            label,
+                            screen::ppi(resolution, diagonal.km / 2.54e-5),
+                            screen,
+                        )
+                    }
+                    _ => unreachable!("structopt requires a value or --resolution with --diagonal"),
+                };
+                if !(ppi.is_finite() && ppi > 0.0) {
+                    fail(ConvertError::OutOfRange {
+                        value: ppi,
+                        reason: "densities, dot pitches and diagonals must be greater than zero"
+                            .to_string(),
+                    });
+                }
+                match to {
+                    PpiTarget::Ppi => say!(
+                        given => Figure::number(ppi, 2, "ppi"),
+                        "{} = {} ppi",
+                        label,
+                        fixed(ppi, 2)
+                    ),
+                    PpiTarget::DotPitchMm => {
+                        let pitch = screen::dot_pitch_mm(ppi);
+                        say!(
+                            given => Figure::number(pitch, 4, "mm"),
+                            "{} = {} mm dot pitch",
+                            label,
+                            fixed(pitch, 4)
+                        )
+                    }
+                }
+            }
+            Command::Refresh { value, to } => {
+                if !(value.is_finite() && value > 0.0) {
+                    fail(ConvertError::OutOfRange {
+                        value,
+                        reason: "refresh rates and frame times must be greater than zero"
+                            .to_string(),
+                    });
+                }
+                let converted = screen::frame_time_ms(value);
+                match to {
+                    RefreshTarget::FrameTimeMs => say!(
+                        Figure::number(value, 2, "Hz") => Figure::number(converted, 3, "ms"),
+                        "{} Hz = {} ms per frame",
+                        value,
+                        fixed(converted, 3)
+                    ),
+                    RefreshTarget::Hz => say!(
+                        Figure::number(value, 3, "ms") => Figure::number(converted, 2, "Hz"),
+                        "{} ms per frame = {} Hz",
+                        value,
+                        fixed(converted, 2)
+                    ),
+                }
+            }
+            Command::Shutter { seconds, to } => {
+                let (scale, unit) = match to {
+                    ExposureUnit::S => (1.0, "s"),
+                    ExposureUnit::Ms => (1e3, "ms"),
+                    ExposureUnit::Us => (1e6, "µs"),
+                };
+                // Show 1/250 rather than 0.004, as shutter speeds are usually written.
+                let reciprocal = 1.0 / seconds;
+                let shown = if seconds < 1.0 && (reciprocal - reciprocal.round()).abs() < 1e-6 {
+                    format!("1/{}", reciprocal.round())
+                } else {
+                    seconds.to_string()
+                };
+                say!(
+                    Figure::number(seconds, 3, "s") => Figure::number(seconds * scale, 3, unit),
+                    "{} s = {} {}",
+                    shown,
+                    fixed(seconds * scale, 3),
+                    unit
+                );
+            }
+            Command::Video {
+                mut preset,
+                codec,
+                to,
+                fps,
+                bitrate,
+            } => {
+                preset.fps = fps.unwrap_or(preset.fps);
+                let (label, mbps) = match bitrate {
+                    Some(bitrate) => (
+                        format!("{} at {}", preset, bitrate),
+                        bitrate.bytes_per_second * 8.0 / 1e6,
+                    ),
+                    None => (
+                        format!("{} {}", preset, codec),
+                        preset.h264_mbps(preset.fps) * codec_efficiency(&codec),
+                    ),
+                };
+                let video = Figure::text(&label, "video");
+                match to {
+                    VideoTarget::BitrateEstimate => say!(
+                        video => Figure::number(mbps, 2, "Mbps"),
+                        "{} = {} Mbps",
+                        label,
+                        fixed(mbps, 2)
+                    ),
+                    VideoTarget::HourlySize => {
+                        let bytes = (mbps * 1e6 / 8.0 * 3600.0).round() as u64;
+                        let (size, unit) = human_bytes(bytes);
+                        say!(
+                            video => Figure::number(size, 2, &format!("{}/h", unit)),
+                            "{} = {} per hour",
+                            label,
+                            bytes_to_human_readable(bytes)
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Supported targets of the `ppi` subcommand.
+enum_with_variants!(pub PpiTarget {
+    Ppi => "ppi",
+    DotPitchMm => "dot-pitch-mm",
+});
+
+// Supported targets of the `refresh` subcommand.
+enum_with_variants!(pub RefreshTarget {
+    FrameTimeMs => "frame-time-ms",
+    Hz => "hz",
+});
+
+// Supported targets of the `shutter` subcommand.
+enum_with_variants!(pub ExposureUnit {
+    S => "s",
+    Ms => "ms",
+    Us => "us",
+});
+
+// Supported codecs of the `video` subcommand.
+enum_with_variants!(pub VideoCodec {
+    H264 => "h264",
+    H265 => "h265",
+    Vp9 => "vp9",
+    Av1 => "av1",
+});
+
+// Supported targets of the `video` subcommand.
+enum_with_variants!(pub VideoTarget {
+    BitrateEstimate => "bitrate-estimate",
+    HourlySize => "hourly-size",
+});
+
+/// Returns the size in bytes of uncompressed PCM audio.
+///
+/// # Example
+/// ```
+/// assert_eq!(audio_size_bytes(44_100.0, 16, 2, 1.0), 176_400.0);
+/// ```
+fn audio_size_bytes(rate: f64, bit_depth: u32, channels: u32, seconds: f64) -> f64 {
+    rate * f64::from(bit_depth) / 8.0 * f64::from(channels) * seconds
+}
+
+/// Returns the bitrate a codec needs relative to H.264 for the same quality.
+fn codec_efficiency(codec: &VideoCodec) -> f64 {
+    match codec {
+        VideoCodec::H264 => 1.0,
+        VideoCodec::H265 => 0.6,
+        VideoCodec::Vp9 => 0.65,
+        VideoCodec::Av1 => 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_size_bytes() {
+        assert_eq!(audio_size_bytes(44_100.0, 16, 2, 1.0), 176_400.0);
+        assert_eq!(audio_size_bytes(48_000.0, 24, 2, 180.0), 51_840_000.0);
+    }
+}
@@ -0,0 +1,674 @@
+//! How conversion results are printed: the number format, `--output` modes,
+//! `--emit` targets and the global `--format`, shared by every subcommand.
+
+use std::fmt;
+use structopt::StructOpt;
+
+use convertx::conversion::{Amount, ConversionResult};
+use convertx::error::ConvertError;
+use convertx::template::Template;
+use convertx::{clipboard, color, json, money, portable, sql, table};
+
+use crate::fail;
+
+// Supported places of `--emit`.
+enum_with_variants!(pub Emit {
+    Plain => "plain",
+    Json => "json",
+    Clipboard => "clipboard",
+});
+
+// Supported modes of `--output`.
+enum_with_variants!(pub OutputMode {
+    Text => "text",
+    Sql => "sql",
+    Json => "json",
+    Raw => "raw",
+    ScriptFilter => "script-filter",
+    Markdown => "markdown",
+    Html => "html",
+    Spoken => "spoken",
+});
+
+// Supported rounding policies for displayed values.
+enum_with_variants!(pub RoundMode {
+    Floor => "floor",
+    Ceil => "ceil",
+    HalfEven => "half-even",
+});
+
+// Where the results of a conversion go, for `--emit`.
+#[derive(StructOpt, Debug)]
+pub struct EmitOpts {
+    /// Send the results to several places at once, e.g. `plain,json,clipboard`:
+    /// `plain` prints them as without `--emit`, `json` writes the JSON of
+    /// `--output json` to `--json-file` or stdout, and `clipboard` copies the
+    /// converted numbers.
+    #[structopt(long, use_delimiter = true, possible_values = &Emit::variants(), case_insensitive = true)]
+    emit: Vec<Emit>,
+    /// File to write the JSON of `--emit json` to; alone it is the same as
+    /// `--emit plain,json`.
+    #[structopt(long, parse(from_os_str))]
+    json_file: Option<std::path::PathBuf>,
+}
+
+impl EmitOpts {
+    /// Sends results to the places given: `print` prints them as without
+    /// `--emit`, `json` is their JSON and `raw` their converted numbers.
+    pub fn emit(&self, print: impl FnOnce(), json: &str, raw: &str) {
+        let sinks = match (self.emit.is_empty(), &self.json_file) {
+            (true, None) => vec![Emit::Plain],
+            (true, Some(_)) => vec![Emit::Plain, Emit::Json],
+            (false, _) => self.emit.clone(),
+        };
+        if sinks.contains(&Emit::Plain) {
+            print();
+        }
+        if sinks.contains(&Emit::Json) {
+            match &self.json_file {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(path, format!("{}\n", json)) {
+                        fail(ConvertError::Io(format!(
+                            "could not write {}: {}",
+                            path.display(),
+                            e
+                        )));
+                    }
+                }
+                None => println!("{}", json),
+            }
+        }
+        if sinks.contains(&Emit::Clipboard) {
+            if let Err(message) = clipboard::copy(raw) {
+                fail(ConvertError::Io(message));
+            }
+        }
+    }
+}
+
+/// Decimal places given with `--precision`, or `usize::MAX` if it is not.
+pub static PRECISION: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(usize::MAX);
+
+/// Significant digits the default precision keeps of values below 1.
+const SIGNIFICANT_DIGITS: usize = 3;
+
+/// Values smaller than this fraction of the largest value shown, or of 1, are
+/// taken as floating-point noise around zero.
+const NOISE: f64 = 1e-12;
+
+/// Returns the decimal places to show `values` with where a subcommand
+/// defaults to `default`: those of `--precision` if given, otherwise `default`,
+/// or more if the smallest value would show fewer than [`SIGNIFICANT_DIGITS`].
+/// Values within [`NOISE`] of zero are shown as zero is.
+///
+/// # Example
+/// ```
+/// assert_eq!(places(&[0.000277], 4), 6);
+/// assert_eq!(places(&[3280.84, 0.5], 2), 3);
+/// assert_eq!(places(&[32.0, 4.97e-14], 2), 2);
+/// ```
+pub fn places(values: &[f64], default: usize) -> usize {
+    let precision = PRECISION.load(std::sync::atomic::Ordering::Relaxed);
+    if precision != usize::MAX {
+        return precision;
+    }
+    let finite = || values.iter().map(|v| v.abs()).filter(|v| v.is_finite());
+    let noise = NOISE * finite().fold(1.0, f64::max);
+    let smallest = finite().filter(|v| *v > noise).fold(1.0, f64::min);
+    if smallest >= 1.0 {
+        return default;
+    }
+    // The decimal place of the first significant digit, e.g. 4 for 0.000277.
+    let first = (-portable::log10(smallest)).ceil() as usize;
+    default.max((first + SIGNIFICANT_DIGITS - 1).min(16))
+}
+
+/// Formats `value` with [`places`] decimal places, for the subcommands that
+/// take no [`NumberFormat`] options.
+pub fn fixed(value: f64, default: usize) -> String {
+    format!("{:.*}", places(&[value], default), value)
+}
+
+/// Formats a sum of money with thousands separators and two decimal places,
+/// or those of `--precision`.
+pub fn money_amount(amount: f64) -> String {
+    money::format_places(amount, places(&[], 2))
+}
+
+// How numbers are rounded and laid out in conversion output.
+#[derive(StructOpt, Debug, Default)]
+pub struct NumberFormat {
+    /// Rounding policy for displayed values (default: round to nearest).
+    #[structopt(long, possible_values = &RoundMode::variants(), case_insensitive = true)]
+    pub round: Option<RoundMode>,
+    /// Show values in engineering notation (exponent a multiple of 3).
+    #[structopt(long)]
+    pub engineering: bool,
+}
+
+impl NumberFormat {
+    /// Formats `value` with `precision` decimal places.
+    ///
+    /// # Example
+    /// ```
+    /// let fmt = NumberFormat { round: Some(RoundMode::Floor), engineering: true };
+    /// assert_eq!(fmt.number(12345.0, 2), "12.34e3");
+    /// ```
+    fn number(&self, value: f64, precision: usize) -> String {
+        if !self.engineering || value == 0.0 || !value.is_finite() {
+            let suffix = if self.engineering && value.is_finite() {
+                "e0"
+            } else {
+                ""
+            };
+            return format!("{:.*}{}", precision, self.round(value, precision), suffix);
+        }
+        let mut exponent = (portable::log10(value.abs()) / 3.0).floor() as i32 * 3;
+        let mut mantissa = self.round(value / portable::powi(10.0, exponent), precision);
+        // A mantissa that displays as 1000 belongs to the next exponent group.
+        if mantissa.abs() >= 1000.0 - 0.5 / portable::powi(10.0, precision as i32) {
+            exponent += 3;
+            mantissa = self.round(value / portable::powi(10.0, exponent), precision);
+        }
+        format!("{:.*}e{}", precision, mantissa, exponent)
+    }
+
+    /// Formats every component of `amount` with [`NumberFormat::number`], at
+    /// the [`places`] for a `default`. Engineering notation only takes its
+    /// places from `--precision`, as its mantissas are never small.
+    pub fn amount(&self, amount: &Amount, default: usize) -> String {
+        let precision = self.places(amount, default);
+        amount.render(|v| self.number(v, precision))
+    }
+
+    /// Formats `amount` as [`NumberFormat::amount`] does, spelled out to be
+    /// read aloud, e.g. `minus 40` or `5 to 7`.
+    pub fn spoken(&self, amount: &Amount, default: usize) -> String {
+        let precision = self.places(amount, default);
+        amount.spoken(|v| self.number(v, precision))
+    }
+
+    /// The decimal places of `amount` for a `default`.
+    fn places(&self, amount: &Amount, default: usize) -> usize {
+        let values = match self.engineering {
+            true => Vec::new(),
+            false => amount.points(),
+        };
+        places(&values, default)
+    }
+
+    /// Applies the rounding policy at `precision` decimal places.
+    ///
+    /// Scaled values within floating-point noise of a boundary are snapped to it
+    /// first, so that e.g. 0.29 does not floor to 0.28.
+    pub fn round(&self, value: f64, precision: usize) -> f64 {
+        let scale = portable::powi(10.0, precision as i32);
+        let scaled = value * scale;
+        let snap = |target: f64| {
+            if (scaled - target).abs() <= 1e-9 * scaled.abs().max(1.0) {
+                target
+            } else {
+                scaled
+            }
+        };
+        match self.round {
+            Some(RoundMode::Floor) => snap(scaled.round()).floor() / scale,
+            Some(RoundMode::Ceil) => snap(scaled.round()).ceil() / scale,
+            Some(RoundMode::HalfEven) => {
+                snap(scaled.trunc() + 0.5 * scaled.signum()).round_ties_even() / scale
+            }
+            None => value,
+        }
+    }
+}
+
+/// A figure in a line of a subcommand's result: a number and its unit, or
+/// text such as a date and what it is written in, e.g. a calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figure {
+    /// The number, unrounded, or `None` for text.
+    number: Option<f64>,
+    /// The figure as the line shows it, e.g. "1.50" or "1 Adar 5785".
+    shown: String,
+    /// The unit of the number, e.g. "MB", or what the text is in.
+    unit: String,
+}
+
+impl Figure {
+    /// A number shown with [`fixed`] places, `places` by default.
+    pub fn number(number: f64, places: usize, unit: &str) -> Figure {
+        Figure {
+            number: Some(number),
+            shown: fixed(number, places),
+            unit: unit.to_string(),
+        }
+    }
+
+    /// A sum of money, shown with two decimal places or those of `--precision`.
+    pub fn money(amount: f64, unit: &str) -> Figure {
+        Figure {
+            number: Some(amount),
+            shown: format!("{:.*}", places(&[], 2), amount),
+            unit: unit.to_string(),
+        }
+    }
+
+    /// Text that is no number, written in `unit`.
+    pub fn text(text: impl fmt::Display, unit: &str) -> Figure {
+        Figure {
+            number: None,
+            shown: text.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
+    /// The figure shown as `shown` rather than with the places it was given,
+    /// e.g. in scientific notation.
+    pub fn shown(self, shown: impl fmt::Display) -> Figure {
+        Figure {
+            shown: shown.to_string(),
+            ..self
+        }
+    }
+
+    /// The figure as JSON: its unrounded number, or its text.
+    fn json(&self) -> json::Value {
+        match self.number {
+            Some(number) => json::Value::Number(number),
+            None => json::Value::Text(self.shown.clone()),
+        }
+    }
+
+    /// The figure as a template shows it, with `places` if the template gives
+    /// them.
+    fn render(&self, places: Option<usize>) -> String {
+        match (self.number, places) {
+            (Some(number), Some(places)) => format!("{:.*}", places, number),
+            _ => self.shown.clone(),
+        }
+    }
+}
+
+/// The name of the subcommand run, which the output of [`say`] gives as its
+/// category.
+pub static SUBCOMMAND: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Prints a line of a subcommand's result, usually a `value = result`
+/// sentence, in the output mode of `--format`: `sentence` highlighted as
+/// text, a JSON object of the figures `value` and `result` with their units,
+/// only `result` with `raw`, or a template of them. The other modes lay out
+/// unit conversions, so they are an error.
+pub fn say(value: Option<Figure>, result: Figure, sentence: &str) {
+    let category = SUBCOMMAND.get().map_or("", String::as_str);
+    match FORMAT.get() {
+        None | Some(Format::Mode(OutputMode::Text)) => {
+            println!("{}", color::highlight(sentence))
+        }
+        Some(Format::Mode(OutputMode::Json)) => {
+            let mut fields = vec![("category", json::Value::Text(category.to_string()))];
+            if let Some(value) = &value {
+                fields.push(("value", value.json()));
+                fields.push(("from", json::Value::Text(value.unit.clone())));
+            }
+            fields.push(("to", json::Value::Text(result.unit.clone())));
+            fields.push(("result", result.json()));
+            println!("{}", json::Value::Object(fields))
+        }
+        Some(Format::Mode(OutputMode::Raw)) => println!("{}", result.shown),
+        Some(Format::Template(template)) => {
+            let from = value.as_ref().map_or("", |value| value.unit.as_str());
+            let line = template.render(|field, places| match field {
+                "value" => value.as_ref().map_or(String::new(), |v| v.render(places)),
+                "from" | "from_symbol" => from.to_string(),
+                "to" | "to_symbol" => result.unit.clone(),
+                "result" => result.render(places),
+                "category" => category.to_string(),
+                _ => unreachable!("template fields are validated when parsing"),
+            });
+            println!("{}", line)
+        }
+        Some(Format::Mode(mode)) => fail(ConvertError::Parse(format!(
+            "--format {} lays out unit conversions; this subcommand prints text, json, raw or a template",
+            mode
+        ))),
+    }
+}
+
+/// Prints `text`, such as the heading of a table of [`say`] lines, only with
+/// the text output of `--format`, as it has no figures.
+#[cfg(any(feature = "dates", feature = "data"))]
+pub fn heading(text: &str) {
+    if matches!(FORMAT.get(), None | Some(Format::Mode(OutputMode::Text))) {
+        println!("{}", text);
+    }
+}
+
+/// The sentence of `--spoken` for `conversion`, e.g. "10 kilometers equals
+/// 6.2137 miles", with its amounts spelled out by `spoken`.
+pub fn spoken_conversion(
+    conversion: &ConversionResult,
+    spoken: impl Fn(&Amount) -> String,
+) -> String {
+    format!(
+        "{} {} equals {} {}",
+        spoken(&conversion.value),
+        spoken_unit(&conversion.from),
+        spoken(&conversion.result),
+        spoken_unit(&conversion.to)
+    )
+}
+
+/// Writes the subscript digits of a unit name such as "ppm CaCO₃" as the
+/// digits they stand for, which speech reads out rather than skipping.
+fn spoken_unit(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '₀'..='₉' => char::from(b'0' + (c as u32 - '₀' as u32) as u8),
+            c => c,
+        })
+        .collect()
+}
+
+/// The row of `conversion` in the tables of `--format markdown` and `html`:
+/// the formatted `value` and `result`, each followed by its unit.
+pub fn table_row(
+    conversion: ConversionResult,
+    value: String,
+    result: String,
+    symbols: bool,
+) -> Vec<String> {
+    let (from, to) = match symbols {
+        true => (conversion.from_symbol, conversion.to_symbol),
+        false => (conversion.from, conversion.to),
+    };
+    vec![value, from, result, to]
+}
+
+/// Renders conversions as the table of `mode`, Markdown or HTML, with one of
+/// `rows` from [`table_row`] for each.
+pub fn conversion_table(rows: Vec<Vec<String>>, mode: &OutputMode) -> String {
+    use table::Align::{Left, Right};
+    let mut table = table::Table::new(&[
+        ("Value", Right),
+        ("From", Left),
+        ("Result", Right),
+        ("To", Left),
+    ]);
+    for row in rows {
+        table.push(row);
+    }
+    match mode {
+        OutputMode::Html => table.html(),
+        _ => table.markdown(),
+    }
+}
+
+/// What `--format` takes: the name of an output mode, or a template.
+#[derive(Debug, Clone)]
+pub enum Format {
+    Mode(OutputMode),
+    Template(Template),
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(mode) => Ok(Format::Mode(mode)),
+            Err(_) => s.parse().map(Format::Template),
+        }
+    }
+}
+
+/// The `--format` given, which every subcommand accepts.
+pub static FORMAT: std::sync::OnceLock<Format> = std::sync::OnceLock::new();
+
+/// The template and output mode that `--format`, `--output`, `--quiet` and
+/// `--spoken` ask for.
+pub fn output_format(
+    output: &Option<OutputMode>,
+    quiet: bool,
+    spoken: bool,
+) -> (Option<&Template>, Option<&OutputMode>) {
+    match FORMAT.get() {
+        _ if quiet => (None, Some(&OutputMode::Raw)),
+        _ if spoken => (None, Some(&OutputMode::Spoken)),
+        Some(Format::Template(template)) => (Some(template), None),
+        Some(Format::Mode(mode)) => (None, Some(mode)),
+        None => (None, output.as_ref()),
+    }
+}
+
+/// Renders `conversion` with `template`, formatting numbers at `precision`
+/// decimal places unless a placeholder specifies its own.
+pub fn render_conversion(
+    conversion: &ConversionResult,
+    template: &Template,
+    number: &NumberFormat,
+    precision: usize,
+) -> String {
+    template.render(|field, places| {
+        // Places written in the template are kept as they are.
+        let amount = |amount: &Amount| match places {
+            Some(places) => amount.render(|v| number.number(v, places)),
+            None => number.amount(amount, precision),
+        };
+        match field {
+            "value" => amount(&conversion.value),
+            "from" => conversion.from.clone(),
+            "to" => conversion.to.clone(),
+            "from_symbol" => conversion.from_symbol.clone(),
+            "to_symbol" => conversion.to_symbol.clone(),
+            "result" => amount(&conversion.result),
+            "category" => conversion.category.clone(),
+            _ => unreachable!("template fields are validated when parsing"),
+        }
+    })
+}
+
+/// Returns an `INSERT` statement for `conversion` into `table`, see [`sql`]
+/// for the columns.
+pub fn conversion_sql(conversion: &ConversionResult, table: &sql::Table) -> String {
+    // The nominal value, then the limits of a tolerance or range.
+    let columns = |amount: &Amount| {
+        match *amount {
+            Amount::Exact(v) => [Some(v), None, None],
+            Amount::Tolerance(n, t) => [Some(n), Some(n - t), Some(n + t)],
+            Amount::Range(lo, hi) => [None, Some(lo), Some(hi)],
+        }
+        .map(|v| v.map_or(sql::Value::Null, sql::Value::Number))
+    };
+    let [value, value_min, value_max] = columns(&conversion.value);
+    let [result, result_min, result_max] = columns(&conversion.result);
+    sql::insert(
+        table,
+        &[
+            sql::Value::Text(conversion.category.clone()),
+            value,
+            sql::Value::Text(conversion.from_unit.clone()),
+            sql::Value::Text(conversion.to_unit.clone()),
+            result,
+            value_min,
+            value_max,
+            result_min,
+            result_max,
+        ],
+    )
+}
+
+/// Returns `conversion` as a line of JSON, see [`json`] for the format.
+///
+/// Exact amounts are numbers, tolerances `{"nominal": n, "tolerance": t}` and
+/// ranges `{"min": lo, "max": hi}`.
+pub fn conversion_json(conversion: &ConversionResult) -> String {
+    let amount = |amount: &Amount| match *amount {
+        Amount::Exact(v) => json::Value::Number(v),
+        Amount::Tolerance(n, t) => json::Value::Object(vec![
+            ("nominal", json::Value::Number(n)),
+            ("tolerance", json::Value::Number(t)),
+        ]),
+        Amount::Range(lo, hi) => json::Value::Object(vec![
+            ("min", json::Value::Number(lo)),
+            ("max", json::Value::Number(hi)),
+        ]),
+    };
+    json::object(&[
+        json::Value::Text(conversion.category.clone()),
+        amount(&conversion.value),
+        json::Value::Text(conversion.from_unit.clone()),
+        json::Value::Text(conversion.to_unit.clone()),
+        json::Value::Text(conversion.from_symbol.clone()),
+        json::Value::Text(conversion.to_symbol.clone()),
+        amount(&conversion.result),
+    ])
+}
+
+/// Prints where the definition of each of `units`, given as `(name, source)`,
+/// is published, once per unit, for `--cite`.
+pub fn print_citations(units: impl IntoIterator<Item = (String, Option<&'static str>)>) {
+    let mut cited: Vec<String> = Vec::new();
+    for (name, source) in units {
+        if cited.contains(&name) {
+            continue;
+        }
+        println!(
+            "Source for {}: {}",
+            name,
+            source.unwrap_or("none on record")
+        );
+        cited.push(name);
+    }
+}
+
+/// Convert a number of bytes to a human-readable string.
+///
+/// # Example
+/// ```
+/// assert_eq!(bytes_to_human_readable(1048576), "1.00 MB");
+/// ```
+pub fn bytes_to_human_readable(num_bytes: u64) -> String {
+    let (n, unit) = human_bytes(num_bytes);
+    format!("{} {}", fixed(n, 2), unit)
+}
+
+/// Scales a number of bytes to the largest unit it is at least one of, as
+/// [`bytes_to_human_readable`] shows it.
+///
+/// # Example
+/// ```
+/// assert_eq!(human_bytes(1536), (1.5, "KB"));
+/// ```
+pub fn human_bytes(num_bytes: u64) -> (f64, &'static str) {
+    let units = ["B", "KB", "MB", "GB", "TB", "PB"];
+    let mut idx = 0;
+    let mut n = num_bytes as f64;
+    while n >= 1024.0 && idx < units.len() - 1 {
+        n /= 1024.0;
+        idx += 1;
+    }
+    (n, units[idx])
+}
+
+/// Convert seconds to a human-readable string (e.g., days, hours, minutes, seconds).
+///
+/// # Example
+/// ```
+/// assert_eq!(seconds_to_human_readable(3661), "1h 1m 1s");
+/// ```
+pub fn seconds_to_human_readable(seconds: u64) -> String {
+    let (d, h, mut m, s);
+    m = seconds / 60;
+    s = seconds % 60;
+    h = m / 60;
+    m %= 60;
+    d = h / 24;
+    let mut parts = vec![];
+    if d > 0 {
+        parts.push(format!("{}d", d));
+    }
+    if h % 24 > 0 {
+        parts.push(format!("{}h", h % 24));
+    }
+    if m > 0 {
+        parts.push(format!("{}m", m));
+    }
+    if s > 0 || parts.is_empty() {
+        parts.push(format!("{}s", s));
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_to_human_readable() {
+        assert_eq!(bytes_to_human_readable(1023), "1023.00 B");
+        assert_eq!(bytes_to_human_readable(1024), "1.00 KB");
+        assert_eq!(bytes_to_human_readable(1048576), "1.00 MB");
+        assert_eq!(human_bytes(1536), (1.5, "KB"));
+    }
+
+    #[test]
+    fn test_seconds_to_human_readable() {
+        assert_eq!(seconds_to_human_readable(59), "59s");
+        assert_eq!(seconds_to_human_readable(61), "1m 1s");
+        assert_eq!(seconds_to_human_readable(3661), "1h 1m 1s");
+        assert_eq!(seconds_to_human_readable(90061), "1d 1h 1m 1s");
+    }
+
+    #[test]
+    fn test_spoken_unit() {
+        assert_eq!(spoken_unit("ppm CaCO₃"), "ppm CaCO3");
+        assert_eq!(spoken_unit("H₂O₁₀"), "H2O10");
+        assert_eq!(spoken_unit("miles"), "miles");
+    }
+
+    #[test]
+    fn test_number_format() {
+        let nearest = NumberFormat::default();
+        assert_eq!(nearest.number(3.28084, 4), "3.2808");
+        let floor = NumberFormat {
+            round: Some(RoundMode::Floor),
+            engineering: false,
+        };
+        assert_eq!(floor.number(0.29, 2), "0.29");
+        assert_eq!(floor.number(1.999, 2), "1.99");
+        let ceil = NumberFormat {
+            round: Some(RoundMode::Ceil),
+            engineering: false,
+        };
+        assert_eq!(ceil.number(1.001, 2), "1.01");
+        let half_even = NumberFormat {
+            round: Some(RoundMode::HalfEven),
+            engineering: false,
+        };
+        assert_eq!(half_even.number(2.675, 2), "2.68");
+        assert_eq!(half_even.number(2.665, 2), "2.66");
+        let engineering = NumberFormat {
+            round: None,
+            engineering: true,
+        };
+        assert_eq!(engineering.number(3280.84, 2), "3.28e3");
+        assert_eq!(engineering.number(0.00025, 1), "250.0e-6");
+        assert_eq!(engineering.number(999999.0, 2), "1.00e6");
+        assert_eq!(engineering.number(0.0, 2), "0.00e0");
+    }
+
+    #[test]
+    fn test_places() {
+        assert_eq!(places(&[3280.84], 4), 4);
+        assert_eq!(places(&[0.3048], 4), 4);
+        assert_eq!(places(&[0.000277], 4), 6);
+        assert_eq!(places(&[0.000277], 2), 6);
+        assert_eq!(places(&[0.5, 20.0], 0), 3);
+        assert_eq!(places(&[0.0, f64::NAN], 2), 2);
+        assert_eq!(places(&[1e-10], 4), 12);
+        assert_eq!(places(&[1e-300], 4), 4);
+        assert_eq!(places(&[32.0, 4.97e-14], 2), 2);
+        assert_eq!(places(&[1e6, 1e-7], 2), 2);
+        assert_eq!(fixed(0.001524, 4), "0.00152");
+    }
+}
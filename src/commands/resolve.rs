@@ -0,0 +1,453 @@
+//! Resolves unit names and symbols to units across every category, and two
+//! of them to the conversion between them.
+
+use convertx::conversion::{Amount, ConversionResult};
+use convertx::error::ConvertError;
+use convertx::registry::UnitDef;
+use convertx::{compound, prefix, quantity, registry};
+
+use super::category::{category_converter, unit_registry};
+
+/// A unit found by name or symbol, together with its category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitMatch {
+    pub category: &'static str,
+    pub name: String,
+    pub symbol: &'static str,
+}
+
+/// How the caller chooses a category, named in the error for a unit of
+/// several; `--category` unless a subcommand takes it some other way.
+pub static CATEGORY_CHOICE: std::sync::OnceLock<&'static str> = std::sync::OnceLock::new();
+
+/// Finds the unit matching `token` in every category.
+pub fn find_unit(token: &str) -> Vec<UnitMatch> {
+    unit_matches(unit_registry().lookup(token))
+}
+
+/// Finds the unit matching `token` in every category like [`find_unit`],
+/// reading prefixed symbols in any case, e.g. `tb` as terabytes.
+#[cfg(feature = "finance")]
+fn find_unit_ignoring_case(token: &str) -> Vec<UnitMatch> {
+    unit_matches(unit_registry().lookup_ignoring_case(token))
+}
+
+fn unit_matches(units: Vec<&'static UnitDef>) -> Vec<UnitMatch> {
+    units
+        .into_iter()
+        .map(|unit| UnitMatch {
+            category: &unit.dimension,
+            name: unit.name.clone(),
+            symbol: &unit.symbol,
+        })
+        .collect()
+}
+
+/// Picks the single unit `token` refers to among `matches`.
+///
+/// An abbreviation shared by several categories is an error listing every
+/// candidate, unless `category` narrows it down.
+///
+/// # Example
+/// ```
+/// let found = resolve_unit("km", None, find_unit("km")).unwrap();
+/// assert_eq!(found.category, "length");
+/// ```
+pub fn resolve_unit(
+    token: &str,
+    category: Option<&str>,
+    matches: Vec<UnitMatch>,
+) -> Result<UnitMatch, String> {
+    let mut candidates: Vec<UnitMatch> = matches
+        .into_iter()
+        .filter(|m| category.is_none_or(|c| m.category.eq_ignore_ascii_case(c)))
+        .collect();
+    match candidates.len() {
+        0 => Err(match category {
+            Some(category) => format!("unknown {} unit '{}'", category, token),
+            None => unknown_unit(token),
+        }),
+        1 => Ok(candidates.remove(0)),
+        _ => {
+            let listed: Vec<String> = candidates
+                .iter()
+                .map(|m| format!("{} ({})", m.name, m.category))
+                .collect();
+            Err(format!(
+                "'{}' is ambiguous, it could be: {}. Pass {} to choose.",
+                token,
+                listed.join(", "),
+                CATEGORY_CHOICE.get().unwrap_or(&"--category")
+            ))
+        }
+    }
+}
+
+/// The error for a `token` that names no unit, listing the qualified units
+/// to write instead if it is one that needs a qualifier, such as "tons".
+fn unknown_unit(token: &str) -> String {
+    match registry::ambiguity(token) {
+        Some(ambiguity) if ambiguity.definition.is_none() => ambiguity.message(token),
+        _ => format!("unknown unit '{}'", token),
+    }
+}
+
+/// Warns about the units in `text` that have several common definitions,
+/// naming the one used, e.g. for `gal`.
+pub fn warn_ambiguous(text: &str) {
+    for (word, ambiguity) in registry::ambiguities(text) {
+        if ambiguity.definition.is_some() {
+            eprintln!("warning: {}", ambiguity.message(word));
+        }
+    }
+}
+
+/// Resolves `from` and `to` to units of one category, `category` if given.
+///
+/// Only categories in which both tokens are units are considered, so either
+/// unit can settle an abbreviation that is ambiguous on its own, except an
+/// informal spelling such as `oz`, which needs `category` to choose.
+///
+/// # Example
+/// ```
+/// let (from, to) = resolve_pair("km", "mi", None).unwrap();
+/// assert_eq!((from.category, to.name.as_str()), ("length", "miles"));
+/// ```
+pub fn resolve_pair(
+    from: &str,
+    to: &str,
+    category: Option<&str>,
+) -> Result<(UnitMatch, UnitMatch), ConvertError> {
+    resolve_found(from, to, category, find_unit)
+}
+
+/// Resolves `from` and `to` like [`resolve_pair`], reading prefixed symbols
+/// in any case, e.g. `gb` and `tb`, for units typed as part of a price.
+#[cfg(feature = "finance")]
+pub fn resolve_pair_ignoring_case(
+    from: &str,
+    to: &str,
+    category: Option<&str>,
+) -> Result<(UnitMatch, UnitMatch), ConvertError> {
+    resolve_found(from, to, category, find_unit_ignoring_case)
+}
+
+fn resolve_found(
+    from: &str,
+    to: &str,
+    category: Option<&str>,
+    find_unit: fn(&str) -> Vec<UnitMatch>,
+) -> Result<(UnitMatch, UnitMatch), ConvertError> {
+    let (from_matches, to_matches) = (find_unit(from), find_unit(to));
+    for (token, matches) in [(from, &from_matches), (to, &to_matches)] {
+        if matches.is_empty() {
+            return Err(ConvertError::Parse(unknown_unit(token)));
+        }
+        if registry::is_informal(token) {
+            resolve_unit(token, category, matches.clone())?;
+        }
+    }
+    let shared: Vec<UnitMatch> = from_matches
+        .into_iter()
+        .filter(|m| to_matches.iter().any(|t| t.category == m.category))
+        .collect();
+    if shared.is_empty() {
+        return Err(ConvertError::DimensionMismatch {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+    let found = resolve_unit(from, category, shared)?;
+    let target = resolve_unit(to, Some(found.category), to_matches)?;
+    Ok((found, target))
+}
+
+/// Returns the registered unit `found` refers to.
+pub fn registered(found: &UnitMatch) -> &'static UnitDef {
+    unit_registry()
+        .units()
+        .iter()
+        .find(|unit| unit.name == found.name && unit.dimension == found.category)
+        .or_else(|| prefix::find(found.category, &found.name).map(|unit| &unit.def))
+        .expect("found units are registered or prefixed")
+}
+
+/// Converts every point of `value` with `convert`, failing like
+/// [`convert_units`](convertx::unit::convert_units).
+fn convert_points(
+    value: &Amount,
+    convert: impl Fn(f64) -> Result<f64, ConvertError>,
+) -> Result<Amount, ConvertError> {
+    let points = value
+        .points()
+        .into_iter()
+        .map(|point| match convert(quantity::check_finite(point)?)? {
+            result if point.is_finite() && !result.is_finite() => Err(ConvertError::OutOfRange {
+                value: point,
+                reason: "the result is too large to represent".to_string(),
+            }),
+            result => Ok(result),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(value.with_points(&points))
+}
+
+/// Reads `token` as a part of a compound unit: a unit of [`compound::named`]
+/// or a registered unit of a category in [`compound::CATEGORY_UNITS`]. Units
+/// of packs missing from `units_pack` are left out.
+fn compound_units(token: &str, units_pack: &[&str]) -> Vec<compound::Unit> {
+    let sized = find_unit(token).into_iter().filter_map(|found| {
+        let unit = registered(&found);
+        if unit.pack.is_some_and(|pack| !units_pack.contains(&pack)) {
+            return None;
+        }
+        let (_, reference, size) = compound::CATEGORY_UNITS
+            .iter()
+            .find(|(category, ..)| *category == found.category)?;
+        let convert = unit_registry()
+            .converter(&unit.name, reference, &unit.dimension)
+            .ok()?;
+        (convert(0.0) == 0.0).then(|| compound::Unit {
+            factor: convert(1.0) * size.factor,
+            dimension: size.dimension,
+        })
+    });
+    compound::named(token).into_iter().chain(sized).collect()
+}
+
+/// A conversion between two fixed units, shareable across threads.
+pub type Converter = std::sync::Arc<dyn Fn(f64) -> Result<f64, ConvertError> + Send + Sync>;
+
+/// Returns a converter between two units found in one category: the
+/// category's own conversion, so that it agrees with the category subcommand,
+/// or else the registry's affine map between them.
+pub fn pair_converter(from: &UnitMatch, to: &UnitMatch) -> Result<Converter, ConvertError> {
+    if let Some(convert) = category_converter(from.category, &from.name, &to.name) {
+        return Ok(convert);
+    }
+    let convert = unit_registry().converter(&from.name, &to.name, from.category)?;
+    Ok(std::sync::Arc::new(move |value| {
+        quantity::check_finite(value).map(&convert)
+    }))
+}
+
+/// How a resolved conversion shows a unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitLabel {
+    /// The canonical name, see [`ConversionResult::from_unit`].
+    pub id: String,
+    pub singular: String,
+    pub plural: String,
+    pub symbol: String,
+    /// Where the unit's definition is published, see [`registry::source`].
+    pub source: Option<&'static str>,
+}
+
+/// Two units resolved to the conversion between them, see
+/// [`resolve_conversion`].
+#[derive(Clone)]
+pub struct Resolved {
+    pub category: String,
+    pub from: UnitLabel,
+    pub to: UnitLabel,
+    convert: Converter,
+}
+
+impl Resolved {
+    /// Converts `value`, failing like
+    /// [`convert_units`](convertx::unit::convert_units), naming the units for
+    /// the amounts as `shown` writes them.
+    pub fn convert(
+        &self,
+        value: &Amount,
+        shown: impl Fn(&Amount) -> String,
+    ) -> Result<ConversionResult, ConvertError> {
+        let result = convert_points(value, &*self.convert)?;
+        let name = |unit: &UnitLabel, amount: &Amount| match amount.is_singular(&shown(amount)) {
+            true => unit.singular.clone(),
+            false => unit.plural.clone(),
+        };
+        Ok(ConversionResult {
+            category: self.category.clone(),
+            value: *value,
+            from: name(&self.from, value),
+            to: name(&self.to, &result),
+            from_symbol: self.from.symbol.clone(),
+            to_symbol: self.to.symbol.clone(),
+            result,
+            from_unit: self.from.id.clone(),
+            to_unit: self.to.id.clone(),
+        })
+    }
+}
+
+/// Resolves `from` and `to` to units of one category like [`resolve_pair`],
+/// or else, unless `category` is given, to compound units of one dimension,
+/// see [`compound::conversion`], whose category is named after the dimension.
+/// Units of packs missing from `units_pack` are an error.
+///
+/// # Example
+/// ```
+/// let resolved = resolve_conversion("km/h", "m/s", None, &[]).unwrap();
+/// let shown = |amount: &Amount| amount.render(|v| v.to_string());
+/// assert_eq!(resolved.convert(&"36".parse().unwrap(), shown).unwrap().result, Amount::Exact(10.0));
+/// ```
+pub fn resolve_conversion(
+    from: &str,
+    to: &str,
+    category: Option<&str>,
+    units_pack: &[&str],
+) -> Result<Resolved, ConvertError> {
+    let (from_unit, to_unit, convert) = match resolve_pair(from, to, category) {
+        Ok((from, to)) => (
+            registered(&from),
+            registered(&to),
+            pair_converter(&from, &to)?,
+        ),
+        // An ambiguous informal unit such as `oz` is not read as a compound
+        // unit either, nor are units given a category they are not in.
+        Err(error) if [from, to].into_iter().any(registry::is_informal) => return Err(error),
+        Err(error) if category.is_some() => return Err(error),
+        // Units outside a single category, e.g. `kg*m/s^2` and `N`, convert
+        // when their dimensions match.
+        Err(error) => {
+            let atom = |token: &str| compound_units(token, units_pack);
+            let (factor, dimension) = match compound::conversion(from, to, &atom) {
+                Ok(conversion) => conversion,
+                Err(mismatch @ ConvertError::DimensionMismatch { .. }) => return Err(mismatch),
+                Err(_) => return Err(error),
+            };
+            let label = |text: &str| {
+                let (singular, plural) =
+                    compound::names(text).unwrap_or_else(|| (text.to_string(), text.to_string()));
+                UnitLabel {
+                    id: text.to_string(),
+                    singular,
+                    plural,
+                    symbol: text.to_string(),
+                    source: None,
+                }
+            };
+            return Ok(Resolved {
+                category: dimension
+                    .name()
+                    .map_or_else(|| dimension.to_string(), str::to_string),
+                from: label(from),
+                to: label(to),
+                convert: std::sync::Arc::new(move |value| Ok(value * factor)),
+            });
+        }
+    };
+    for unit in [from_unit, to_unit] {
+        if let Some(pack) = unit.pack.filter(|pack| !units_pack.contains(pack)) {
+            return Err(ConvertError::Parse(format!(
+                "'{}' is part of the {} unit pack, enable it with --units-pack {}",
+                unit.name, pack, pack
+            )));
+        }
+    }
+    let label = |unit: &UnitDef| UnitLabel {
+        id: unit.name.clone(),
+        singular: unit.singular.clone(),
+        plural: unit.plural.clone(),
+        symbol: unit.symbol.clone(),
+        source: match unit.pack {
+            Some(_) => None,
+            None => registry::source(&unit.name, &unit.dimension),
+        },
+    };
+    Ok(Resolved {
+        category: from_unit.dimension.clone(),
+        from: label(from_unit),
+        to: label(to_unit),
+        convert,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use convertx::unit::convert_units;
+    use convertx::units::{LengthUnit, TempUnit};
+
+    #[test]
+    fn test_resolve_unit() {
+        let found = resolve_unit("ft", None, find_unit("ft")).unwrap();
+        assert_eq!((found.category, found.name.as_str()), ("length", "feet"));
+        assert!(resolve_unit("parsec", None, find_unit("parsec")).is_err());
+        assert!(resolve_unit("ft", Some("mass"), find_unit("ft")).is_err());
+
+        let ambiguous = vec![
+            UnitMatch {
+                category: "length",
+                name: "meters".into(),
+                symbol: "m",
+            },
+            UnitMatch {
+                category: "time",
+                name: "minutes".into(),
+                symbol: "min",
+            },
+        ];
+        let err = resolve_unit("m", None, ambiguous.clone()).unwrap_err();
+        assert!(err.contains("meters (length), minutes (time)"));
+        assert_eq!(
+            resolve_unit("m", Some("time"), ambiguous).unwrap().name,
+            "minutes"
+        );
+    }
+
+    #[test]
+    fn test_resolve_pair() {
+        let (from, to) = resolve_pair("km", "mi", None).unwrap();
+        assert_eq!((from.category, to.name.as_str()), ("length", "miles"));
+        let (from, _) = resolve_pair("oz", "ml", Some("volume")).unwrap();
+        assert_eq!(
+            (from.category, from.name.as_str()),
+            ("volume", "fluid_ounces")
+        );
+        assert!(resolve_pair("oz", "g", None).is_err());
+        assert_eq!(
+            resolve_pair("fl oz", "ml", None).unwrap().0.category,
+            "volume"
+        );
+        assert!(matches!(
+            resolve_pair("oz", "oz", None),
+            Err(ConvertError::Parse(message)) if message.contains("is ambiguous")
+        ));
+        assert_eq!(resolve_pair("oz", "oz", Some("mass")).unwrap().0.name, "oz");
+        assert!(matches!(
+            resolve_pair("km", "kg", None),
+            Err(ConvertError::DimensionMismatch { .. })
+        ));
+        assert_eq!(
+            resolve_pair("parsecs", "km", None).unwrap_err(),
+            ConvertError::Parse("unknown unit 'parsecs'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_conversion() {
+        let shown = |amount: &Amount| amount.render(|v| v.to_string());
+        let convert = |value: f64, from: &str, to: &str| {
+            let resolved = resolve_conversion(from, to, None, &[]).unwrap();
+            resolved
+                .convert(&Amount::Exact(value), shown)
+                .unwrap()
+                .result
+        };
+        assert_eq!(convert(32.0, "f", "c"), Amount::Exact(0.0));
+        // Units of a category convert as its subcommand does.
+        let length = convert_units(5.0, LengthUnit::Kilometers, "mi".parse().unwrap());
+        assert_eq!(convert(5.0, "km", "mi"), Amount::Exact(length.unwrap()));
+        let temperature = convert_units(98.6, TempUnit::F, TempUnit::K);
+        assert_eq!(convert(98.6, "f", "k"), Amount::Exact(temperature.unwrap()));
+        assert_eq!(convert(36.0, "km/h", "m/s"), Amount::Exact(10.0));
+        // A category rules out reading the units as compound units.
+        assert!(matches!(
+            resolve_conversion("km", "mi", Some("mass"), &[]),
+            Err(ConvertError::Parse(message)) if message == "unknown mass unit 'km'"
+        ));
+        assert!(resolve_conversion("kg*m/s^2", "N", Some("length"), &[]).is_err());
+    }
+}
@@ -0,0 +1,674 @@
+//! Subcommands that answer conversions over the network: `serve`, a JSON API
+//! and optional gRPC interface, and `bot`, for chat slash commands.
+
+use structopt::StructOpt;
+
+#[cfg(feature = "bot")]
+use convertx::bot;
+#[cfg(feature = "grpc")]
+use convertx::conversion::Amount;
+use convertx::conversion::{ConversionResult, Measurement};
+use convertx::error::ConvertError;
+#[cfg(feature = "grpc")]
+use convertx::grpc;
+use convertx::registry::UnitDef;
+use convertx::{cache, json, metrics, openapi, pack, serve};
+
+use super::category::{unit_registry, CATEGORIES};
+#[cfg(feature = "bot")]
+use super::convert::{evaluate, show_value};
+use super::output::conversion_json;
+#[cfg(feature = "bot")]
+use super::output::NumberFormat;
+use super::resolve::{resolve_conversion, Resolved, CATEGORY_CHOICE};
+use crate::fail;
+
+// The command line is parsed once, so the size of `bot`'s options is no cost.
+#[allow(clippy::large_enum_variant)]
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Serve conversions as JSON over HTTP, e.g.
+    /// `curl "localhost:8080/convert?value=5&from=km&to=mi"`.
+    Serve {
+        /// Address to listen on; port 0 picks a free port.
+        #[structopt(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// Number of resolved unit pairs to keep; 0 turns the cache off.
+        #[structopt(long, default_value = "1024")]
+        cache_size: usize,
+        /// Requests each client address may make a minute; 0 for no limit.
+        #[structopt(long, default_value = "600")]
+        rate_limit: u32,
+        /// The most values one request may convert.
+        #[structopt(long, default_value = "1000")]
+        max_batch: usize,
+        /// The longest request line, headers and body accepted, in bytes.
+        #[structopt(long, default_value = "8192")]
+        max_request_bytes: usize,
+        /// Seconds a client may take to send its request or read the response.
+        #[structopt(long, default_value = "10")]
+        timeout: u64,
+        /// Connections handled at once, further ones waiting; 0 for no limit.
+        #[structopt(long, default_value = "256")]
+        max_connections: usize,
+        /// Enable an optional unit pack compiled into this build (repeatable).
+        #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+        units_pack: Vec<&'static str>,
+        /// Also serve the gRPC interface on this address, e.g. 127.0.0.1:50051.
+        #[cfg(feature = "grpc")]
+        #[structopt(long)]
+        grpc: Option<std::net::SocketAddr>,
+        /// Print the OpenAPI 3 document of the endpoints instead of serving them.
+        #[structopt(long)]
+        openapi: bool,
+    },
+    /// Answer Slack or Discord slash commands over HTTP, e.g.
+    /// `/convert 5 km to mi`, with the expressions of `eval`.
+    #[cfg(feature = "bot")]
+    Bot {
+        /// Answer Slack slash commands, checked with the app's signing secret.
+        #[structopt(long, required_unless = "discord", conflicts_with = "discord")]
+        slack: bool,
+        /// Answer Discord interactions, checked with the application's public key.
+        #[structopt(long)]
+        discord: bool,
+        /// The Slack app's signing secret.
+        #[structopt(long, env = "SLACK_SIGNING_SECRET", hide_env_values = true)]
+        signing_secret: Option<String>,
+        /// The Discord application's public key, in hexadecimal.
+        #[structopt(long, env = "DISCORD_PUBLIC_KEY", parse(try_from_str = bot::public_key))]
+        public_key: Option<ed25519_dalek::VerifyingKey>,
+        /// Answer requests without checking their signatures, to try the bot locally.
+        #[structopt(long, conflicts_with_all = &["signing-secret", "public-key"])]
+        no_verify: bool,
+        /// Address to listen on; port 0 picks a free port.
+        #[structopt(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// The longest request line, headers and body accepted, in bytes.
+        #[structopt(long, default_value = "16384")]
+        max_request_bytes: usize,
+        /// Seconds a client may take to send its request or read the response.
+        #[structopt(long, default_value = "10")]
+        timeout: u64,
+        /// Connections handled at once, further ones waiting; 0 for no limit.
+        #[structopt(long, default_value = "256")]
+        max_connections: usize,
+        /// Category to read a unit in when it could belong to several.
+        #[structopt(long, possible_values = CATEGORIES, case_insensitive = true)]
+        category: Option<String>,
+        /// Enable an optional unit pack compiled into this build (repeatable).
+        #[structopt(long = "units-pack", number_of_values = 1, parse(try_from_str = pack::parse_name))]
+        units_pack: Vec<&'static str>,
+        /// Show unit symbols (e.g. "km") instead of spelled-out names.
+        #[structopt(long)]
+        symbols: bool,
+        #[structopt(flatten)]
+        number: NumberFormat,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Serve {
+                listen,
+                cache_size,
+                rate_limit,
+                max_batch,
+                max_request_bytes,
+                timeout,
+                max_connections,
+                units_pack,
+                openapi,
+                #[cfg(feature = "grpc")]
+                grpc,
+            } => {
+                if openapi {
+                    let units: Vec<UnitDef> =
+                        enabled_units(&units_pack).into_iter().cloned().collect();
+                    let server = format!("http://{}", listen);
+                    let version = env!("CARGO_PKG_VERSION");
+                    let document =
+                        openapi::document(&server, version, CATEGORIES, &units, max_batch);
+                    println!("{}", document);
+                    return;
+                }
+                let listener = std::net::TcpListener::bind(&listen).unwrap_or_else(|error| {
+                    fail(ConvertError::Io(format!(
+                        "cannot listen on {}: {}",
+                        listen, error
+                    )))
+                });
+                if let Ok(address) = listener.local_addr() {
+                    eprintln!("listening on http://{}", address);
+                }
+                // Both the HTTP API and gRPC take a category as a `category` parameter.
+                CATEGORY_CHOICE
+                    .set("the category parameter")
+                    .expect("the server is started once");
+                let limits = serve::Limits {
+                    requests_per_minute: rate_limit,
+                    max_request_bytes,
+                    timeout: std::time::Duration::from_secs(timeout),
+                    max_connections,
+                };
+                let service =
+                    std::sync::Arc::new(ConvertService::new(cache_size, max_batch, units_pack));
+                #[cfg(feature = "grpc")]
+                if let Some(address) = grpc {
+                    let listener = std::net::TcpListener::bind(address).unwrap_or_else(|error| {
+                        fail(ConvertError::Io(format!(
+                            "cannot listen on {}: {}",
+                            address, error
+                        )))
+                    });
+                    if let Ok(address) = listener.local_addr() {
+                        eprintln!("listening on grpc://{}", address);
+                    }
+                    let service = GrpcService(std::sync::Arc::clone(&service));
+                    let timeout = limits.timeout;
+                    std::thread::spawn(move || {
+                        if let Err(error) = grpc::run(listener, timeout, service) {
+                            fail(ConvertError::Io(error.to_string()));
+                        }
+                    });
+                }
+                let metrics = std::sync::Arc::clone(&service.metrics);
+                if let Err(error) = serve::run(listener, limits, metrics, move |request| {
+                    service.handle(request)
+                }) {
+                    fail(ConvertError::Io(error.to_string()));
+                }
+            }
+            #[cfg(feature = "bot")]
+            Command::Bot {
+                slack,
+                discord,
+                signing_secret,
+                public_key,
+                no_verify,
+                listen,
+                max_request_bytes,
+                timeout,
+                max_connections,
+                category,
+                units_pack,
+                symbols,
+                number,
+            } => {
+                let unverified = match (slack, discord) {
+                    (true, _) if signing_secret.is_none() => {
+                        Some("--signing-secret (or SLACK_SIGNING_SECRET) is needed to check that requests come from Slack")
+                    }
+                    (_, true) if public_key.is_none() => {
+                        Some("--public-key (or DISCORD_PUBLIC_KEY) is needed to check that requests come from Discord")
+                    }
+                    _ => None,
+                };
+                if let Some(message) = unverified.filter(|_| !no_verify) {
+                    fail(ConvertError::Parse(format!(
+                        "{}, or --no-verify to try the bot locally",
+                        message
+                    )));
+                }
+                let bot = match slack {
+                    true => bot::Bot::Slack { signing_secret },
+                    false => bot::Bot::Discord { public_key },
+                };
+                let listener = std::net::TcpListener::bind(&listen).unwrap_or_else(|error| {
+                    fail(ConvertError::Io(format!(
+                        "cannot listen on {}: {}",
+                        listen, error
+                    )))
+                });
+                if let Ok(address) = listener.local_addr() {
+                    eprintln!("listening on http://{}", address);
+                }
+                // Every command comes from the platform's own addresses, so limiting
+                // the requests of each would limit all users together.
+                let limits = serve::Limits {
+                    requests_per_minute: 0,
+                    max_request_bytes,
+                    timeout: std::time::Duration::from_secs(timeout),
+                    max_connections,
+                };
+                let metrics = std::sync::Arc::new(metrics::Metrics::new(&["/"], CATEGORIES));
+                let convert = move |question: &str| {
+                    evaluate(question, category.as_deref(), &units_pack)
+                        .map(|value| show_value(&value, &number, symbols))
+                        .map_err(|error| error.to_string())
+                };
+                let handler = move |request: &serve::Request| match request.path.as_str() {
+                    "/" if request.method == "POST" => {
+                        bot.answer(request, std::time::SystemTime::now(), &convert)
+                    }
+                    "/" => serve::Response::error(405, "only POST is supported"),
+                    path => serve::Response::error(404, &format!("no endpoint at {}", path)),
+                };
+                if let Err(error) = serve::run(listener, limits, metrics, handler) {
+                    fail(ConvertError::Io(error.to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// The built-in units and those of `units_pack`, as `serve` lists them.
+fn enabled_units(units_pack: &[&str]) -> Vec<&'static UnitDef> {
+    unit_registry()
+        .units()
+        .iter()
+        .filter(|unit| unit.pack.is_none_or(|pack| units_pack.contains(&pack)))
+        .collect()
+}
+
+/// Cache keys of [`ConvertService`]: the category asked for, if any, and the
+/// units as written.
+type PairKey = (Option<String>, String, String);
+
+/// The `convertx serve` API, which keeps the unit pairs it resolves in an LRU
+/// cache and counts what it does for `/info`.
+struct ConvertService {
+    units_pack: Vec<&'static str>,
+    /// The most values one request may convert.
+    max_batch: usize,
+    cache: std::sync::Mutex<cache::LruCache<PairKey, Result<Resolved, ConvertError>>>,
+    requests: std::sync::atomic::AtomicU64,
+    started: std::time::Instant,
+    /// Shared with the server, which records every response in it.
+    metrics: std::sync::Arc<metrics::Metrics>,
+}
+
+/// The endpoints of [`ConvertService`], which `/metrics` labels requests with.
+const ENDPOINTS: &[&str] = &["/", "/convert", "/info", "/metrics"];
+
+impl ConvertService {
+    fn new(cache_size: usize, max_batch: usize, units_pack: Vec<&'static str>) -> Self {
+        ConvertService {
+            units_pack,
+            max_batch,
+            cache: std::sync::Mutex::new(cache::LruCache::new(cache_size)),
+            requests: Default::default(),
+            started: std::time::Instant::now(),
+            metrics: std::sync::Arc::new(metrics::Metrics::new(ENDPOINTS, CATEGORIES)),
+        }
+    }
+
+    /// Routes `request` to its endpoint.
+    fn handle(&self, request: &serve::Request) -> serve::Response {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/convert") if request.params("value").count() > self.max_batch => {
+                serve::Response::error(
+                    413,
+                    &format!("at most {} values can be converted at once", self.max_batch),
+                )
+            }
+            ("GET", "/convert") => match self.convert(request) {
+                Ok(body) => serve::Response::json(body),
+                Err(error) => serve::Response::error(400, &error.to_string()),
+            },
+            ("GET", "/") => serve::Response {
+                content_type: "text/html; charset=utf-8",
+                ..serve::Response::json(serve::INDEX.to_string())
+            },
+            ("GET", "/info") => serve::Response::json(self.info()),
+            ("GET", "/metrics") => serve::Response {
+                content_type: "text/plain; version=0.0.4",
+                ..serve::Response::json(self.metrics())
+            },
+            (_, "/" | "/convert" | "/info" | "/metrics") => {
+                serve::Response::error(405, "only GET is supported")
+            }
+            (_, path) => serve::Response::error(404, &format!("no endpoint at {}", path)),
+        }
+    }
+
+    /// `GET /convert?value=5&from=km&to=mi`, with an optional `category`; the
+    /// unit may be written with the value instead, as in `value=5km`. Several
+    /// `value`s are converted to a JSON array.
+    fn convert(&self, request: &serve::Request) -> Result<String, ConvertError> {
+        let param = |name: &str| request.param(name).filter(|value| !value.is_empty());
+        let required = |name: &str| {
+            param(name)
+                .ok_or_else(|| ConvertError::Parse(format!("missing the {} parameter", name)))
+        };
+        let (to, category) = (required("to")?, param("category"));
+        let values: Vec<&str> = request
+            .params("value")
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.is_empty() {
+            required("value")?;
+        }
+        let mut objects = Vec::new();
+        for value in &values {
+            let conversion = self.convert_value(value, param("from"), to, category)?;
+            objects.push(conversion_json(&conversion));
+        }
+        Ok(match objects.len() {
+            1 => objects.remove(0),
+            _ => format!("[{}]", objects.join(",")),
+        })
+    }
+
+    /// Converts `value` from the unit written with it, or else `from`, to
+    /// `to`, looking the pair up in the cache.
+    fn convert_value(
+        &self,
+        value: &str,
+        from: Option<&str>,
+        to: &str,
+        category: Option<&str>,
+    ) -> Result<ConversionResult, ConvertError> {
+        let value: Measurement = value.parse()?;
+        let from = match (value.unit.as_deref(), from) {
+            (Some(written), Some(from)) if written != from => {
+                return Err(ConvertError::Parse(format!(
+                    "the value is in {} but from is {}",
+                    written, from
+                )))
+            }
+            (Some(written), _) => written,
+            (None, Some(from)) => from,
+            (None, None) => {
+                return Err(ConvertError::Parse(
+                    "missing the from parameter".to_string(),
+                ))
+            }
+        };
+        let key = (
+            category.map(str::to_string),
+            from.to_string(),
+            to.to_string(),
+        );
+        let resolved = self
+            .cache
+            .lock()
+            .expect("the cache lock is not poisoned")
+            .get_or_insert_with(key, || {
+                resolve_conversion(from, to, category, &self.units_pack)
+            })?;
+        // The numbers are sent unrounded, so the units are named for them.
+        let conversion =
+            resolved.convert(&value.amount, |amount| amount.render(|v| v.to_string()))?;
+        self.metrics.conversion(&conversion.category);
+        Ok(conversion)
+    }
+
+    /// `GET /metrics`: the server's [`metrics::Metrics`], with the uptime and
+    /// cache counters, in the Prometheus text format.
+    fn metrics(&self) -> String {
+        let stats = self
+            .cache
+            .lock()
+            .expect("the cache lock is not poisoned")
+            .stats();
+        let mut gauges = String::new();
+        for (name, kind, help, value) in [
+            (
+                "convertx_uptime_seconds",
+                "gauge",
+                "Seconds since the server started.",
+                self.started.elapsed().as_secs(),
+            ),
+            (
+                "convertx_cache_entries",
+                "gauge",
+                "Unit pairs in the cache.",
+                stats.entries as u64,
+            ),
+            (
+                "convertx_cache_hits_total",
+                "counter",
+                "Unit pairs found in the cache.",
+                stats.hits,
+            ),
+            (
+                "convertx_cache_misses_total",
+                "counter",
+                "Unit pairs looked up and added to the cache.",
+                stats.misses,
+            ),
+            (
+                "convertx_cache_evictions_total",
+                "counter",
+                "Unit pairs dropped from the cache to make room.",
+                stats.evictions,
+            ),
+        ] {
+            gauges += &format!(
+                "# HELP {} {}\n# TYPE {} {}\n{} {}\n",
+                name, help, name, kind, name, value
+            );
+        }
+        self.metrics.render(&gauges)
+    }
+
+    /// `GET /info`: the version, uptime, requests served and cache counters.
+    fn info(&self) -> String {
+        use json::Value::{Number, Object, Text};
+        let stats = self
+            .cache
+            .lock()
+            .expect("the cache lock is not poisoned")
+            .stats();
+        let requests = self.requests.load(std::sync::atomic::Ordering::Relaxed);
+        Object(vec![
+            ("version", Text(env!("CARGO_PKG_VERSION").to_string())),
+            (
+                "uptime_seconds",
+                Number(self.started.elapsed().as_secs() as f64),
+            ),
+            ("requests", Number(requests as f64)),
+            (
+                "cache",
+                Object(vec![
+                    ("capacity", Number(stats.capacity as f64)),
+                    ("entries", Number(stats.entries as f64)),
+                    ("hits", Number(stats.hits as f64)),
+                    ("misses", Number(stats.misses as f64)),
+                    ("evictions", Number(stats.evictions as f64)),
+                ]),
+            ),
+        ])
+        .to_string()
+    }
+}
+
+/// The gRPC interface of a [`ConvertService`], see `proto/convertx.proto`.
+#[cfg(feature = "grpc")]
+struct GrpcService(std::sync::Arc<ConvertService>);
+
+#[cfg(feature = "grpc")]
+impl GrpcService {
+    /// Counts a call for `/info` and reads an empty string as not given.
+    fn call<'a>(&self, category: &'a str) -> Option<&'a str> {
+        self.0
+            .requests
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Some(category).filter(|category| !category.is_empty())
+    }
+
+    /// Converts `values` as [`ConvertService::convert_value`] does.
+    fn convert<'a>(
+        &self,
+        values: impl IntoIterator<Item = &'a str>,
+        from: &str,
+        to: &str,
+        category: Option<&str>,
+    ) -> Result<Vec<grpc::proto::Conversion>, tonic::Status> {
+        let from = Some(from).filter(|from| !from.is_empty());
+        values
+            .into_iter()
+            .map(|value| {
+                self.0
+                    .convert_value(value, from, to, category)
+                    .map(grpc_conversion)
+                    .map_err(|error| tonic::Status::invalid_argument(error.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Returns `amount` as a protobuf message.
+#[cfg(feature = "grpc")]
+fn grpc_amount(amount: &Amount) -> grpc::proto::Amount {
+    use grpc::proto::{amount::Kind, Range, Tolerance};
+    let kind = match *amount {
+        Amount::Exact(value) => Kind::Exact(value),
+        Amount::Tolerance(nominal, tolerance) => Kind::Tolerance(Tolerance { nominal, tolerance }),
+        Amount::Range(min, max) => Kind::Range(Range { min, max }),
+    };
+    grpc::proto::Amount { kind: Some(kind) }
+}
+
+/// Returns `conversion` as a protobuf message, with the fields of
+/// [`conversion_json`].
+#[cfg(feature = "grpc")]
+fn grpc_conversion(conversion: ConversionResult) -> grpc::proto::Conversion {
+    grpc::proto::Conversion {
+        value: Some(grpc_amount(&conversion.value)),
+        result: Some(grpc_amount(&conversion.result)),
+        category: conversion.category,
+        from: conversion.from_unit,
+        to: conversion.to_unit,
+        from_symbol: conversion.from_symbol,
+        to_symbol: conversion.to_symbol,
+    }
+}
+
+#[cfg(feature = "grpc")]
+#[tonic::async_trait]
+impl grpc::Converter for GrpcService {
+    async fn list_units(
+        &self,
+        request: tonic::Request<grpc::proto::ListUnitsRequest>,
+    ) -> Result<tonic::Response<grpc::proto::ListUnitsResponse>, tonic::Status> {
+        let category = self.call(&request.get_ref().category);
+        let units = enabled_units(&self.0.units_pack)
+            .into_iter()
+            .filter(|unit| category.is_none_or(|c| unit.dimension.eq_ignore_ascii_case(c)))
+            .map(|unit| grpc::proto::Unit {
+                name: unit.name.clone(),
+                symbol: unit.symbol.clone(),
+                category: unit.dimension.clone(),
+                pack: unit.pack.unwrap_or_default().to_string(),
+            })
+            .collect();
+        Ok(tonic::Response::new(grpc::proto::ListUnitsResponse {
+            units,
+        }))
+    }
+
+    async fn convert(
+        &self,
+        request: tonic::Request<grpc::proto::ConvertRequest>,
+    ) -> Result<tonic::Response<grpc::proto::Conversion>, tonic::Status> {
+        let request = request.get_ref();
+        let category = self.call(&request.category);
+        let mut conversions = self.convert(
+            [request.value.as_str()],
+            &request.from,
+            &request.to,
+            category,
+        )?;
+        Ok(tonic::Response::new(conversions.remove(0)))
+    }
+
+    async fn batch_convert(
+        &self,
+        request: tonic::Request<grpc::proto::BatchConvertRequest>,
+    ) -> Result<tonic::Response<grpc::proto::BatchConvertResponse>, tonic::Status> {
+        let request = request.get_ref();
+        let category = self.call(&request.category);
+        if request.values.len() > self.0.max_batch {
+            return Err(tonic::Status::invalid_argument(format!(
+                "at most {} values can be converted at once",
+                self.0.max_batch
+            )));
+        }
+        let values = request.values.iter().map(String::as_str);
+        let conversions = self.convert(values, &request.from, &request.to, category)?;
+        Ok(tonic::Response::new(grpc::proto::BatchConvertResponse {
+            conversions,
+        }))
+    }
+}
+
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_service() {
+        use grpc::proto::{
+            amount::Kind, converter_client::ConverterClient, BatchConvertRequest, ConvertRequest,
+            ListUnitsRequest,
+        };
+        let service = std::sync::Arc::new(ConvertService::new(8, 2, Vec::new()));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let timeout = std::time::Duration::from_secs(10);
+        let grpc = GrpcService(std::sync::Arc::clone(&service));
+        std::thread::spawn(move || grpc::run(listener, timeout, grpc));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let mut client = ConverterClient::connect(format!("http://{}", address))
+                .await
+                .unwrap();
+            let request = |value: &str, from: &str| ConvertRequest {
+                value: value.to_string(),
+                from: from.to_string(),
+                to: "mi".to_string(),
+                category: String::new(),
+            };
+            let conversion = client.convert(request("5km", "")).await.unwrap();
+            let conversion = conversion.into_inner();
+            assert_eq!(
+                (conversion.from.as_str(), conversion.to.as_str()),
+                ("kilometers", "miles")
+            );
+            let Some(Kind::Exact(miles)) = conversion.result.and_then(|result| result.kind) else {
+                panic!("an exact value converts to an exact result");
+            };
+            assert!((miles - 3.106856).abs() < 1e-6);
+            let error = client.convert(request("5", "kg")).await.unwrap_err();
+            assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+            let batch = |values: &[&str]| BatchConvertRequest {
+                values: values.iter().map(|value| value.to_string()).collect(),
+                from: "km".to_string(),
+                to: "mi".to_string(),
+                category: String::new(),
+            };
+            let conversions = client.batch_convert(batch(&["1", "2..3"])).await.unwrap();
+            assert_eq!(conversions.into_inner().conversions.len(), 2);
+            let error = client
+                .batch_convert(batch(&["1", "2", "3"]))
+                .await
+                .unwrap_err();
+            assert!(error.message().contains("at most 2 values"));
+
+            let units = client
+                .list_units(ListUnitsRequest {
+                    category: "Length".to_string(),
+                })
+                .await
+                .unwrap()
+                .into_inner()
+                .units;
+            assert!(units.iter().all(|unit| unit.category == "length"));
+            assert!(units.iter().any(|unit| unit.symbol == "km"));
+        });
+        // The km to mi pair was resolved once and shared by every call.
+        let stats = service.cache.lock().unwrap().stats();
+        assert_eq!((stats.misses, stats.hits), (2, 2));
+    }
+}
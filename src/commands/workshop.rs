@@ -0,0 +1,407 @@
+//! Workshop subcommands: wire gauges, drill sizes, bicycle gearing, tires,
+//! fabric, paint and bulk materials.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::quantity::{Area, Distance, Volume, Weight};
+use convertx::unit::convert_units;
+use convertx::units::{SpeedUnit, VolumeUnit, KG_IN_LB};
+use convertx::{drill, fabric, gear, material, paint, quantity, tire, wire};
+
+use super::output::{fixed, Figure};
+use crate::{check_positive, fail};
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert a wire gauge (AWG) to its size, or a size to the nearest gauge.
+    Awg {
+        /// A gauge such as 12 or 4/0, or a size with `--from mm2|diameter-mm`.
+        value: String,
+        /// What the value is.
+        #[structopt(short = "f", long, default_value = "awg", possible_values = &WireSize::variants(), case_insensitive = true)]
+        from: WireSize,
+        /// What to convert to.
+        #[structopt(short = "t", long, possible_values = &WireSize::variants(), case_insensitive = true)]
+        to: WireSize,
+    },
+    /// Convert a drill bit size and suggest the nearest standard bit.
+    Drill {
+        /// Bit size, e.g. 7/32in or 5.5mm.
+        size: Distance,
+        /// Unit to convert to.
+        #[structopt(short = "t", long, possible_values = &DrillUnit::variants(), case_insensitive = true)]
+        to: DrillUnit,
+    },
+    /// Compute bicycle gearing: ratio, development, gear inches or speed.
+    Gear {
+        /// Teeth on the chainring.
+        #[structopt(long)]
+        chainring: u32,
+        /// Teeth on the rear cog.
+        #[structopt(long)]
+        cog: u32,
+        /// Wheel size, e.g. 700x25c or 29x2.2, or its circumference in mm.
+        #[structopt(long, default_value = "700x25c")]
+        wheel: gear::Wheel,
+        /// Pedalling cadence in revolutions per minute, needed for speed.
+        #[structopt(long, parse(try_from_str = quantity::parse_number))]
+        cadence: Option<f64>,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &GearTarget::variants(), case_insensitive = true)]
+        to: GearTarget,
+    },
+    /// Compute tire dimensions and the speedometer error of a different size.
+    Tire {
+        /// Metric tire size, e.g. 225/45R17.
+        size: tire::TireSize,
+        /// Dimension to compute.
+        #[structopt(short = "t", long, possible_values = &TireDimension::variants(), case_insensitive = true)]
+        to: TireDimension,
+        /// Another tire size to compare against, e.g. 215/55R17.
+        #[structopt(long)]
+        compare: Option<tire::TireSize>,
+    },
+    /// Convert a fabric requirement to another length unit and bolt width.
+    Fabric {
+        /// Length and width the pattern calls for, e.g. 2.5yd@45in.
+        #[structopt(long)]
+        pattern: fabric::Cut,
+        /// Length unit and width to buy, e.g. m@140cm.
+        #[structopt(short = "t", long)]
+        to: fabric::Target,
+    },
+    /// Estimate the paint needed to cover an area.
+    Paint {
+        /// Area to paint, e.g. 45m2 or 400ft2.
+        #[structopt(long)]
+        area: Area,
+        /// Spread rate from the tin, e.g. 10m2/L or 350ft2/gal.
+        #[structopt(long)]
+        coverage: paint::Coverage,
+        /// Number of coats.
+        #[structopt(long, default_value = "1")]
+        coats: u32,
+        /// Unit of paint to report.
+        #[structopt(short = "t", long, possible_values = &PaintUnit::variants(), case_insensitive = true)]
+        to: PaintUnit,
+    },
+    /// Estimate the weight or number of bags of a volume of bulk material.
+    Material {
+        /// Volume needed, e.g. 2.5m3 or 3yd3.
+        #[structopt(long)]
+        volume: Volume,
+        /// Material, which sets the density.
+        #[structopt(long)]
+        material: material::Material,
+        /// Weight of one bag.
+        #[structopt(long, default_value = "25kg")]
+        bag: Weight,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &MaterialTarget::variants(), case_insensitive = true)]
+        to: MaterialTarget,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Awg { value, from, to } => {
+                let show = |diameter: f64, unit: &WireSize| match unit {
+                    WireSize::Mm2 => format!("{} mm²", fixed(wire::area_mm2(diameter), 4)),
+                    _ => format!("{} mm diameter", fixed(diameter, 4)),
+                };
+                let figure = |diameter: f64, unit: &WireSize| match unit {
+                    WireSize::Mm2 => Figure::number(wire::area_mm2(diameter), 4, "mm²"),
+                    _ => Figure::number(diameter, 4, "mm"),
+                };
+                let line = match from {
+                    WireSize::Awg => wire::parse_gauge(&value).map(|gauge| {
+                        let name = wire::gauge_name(gauge);
+                        let awg = Figure::text(&name, "AWG");
+                        match to {
+                            WireSize::Awg => (
+                                awg,
+                                Figure::text(&name, "AWG"),
+                                format!("{} AWG = {} AWG", name, name),
+                            ),
+                            _ => {
+                                let diameter = wire::diameter_mm(gauge);
+                                let line = format!("{} AWG = {}", name, show(diameter, &to));
+                                (awg, figure(diameter, &to), line)
+                            }
+                        }
+                    }),
+                    _ => match quantity::parse_number(&value) {
+                        Ok(number) if number > 0.0 => {
+                            let diameter = if from == WireSize::Mm2 {
+                                wire::diameter_from_area(number)
+                            } else {
+                                number
+                            };
+                            let input = show(diameter, &from);
+                            let given = figure(diameter, &from);
+                            Ok(match to {
+                                WireSize::Awg => {
+                                    let gauge = wire::nearest_gauge(diameter);
+                                    let name = wire::gauge_name(gauge);
+                                    let line = format!(
+                                        "{} ≈ {} AWG ({})",
+                                        input,
+                                        name,
+                                        show(wire::diameter_mm(gauge), &from)
+                                    );
+                                    (given, Figure::text(name, "AWG"), line)
+                                }
+                                _ => {
+                                    let line = format!("{} = {}", input, show(diameter, &to));
+                                    (given, figure(diameter, &to), line)
+                                }
+                            })
+                        }
+                        _ => Err(format!("'{}' is not a positive wire size", value)),
+                    },
+                };
+                match line {
+                    Ok((value, result, line)) => say!(value => result, "{}", line),
+                    Err(message) => fail(ConvertError::Parse(message)),
+                }
+            }
+            Command::Drill { size, to } => {
+                check_positive([&size.km], "drill sizes must be greater than zero");
+                match to {
+                    DrillUnit::Mm => {
+                        let mm = size.km * 1e6;
+                        say!(
+                            Figure::text(&size, "drill size") => Figure::number(mm, 4, "mm"),
+                            "{} = {} mm (nearest metric bit: {} mm)",
+                            size,
+                            fixed(mm, 4),
+                            drill::nearest_metric_bit(mm)
+                        );
+                    }
+                    DrillUnit::In => {
+                        let inches = size.km / 2.54e-5;
+                        let (numerator, denominator) = drill::nearest_inch_bit(inches);
+                        say!(
+                            Figure::text(&size, "drill size") => Figure::number(inches, 4, "in"),
+                            "{} = {} in (nearest fractional bit: {}/{} in)",
+                            size,
+                            fixed(inches, 4),
+                            numerator,
+                            denominator
+                        );
+                    }
+                }
+            }
+            Command::Gear {
+                chainring,
+                cog,
+                wheel,
+                cadence,
+                to,
+            } => {
+                check_positive(
+                    &[f64::from(chainring), f64::from(cog)],
+                    "--chainring and --cog must be greater than zero",
+                );
+                let gearing = format!("{}x{} on {}", chainring, cog, wheel);
+                let gears = Figure::text(&gearing, "gearing");
+                match (to, cadence) {
+                    (GearTarget::Speed, Some(cadence)) => {
+                        let kph = gear::speed_kph(chainring, cog, &wheel, cadence);
+                        say!(
+                            Figure::number(cadence, 0, "rpm") => Figure::number(kph, 1, "km/h"),
+                            "{} at {} rpm = {} km/h ({} mph)",
+                            gearing,
+                            cadence,
+                            fixed(kph, 1),
+                            fixed(
+                                convert_units(kph, SpeedUnit::Kph, SpeedUnit::Mph)
+                                    .unwrap_or(f64::NAN),
+                                1
+                            )
+                        );
+                    }
+                    (GearTarget::Speed, None) => fail(ConvertError::Parse(
+                        "--to speed needs --cadence".to_string(),
+                    )),
+                    (GearTarget::Ratio, _) => {
+                        let ratio = f64::from(chainring) / f64::from(cog);
+                        say!(
+                            Figure::text(format!("{}x{}", chainring, cog), "gearing") => Figure::number(ratio, 2, "ratio"),
+                            "{}x{} = {} ratio",
+                            chainring,
+                            cog,
+                            fixed(ratio, 2)
+                        )
+                    }
+                    (GearTarget::Development, _) => {
+                        let development = gear::development_m(chainring, cog, &wheel);
+                        say!(
+                            gears => Figure::number(development, 2, "m"),
+                            "{} = {} m per crank revolution",
+                            gearing,
+                            fixed(development, 2)
+                        )
+                    }
+                    (GearTarget::GearInches, _) => {
+                        let inches = gear::gear_inches(chainring, cog, &wheel);
+                        say!(
+                            gears => Figure::number(inches, 1, "gear inches"),
+                            "{} = {} gear inches",
+                            gearing,
+                            fixed(inches, 1)
+                        )
+                    }
+                }
+            }
+            Command::Tire { size, to, compare } => {
+                let dimension = |tire: &tire::TireSize| {
+                    let mm = match to {
+                        TireDimension::Diameter => tire.diameter_mm(),
+                        TireDimension::Circumference => tire.circumference_mm(),
+                    };
+                    say!(
+                        Figure::text(tire, "tire") => Figure::number(mm, 1, "mm"),
+                        "{} = {} mm ({} in) {}",
+                        tire,
+                        fixed(mm, 1),
+                        fixed(mm / 25.4, 2),
+                        to
+                    );
+                };
+                dimension(&size);
+                if let Some(other) = compare {
+                    dimension(&other);
+                    let error = size.speedometer_error(&other);
+                    say!(
+                        Figure::number(100.0, 0, "km/h") => Figure::number(100.0 + error, 2, "km/h"),
+                        "{} is {}% {} than {}; an indicated 100 km/h is {} km/h",
+                        other,
+                        fixed(error.abs(), 2),
+                        if error < 0.0 { "smaller" } else { "larger" },
+                        size,
+                        fixed(100.0 + error, 2)
+                    );
+                }
+            }
+            Command::Fabric { pattern, to } => {
+                let length = to.length_for(&pattern);
+                say!(
+                    Figure::text(&pattern, "pattern") => Figure::number(length, 2, &to.unit),
+                    "{} = {} {}@{} ({} m²)",
+                    pattern,
+                    fixed(length, 2),
+                    to.unit,
+                    to.width,
+                    fixed(pattern.area_m2(), 2)
+                );
+            }
+            Command::Paint {
+                area,
+                coverage,
+                coats,
+                to,
+            } => {
+                let (unit, symbol) = match to {
+                    PaintUnit::Liters => (VolumeUnit::Liters, "L"),
+                    PaintUnit::Gallons => (VolumeUnit::Gallons, "gal"),
+                };
+                let ml = coverage.ml_needed(&area, coats);
+                let amount = convert_units(ml, VolumeUnit::Milliliters, unit).unwrap_or(f64::NAN);
+                let coats = if coats == 1 {
+                    "1 coat".to_string()
+                } else {
+                    format!("{} coats", coats)
+                };
+                say!(
+                    Figure::text(&area, "area") => Figure::number(amount, 2, symbol),
+                    "{} at {}, {} = {} {}",
+                    area,
+                    coverage,
+                    coats,
+                    fixed(amount, 2),
+                    symbol
+                );
+            }
+            Command::Material {
+                volume,
+                material,
+                bag,
+                to,
+            } => {
+                let kg = material.mass_kg(volume.m3);
+                let (figure, result) = match to {
+                    MaterialTarget::Tons => {
+                        let tons = kg / 1000.0;
+                        (
+                            Figure::number(tons, 2, "t"),
+                            format!("{} t", fixed(tons, 2)),
+                        )
+                    }
+                    MaterialTarget::ShortTons => {
+                        let tons = kg * KG_IN_LB / 2000.0;
+                        let shown = format!("{} short tons", fixed(tons, 2));
+                        (Figure::number(tons, 2, "short tons"), shown)
+                    }
+                    MaterialTarget::Bags => {
+                        check_positive([&bag.grams], "--bag must be greater than zero");
+                        // Part of a bag still has to be bought.
+                        let bags = (kg * 1000.0 / bag.grams).ceil();
+                        let unit = format!("bags of {}", bag);
+                        (Figure::number(bags, 0, &unit), format!("{} {}", bags, unit))
+                    }
+                };
+                say!(
+                    Figure::number(volume.m3, 4, "m³") => figure,
+                    "{} of {} = {}",
+                    volume,
+                    material,
+                    result
+                );
+            }
+        }
+    }
+}
+
+// Supported sizes of the `awg` subcommand.
+enum_with_variants!(pub WireSize {
+    Awg => "awg",
+    Mm2 => "mm2",
+    DiameterMm => "diameter-mm",
+});
+
+// Supported targets of the `drill` subcommand.
+enum_with_variants!(pub DrillUnit {
+    Mm => "mm",
+    In => "in",
+});
+
+// Supported targets of the `gear` subcommand.
+enum_with_variants!(pub GearTarget {
+    Speed => "speed",
+    Ratio => "ratio",
+    Development => "development",
+    GearInches => "gear-inches",
+});
+
+// Supported targets of the `tire` subcommand.
+enum_with_variants!(pub TireDimension {
+    Diameter => "diameter",
+    Circumference => "circumference",
+});
+
+// Supported targets of the `paint` subcommand.
+enum_with_variants!(pub PaintUnit {
+    Liters => "liters",
+    Gallons => "gallons",
+});
+
+// Supported targets of the `material` subcommand.
+enum_with_variants!(pub MaterialTarget {
+    Tons => "tons",
+    ShortTons => "short-tons",
+    Bags => "bags",
+});
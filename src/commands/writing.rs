@@ -0,0 +1,204 @@
+//! Text subcommands: typing speed and reading time, Morse code, the phonetic
+//! alphabet and password entropy.
+
+use structopt::StructOpt;
+
+use convertx::error::ConvertError;
+use convertx::{codes, entropy, quantity, text};
+
+use super::output::{fixed, seconds_to_human_readable, Figure};
+use crate::fail;
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Convert typing speeds and estimate reading time.
+    Text {
+        /// Typing speed in words per minute.
+        #[structopt(long, required_unless = "words", conflicts_with = "words", parse(try_from_str = quantity::parse_number))]
+        wpm: Option<f64>,
+        /// Number of words in a text.
+        #[structopt(long)]
+        words: Option<u64>,
+        /// Metric to convert to: cpm or words-per-hour for `--wpm`, reading-time
+        /// for `--words`.
+        #[structopt(short = "t", long, possible_values = &TextMetric::variants(), case_insensitive = true)]
+        to: TextMetric,
+        /// Reading speed in words per minute used for reading-time (default: 238).
+        #[structopt(long = "reading-wpm", parse(try_from_str = quantity::parse_number))]
+        reading_wpm: Option<f64>,
+    },
+    /// Convert text to Morse code and back.
+    Morse {
+        /// Text, or Morse code with letters separated by spaces and words by `/`.
+        input: String,
+        /// What to convert to (default: the other form of the input).
+        #[structopt(short = "t", long, possible_values = &MorseTarget::variants(), case_insensitive = true)]
+        to: Option<MorseTarget>,
+    },
+    /// Spell text with the NATO phonetic alphabet, or read it back.
+    Phonetic {
+        /// Text, or phonetic code words such as "Romeo Two".
+        input: String,
+        /// What to convert to.
+        #[structopt(short = "t", long, default_value = "phonetic", possible_values = &PhoneticTarget::variants(), case_insensitive = true)]
+        to: PhoneticTarget,
+    },
+    /// Estimate the entropy of a random password or passphrase and the time to guess it.
+    Entropy {
+        /// Symbols to draw from: a count, or digits, hex, lower, alpha, alnum,
+        /// printable or diceware (words).
+        #[structopt(long)]
+        charset: entropy::Charset,
+        /// Number of symbols (characters, or words for diceware).
+        #[structopt(long)]
+        length: u32,
+        /// What to compute.
+        #[structopt(short = "t", long, possible_values = &EntropyTarget::variants(), case_insensitive = true)]
+        to: EntropyTarget,
+        /// Attacker's guesses per second, for time-to-crack.
+        #[structopt(long, default_value = "1e10/s")]
+        rate: entropy::GuessRate,
+    },
+}
+
+impl Command {
+    /// Runs the subcommand.
+    pub fn run(self) {
+        match self {
+            Command::Text {
+                wpm,
+                words,
+                to,
+                reading_wpm,
+            } => match (wpm, words, to) {
+                (Some(wpm), _, TextMetric::Cpm) => {
+                    let cpm = text::chars_per_minute(wpm);
+                    say!(Figure::number(wpm, 0, "wpm") => Figure::number(cpm, 0, "cpm"), "{} wpm = {} cpm", wpm, cpm)
+                }
+                (Some(wpm), _, TextMetric::WordsPerHour) => {
+                    let hourly = text::words_per_hour(wpm);
+                    say!(
+                        Figure::number(wpm, 0, "wpm") => Figure::number(hourly, 0, "words per hour"),
+                        "{} wpm = {} words per hour",
+                        wpm,
+                        hourly
+                    )
+                }
+                (_, Some(words), TextMetric::ReadingTime)
+                    if reading_wpm.is_none_or(|r| r > 0.0) =>
+                {
+                    let reading_wpm = reading_wpm.unwrap_or(text::READING_WPM);
+                    let seconds = text::reading_seconds(words, reading_wpm);
+                    let shown = seconds_to_human_readable(seconds);
+                    say!(
+                        Figure::number(words as f64, 0, "words") => Figure::number(seconds as f64, 0, "s").shown(&shown),
+                        "{} words = {} reading time",
+                        words,
+                        shown
+                    )
+                }
+                (_, Some(_), TextMetric::ReadingTime) => fail(ConvertError::OutOfRange {
+                    value: reading_wpm.unwrap_or_default(),
+                    reason: "--reading-wpm must be greater than zero".to_string(),
+                }),
+                _ => fail(ConvertError::Parse(
+                    "use --wpm with --to cpm or words-per-hour, or --words with --to reading-time"
+                        .to_string(),
+                )),
+            },
+            Command::Morse { input, to } => {
+                let to = to.unwrap_or(if codes::is_morse(&input) {
+                    MorseTarget::Text
+                } else {
+                    MorseTarget::Morse
+                });
+                let result = match to {
+                    MorseTarget::Morse => codes::to_morse(&input),
+                    MorseTarget::Text => codes::from_morse(&input),
+                };
+                match result {
+                    Ok(result) => say!(
+                        Figure::text(&input, "text") => Figure::text(&result, &to.to_string()),
+                        "{}",
+                        result
+                    ),
+                    Err(e) => fail(ConvertError::Parse(e)),
+                }
+            }
+            Command::Phonetic { input, to } => {
+                let result = match to {
+                    PhoneticTarget::Phonetic => codes::to_phonetic(&input),
+                    PhoneticTarget::Text => codes::from_phonetic(&input),
+                };
+                match result {
+                    Ok(result) => say!(
+                        Figure::text(&input, "text") => Figure::text(&result, &to.to_string()),
+                        "{}",
+                        result
+                    ),
+                    Err(e) => fail(ConvertError::Parse(e)),
+                }
+            }
+            Command::Entropy {
+                charset,
+                length,
+                to,
+                rate,
+            } => {
+                let bits = entropy::bits(&charset, length);
+                match to {
+                    EntropyTarget::Bits => {
+                        say!(
+                            Figure::number(f64::from(length), 0, "symbols") => Figure::number(bits, 2, "bits"),
+                            "{} symbols from {} = {} bits",
+                            length,
+                            charset,
+                            fixed(bits, 2)
+                        )
+                    }
+                    EntropyTarget::TimeToCrack => {
+                        const YEAR: f64 = 365.25 * 86_400.0;
+                        let seconds = entropy::seconds_to_crack(bits, &rate);
+                        let time = if seconds < YEAR {
+                            seconds_to_human_readable(seconds.round() as u64)
+                        } else {
+                            format!("{:.3e} years", seconds / YEAR)
+                        };
+                        say!(
+                            Figure::number(bits, 2, "bits") => Figure::number(seconds, 0, "s").shown(&time),
+                            "{} bits at {} guesses/s = {} on average",
+                            fixed(bits, 2),
+                            rate,
+                            time
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Supported targets of the `text` subcommand.
+enum_with_variants!(pub TextMetric {
+    Cpm => "cpm",
+    WordsPerHour => "words-per-hour",
+    ReadingTime => "reading-time",
+});
+
+// Supported targets of the `morse` subcommand.
+enum_with_variants!(pub MorseTarget {
+    Morse => "morse",
+    Text => "text",
+});
+
+// Supported targets of the `phonetic` subcommand.
+enum_with_variants!(pub PhoneticTarget {
+    Phonetic => "phonetic",
+    Text => "text",
+});
+
+// Supported targets of the `entropy` subcommand.
+enum_with_variants!(pub EntropyTarget {
+    Bits => "bits",
+    TimeToCrack => "time-to-crack",
+});
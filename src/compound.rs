@@ -0,0 +1,577 @@
+//! Compound units such as `kg*m/s^2`, `km/h` or `J/s`, compared by dimension.
+//!
+//! Every unit is a factor times a product of powers of the SI base units, whose
+//! exponents make up its [`Dimension`]. Two units convert whenever their
+//! dimensions match, whichever categories their parts come from, so
+//! `kg*m/s^2` converts to newtons and `kW*h` to megajoules.
+
+use std::fmt;
+
+use crate::error::ConvertError;
+use crate::portable::powi;
+use crate::prefix::{Prefix, PREFIXES};
+use crate::registry::UnitDef;
+
+/// Symbols of the base units that a [`Dimension`] counts powers of, in order.
+pub const BASES: [&str; 8] = ["m", "kg", "s", "A", "K", "mol", "cd", "bit"];
+
+/// Powers of the base units of [`BASES`], e.g. `[1, 0, -1, 0, 0, 0, 0, 0]` for
+/// a speed in m·s⁻¹.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dimension(pub [i8; 8]);
+
+/// Dimensions with their own names, which also name the categories.
+const NAMES: &[(&str, Dimension)] = &[
+    ("count", Dimension([0, 0, 0, 0, 0, 0, 0, 0])),
+    ("length", Dimension([1, 0, 0, 0, 0, 0, 0, 0])),
+    ("mass", Dimension([0, 1, 0, 0, 0, 0, 0, 0])),
+    ("time", Dimension([0, 0, 1, 0, 0, 0, 0, 0])),
+    ("current", Dimension([0, 0, 0, 1, 0, 0, 0, 0])),
+    ("temperature", Dimension([0, 0, 0, 0, 1, 0, 0, 0])),
+    ("amount of substance", Dimension([0, 0, 0, 0, 0, 1, 0, 0])),
+    ("luminous intensity", Dimension([0, 0, 0, 0, 0, 0, 1, 0])),
+    ("information", Dimension([0, 0, 0, 0, 0, 0, 0, 1])),
+    ("area", Dimension([2, 0, 0, 0, 0, 0, 0, 0])),
+    ("volume", Dimension([3, 0, 0, 0, 0, 0, 0, 0])),
+    ("speed", Dimension([1, 0, -1, 0, 0, 0, 0, 0])),
+    ("acceleration", Dimension([1, 0, -2, 0, 0, 0, 0, 0])),
+    ("frequency", Dimension([0, 0, -1, 0, 0, 0, 0, 0])),
+    ("force", Dimension([1, 1, -2, 0, 0, 0, 0, 0])),
+    ("pressure", Dimension([-1, 1, -2, 0, 0, 0, 0, 0])),
+    ("energy", Dimension([2, 1, -2, 0, 0, 0, 0, 0])),
+    ("power", Dimension([2, 1, -3, 0, 0, 0, 0, 0])),
+    ("density", Dimension([-3, 1, 0, 0, 0, 0, 0, 0])),
+    ("charge", Dimension([0, 0, 1, 1, 0, 0, 0, 0])),
+    ("voltage", Dimension([2, 1, -3, -1, 0, 0, 0, 0])),
+    ("resistance", Dimension([2, 1, -3, -2, 0, 0, 0, 0])),
+    ("datarate", Dimension([0, 0, -1, 0, 0, 0, 0, 1])),
+];
+
+impl Dimension {
+    /// Returns this dimension times `other` raised to `power`.
+    pub fn times(self, other: Dimension, power: i8) -> Dimension {
+        let mut powers = self.0;
+        for (power_of, other) in powers.iter_mut().zip(other.0) {
+            *power_of = power_of.saturating_add(other.saturating_mul(power));
+        }
+        Dimension(powers)
+    }
+
+    /// The name of the dimension, e.g. "force", if it has one.
+    pub fn name(&self) -> Option<&'static str> {
+        NAMES
+            .iter()
+            .find(|(_, dimension)| dimension == self)
+            .map(|(name, _)| *name)
+    }
+}
+
+impl fmt::Display for Dimension {
+    /// Writes the dimension in base units, e.g. `m·kg·s⁻²`, or `1` if it has none.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        let mut parts = Vec::new();
+        for (base, power) in BASES.iter().zip(self.0) {
+            let mut part = base.to_string();
+            if power != 1 {
+                if power < 0 {
+                    part.push('⁻');
+                }
+                for digit in power.unsigned_abs().to_string().bytes() {
+                    part.push(DIGITS[usize::from(digit - b'0')]);
+                }
+            }
+            if power != 0 {
+                parts.push(part);
+            }
+        }
+        match parts.is_empty() {
+            true => write!(f, "1"),
+            false => write!(f, "{}", parts.join("·")),
+        }
+    }
+}
+
+/// A unit that is `factor` times the base units of its `dimension`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub factor: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    /// The dimensionless unit 1.
+    const ONE: Unit = unit(1.0, [0; 8]);
+
+    /// Returns this unit times `other` raised to `power`.
+    fn times(self, other: Unit, power: i8) -> Unit {
+        Unit {
+            factor: self.factor * powi(other.factor, i32::from(power)),
+            dimension: self.dimension.times(other.dimension, power),
+        }
+    }
+}
+
+const fn unit(factor: f64, powers: [i8; 8]) -> Unit {
+    Unit {
+        factor,
+        dimension: Dimension(powers),
+    }
+}
+
+/// A unit of [`UNITS`].
+type Entry = (&'static str, &'static str, &'static str, Unit);
+
+/// Units that no category defines, as `(symbol, name, singular, unit)`. Each also takes
+/// SI prefixes, e.g. `kWh` or `milliseconds`. Symbols match exactly, as `mW`
+/// and `MW` differ; names ignore case.
+const UNITS: &[Entry] = &[
+    (
+        "s",
+        "seconds",
+        "second",
+        unit(1.0, [0, 0, 1, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "min",
+        "minutes",
+        "minute",
+        unit(60.0, [0, 0, 1, 0, 0, 0, 0, 0]),
+    ),
+    ("h", "hours", "hour", unit(3600.0, [0, 0, 1, 0, 0, 0, 0, 0])),
+    ("d", "days", "day", unit(86_400.0, [0, 0, 1, 0, 0, 0, 0, 0])),
+    ("g", "grams", "gram", unit(1e-3, [0, 1, 0, 0, 0, 0, 0, 0])),
+    (
+        "N",
+        "newtons",
+        "newton",
+        unit(1.0, [1, 1, -2, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "lbf",
+        "pounds-force",
+        "pound-force",
+        unit(4.448_221_615_260_5, [1, 1, -2, 0, 0, 0, 0, 0]),
+    ),
+    ("J", "joules", "joule", unit(1.0, [2, 1, -2, 0, 0, 0, 0, 0])),
+    (
+        "cal",
+        "calories",
+        "calorie",
+        unit(4.184, [2, 1, -2, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "cal_th",
+        "thermochemical_calories",
+        "thermochemical calorie",
+        unit(4.184, [2, 1, -2, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "Wh",
+        "watt-hours",
+        "watt-hour",
+        unit(3600.0, [2, 1, -2, 0, 0, 0, 0, 0]),
+    ),
+    ("W", "watts", "watt", unit(1.0, [2, 1, -3, 0, 0, 0, 0, 0])),
+    (
+        "hp",
+        "horsepower",
+        "horsepower",
+        unit(745.699_871_582_270_2, [2, 1, -3, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "hp_I",
+        "mechanical_horsepower",
+        "mechanical horsepower",
+        unit(745.699_871_582_270_2, [2, 1, -3, 0, 0, 0, 0, 0]),
+    ),
+    (
+        "PS",
+        "metric_horsepower",
+        "metric horsepower",
+        unit(735.498_75, [2, 1, -3, 0, 0, 0, 0, 0]),
+    ),
+    ("Hz", "hertz", "hertz", unit(1.0, [0, 0, -1, 0, 0, 0, 0, 0])),
+    (
+        "A",
+        "amperes",
+        "ampere",
+        unit(1.0, [0, 0, 0, 1, 0, 0, 0, 0]),
+    ),
+    (
+        "C",
+        "coulombs",
+        "coulomb",
+        unit(1.0, [0, 0, 1, 1, 0, 0, 0, 0]),
+    ),
+    ("V", "volts", "volt", unit(1.0, [2, 1, -3, -1, 0, 0, 0, 0])),
+    ("Ω", "ohms", "ohm", unit(1.0, [2, 1, -3, -2, 0, 0, 0, 0])),
+    ("mol", "moles", "mole", unit(1.0, [0, 0, 0, 0, 0, 1, 0, 0])),
+    (
+        "cd",
+        "candelas",
+        "candela",
+        unit(1.0, [0, 0, 0, 0, 0, 0, 1, 0]),
+    ),
+];
+
+/// The unit of each category that its other units are converted through, as
+/// `(category, unit name, size in base units)`. Only units with no offset from
+/// it take part in compound units, so of the temperatures only kelvins do.
+pub const CATEGORY_UNITS: &[(&str, &str, Unit)] = &[
+    ("length", "meters", unit(1.0, [1, 0, 0, 0, 0, 0, 0, 0])),
+    ("mass", "kg", unit(1.0, [0, 1, 0, 0, 0, 0, 0, 0])),
+    ("temperature", "k", unit(1.0, [0, 0, 0, 0, 1, 0, 0, 0])),
+    ("area", "sqm", unit(1.0, [2, 0, 0, 0, 0, 0, 0, 0])),
+    ("volume", "liters", unit(1e-3, [3, 0, 0, 0, 0, 0, 0, 0])),
+    ("speed", "mps", unit(1.0, [1, 0, -1, 0, 0, 0, 0, 0])),
+    ("pressure", "pa", unit(1.0, [-1, 1, -2, 0, 0, 0, 0, 0])),
+    ("count", "items", unit(1.0, [0, 0, 0, 0, 0, 0, 0, 0])),
+    ("datarate", "bps", unit(1.0, [0, 0, -1, 0, 0, 0, 0, 1])),
+    ("information", "bits", unit(1.0, [0, 0, 0, 0, 0, 0, 0, 1])),
+    ("hashrate", "h/s", unit(1.0, [0, 0, -1, 0, 0, 0, 0, 0])),
+];
+
+/// Returns true if `token` is `name`, ignoring case and reading hyphens and
+/// spaces as underscores, so `metric-horsepower` is `metric_horsepower`.
+fn is_name(name: &str, token: &str) -> bool {
+    let spelled = |text: &str| text.replace(['-', ' '], "_");
+    spelled(name).eq_ignore_ascii_case(&spelled(token))
+}
+
+/// Returns the entry of `UNITS` that `token` names and the SI prefix it is
+/// written with, if any.
+fn entry(token: &str) -> Option<(Option<&'static Prefix>, &'static Entry)> {
+    let find = |token: &str| {
+        UNITS.iter().find(|(symbol, name, singular, _)| {
+            *symbol == token || is_name(name, token) || is_name(singular, token)
+        })
+    };
+    if let Some(entry) = find(token) {
+        return Some((None, entry));
+    }
+    PREFIXES.iter().find_map(|prefix| {
+        let rest = prefix
+            .symbols
+            .iter()
+            .find_map(|symbol| token.strip_prefix(symbol))
+            .filter(|rest| UNITS.iter().any(|(symbol, ..)| symbol == rest))
+            .or_else(|| {
+                let name = token.get(..prefix.name.len())?;
+                name.eq_ignore_ascii_case(prefix.name)
+                    .then(|| &token[prefix.name.len()..])
+            })?;
+        Some((Some(prefix), find(rest)?))
+    })
+}
+
+/// Returns the unit of `UNITS` that `token` names, with or without an SI
+/// prefix, if any.
+pub fn named(token: &str) -> Option<Unit> {
+    let (prefix, &(_, _, _, unit)) = entry(token)?;
+    Some(Unit {
+        factor: prefix.map_or(1.0, |prefix| prefix.factor) * unit.factor,
+        ..unit
+    })
+}
+
+/// Returns the singular and plural of the unit of `UNITS` that `token` spells
+/// out, e.g. "kilocalorie" and "kilocalories" for `kilocalories`, or `None`
+/// if it is not one or is written as a symbol such as `kcal`.
+pub fn names(token: &str) -> Option<(String, String)> {
+    let (prefix, (_, name, singular, _)) = entry(token)?;
+    let prefix = prefix.map_or("", |prefix| prefix.name);
+    let rest = token
+        .get(..prefix.len())
+        .filter(|written| written.eq_ignore_ascii_case(prefix))
+        .map(|_| &token[prefix.len()..])?;
+    (is_name(name, rest) || is_name(singular, rest)).then(|| {
+        (
+            format!("{}{}", prefix, singular),
+            format!("{}{}", prefix, name.replace('_', " ")),
+        )
+    })
+}
+
+/// The units of `UNITS` as the registry would show them, so that they can be
+/// listed with the units of the categories.
+pub fn unit_defs() -> Vec<UnitDef> {
+    UNITS
+        .iter()
+        .filter_map(|(symbol, name, singular, unit)| {
+            Some(UnitDef {
+                plural: name.replace('_', " "),
+                ..UnitDef::new(name, singular, symbol, unit.dimension.name()?)
+            })
+        })
+        .collect()
+}
+
+/// Superscript digits, for powers written like `m²` or `s⁻¹`.
+const SUPERSCRIPTS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹⁻";
+
+/// A recursive descent parser of compound units, where an atom may stand for
+/// several units, e.g. `oz` for ounces and fluid ounces.
+struct Parser<'a, F> {
+    text: &'a str,
+    rest: &'a str,
+    atom: &'a F,
+}
+
+impl<F: Fn(&str) -> Vec<Unit>> Parser<'_, F> {
+    /// product := factor (('*' | '·' | '/') factor)*
+    fn product(&mut self) -> Result<Vec<Unit>, String> {
+        let mut units = self.factor()?;
+        loop {
+            self.rest = self.rest.trim_start();
+            let power = match self.rest.chars().next() {
+                Some('*' | '·') => 1,
+                Some('/') => -1,
+                _ => return Ok(units),
+            };
+            self.rest = &self.rest[self.rest.chars().next().map_or(0, char::len_utf8)..];
+            let factor = self.factor()?;
+            units = combine(&units, &factor, power);
+        }
+    }
+
+    /// factor := ('(' product ')' | atom) power?
+    fn factor(&mut self) -> Result<Vec<Unit>, String> {
+        self.rest = self.rest.trim_start();
+        let units = if let Some(rest) = self.rest.strip_prefix('(') {
+            self.rest = rest;
+            let units = self.product()?;
+            self.rest = self
+                .rest
+                .trim_start()
+                .strip_prefix(')')
+                .ok_or_else(|| format!("missing ')' in '{}'", self.text))?;
+            units
+        } else {
+            let end = self
+                .rest
+                .find(|c: char| "*·/^()".contains(c) || SUPERSCRIPTS.contains(c))
+                .unwrap_or(self.rest.len());
+            let token = self.rest[..end].trim();
+            self.rest = &self.rest[end..];
+            if token.is_empty() {
+                return Err(format!("expected a unit in '{}'", self.text));
+            }
+            let units = (self.atom)(token);
+            if units.is_empty() {
+                return Err(format!("unknown unit '{}'", token));
+            }
+            units
+        };
+        let power = self.power()?;
+        Ok(combine(&[Unit::ONE], &units, power))
+    }
+
+    /// power := ('^' '-'? digits | superscripts)?
+    fn power(&mut self) -> Result<i8, String> {
+        let (digits, superscript) = match self.rest.strip_prefix('^') {
+            Some(rest) => (rest, false),
+            None if self.rest.starts_with(|c| SUPERSCRIPTS.contains(c)) => (self.rest, true),
+            None => return Ok(1),
+        };
+        let end = digits
+            .find(|c: char| match superscript {
+                true => !SUPERSCRIPTS.contains(c),
+                false => !(c.is_ascii_digit() || c == '-'),
+            })
+            .unwrap_or(digits.len());
+        let written: String = digits[..end]
+            .chars()
+            .map(|c| match SUPERSCRIPTS.chars().position(|s| s == c) {
+                Some(10) => '-',
+                Some(digit) => char::from(b'0' + digit as u8),
+                None => c,
+            })
+            .collect();
+        self.rest = &digits[end..];
+        written
+            .parse()
+            .map_err(|_| format!("'{}' is not a power in '{}'", written, self.text))
+    }
+}
+
+/// Returns every product of one of `left` and one of `right` raised to `power`,
+/// without repeats.
+fn combine(left: &[Unit], right: &[Unit], power: i8) -> Vec<Unit> {
+    let mut units: Vec<Unit> = Vec::new();
+    for l in left {
+        for r in right {
+            let unit = l.times(*r, power);
+            if !units.contains(&unit) {
+                units.push(unit);
+            }
+        }
+    }
+    units
+}
+
+/// Parses a compound unit such as `kg*m/s^2`, `J/(kg*K)` or `m·s⁻¹`, reading
+/// each atom with `atom`. Every reading of atoms that stand for several units
+/// is returned.
+///
+/// # Example
+/// ```
+/// # use convertx::compound::*;
+/// let units = parse("kW*h", &|token| named(token).into_iter().collect()).unwrap();
+/// assert_eq!(units[0].dimension.name(), Some("energy"));
+/// ```
+pub fn parse<F: Fn(&str) -> Vec<Unit>>(text: &str, atom: &F) -> Result<Vec<Unit>, String> {
+    let mut parser = Parser {
+        text,
+        rest: text,
+        atom,
+    };
+    let units = parser.product()?;
+    match parser.rest.trim() {
+        "" => Ok(units),
+        rest => Err(format!("unexpected '{}' in '{}'", rest, text)),
+    }
+}
+
+/// Returns the factor converting values in the compound unit `from` to `to`,
+/// and their shared dimension.
+///
+/// A whole unit such as `km/h` that `atom` knows is read as one atom before
+/// being split into parts. When an atom stands for several units, the reading
+/// in which both sides have the same dimension is used; if several do, the
+/// conversion is ambiguous.
+///
+/// # Example
+/// ```
+/// # use convertx::compound::*;
+/// let atom = |token: &str| named(token).into_iter().collect();
+/// let (factor, dimension) = conversion("kW*h", "MJ", &atom).unwrap();
+/// assert!((factor - 3.6).abs() < 1e-9);
+/// assert_eq!(dimension.name(), Some("energy"));
+/// ```
+pub fn conversion<F: Fn(&str) -> Vec<Unit>>(
+    from: &str,
+    to: &str,
+    atom: &F,
+) -> Result<(f64, Dimension), ConvertError> {
+    let read = |text: &str| match atom(text.trim()) {
+        units if units.is_empty() => parse(text, atom),
+        units => Ok(units),
+    };
+    let (from_units, to_units) = (read(from)?, read(to)?);
+    let mut readings = from_units.iter().flat_map(|f| {
+        to_units
+            .iter()
+            .filter(move |t| t.dimension == f.dimension)
+            .map(move |t| (f.factor / t.factor, f.dimension))
+    });
+    let Some((factor, dimension)) = readings.next() else {
+        return Err(ConvertError::DimensionMismatch {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    };
+    if readings.any(|(other, other_dimension)| {
+        other_dimension != dimension || (other - factor).abs() > 1e-12 * factor.abs()
+    }) {
+        return Err(ConvertError::Parse(format!(
+            "'{}' to '{}' is ambiguous, as a unit has several meanings",
+            from, to
+        )));
+    }
+    Ok((factor, dimension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Units of [`UNITS`], plus meters, kilometers and both kinds of ounce.
+    fn atom(token: &str) -> Vec<Unit> {
+        let extra = [
+            ("m", unit(1.0, [1, 0, 0, 0, 0, 0, 0, 0])),
+            ("km", unit(1e3, [1, 0, 0, 0, 0, 0, 0, 0])),
+            ("kg", unit(1.0, [0, 1, 0, 0, 0, 0, 0, 0])),
+            ("oz", unit(0.028_349_523_125, [0, 1, 0, 0, 0, 0, 0, 0])),
+            ("oz", unit(2.957_352_956_25e-5, [3, 0, 0, 0, 0, 0, 0, 0])),
+            ("mL", unit(1e-6, [3, 0, 0, 0, 0, 0, 0, 0])),
+        ];
+        let extra = extra.iter().filter(|(symbol, _)| *symbol == token);
+        named(token)
+            .into_iter()
+            .chain(extra.map(|(_, unit)| *unit))
+            .collect()
+    }
+
+    fn factor(from: &str, to: &str) -> f64 {
+        conversion(from, to, &atom).unwrap().0
+    }
+
+    #[test]
+    fn test_parse() {
+        let newton = unit(1.0, [1, 1, -2, 0, 0, 0, 0, 0]);
+        assert_eq!(parse("kg*m/s^2", &atom), Ok(vec![newton]));
+        assert_eq!(parse("kg·m·s⁻²", &atom), Ok(vec![newton]));
+        assert_eq!(parse("kg * m / (s * s)", &atom), Ok(vec![newton]));
+        assert_eq!(parse("oz/s", &atom).unwrap().len(), 2);
+        assert!(parse("kg*", &atom).is_err());
+        assert!(parse("kg*(m", &atom).is_err());
+        assert_eq!(
+            parse("parsec/s", &atom),
+            Err("unknown unit 'parsec'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(factor("kg*m/s^2", "N"), 1.0);
+        assert!((factor("km/h", "m/s") - 1.0 / 3.6).abs() < 1e-15);
+        assert_eq!(factor("J/s", "W"), 1.0);
+        assert_eq!(factor("kW*h", "MJ"), 3.6);
+        assert_eq!(factor("kilowatt-hours", "kWh"), 1.0);
+        assert_eq!(factor("thermochemical_calories", "cal"), 1.0);
+        assert!((factor("PS", "mechanical_horsepower") - 0.986_320_07).abs() < 1e-8);
+        assert_eq!(factor("kN*m", "kJ"), 1.0);
+        assert_eq!(factor("ms", "s"), 1e-3);
+        assert!((factor("oz/s", "mL/s") - 29.573_529_562_5).abs() < 1e-9);
+        assert!(matches!(
+            conversion("oz", "oz", &atom),
+            Err(ConvertError::Parse(_))
+        ));
+        assert_eq!(
+            conversion("kg*m/s^2", "W", &atom).map(|(_, d)| d),
+            Err(ConvertError::DimensionMismatch {
+                from: "kg*m/s^2".to_string(),
+                to: "W".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        let read = |token| names(token).map(|(singular, plural)| singular + " / " + &plural);
+        assert_eq!(read("kilocalories").unwrap(), "kilocalorie / kilocalories");
+        assert_eq!(
+            read("Metric-Horsepower").unwrap(),
+            "metric horsepower / metric horsepower"
+        );
+        assert_eq!(read("kcal"), None);
+        assert_eq!(named("thermochemical-calories"), named("cal_th"));
+        let listed = unit_defs();
+        let joules = listed.iter().find(|unit| unit.name == "joules").unwrap();
+        assert_eq!(
+            (joules.singular.as_str(), joules.dimension.as_str()),
+            ("joule", "energy")
+        );
+    }
+
+    #[test]
+    fn test_dimension() {
+        let force = unit(1.0, [1, 1, -2, 0, 0, 0, 0, 0]).dimension;
+        assert_eq!(force.to_string(), "m·kg·s⁻²");
+        assert_eq!(force.name(), Some("force"));
+        assert_eq!(Dimension::default().to_string(), "1");
+        let jerk = force.times(Dimension([0, 1, 1, 0, 0, 0, 0, 0]), -1);
+        assert_eq!((jerk.to_string(), jerk.name()), ("m·s⁻³".to_string(), None));
+    }
+}
@@ -0,0 +1,341 @@
+//! Amounts to convert and the results of conversions.
+
+use std::fmt;
+
+use crate::error::ConvertError;
+use crate::quantity::{is_exponent, parse_number};
+use crate::units::UnitNames;
+
+/// A numeric input: a single value, a nominal value with a symmetric tolerance,
+/// or an inclusive range.
+///
+/// `Display` honours the formatter precision for every component, so
+/// `format!("{:.2}", amount)` renders e.g. `10.00±0.20` or `5.00..7.00`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Amount {
+    Exact(f64),
+    Tolerance(f64, f64),
+    Range(f64, f64),
+}
+
+impl Amount {
+    /// Returns the individual numbers that must be converted for this amount.
+    ///
+    /// A tolerance is expanded to its nominal value and both limits so that it is
+    /// carried correctly through affine or non-linear conversions.
+    pub fn points(&self) -> Vec<f64> {
+        match *self {
+            Amount::Exact(v) => vec![v],
+            Amount::Tolerance(n, t) => vec![n, n - t, n + t],
+            Amount::Range(lo, hi) => vec![lo, hi],
+        }
+    }
+
+    /// Returns true if unit names should be singular for this amount written
+    /// as `shown`, i.e. it is one number that shows as one (or minus one), as
+    /// 0.99999 does rounded to `1.0000`.
+    pub fn is_singular(&self, shown: &str) -> bool {
+        matches!(*self, Amount::Exact(_)) && shown.parse().is_ok_and(|v: f64| v.abs() == 1.0)
+    }
+
+    /// Renders the amount, formatting each component with `number`.
+    pub fn render(&self, number: impl Fn(f64) -> String) -> String {
+        match *self {
+            Amount::Exact(v) => number(v),
+            Amount::Tolerance(n, t) => format!("{}±{}", number(n), number(t)),
+            Amount::Range(lo, hi) => format!("{}..{}", number(lo), number(hi)),
+        }
+    }
+
+    /// Renders the amount to be read aloud, formatting each component with
+    /// `number` and spelling it out with [`spoken_number`], e.g. `10 plus or
+    /// minus 0.2` or `5 to 7`.
+    pub fn spoken(&self, number: impl Fn(f64) -> String) -> String {
+        let number = |v| spoken_number(&number(v));
+        match *self {
+            Amount::Exact(v) => number(v),
+            Amount::Tolerance(n, t) => format!("{} plus or minus {}", number(n), number(t)),
+            Amount::Range(lo, hi) => format!("{} to {}", number(lo), number(hi)),
+        }
+    }
+
+    /// Rebuilds an amount of the same shape from converted `points`.
+    pub fn with_points(&self, points: &[f64]) -> Amount {
+        match *self {
+            Amount::Exact(_) => Amount::Exact(points[0]),
+            Amount::Tolerance(..) => {
+                let n = points[0];
+                Amount::Tolerance(n, (points[1] - n).abs().max((points[2] - n).abs()))
+            }
+            Amount::Range(..) => Amount::Range(points[0].min(points[1]), points[0].max(points[1])),
+        }
+    }
+}
+
+impl ::std::str::FromStr for Amount {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = parse_number;
+        let parsed = if let Some((n, t)) = s.split_once('±').or_else(|| s.split_once("+-")) {
+            number(n).and_then(|n| Ok(Amount::Tolerance(n, number(t)?.abs())))
+        } else if let Some((lo, hi)) = s.split_once("..") {
+            number(lo).and_then(|lo| Ok(Amount::Range(lo, number(hi)?)))
+        } else {
+            number(s).map(Amount::Exact)
+        };
+        parsed.map_err(|error| match error {
+            // Keep the hint for NaN and infinities, which do parse.
+            ConvertError::NonFinite { .. } => error.to_string(),
+            _ => format!(
+                "'{}' is not a number, a fraction like 1/2, a tolerance like 10±0.2, or a range like 5..7",
+                s
+            ),
+        })
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision();
+        let rendered = self.render(|v| match precision {
+            Some(p) => format!("{:.*}", p, v),
+            None => v.to_string(),
+        });
+        write!(f, "{}", rendered)
+    }
+}
+
+/// An [`Amount`] as typed on the command line, optionally with the unit it is
+/// in attached, e.g. `5km`, `3.5 lb` or `10±0.2mm`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub amount: Amount,
+    /// The unit as written, e.g. "km"; `None` for a bare amount.
+    pub unit: Option<String>,
+}
+
+impl ::std::str::FromStr for Measurement {
+    type Err = String;
+    /// Parses an amount, or else splits the number from a unit suffix that
+    /// starts with a letter or `°`. The exponent of a number is no suffix, so
+    /// `1e3` stays a number and `1e3m` is 1000 m.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = match s.parse() {
+            Ok(amount) => return Ok(Measurement { amount, unit: None }),
+            Err(error) => error,
+        };
+        let exponent = |i: usize| {
+            is_exponent(&s[i..]) && s[..i].ends_with(|c: char| c.is_ascii_digit() || c == '.')
+        };
+        let split = s
+            .char_indices()
+            .find(|&(i, c)| (c.is_alphabetic() || c == '°') && !exponent(i))
+            .map(|(i, _)| i)
+            .filter(|&i| i > 0)
+            .ok_or(error)?;
+        let (number, unit) = s.split_at(split);
+        let amount = number.trim().parse()?;
+        Ok(Measurement {
+            amount,
+            unit: Some(unit.trim().to_string()),
+        })
+    }
+}
+
+/// The outcome of a single conversion, as shown by the output formats.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionResult {
+    /// The category converted in, e.g. "length".
+    pub category: String,
+    pub value: Amount,
+    /// The name of the unit of `value`, singular or plural to match it.
+    pub from: String,
+    pub to: String,
+    pub from_symbol: String,
+    pub to_symbol: String,
+    pub result: Amount,
+    /// The canonical name of the unit of `value`, e.g. "kilometers", which
+    /// unlike `from` does not change with the value.
+    pub from_unit: String,
+    pub to_unit: String,
+}
+
+impl ConversionResult {
+    /// Describes converting `value` in `from` to `result` in `to`, naming the
+    /// units for the amounts as `shown` writes them.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::conversion::*;
+    /// # use convertx::units::LengthUnit;
+    /// let km = Amount::Exact(1.0);
+    /// let shown = |amount: &Amount| amount.render(|v| format!("{:.2}", v));
+    /// let conversion = ConversionResult::new("length", km, &LengthUnit::Kilometers, Amount::Exact(1000.0), &LengthUnit::Meters, shown);
+    /// assert_eq!((conversion.from.as_str(), conversion.to.as_str()), ("kilometer", "meters"));
+    /// ```
+    pub fn new<U: UnitNames>(
+        category: &str,
+        value: Amount,
+        from: &U,
+        result: Amount,
+        to: &U,
+        shown: impl Fn(&Amount) -> String,
+    ) -> ConversionResult {
+        ConversionResult {
+            category: category.to_string(),
+            value,
+            from: from.name(value.is_singular(&shown(&value))).to_string(),
+            to: to.name(result.is_singular(&shown(&result))).to_string(),
+            from_symbol: from.symbol().to_string(),
+            to_symbol: to.symbol().to_string(),
+            result,
+            from_unit: from.id().to_string(),
+            to_unit: to.id().to_string(),
+        }
+    }
+}
+
+/// Spells out a formatted number for a screen reader or speech synthesizer:
+/// a leading minus sign and an exponent in words, without the zeros that end
+/// a fraction.
+///
+/// # Example
+/// ```
+/// # use convertx::conversion::*;
+/// assert_eq!(spoken_number("-16404.2000"), "minus 16404.2");
+/// assert_eq!(spoken_number("1.50e-6"), "1.5 times ten to the minus 6");
+/// ```
+pub fn spoken_number(text: &str) -> String {
+    let (text, minus) = match text.strip_prefix('-') {
+        Some(magnitude) => (magnitude, "minus "),
+        None => (text, ""),
+    };
+    let (mantissa, exponent) = text.split_once('e').unwrap_or((text, ""));
+    let mantissa = match mantissa {
+        "NaN" => "not a number",
+        "inf" => "infinity",
+        _ if mantissa.contains('.') => mantissa.trim_end_matches('0').trim_end_matches('.'),
+        _ => mantissa,
+    };
+    let exponent = match exponent {
+        "" | "0" => String::new(),
+        _ => format!(" times ten to the {}", spoken_number(exponent)),
+    };
+    format!("{}{}{}", minus, mantissa, exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::LengthUnit;
+
+    #[test]
+    fn test_conversion_result() {
+        let conversion = ConversionResult::new(
+            "length",
+            Amount::Exact(1.0),
+            &LengthUnit::Kilometers,
+            Amount::Exact(1000.0),
+            &LengthUnit::Meters,
+            |amount| amount.render(|v| v.to_string()),
+        );
+        assert_eq!(conversion.from, "kilometer");
+        assert_eq!(conversion.to, "meters");
+        assert_eq!(conversion.from_unit, "kilometers");
+        assert_eq!(conversion.to_symbol, "m");
+    }
+
+    #[test]
+    fn test_is_singular() {
+        assert!(Amount::Exact(1.0).is_singular("1"));
+        assert!(Amount::Exact(0.99999).is_singular("1.0000"));
+        assert!(Amount::Exact(-1.0).is_singular("-1.00e0"));
+        assert!(!Amount::Exact(0.99999).is_singular("0.99999"));
+        assert!(!Amount::Range(1.0, 1.0).is_singular("1..1"));
+    }
+
+    #[test]
+    fn test_spoken() {
+        assert_eq!(spoken_number("3.1069"), "3.1069");
+        assert_eq!(spoken_number("5.0000"), "5");
+        assert_eq!(spoken_number("100"), "100");
+        assert_eq!(spoken_number("-40.00"), "minus 40");
+        assert_eq!(spoken_number("12.34e3"), "12.34 times ten to the 3");
+        assert_eq!(spoken_number("5.00e0"), "5");
+        assert_eq!(spoken_number("-inf"), "minus infinity");
+        let number = |v: f64| format!("{:.2}", v);
+        assert_eq!(
+            Amount::Tolerance(10.0, 0.2).spoken(number),
+            "10 plus or minus 0.2"
+        );
+        assert_eq!(Amount::Range(5.0, 7.5).spoken(number), "5 to 7.5");
+    }
+
+    #[test]
+    fn test_measurement() {
+        let parse = |s: &str| s.parse::<Measurement>();
+        let km = parse("5km").unwrap();
+        assert_eq!(
+            (km.amount, km.unit.as_deref()),
+            (Amount::Exact(5.0), Some("km"))
+        );
+        let lb = parse("3.5 lb").unwrap();
+        assert_eq!(
+            (lb.amount, lb.unit.as_deref()),
+            (Amount::Exact(3.5), Some("lb"))
+        );
+        assert_eq!(parse("1 1/2cups").unwrap().amount, Amount::Exact(1.5));
+        assert_eq!(parse("5..7°C").unwrap().unit.as_deref(), Some("°C"));
+        assert_eq!(
+            parse("1e3").unwrap(),
+            Measurement {
+                amount: Amount::Exact(1000.0),
+                unit: None
+            }
+        );
+        let m = parse("1e3m").unwrap();
+        assert_eq!(
+            (m.amount, m.unit.as_deref()),
+            (Amount::Exact(1000.0), Some("m"))
+        );
+        let kg = parse("2.5E-3kg").unwrap();
+        assert_eq!(
+            (kg.amount, kg.unit.as_deref()),
+            (Amount::Exact(0.0025), Some("kg"))
+        );
+        assert_eq!(parse("2em").unwrap().unit.as_deref(), Some("em"));
+        assert!(parse("1e400")
+            .unwrap_err()
+            .contains("inf is not a finite number"));
+        assert!(parse("1e400m")
+            .unwrap_err()
+            .contains("inf is not a finite number"));
+        assert!(parse("km").is_err());
+        assert!(parse("5.5.5km").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let conversion = ConversionResult::new(
+            "length",
+            Amount::Range(1.0, 2.0),
+            &LengthUnit::Kilometers,
+            Amount::Range(1000.0, 2000.0),
+            &LengthUnit::Meters,
+            |amount| amount.render(|v| v.to_string()),
+        );
+        let json = serde_json::to_string(&conversion).unwrap();
+        assert!(json.contains(r#""value":{"range":[1.0,2.0]}"#));
+        assert_eq!(
+            serde_json::from_str::<ConversionResult>(&json).unwrap(),
+            conversion
+        );
+    }
+}
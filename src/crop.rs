@@ -0,0 +1,66 @@
+//! Crop bushel weights for `convertx yield`.
+//!
+//! A bushel is a volume, so yields in bushels depend on the crop; these are the
+//! standard test weights used by the USDA for grain trading.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Standard bushel weights in pounds, by crop.
+const BUSHEL_LB: &[(&str, f64)] = &[
+    ("wheat", 60.0),
+    ("soybeans", 60.0),
+    ("corn", 56.0),
+    ("sorghum", 56.0),
+    ("rye", 56.0),
+    ("canola", 50.0),
+    ("barley", 48.0),
+    ("oats", 32.0),
+];
+
+/// A crop from the bushel weight table, e.g. `wheat` or `corn`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crop {
+    name: &'static str,
+    pub bushel_lb: f64,
+}
+
+impl FromStr for Crop {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BUSHEL_LB
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+            .map(|(name, bushel_lb)| Crop {
+                name,
+                bushel_lb: *bushel_lb,
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = BUSHEL_LB.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown crop '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Crop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop() {
+        let corn: Crop = "Corn".parse().unwrap();
+        assert_eq!(corn.bushel_lb, 56.0);
+        assert_eq!(corn.to_string(), "corn");
+        assert!("quinoa".parse::<Crop>().is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! Converting columns of a CSV file in place for `convertx csv`.
+//!
+//! Records are split as RFC 4180 describes: fields may be quoted with `"`,
+//! a quote inside a quoted field is doubled, and quoted fields may hold the
+//! delimiter and line breaks. Only the converted fields are rewritten; every
+//! other field, the line endings and the quoting are copied as they are.
+
+use std::borrow::Cow;
+
+use crate::error::ConvertError;
+
+/// A column to convert, by zero-based index or by its name in the header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl ::std::str::FromStr for Column {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse() {
+            Ok(index) => Column::Index(index),
+            Err(_) => Column::Name(s.to_string()),
+        })
+    }
+}
+
+/// Reads a `--delimiter`: a single character, or `tab` (or `\t`) for tabs.
+///
+/// # Example
+/// ```
+/// # use convertx::csv::*;
+/// assert_eq!(parse_delimiter(";"), Ok(';'));
+/// assert_eq!(parse_delimiter("tab"), Ok('\t'));
+/// ```
+pub fn parse_delimiter(s: &str) -> Result<char, String> {
+    if s.eq_ignore_ascii_case("tab") || s == "\\t" {
+        return Ok('\t');
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some('"' | '\r' | '\n'), None) => Err(format!("'{}' cannot be a delimiter", s)),
+        (Some(delimiter), None) => Ok(delimiter),
+        _ => Err(format!(
+            "the delimiter must be a single character or tab, not '{}'",
+            s
+        )),
+    }
+}
+
+/// A record of a CSV file, as written in it.
+#[derive(Debug, PartialEq)]
+struct Record<'a> {
+    /// The one-based line the record starts on.
+    line: usize,
+    /// Each field as written, with its quotes.
+    fields: Vec<&'a str>,
+    /// The line ending after the record: `\n`, `\r\n`, or none at the end of
+    /// the input.
+    end: &'a str,
+}
+
+impl Record<'_> {
+    /// Returns true for an empty line, which is copied through.
+    fn is_blank(&self) -> bool {
+        self.fields == [""]
+    }
+}
+
+/// Splits `input` into records of fields separated by `delimiter`.
+fn records(input: &str, delimiter: char) -> Result<Vec<Record<'_>>, String> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let (mut start, mut line, mut record_line) = (0, 1, 1);
+    let mut quoted = false;
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek().is_some_and(|&(_, next)| next == '"') => {
+                    chars.next();
+                }
+                '"' => quoted = false,
+                '\n' => line += 1,
+                _ => {}
+            }
+        } else if c == '"' && i == start {
+            quoted = true;
+        } else if c == delimiter {
+            fields.push(&input[start..i]);
+            start = i + c.len_utf8();
+        } else if c == '\n' {
+            let end = match input[start..i].ends_with('\r') {
+                true => i - 1,
+                false => i,
+            };
+            fields.push(&input[start..end]);
+            records.push(Record {
+                line: record_line,
+                fields: std::mem::take(&mut fields),
+                end: &input[end..=i],
+            });
+            line += 1;
+            (start, record_line) = (i + 1, line);
+        }
+    }
+    if quoted {
+        return Err(format!(
+            "line {}: a quoted field is not closed",
+            record_line
+        ));
+    }
+    if start < input.len() || !fields.is_empty() {
+        fields.push(&input[start..]);
+        records.push(Record {
+            line: record_line,
+            fields,
+            end: "",
+        });
+    }
+    Ok(records)
+}
+
+/// Returns the text of a field as written, without its quotes.
+fn unquote(field: &str) -> Cow<'_, str> {
+    match field
+        .strip_prefix('"')
+        .and_then(|field| field.strip_suffix('"'))
+    {
+        Some(inner) if inner.contains('"') => Cow::Owned(inner.replace("\"\"", "\"")),
+        Some(inner) => Cow::Borrowed(inner),
+        None => Cow::Borrowed(field),
+    }
+}
+
+/// Converts the `columns` of the CSV `input`, whose fields are separated by
+/// `delimiter`, with `convert`, returning the CSV with every other field as
+/// it was. Converted values are written with `places` decimal places, or as
+/// few as show them exactly. Empty fields and blank lines are kept.
+///
+/// The first record is a header, and kept, if a column is given by name or
+/// if one of its fields in the columns is not a number.
+///
+/// # Example
+/// ```
+/// # use convertx::csv::*;
+/// let out = convert("id,lb\n1,\"2.5\"\n", ',', &[Column::Name("lb".into())], None, &|v| Ok(v * 2.0));
+/// assert_eq!(out.unwrap(), "id,lb\n1,\"5\"\n");
+/// ```
+pub fn convert(
+    input: &str,
+    delimiter: char,
+    columns: &[Column],
+    places: Option<usize>,
+    convert: &dyn Fn(f64) -> Result<f64, ConvertError>,
+) -> Result<String, String> {
+    let records = records(input, delimiter)?;
+    let first = records.iter().position(|record| !record.is_blank());
+    let header = first.map(|first| &records[first]).filter(|record| {
+        columns.iter().any(|column| match column {
+            Column::Name(_) => true,
+            Column::Index(index) => record.fields.get(*index).is_some_and(|field| {
+                let text = unquote(field);
+                !text.trim().is_empty() && text.trim().parse::<f64>().is_err()
+            }),
+        })
+    });
+    let indices = columns
+        .iter()
+        .map(|column| match column {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => header
+                .and_then(|header| {
+                    header
+                        .fields
+                        .iter()
+                        .position(|field| unquote(field).trim() == name)
+                })
+                .ok_or_else(|| format!("there is no column named '{}'", name)),
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    let mut out = String::with_capacity(input.len());
+    for (i, record) in records.iter().enumerate() {
+        let mut fields: Vec<Cow<str>> = record.fields.iter().map(|&f| Cow::Borrowed(f)).collect();
+        if !record.is_blank() && Some(i) != header.and(first) {
+            for &index in &indices {
+                let field = fields
+                    .get_mut(index)
+                    .ok_or_else(|| format!("line {}: there is no column {}", record.line, index))?;
+                let text = unquote(field);
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                let value: f64 = text
+                    .parse()
+                    .map_err(|_| format!("line {}: '{}' is not a number", record.line, text))?;
+                let converted =
+                    convert(value).map_err(|e| format!("line {}: {}", record.line, e))?;
+                let converted = match places {
+                    Some(places) => format!("{:.*}", places, converted),
+                    None => converted.to_string(),
+                };
+                *field = match field.starts_with('"') {
+                    true => Cow::Owned(format!("\"{}\"", converted)),
+                    false => Cow::Owned(converted),
+                };
+            }
+        }
+        for (n, field) in fields.iter().enumerate() {
+            if n > 0 {
+                out.push(delimiter);
+            }
+            out.push_str(field);
+        }
+        out.push_str(record.end);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(value: f64) -> Result<f64, ConvertError> {
+        Ok(value * 2.0)
+    }
+
+    #[test]
+    fn test_records() {
+        let input = "a,\"b,\"\"c\"\"\r\nd\"\n\n1,2";
+        let records = records(input, ',').unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].fields, ["a", "\"b,\"\"c\"\"\r\nd\""]);
+        assert_eq!((records[0].line, records[0].end), (1, "\n"));
+        assert!(records[1].is_blank());
+        assert_eq!((records[2].line, records[2].end), (4, ""));
+        assert_eq!(unquote(records[0].fields[1]), "b,\"c\"\r\nd");
+        assert_eq!(
+            super::records("a,\"b\n", ','),
+            Err("line 1: a quoted field is not closed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let input = "name;weight;\"note; kept\"\r\nbox;2.5;\"x\"\r\n\r\ncrate;\"4\";\r\nbag;;y\r\n";
+        assert_eq!(
+            convert(input, ';', &[Column::Name("weight".into())], None, &double).unwrap(),
+            "name;weight;\"note; kept\"\r\nbox;5;\"x\"\r\n\r\ncrate;\"8\";\r\nbag;;y\r\n"
+        );
+        assert_eq!(
+            convert(
+                "1,2\n3,4",
+                ',',
+                &[Column::Index(0), Column::Index(1)],
+                Some(1),
+                &double
+            )
+            .unwrap(),
+            "2.0,4.0\n6.0,8.0"
+        );
+        assert_eq!(
+            convert("1,2\n3,x\n", ',', &[Column::Index(1)], None, &double),
+            Err("line 2: 'x' is not a number".to_string())
+        );
+        assert_eq!(
+            convert("1,2\n3\n", ',', &[Column::Index(1)], None, &double),
+            Err("line 2: there is no column 1".to_string())
+        );
+        assert_eq!(
+            convert("1,2\n", ',', &[Column::Name("kg".into())], None, &double),
+            Err("there is no column named 'kg'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter("\\t"), Ok('\t'));
+        assert_eq!(parse_delimiter("|"), Ok('|'));
+        assert!(parse_delimiter("\"").is_err());
+        assert!(parse_delimiter(";;").is_err());
+        assert!(parse_delimiter("").is_err());
+        assert_eq!("2".parse(), Ok(Column::Index(2)));
+        assert_eq!("kg".parse(), Ok(Column::Name("kg".into())));
+    }
+}
@@ -0,0 +1,274 @@
+//! Date formats for `convertx date`.
+//!
+//! Besides ISO 8601, RFC 2822 and Unix timestamps, dates can be read and
+//! written with a field pattern such as `mdY`: `d` is the day, `m` the month,
+//! `Y` a four-digit and `y` a two-digit year (2000-2099). Patterns read the
+//! numbers in any separated form (`03/04/2025`, `03.04.2025`) and write them
+//! separated by `/`.
+//!
+//! Days can also be given as an ISO week date (`2025-W14-3`) or an ordinal
+//! date (`2025-092`) for `convertx week`.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc, Weekday,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// A date, or a date and time when the input had one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moment {
+    pub at: DateTime<FixedOffset>,
+    pub has_time: bool,
+}
+
+impl Moment {
+    /// Returns a moment at midnight UTC on `date`.
+    pub fn from_date(date: NaiveDate) -> Moment {
+        Moment {
+            at: date
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()
+                .and_utc()
+                .fixed_offset(),
+            has_time: false,
+        }
+    }
+}
+
+/// How a date is written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateFormat {
+    Iso,
+    Rfc2822,
+    Unix,
+    Pattern(String),
+}
+
+impl DateFormat {
+    /// Parses `s` in this format.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::date::*;
+    /// let format: DateFormat = "mdY".parse().unwrap();
+    /// assert_eq!(format.format(&format.parse("03/04/2025").unwrap()), "03/04/2025");
+    /// ```
+    pub fn parse(&self, s: &str) -> Result<Moment, String> {
+        let s = s.trim();
+        let invalid = || format!("'{}' is not a {} date", s, self);
+        match self {
+            DateFormat::Iso => {
+                if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+                    return Ok(Moment { at, has_time: true });
+                }
+                if let Ok(naive) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+                    let at = naive.and_utc().fixed_offset();
+                    return Ok(Moment { at, has_time: true });
+                }
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map(Moment::from_date)
+                    .map_err(|_| invalid())
+            }
+            DateFormat::Rfc2822 => DateTime::parse_from_rfc2822(s)
+                .map(|at| Moment { at, has_time: true })
+                .map_err(|_| invalid()),
+            DateFormat::Unix => s
+                .parse::<i64>()
+                .ok()
+                .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0))
+                .map(|at| Moment {
+                    at: at.fixed_offset(),
+                    has_time: true,
+                })
+                .ok_or_else(invalid),
+            DateFormat::Pattern(pattern) => {
+                let numbers: Vec<&str> = s
+                    .split(|c: char| !c.is_ascii_digit())
+                    .filter(|part| !part.is_empty())
+                    .collect();
+                if numbers.len() != pattern.len() {
+                    return Err(invalid());
+                }
+                let (mut year, mut month, mut day) = (None, None, None);
+                for (field, number) in pattern.chars().zip(numbers) {
+                    let value: u32 = number.parse().map_err(|_| invalid())?;
+                    match field {
+                        'Y' => year = Some(value as i32),
+                        'y' if number.len() == 2 => year = Some(2000 + value as i32),
+                        'm' => month = Some(value),
+                        'd' => day = Some(value),
+                        _ => return Err(invalid()),
+                    }
+                }
+                NaiveDate::from_ymd_opt(
+                    year.ok_or_else(invalid)?,
+                    month.ok_or_else(invalid)?,
+                    day.ok_or_else(invalid)?,
+                )
+                .map(Moment::from_date)
+                .ok_or_else(invalid)
+            }
+        }
+    }
+
+    /// Writes `moment` in this format.
+    pub fn format(&self, moment: &Moment) -> String {
+        let at = moment.at;
+        match self {
+            DateFormat::Iso if moment.has_time => at.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            DateFormat::Iso => at.format("%Y-%m-%d").to_string(),
+            DateFormat::Rfc2822 => at.to_rfc2822(),
+            DateFormat::Unix => at.timestamp().to_string(),
+            DateFormat::Pattern(pattern) => pattern
+                .chars()
+                .map(|field| match field {
+                    'Y' => format!("{:04}", at.year()),
+                    'y' => format!("{:02}", at.year().rem_euclid(100)),
+                    'm' => format!("{:02}", at.month()),
+                    _ => format!("{:02}", at.day()),
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "iso" => Ok(DateFormat::Iso),
+            "rfc2822" => Ok(DateFormat::Rfc2822),
+            "unix" => Ok(DateFormat::Unix),
+            pattern => {
+                let count = |c| pattern.matches(c).count();
+                let valid = pattern.chars().all(|c| "dmYy".contains(c))
+                    && count('d') == 1
+                    && count('m') == 1
+                    && count('Y') + count('y') == 1;
+                if valid {
+                    Ok(DateFormat::Pattern(pattern.to_string()))
+                } else {
+                    Err(format!(
+                        "unknown date format '{}', expected iso, rfc2822, unix or a pattern such as mdY or dmy",
+                        pattern
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateFormat::Iso => write!(f, "iso"),
+            DateFormat::Rfc2822 => write!(f, "rfc2822"),
+            DateFormat::Unix => write!(f, "unix"),
+            DateFormat::Pattern(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+/// Parses a calendar date (`2025-04-02`), an ISO week date (`2025-W14-3`,
+/// or `2025-W14` for its Monday) or an ordinal date (`2025-092`).
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveDate;
+/// # use convertx::date::*;
+/// let day = NaiveDate::from_ymd_opt(2025, 4, 2).unwrap();
+/// assert_eq!(parse_day("2025-W14-3"), Ok(day));
+/// assert_eq!(parse_day("2025-092"), Ok(day));
+/// ```
+pub fn parse_day(s: &str) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    let invalid = || {
+        format!(
+            "'{}' is not a date like 2025-04-02, 2025-W14-3 or 2025-092",
+            s
+        )
+    };
+    if let Some((year, week)) = s.split_once("-W") {
+        let (week, day) = week.split_once('-').unwrap_or((week, "1"));
+        let number = |part: &str| part.parse::<u32>().ok();
+        let weekday = number(day)
+            .filter(|day| (1..=7).contains(day))
+            .and_then(|day| Weekday::try_from(day as u8 - 1).ok())
+            .ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        return number(week)
+            .and_then(|week| NaiveDate::from_isoywd_opt(year, week, weekday))
+            .ok_or_else(invalid);
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%j"))
+        .map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(value: &str, from: &str, to: &str) -> Result<String, String> {
+        let from: DateFormat = from.parse()?;
+        let to: DateFormat = to.parse()?;
+        Ok(to.format(&from.parse(value)?))
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!(
+            convert("03/04/2025", "mdY", "iso"),
+            Ok("2025-03-04".to_string())
+        );
+        assert_eq!(
+            convert("03.04.2025", "dmY", "iso"),
+            Ok("2025-04-03".to_string())
+        );
+        assert_eq!(
+            convert("2025-04-03", "iso", "dmy"),
+            Ok("03/04/25".to_string())
+        );
+        assert!(convert("02/30/2025", "mdY", "iso").is_err());
+        assert!(convert("03/04", "mdY", "iso").is_err());
+        assert!("mdd".parse::<DateFormat>().is_err());
+        assert!("mdYy".parse::<DateFormat>().is_err());
+    }
+
+    #[test]
+    fn test_parse_day() {
+        let day = NaiveDate::from_ymd_opt(2025, 4, 2).unwrap();
+        assert_eq!(parse_day("2025-04-02"), Ok(day));
+        assert_eq!(parse_day("2025-W14-3"), Ok(day));
+        assert_eq!(parse_day("2025-092"), Ok(day));
+        assert_eq!(
+            parse_day("2025-W01"),
+            Ok(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap())
+        );
+        assert!(parse_day("2025-W54-1").is_err());
+        assert!(parse_day("2025-W14-8").is_err());
+        assert!(parse_day("2025-366").is_err());
+    }
+
+    #[test]
+    fn test_standard_formats() {
+        assert_eq!(
+            convert("2025-03-04", "iso", "unix"),
+            Ok("1741046400".to_string())
+        );
+        assert_eq!(
+            convert("1741046400", "unix", "rfc2822"),
+            Ok("Tue, 4 Mar 2025 00:00:00 +0000".to_string())
+        );
+        assert_eq!(
+            convert("Tue, 4 Mar 2025 10:30:00 +0100", "rfc2822", "iso"),
+            Ok("2025-03-04T10:30:00+01:00".to_string())
+        );
+        assert_eq!(
+            convert("2025-03-04T09:30:00Z", "iso", "unix"),
+            Ok("1741080600".to_string())
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Standard drill bit sizes for `convertx drill`.
+//!
+//! Metric bits come in 0.5 mm steps from 1 mm to 13 mm, as in common jobber
+//! sets; fractional-inch bits come in 1/64 in steps up to 1/2 in.
+
+/// Smallest and largest standard metric bit, in millimeters.
+const METRIC_RANGE: (f64, f64) = (1.0, 13.0);
+/// Step between standard metric bits, in millimeters.
+const METRIC_STEP: f64 = 0.5;
+/// Denominator of fractional-inch bit sizes.
+const INCH_DENOMINATOR: u32 = 64;
+/// Largest fractional-inch bit, in 64ths of an inch.
+const INCH_MAX: u32 = 32;
+
+/// Returns the standard metric bit closest to `mm`, in millimeters.
+///
+/// # Example
+/// ```
+/// # use convertx::drill::*;
+/// assert_eq!(nearest_metric_bit(5.56), 5.5);
+/// ```
+pub fn nearest_metric_bit(mm: f64) -> f64 {
+    ((mm / METRIC_STEP).round() * METRIC_STEP).clamp(METRIC_RANGE.0, METRIC_RANGE.1)
+}
+
+/// Returns the fractional-inch bit closest to `inches` as a reduced
+/// `(numerator, denominator)` pair.
+///
+/// # Example
+/// ```
+/// # use convertx::drill::*;
+/// assert_eq!(nearest_inch_bit(0.22), (7, 32));
+/// ```
+pub fn nearest_inch_bit(inches: f64) -> (u32, u32) {
+    let sixty_fourths = (inches * f64::from(INCH_DENOMINATOR)).round() as u32;
+    let mut numerator = sixty_fourths.clamp(1, INCH_MAX);
+    let mut denominator = INCH_DENOMINATOR;
+    while numerator.is_multiple_of(2) {
+        numerator /= 2;
+        denominator /= 2;
+    }
+    (numerator, denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_metric_bit() {
+        assert_eq!(nearest_metric_bit(5.5563), 5.5);
+        assert_eq!(nearest_metric_bit(6.3), 6.5);
+        assert_eq!(nearest_metric_bit(0.2), 1.0);
+        assert_eq!(nearest_metric_bit(25.0), 13.0);
+    }
+
+    #[test]
+    fn test_nearest_inch_bit() {
+        assert_eq!(nearest_inch_bit(0.21875), (7, 32));
+        assert_eq!(nearest_inch_bit(0.25), (1, 4));
+        assert_eq!(nearest_inch_bit(0.5), (1, 2));
+        assert_eq!(nearest_inch_bit(0.0), (1, 64));
+        assert_eq!(nearest_inch_bit(2.0), (1, 2));
+    }
+}
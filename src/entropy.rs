@@ -0,0 +1,129 @@
+//! Password and passphrase entropy for `convertx entropy`.
+//!
+//! A secret of `length` symbols drawn uniformly from `charset` symbols has
+//! `length × log2(charset)` bits of entropy; an attacker finds it after trying
+//! half of the possibilities on average.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::portable::{exp2, log2};
+
+/// Named character sets and their sizes.
+const CHARSETS: &[(&str, f64)] = &[
+    ("digits", 10.0),
+    ("hex", 16.0),
+    ("lower", 26.0),
+    ("alpha", 52.0),
+    ("alnum", 62.0),
+    ("printable", 94.0),
+    // The Diceware word list, for passphrases measured in words.
+    ("diceware", 7776.0),
+];
+
+/// The number of symbols a secret is drawn from, given as a count or a name
+/// such as `alnum`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charset {
+    pub size: f64,
+    name: String,
+}
+
+impl FromStr for Charset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        let size = CHARSETS
+            .iter()
+            .find(|(charset, _)| charset.eq_ignore_ascii_case(name))
+            .map(|(_, size)| *size)
+            .or_else(|| name.parse::<f64>().ok().filter(|size| *size >= 2.0));
+        match size {
+            Some(size) => Ok(Charset {
+                size,
+                name: name.to_string(),
+            }),
+            None => {
+                let names: Vec<&str> = CHARSETS.iter().map(|(charset, _)| *charset).collect();
+                Err(format!(
+                    "unknown charset '{}', expected a size of at least 2 or one of: {}",
+                    name,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A guessing rate in guesses per second, e.g. `1e10` or `1e10/s`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessRate {
+    pub per_second: f64,
+    text: String,
+}
+
+impl FromStr for GuessRate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim().trim_end_matches("/s");
+        text.parse::<f64>()
+            .ok()
+            .filter(|rate| *rate > 0.0 && rate.is_finite())
+            .map(|per_second| GuessRate {
+                per_second,
+                text: text.to_string(),
+            })
+            .ok_or_else(|| format!("'{}' is not a guessing rate like 1e10/s", s))
+    }
+}
+
+impl fmt::Display for GuessRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Returns the entropy in bits of `length` symbols from `charset`.
+///
+/// # Example
+/// ```
+/// # use convertx::entropy::*;
+/// assert_eq!(bits(&"hex".parse().unwrap(), 8), 32.0);
+/// ```
+pub fn bits(charset: &Charset, length: u32) -> f64 {
+    f64::from(length) * log2(charset.size)
+}
+
+/// Returns the average seconds to guess a secret of `bits` bits at `rate`.
+pub fn seconds_to_crack(bits: f64, rate: &GuessRate) -> f64 {
+    exp2(bits - 1.0) / rate.per_second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("Alnum".parse::<Charset>().unwrap().size, 62.0);
+        assert_eq!("94".parse::<Charset>().unwrap().size, 94.0);
+        assert!("1".parse::<Charset>().is_err());
+        assert!("emoji".parse::<Charset>().is_err());
+        assert_eq!("1e10/s".parse::<GuessRate>().unwrap().per_second, 1e10);
+        assert!("0/s".parse::<GuessRate>().is_err());
+    }
+
+    #[test]
+    fn test_entropy() {
+        let printable: Charset = "94".parse().unwrap();
+        assert!((bits(&printable, 16) - 104.873).abs() < 1e-3);
+        let rate: GuessRate = "1000".parse().unwrap();
+        assert_eq!(seconds_to_crack(20.0, &rate), 524.288);
+    }
+}
@@ -0,0 +1,116 @@
+//! The error type of the conversion functions.
+//!
+//! Each kind of failure has its own exit code, so scripts can tell a typo in
+//! a unit from a conversion that does not exist.
+
+use thiserror::Error;
+
+/// Why a conversion failed.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ConvertError {
+    /// The units are compatible, but this conversion between them is not defined.
+    #[error("conversion from {from} to {to} is not supported: {reason}")]
+    Unsupported {
+        from: String,
+        to: String,
+        reason: String,
+    },
+    /// The units measure different things, e.g. a length and a mass.
+    #[error("cannot convert '{from}' to '{to}'")]
+    DimensionMismatch { from: String, to: String },
+    /// The value has no counterpart in the target unit, or overflows it.
+    #[error("{} is out of range: {reason}", shown(*.value))]
+    OutOfRange { value: f64, reason: String },
+    /// A value or unit could not be read.
+    #[error("{0}")]
+    Parse(String),
+    /// The value is NaN or infinite, which is only converted when allowed.
+    #[error("{} is not a finite number; pass --allow-non-finite to convert it anyway", shown(*.value))]
+    NonFinite { value: f64 },
+    /// The result has a fractional part, and `--strict-int` forbids rounding it.
+    #[error("{} {unit} is not a whole number; --strict-int does not round it", shown(*.value))]
+    NotWhole { value: f64, unit: String },
+    /// Reading the input or writing the output failed, e.g. a file or socket.
+    #[error("{0}")]
+    Io(String),
+}
+
+impl ConvertError {
+    /// The process exit code for this error.
+    ///
+    /// | Error               | Code |
+    /// |---------------------|------|
+    /// | `Io`                | 1    |
+    /// | `Parse`             | 2    |
+    /// | `Unsupported`       | 3    |
+    /// | `DimensionMismatch` | 4    |
+    /// | `OutOfRange`        | 5    |
+    /// | `NonFinite`         | 6    |
+    /// | `NotWhole`          | 7    |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ConvertError::Io(_) => 1,
+            ConvertError::Parse(_) => 2,
+            ConvertError::Unsupported { .. } => 3,
+            ConvertError::DimensionMismatch { .. } => 4,
+            ConvertError::OutOfRange { .. } => 5,
+            ConvertError::NonFinite { .. } => 6,
+            ConvertError::NotWhole { .. } => 7,
+        }
+    }
+}
+
+/// Formats `value` for a message, in scientific notation if it is very large
+/// or very small.
+fn shown(value: f64) -> String {
+    if value != 0.0 && !(1e-4..1e15).contains(&value.abs()) {
+        format!("{:e}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+impl From<String> for ConvertError {
+    /// Wraps the `String` errors of the unit parsers.
+    fn from(message: String) -> ConvertError {
+        ConvertError::Parse(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_and_codes() {
+        let mismatch = ConvertError::DimensionMismatch {
+            from: "km".to_string(),
+            to: "kg".to_string(),
+        };
+        assert_eq!(mismatch.to_string(), "cannot convert 'km' to 'kg'");
+        assert_eq!(mismatch.exit_code(), 4);
+        let range = ConvertError::OutOfRange {
+            value: 1e308,
+            reason: "the result is not finite".to_string(),
+        };
+        assert_eq!(
+            range.to_string(),
+            "1e308 is out of range: the result is not finite"
+        );
+        let parse: ConvertError = "unknown unit 'parsec'".to_string().into();
+        assert_eq!(parse.exit_code(), 2);
+        let nan = ConvertError::NonFinite { value: f64::NAN };
+        assert!(nan.to_string().starts_with("NaN is not a finite number"));
+        assert_eq!(nan.exit_code(), 6);
+        let inexact = ConvertError::NotWhole {
+            value: 1.5,
+            unit: "KiB".to_string(),
+        };
+        assert_eq!(
+            inexact.to_string(),
+            "1.5 KiB is not a whole number; --strict-int does not round it"
+        );
+        assert_eq!(inexact.exit_code(), 7);
+        assert_eq!(ConvertError::Io("no such file".to_string()).exit_code(), 1);
+    }
+}
@@ -0,0 +1,445 @@
+//! Arithmetic on quantities for `convertx eval`.
+//!
+//! An expression such as `(3 km + 250 m) / 2 in feet` is split into tokens,
+//! parsed into an [`Expr`] tree by recursive descent and then evaluated, with
+//! every unit converted through a [`UnitRegistry`]:
+//!
+//! + `+` and `-` take quantities of one dimension and give the unit on the left;
+//! + `*` and `/` scale a quantity by a plain number, and dividing two
+//!   quantities of one dimension gives their plain ratio;
+//! + a trailing `in <unit>` or `to <unit>` converts the result.
+
+use std::str::FromStr;
+
+use crate::error::ConvertError;
+use crate::quantity::is_exponent;
+use crate::registry::{UnitDef, UnitRegistry};
+
+/// A token of an expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    /// A unit name or symbol, or one of the keywords `in` and `to`.
+    Word(String),
+    /// One of `+ - * / ( )`.
+    Symbol(char),
+}
+
+/// Splits `s` into tokens.
+///
+/// A word starts with a letter or `°` and runs on through letters, digits and
+/// `_`; it also takes a `/` followed by a letter, so `km/h` is one word.
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            let mut exponent = false;
+            while let Some(&(i, c)) = chars.peek() {
+                let sign = matches!(c, '+' | '-') && exponent && s[..i].ends_with(['e', 'E']);
+                let starts_exponent = !exponent && is_exponent(&s[i..]);
+                if !(c.is_ascii_digit() || (c == '.' && !exponent) || sign || starts_exponent) {
+                    break;
+                }
+                exponent |= starts_exponent;
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let text = &s[start..end];
+            let number = text
+                .parse()
+                .map_err(|_| format!("'{}' is not a number", text))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '°' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                let slash = c == '/' && s[i + 1..].starts_with(char::is_alphabetic);
+                if !(c.is_alphanumeric() || c == '_' || c == '°' || slash) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Word(s[start..end].to_string()));
+        } else if "+-*/()".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected '{}' in the expression", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// A value in a unit, e.g. `3 km` or `(1 + 2) km`.
+    Unit(Box<Expr>, String),
+    Neg(Box<Expr>),
+    /// Two operands and one of `+ - * /`.
+    Binary(Box<Expr>, char, Box<Expr>),
+}
+
+/// An expression with the unit, if any, to give its result in.
+///
+/// # Example
+/// ```
+/// # use convertx::expr::*;
+/// let expression: Expression = "(3 km + 250 m) / 2 in feet".parse().unwrap();
+/// assert_eq!(expression.target.as_deref(), Some("feet"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub expr: Expr,
+    pub target: Option<String>,
+}
+
+/// A recursive descent parser over the tokens of an expression.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Returns true if the next tokens are `in` or `to`, a unit and the end, so
+    /// `3 in in cm` reads as inches but `3 in cm` as a conversion.
+    fn at_target(&self) -> bool {
+        matches!(
+            &self.tokens[self.position.min(self.tokens.len())..],
+            [Token::Word(keyword), Token::Word(_)] if keyword == "in" || keyword == "to"
+        )
+    }
+
+    /// expression := sum ((`in` | `to`) word)?
+    fn expression(&mut self) -> Result<Expression, String> {
+        let expr = self.sum()?;
+        let mut target = None;
+        if self.at_target() {
+            if let Some(Token::Word(unit)) = self.tokens.last() {
+                target = Some(unit.clone());
+            }
+            self.position += 2;
+        }
+        match self.next() {
+            None => Ok(Expression { expr, target }),
+            Some(token) => Err(format!("unexpected {} in the expression", describe(&token))),
+        }
+    }
+
+    /// sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut expr = self.product()?;
+        while let Some(&Token::Symbol(op @ ('+' | '-'))) = self.peek() {
+            self.next();
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.product()?));
+        }
+        Ok(expr)
+    }
+
+    /// product := factor (('*' | '/') factor)*
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut expr = self.factor()?;
+        while let Some(&Token::Symbol(op @ ('*' | '/'))) = self.peek() {
+            self.next();
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.factor()?));
+        }
+        Ok(expr)
+    }
+
+    /// factor := '-' factor | (number | '(' sum ')') word?
+    fn factor(&mut self) -> Result<Expr, String> {
+        let expr = match self.next() {
+            Some(Token::Symbol('-')) => return Ok(Expr::Neg(Box::new(self.factor()?))),
+            Some(Token::Number(number)) => Expr::Number(number),
+            Some(Token::Symbol('(')) => {
+                let expr = self.sum()?;
+                match self.next() {
+                    Some(Token::Symbol(')')) => expr,
+                    _ => return Err("missing ')' in the expression".to_string()),
+                }
+            }
+            Some(token) => return Err(format!("expected a number, not {}", describe(&token))),
+            None => return Err("the expression ends too early".to_string()),
+        };
+        match self.peek() {
+            Some(Token::Word(unit)) if !self.at_target() => {
+                let unit = unit.clone();
+                self.next();
+                Ok(Expr::Unit(Box::new(expr), unit))
+            }
+            _ => Ok(expr),
+        }
+    }
+}
+
+/// Describes `token` for an error message.
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(number) => format!("the number {}", number),
+        Token::Word(word) => format!("'{}'", word),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+    }
+}
+
+impl FromStr for Expression {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        parser.expression()
+    }
+}
+
+/// The value of an expression: a number, in a unit unless it is plain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Value<'a> {
+    pub number: f64,
+    pub unit: Option<&'a UnitDef>,
+}
+
+/// Evaluates expressions, resolving unit tokens with `resolve` and converting
+/// between units with `registry`.
+pub struct Evaluator<'a, F> {
+    pub registry: &'a UnitRegistry,
+    pub resolve: F,
+}
+
+impl<'a, F> Evaluator<'a, F>
+where
+    F: Fn(&str) -> Result<&'a UnitDef, ConvertError>,
+{
+    /// Evaluates `expression`, converting the result to its target unit.
+    pub fn evaluate(&self, expression: &Expression) -> Result<Value<'a>, ConvertError> {
+        let value = self.value(&expression.expr)?;
+        let value = match &expression.target {
+            Some(target) => self.convert(value, (self.resolve)(target)?)?,
+            None => value,
+        };
+        if !value.number.is_finite() {
+            return Err(ConvertError::OutOfRange {
+                value: value.number,
+                reason: "the result is too large to represent".to_string(),
+            });
+        }
+        Ok(value)
+    }
+
+    fn value(&self, expr: &Expr) -> Result<Value<'a>, ConvertError> {
+        match expr {
+            Expr::Number(number) => Ok(Value {
+                number: *number,
+                unit: None,
+            }),
+            Expr::Unit(expr, unit) => match self.value(expr)? {
+                Value { number, unit: None } => Ok(Value {
+                    number,
+                    unit: Some((self.resolve)(unit)?),
+                }),
+                Value {
+                    unit: Some(inner), ..
+                } => Err(ConvertError::Parse(format!(
+                    "'{}' follows a value that is already in {}",
+                    unit, inner.plural
+                ))),
+            },
+            Expr::Neg(expr) => {
+                let value = self.value(expr)?;
+                Ok(Value {
+                    number: -value.number,
+                    ..value
+                })
+            }
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (self.value(left)?, self.value(right)?);
+                self.apply(left, *op, right)
+            }
+        }
+    }
+
+    /// Applies `op` to two values.
+    fn apply(
+        &self,
+        left: Value<'a>,
+        op: char,
+        right: Value<'a>,
+    ) -> Result<Value<'a>, ConvertError> {
+        let number = |number| Value { number, ..left };
+        match (op, left.unit, right.unit) {
+            ('+' | '-', None, None) => Ok(number(match op {
+                '+' => left.number + right.number,
+                _ => left.number - right.number,
+            })),
+            ('+' | '-', Some(unit), _) => {
+                let right = self.convert(right, unit)?;
+                Ok(number(match op {
+                    '+' => left.number + right.number,
+                    _ => left.number - right.number,
+                }))
+            }
+            ('+' | '-', None, Some(unit)) => Err(ConvertError::DimensionMismatch {
+                from: unit.symbol.clone(),
+                to: "a plain number".to_string(),
+            }),
+            ('*', _, None) => Ok(number(left.number * right.number)),
+            ('*', None, Some(_)) => Ok(Value {
+                number: left.number * right.number,
+                ..right
+            }),
+            ('/', _, _) if right.number == 0.0 => Err(ConvertError::OutOfRange {
+                value: left.number,
+                reason: "it is divided by zero".to_string(),
+            }),
+            ('/', _, None) => Ok(number(left.number / right.number)),
+            ('/', Some(unit), Some(_)) => {
+                let right = self.convert(right, unit)?;
+                Ok(Value {
+                    number: left.number / right.number,
+                    unit: None,
+                })
+            }
+            (_, left, Some(right)) => Err(ConvertError::Parse(format!(
+                "cannot {} {} by {}; only a plain number can scale a quantity",
+                if op == '*' { "multiply" } else { "divide" },
+                left.map_or("a plain number", |unit| unit.plural.as_str()),
+                right.plural
+            ))),
+            _ => unreachable!("the parser only produces + - * /"),
+        }
+    }
+
+    /// Converts `value` to `unit`, which must be of the same dimension.
+    fn convert(&self, value: Value<'a>, unit: &'a UnitDef) -> Result<Value<'a>, ConvertError> {
+        let Some(from) = value.unit.filter(|from| from.dimension == unit.dimension) else {
+            return Err(ConvertError::DimensionMismatch {
+                from: value
+                    .unit
+                    .map_or("a plain number", |from| from.symbol.as_str())
+                    .to_string(),
+                to: unit.symbol.clone(),
+            });
+        };
+        let convert = self
+            .registry
+            .converter(&from.name, &unit.name, &unit.dimension)?;
+        Ok(Value {
+            number: convert(value.number),
+            unit: Some(unit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> UnitRegistry {
+        let mut registry = UnitRegistry::default();
+        let meters = registry.add(UnitDef::new("meters", "meter", "m", "length"));
+        let km = registry.add(UnitDef::new("kilometers", "kilometer", "km", "length"));
+        let inches = registry.add(UnitDef::new("inches", "inch", "in", "length"));
+        registry.link(km, meters, 1000.0, 0.0);
+        registry.link(inches, meters, 0.0254, 0.0);
+        registry.add(UnitDef::new("kilograms", "kilogram", "kg", "mass"));
+        registry
+    }
+
+    fn eval(registry: &UnitRegistry, s: &str) -> Result<(f64, Option<String>), ConvertError> {
+        let evaluator = Evaluator {
+            registry,
+            resolve: |token: &str| {
+                registry
+                    .lookup(token)
+                    .first()
+                    .copied()
+                    .ok_or_else(|| ConvertError::Parse(format!("unknown unit '{}'", token)))
+            },
+        };
+        let value = evaluator.evaluate(&s.parse()?)?;
+        Ok((value.number, value.unit.map(|unit| unit.symbol.clone())))
+    }
+
+    #[test]
+    fn test_parse() {
+        let expression: Expression = "-(1 + 2e3) km * 2 to m".parse().unwrap();
+        let sum = Expr::Binary(
+            Box::new(Expr::Number(1.0)),
+            '+',
+            Box::new(Expr::Number(2000.0)),
+        );
+        let km = Expr::Unit(Box::new(sum), "km".to_string());
+        let expected = Expr::Binary(
+            Box::new(Expr::Neg(Box::new(km))),
+            '*',
+            Box::new(Expr::Number(2.0)),
+        );
+        assert_eq!(expression.expr, expected);
+        assert_eq!(expression.target.as_deref(), Some("m"));
+        let speed: Expression = "60 km/h".parse().unwrap();
+        assert_eq!(
+            speed.expr,
+            Expr::Unit(Box::new(Expr::Number(60.0)), "km/h".to_string())
+        );
+        assert!("(1 + 2".parse::<Expression>().is_err());
+        assert!("1 +".parse::<Expression>().is_err());
+        assert!("1 $ 2".parse::<Expression>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let registry = registry();
+        assert_eq!(
+            eval(&registry, "(3 km + 250 m) / 2 in m"),
+            Ok((1625.0, Some("m".to_string())))
+        );
+        assert_eq!(eval(&registry, "1 + 2 * 3"), Ok((7.0, None)));
+        assert_eq!(eval(&registry, "3 km / 500 m"), Ok((6.0, None)));
+        assert_eq!(
+            eval(&registry, "2 * 3 km to m"),
+            Ok((6000.0, Some("m".to_string())))
+        );
+        let (inches, _) = eval(&registry, "1 in in m").unwrap();
+        assert!((inches - 0.0254).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let registry = registry();
+        assert!(matches!(
+            eval(&registry, "1 km + 1 kg"),
+            Err(ConvertError::DimensionMismatch { .. })
+        ));
+        assert!(matches!(
+            eval(&registry, "1 km + 1"),
+            Err(ConvertError::DimensionMismatch { .. })
+        ));
+        assert!(matches!(
+            eval(&registry, "1 km * 1 m"),
+            Err(ConvertError::Parse(_))
+        ));
+        assert!(matches!(
+            eval(&registry, "1 km / 0"),
+            Err(ConvertError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            eval(&registry, "1 km in kg"),
+            Err(ConvertError::DimensionMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Fabric yardage for `convertx fabric`.
+//!
+//! Fabric is sold by length off a bolt of fixed width, so converting a
+//! pattern's requirement to another width keeps the area and solves for the
+//! new length.
+
+use crate::quantity::Distance;
+use std::fmt;
+use std::str::FromStr;
+
+/// A length of fabric of a given width, e.g. `2.5yd@45in`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cut {
+    pub length: Distance,
+    pub width: Distance,
+}
+
+impl Cut {
+    /// Returns the area of the cut in square meters.
+    pub fn area_m2(&self) -> f64 {
+        self.length.km * self.width.km * 1e6
+    }
+}
+
+impl FromStr for Cut {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (length, width) = split(s, "2.5yd@45in")?;
+        Ok(Cut {
+            length: length.parse()?,
+            width: parse_width(width)?,
+        })
+    }
+}
+
+impl fmt::Display for Cut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.length, self.width)
+    }
+}
+
+/// A length unit and bolt width to convert a cut to, e.g. `m@140cm`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub unit: String,
+    pub width: Distance,
+    km_per_unit: f64,
+}
+
+impl Target {
+    /// Returns the length of fabric, in the target unit, that has the same
+    /// area as `cut` at the target width.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::fabric::*;
+    /// let cut: Cut = "2m@150cm".parse().unwrap();
+    /// let target: Target = "m@100cm".parse().unwrap();
+    /// assert!((target.length_for(&cut) - 3.0).abs() < 1e-9);
+    /// ```
+    pub fn length_for(&self, cut: &Cut) -> f64 {
+        cut.length.km * cut.width.km / self.width.km / self.km_per_unit
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (unit, width) = split(s, "m@140cm")?;
+        // Reuse the distance parser to look the unit up as one of itself.
+        let one: Distance = format!("1{}", unit).parse()?;
+        Ok(Target {
+            unit: unit.to_string(),
+            width: parse_width(width)?,
+            km_per_unit: one.km,
+        })
+    }
+}
+
+/// Parses a bolt width, which must be greater than zero.
+fn parse_width(s: &str) -> Result<Distance, String> {
+    let width: Distance = s.parse()?;
+    match width.km > 0.0 {
+        true => Ok(width),
+        false => Err(format!("the width '{}' must be greater than zero", s)),
+    }
+}
+
+/// Splits `length@width`, rejecting anything else with an example of the form.
+fn split<'a>(s: &'a str, example: &str) -> Result<(&'a str, &'a str), String> {
+    s.trim()
+        .split_once('@')
+        .map(|(left, right)| (left.trim(), right.trim()))
+        .filter(|(left, right)| !left.is_empty() && !right.is_empty())
+        .ok_or_else(|| format!("'{}' is not a length@width like {}", s, example))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cut: Cut = "2.5yd@45in".parse().unwrap();
+        assert!((cut.area_m2() - 2.612898).abs() < 1e-6);
+        assert_eq!(cut.to_string(), "2.5yd@45in");
+        assert!("2.5yd".parse::<Cut>().is_err());
+        assert!("m@".parse::<Target>().is_err());
+        assert!("furlong@140cm".parse::<Target>().is_err());
+        assert!("2.5yd@0in".parse::<Cut>().is_err());
+        assert!("m@0cm".parse::<Target>().is_err());
+        assert!("m@-140cm".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn test_length_for() {
+        let cut: Cut = "2.5yd@45in".parse().unwrap();
+        let metric: Target = "m@140cm".parse().unwrap();
+        assert!((metric.length_for(&cut) - 1.866356).abs() < 1e-6);
+        let same: Target = "yd@45in".parse().unwrap();
+        assert!((same.length_for(&cut) - 2.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,114 @@
+//! Bicycle gearing for `convertx gear`.
+//!
+//! Wheel circumferences are the usual bike computer calibration values for each
+//! tire size; any other wheel can be given as a circumference in millimeters.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Common wheel and tire sizes with their rolling circumference in millimeters.
+const WHEELS: &[(&str, f64)] = &[
+    ("700x23c", 2096.0),
+    ("700x25c", 2105.0),
+    ("700x28c", 2136.0),
+    ("700x32c", 2155.0),
+    ("700x35c", 2168.0),
+    ("700x38c", 2180.0),
+    ("26x1.95", 2050.0),
+    ("26x2.0", 2055.0),
+    ("26x2.1", 2068.0),
+    ("27.5x2.1", 2148.0),
+    ("27.5x2.25", 2182.0),
+    ("29x2.1", 2288.0),
+    ("29x2.2", 2298.0),
+    ("29x2.3", 2326.0),
+];
+
+/// A wheel, named from the lookup table (e.g. `700x25c`) or given by its
+/// circumference (e.g. `2105` or `2105mm`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wheel {
+    pub circumference_mm: f64,
+    name: String,
+}
+
+impl FromStr for Wheel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        let known = WHEELS
+            .iter()
+            .find(|(wheel, _)| wheel.eq_ignore_ascii_case(name))
+            .map(|(_, circumference)| *circumference);
+        let custom = || {
+            name.trim_end_matches("mm")
+                .parse::<f64>()
+                .ok()
+                .filter(|mm| *mm > 0.0)
+        };
+        match known.or_else(custom) {
+            Some(circumference_mm) => Ok(Wheel {
+                circumference_mm,
+                name: name.to_string(),
+            }),
+            None => {
+                let names: Vec<&str> = WHEELS.iter().map(|(wheel, _)| *wheel).collect();
+                Err(format!(
+                    "unknown wheel '{}', expected a circumference in mm or one of: {}",
+                    name,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Wheel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Returns the distance in meters covered per crank revolution.
+///
+/// # Example
+/// ```
+/// # use convertx::gear::*;
+/// let wheel: Wheel = "2100".parse().unwrap();
+/// assert_eq!(development_m(42, 21, &wheel), 4.2);
+/// ```
+pub fn development_m(chainring: u32, cog: u32, wheel: &Wheel) -> f64 {
+    f64::from(chainring) / f64::from(cog) * wheel.circumference_mm / 1000.0
+}
+
+/// Returns the road speed in km/h at `cadence` crank revolutions per minute.
+pub fn speed_kph(chainring: u32, cog: u32, wheel: &Wheel, cadence: f64) -> f64 {
+    development_m(chainring, cog, wheel) * cadence * 60.0 / 1000.0
+}
+
+/// Returns the gear in inches: the gear ratio times the wheel diameter.
+pub fn gear_inches(chainring: u32, cog: u32, wheel: &Wheel) -> f64 {
+    let diameter_in = wheel.circumference_mm / std::f64::consts::PI / 25.4;
+    f64::from(chainring) / f64::from(cog) * diameter_in
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wheel() {
+        assert_eq!("700x25C".parse::<Wheel>().unwrap().circumference_mm, 2105.0);
+        assert_eq!("2110mm".parse::<Wheel>().unwrap().circumference_mm, 2110.0);
+        assert!("700x99c".parse::<Wheel>().is_err());
+        assert!("0".parse::<Wheel>().is_err());
+    }
+
+    #[test]
+    fn test_gearing() {
+        let wheel: Wheel = "700x25c".parse().unwrap();
+        assert!((development_m(50, 12, &wheel) - 8.7708).abs() < 1e-4);
+        assert!((speed_kph(50, 12, &wheel, 90.0) - 47.3625).abs() < 1e-4);
+        assert!((gear_inches(50, 12, &wheel) - 109.91).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,73 @@
+//! Brewing gravity scales for `convertx gravity`.
+//!
+//! Specific gravity is converted to degrees Plato and Brix with the usual
+//! homebrewing polynomials, and back with their standard approximations, so a
+//! round trip agrees to about 0.001 SG.
+
+use crate::portable::powi;
+
+/// Converts specific gravity to degrees Plato.
+///
+/// # Example
+/// ```
+/// # use convertx::gravity::*;
+/// assert!((sg_to_plato(1.050) - 12.39).abs() < 0.01);
+/// ```
+pub fn sg_to_plato(sg: f64) -> f64 {
+    -616.868 + 1111.14 * sg - 630.272 * powi(sg, 2) + 135.997 * powi(sg, 3)
+}
+
+/// Converts degrees Plato to specific gravity.
+pub fn plato_to_sg(plato: f64) -> f64 {
+    1.0 + plato / (258.6 - plato / 258.2 * 227.1)
+}
+
+/// Converts specific gravity to degrees Brix.
+pub fn sg_to_brix(sg: f64) -> f64 {
+    ((182.4601 * sg - 775.6821) * sg + 1262.7794) * sg - 669.5622
+}
+
+/// Converts degrees Brix to specific gravity.
+pub fn brix_to_sg(brix: f64) -> f64 {
+    plato_to_sg(brix)
+}
+
+/// Estimates alcohol by volume in percent from original and final gravity.
+/// A final gravity above the original is an error, as fermentation only
+/// lowers it.
+///
+/// # Example
+/// ```
+/// # use convertx::gravity::*;
+/// assert!((abv(1.050, 1.010).unwrap() - 5.25).abs() < 1e-9);
+/// ```
+pub fn abv(og: f64, fg: f64) -> Result<f64, String> {
+    if fg > og {
+        return Err(format!(
+            "the final gravity {:.3} is above the original gravity {:.3}; fermentation only lowers it",
+            fg, og
+        ));
+    }
+    Ok((og - fg) * 131.25)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plato_and_brix() {
+        assert!((sg_to_plato(1.050) - 12.39).abs() < 0.01);
+        assert!((sg_to_brix(1.050) - 12.39).abs() < 0.01);
+        assert!(sg_to_plato(1.000).abs() < 0.01);
+        assert!((plato_to_sg(sg_to_plato(1.080)) - 1.080).abs() < 1e-3);
+        assert!((brix_to_sg(sg_to_brix(1.040)) - 1.040).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_abv() {
+        assert!((abv(1.050, 1.010).unwrap() - 5.25).abs() < 1e-9);
+        assert_eq!(abv(1.040, 1.040), Ok(0.0));
+        assert!(abv(1.010, 1.050).is_err());
+    }
+}
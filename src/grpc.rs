@@ -0,0 +1,41 @@
+//! The gRPC interface of `convertx serve --grpc`.
+//!
+//! [`proto`] is generated by tonic from `proto/convertx.proto`; the binary
+//! implements its [`Converter`] trait on the same service that answers the
+//! HTTP API, so both share one unit lookup and cache.
+
+use std::error::Error;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+
+/// The messages, client and server generated from `proto/convertx.proto`.
+pub mod proto {
+    tonic::include_proto!("convertx.v1");
+}
+
+pub use proto::converter_server::{Converter, ConverterServer};
+
+/// Serves `converter` on `listener` until it fails, on a runtime of its own.
+/// Each call may take up to `timeout`.
+pub fn run<C: Converter>(
+    listener: TcpListener,
+    timeout: Duration,
+    converter: C,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        listener.set_nonblocking(true)?;
+        let incoming = TcpIncoming::from(tokio::net::TcpListener::from_std(listener)?);
+        Server::builder()
+            .timeout(timeout)
+            .add_service(ConverterServer::new(converter))
+            .serve_with_incoming(incoming)
+            .await?;
+        Ok(())
+    })
+}
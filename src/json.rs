@@ -0,0 +1,155 @@
+//! JSON lines for `--output json`.
+//!
+//! Each conversion becomes one JSON object on its own line. The objects are
+//! written here rather than with a serialization crate, so even the smallest
+//! build can produce them.
+//!
+//! The format is stable: the fields of [`FIELDS`] keep their names, order and
+//! types, and new fields are only ever added at the end. `from` and `to` are
+//! the canonical unit names that `convertx units` lists, e.g. "kilometers",
+//! whatever the value.
+
+use std::fmt;
+
+/// Fields written for every conversion, in order.
+pub const FIELDS: &[&str] = &[
+    "category",
+    "value",
+    "from",
+    "to",
+    "from_symbol",
+    "to_symbol",
+    "result",
+];
+
+/// A value in a JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A number, written unrounded; `null` if it is not finite.
+    Number(f64),
+    /// Text, written as an escaped string.
+    Text(String),
+    /// `true` or `false`.
+    Bool(bool),
+    /// A list of values.
+    Array(Vec<Value>),
+    /// A nested object of named values.
+    Object(Vec<(&'static str, Value)>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) if number.is_finite() => write!(f, "{}", number),
+            Value::Number(_) => write!(f, "null"),
+            Value::Text(text) => {
+                write!(f, "\"")?;
+                for c in text.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    write!(f, "{}{}", separator, value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    write!(f, "{}\"{}\":{}", separator, name, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Returns a JSON object with one of `values` for each of [`FIELDS`].
+///
+/// # Example
+/// ```
+/// # use convertx::json::*;
+/// let row = [Value::Text("mass".into()), Value::Number(2.0)];
+/// assert_eq!(object(&row), r#"{"category":"mass","value":2}"#);
+/// ```
+pub fn object(values: &[Value]) -> String {
+    let fields = FIELDS.iter().copied().zip(values.iter().cloned()).collect();
+    Value::Object(fields).to_string()
+}
+
+/// Returns the results of a launcher script filter, in the JSON format of
+/// Alfred's script filters that other launchers read too: one item for each
+/// of `items`' title, subtitle and `arg`, what choosing the item passes on.
+///
+/// # Example
+/// ```
+/// # use convertx::json::*;
+/// let items = [("3.1069 miles".into(), "5 kilometers = 3.1069 miles".into(), "3.1069".into())];
+/// assert!(script_filter(&items).starts_with(r#"{"items":[{"title":"3.1069 miles","#));
+/// ```
+pub fn script_filter(items: &[(String, String, String)]) -> String {
+    let items = items
+        .iter()
+        .map(|(title, subtitle, arg)| {
+            Value::Object(vec![
+                ("title", Value::Text(title.clone())),
+                ("subtitle", Value::Text(subtitle.clone())),
+                ("arg", Value::Text(arg.clone())),
+            ])
+        })
+        .collect();
+    Value::Object(vec![("items", Value::Array(items))]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Value::Number(1000.0).to_string(), "1000");
+        assert_eq!(Value::Number(0.25).to_string(), "0.25");
+        assert_eq!(Value::Number(f64::NAN).to_string(), "null");
+        assert_eq!(
+            Value::Text("a \"b\"\\\n\u{1}".into()).to_string(),
+            r#""a \"b\"\\\n\u0001""#
+        );
+        let range = Value::Object(vec![
+            ("min", Value::Number(5.0)),
+            ("max", Value::Number(7.0)),
+        ]);
+        assert_eq!(range.to_string(), r#"{"min":5,"max":7}"#);
+        let list = Value::Array(vec![Value::Bool(true), Value::Text("km".into())]);
+        assert_eq!(list.to_string(), r#"[true,"km"]"#);
+        assert_eq!(Value::Array(Vec::new()).to_string(), "[]");
+    }
+
+    #[test]
+    fn test_object() {
+        let row = [Value::Text("mass".into()), Value::Number(2.0)];
+        assert_eq!(object(&row), r#"{"category":"mass","value":2}"#);
+    }
+
+    #[test]
+    fn test_script_filter() {
+        let item = ("2 kg".into(), "4.4 lb = 2 kg".into(), "2".into());
+        assert_eq!(
+            script_filter(&[item]),
+            r#"{"items":[{"title":"2 kg","subtitle":"4.4 lb = 2 kg","arg":"2"}]}"#
+        );
+        assert_eq!(script_filter(&[]), r#"{"items":[]}"#);
+    }
+}
@@ -0,0 +1,569 @@
+//! Converting the numbers at a path of a JSON document for `convertx json`.
+//!
+//! The document is read with a small parser of its own, like the JSON of
+//! [`json`](crate::json) is written without a serialization crate. It keeps
+//! where each number is written, so only those numbers are replaced and the
+//! rest of the text, its layout and key order included, is copied as it is.
+//! The input may hold several documents, e.g. one per line.
+
+use std::ops::Range;
+
+use crate::error::ConvertError;
+
+/// Documents nested deeper than this are refused rather than risking the
+/// stack.
+const MAX_DEPTH: usize = 256;
+
+/// One step of a [`Path`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// The value of a key of an object, `.name` or `["name"]`.
+    Key(String),
+    /// An element of an array, `[0]`.
+    Index(usize),
+    /// Every element of an array, `[]`.
+    Each,
+}
+
+/// Where the numbers to convert are in a document, written as in jq, e.g.
+/// `.readings[].temp_f`; `.` is the whole document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path(pub Vec<Step>);
+
+impl ::std::str::FromStr for Path {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str| format!("invalid path '{}': {}", s, reason);
+        if !s.starts_with(['.', '[']) {
+            return Err(error("it starts with '.', e.g. .readings[].temp_f"));
+        }
+        let mut steps = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let end = after
+                    .find(']')
+                    .ok_or_else(|| error("a '[' is not closed"))?;
+                let inside = after[..end].trim();
+                steps.push(if inside.is_empty() {
+                    Step::Each
+                } else if let Some(key) = inside
+                    .strip_prefix('"')
+                    .and_then(|key| key.strip_suffix('"'))
+                {
+                    Step::Key(key.to_string())
+                } else {
+                    Step::Index(
+                        inside
+                            .parse()
+                            .map_err(|_| error("write [N], [] or [\"key\"]"))?,
+                    )
+                });
+                rest = &after[end + 1..];
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                match &after[..end] {
+                    // A lone `.`, or one before a bracket as in `.[0]`.
+                    "" if end == after.len() || after.starts_with('[') => {}
+                    "" => return Err(error("a key is missing after '.'")),
+                    key => steps.push(Step::Key(key.to_string())),
+                }
+                rest = &after[end..];
+            } else {
+                return Err(error("write .key or [N] after ']'"));
+            }
+        }
+        Ok(Path(steps))
+    }
+}
+
+/// A value of a document, holding only what finding and replacing numbers
+/// needs.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Null,
+    Bool,
+    /// A number, by where it is written.
+    Number(Range<usize>),
+    Text,
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl Node {
+    /// What the value is, for messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            Node::Null => "null",
+            Node::Bool => "a boolean",
+            Node::Number(_) => "a number",
+            Node::Text => "a string",
+            Node::Array(_) => "an array",
+            Node::Object(_) => "an object",
+        }
+    }
+}
+
+/// Reads JSON values out of a text.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser {
+            text,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    /// An error at the current position, e.g. "line 2, column 5: expected ':'".
+    fn error(&self, reason: &str) -> String {
+        let before = &self.text[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+            .chars()
+            .count()
+            + 1;
+        format!("line {}, column {}: {}", line, column, reason)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `expected` after any whitespace.
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected '{}'", expected as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Reads the value after any whitespace.
+    fn value(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{' | b'[') if self.depth == MAX_DEPTH => Err(self.error(&format!(
+                "the document is nested more than {} levels deep",
+                MAX_DEPTH
+            ))),
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(|_| Node::Text),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => {
+                for (literal, node) in [
+                    ("true", Node::Bool),
+                    ("false", Node::Bool),
+                    ("null", Node::Null),
+                ] {
+                    if self.text[self.pos..].starts_with(literal) {
+                        self.pos += literal.len();
+                        return Ok(node);
+                    }
+                }
+                Err(self.error("expected a JSON value"))
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Node, String> {
+        self.pos += 1;
+        self.depth += 1;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some(b'"') {
+                    return Err(self.error("expected a key in quotes"));
+                }
+                let key = self.string()?;
+                self.expect(b':')?;
+                fields.push((key, self.value()?));
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected ',' or '}'")),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Node::Object(fields))
+    }
+
+    fn array(&mut self) -> Result<Node, String> {
+        self.pos += 1;
+        self.depth += 1;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                elements.push(self.value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected ',' or ']'")),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Node::Array(elements))
+    }
+
+    /// Reads a string at the current position, decoding its escapes.
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut decoded = String::new();
+        loop {
+            let Some(c) = self.text[self.pos..].chars().next() else {
+                return Err(self.error("a string is not closed"));
+            };
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(decoded);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    decoded.push(self.escape()?);
+                }
+                c if u32::from(c) < 0x20 => {
+                    return Err(self.error("a control character must be escaped"))
+                }
+                c => {
+                    self.pos += c.len_utf8();
+                    decoded.push(c);
+                }
+            }
+        }
+    }
+
+    /// Reads the escape after a backslash.
+    fn escape(&mut self) -> Result<char, String> {
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let high = self.hex()?;
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high).ok_or_else(|| self.error("invalid \\u escape"));
+                }
+                // A character outside the basic plane is a surrogate pair.
+                if !self.text[self.pos..].starts_with("\\u") {
+                    return Err(self.error("expected the second half of a surrogate pair"));
+                }
+                self.pos += 2;
+                let low = self.hex()?;
+                let code = 0x10000 + ((high - 0xD800) << 10) + low.wrapping_sub(0xDC00);
+                return match (0xDC00..0xE000).contains(&low) {
+                    true => char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape")),
+                    false => Err(self.error("expected the second half of a surrogate pair")),
+                };
+            }
+            _ => return Err(self.error("invalid escape")),
+        };
+        self.pos += 1;
+        Ok(c)
+    }
+
+    /// Reads the four hex digits of a `\u` escape.
+    fn hex(&mut self) -> Result<u32, String> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("expected four hex digits"))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(digits, 16).expect("the digits are hex"))
+    }
+
+    /// Reads a number as JSON writes them: an optional minus, an integer
+    /// without leading zeros, then an optional fraction and exponent.
+    fn number(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            let from = parser.pos;
+            while parser.peek().is_some_and(|b| b.is_ascii_digit()) {
+                parser.pos += 1;
+            }
+            parser.pos - from
+        };
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let leading_zero = self.peek() == Some(b'0');
+        match digits(self) {
+            0 => return Err(self.error("expected a digit")),
+            n if leading_zero && n > 1 => return Err(self.error("a number cannot start with 0")),
+            _ => {}
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if digits(self) == 0 {
+                return Err(self.error("expected a digit after '.'"));
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if digits(self) == 0 {
+                return Err(self.error("expected a digit in the exponent"));
+            }
+        }
+        Ok(Node::Number(start..self.pos))
+    }
+}
+
+/// Returns where a value is for messages as jq writes it, e.g. `.[1]`, or
+/// `.` for the whole document.
+fn location(at: &str) -> String {
+    match at.starts_with('.') {
+        true => at.to_string(),
+        false => format!(".{}", at),
+    }
+}
+
+/// Collects the values at `steps` below `node`, each with where it is, e.g.
+/// `.readings[2].temp_f`. Keys missing from an object, indices past the end
+/// of an array and nulls along the way are skipped.
+fn find<'n>(
+    node: &'n Node,
+    steps: &[Step],
+    at: String,
+    found: &mut Vec<(String, &'n Node)>,
+) -> Result<(), String> {
+    let Some((step, rest)) = steps.split_first() else {
+        found.push((at, node));
+        return Ok(());
+    };
+    match (step, node) {
+        (Step::Key(key), Node::Object(fields)) => {
+            if let Some((_, value)) = fields.iter().find(|(name, _)| name == key) {
+                find(value, rest, format!("{}.{}", at, key), found)?;
+            }
+        }
+        (Step::Index(index), Node::Array(elements)) => {
+            if let Some(element) = elements.get(*index) {
+                find(element, rest, format!("{}[{}]", at, index), found)?;
+            }
+        }
+        (Step::Each, Node::Array(elements)) => {
+            for (index, element) in elements.iter().enumerate() {
+                find(element, rest, format!("{}[{}]", at, index), found)?;
+            }
+        }
+        // A path through a null, e.g. an optional object, finds nothing.
+        (_, Node::Null) => {}
+        (Step::Key(_), node) => {
+            return Err(format!(
+                "{} is {}, not an object",
+                location(&at),
+                node.kind()
+            ))
+        }
+        (_, node) => {
+            return Err(format!(
+                "{} is {}, not an array",
+                location(&at),
+                node.kind()
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Converts the numbers at `path` in the JSON `input` with `convert`,
+/// returning the input with only those numbers replaced, and how many were
+/// converted. Converted numbers are written with `places` decimal places, or
+/// as few as show them exactly; nulls at the path are kept.
+///
+/// # Example
+/// ```
+/// # use convertx::jsonpath::*;
+/// let path = ".readings[].temp".parse().unwrap();
+/// let input = r#"{"readings": [{"temp": 1}, {"temp": 2.5}]}"#;
+/// let (out, count) = convert(input, &path, None, &|v| Ok(v * 2.0)).unwrap();
+/// assert_eq!((out.as_str(), count), (r#"{"readings": [{"temp": 2}, {"temp": 5}]}"#, 2));
+/// ```
+pub fn convert(
+    input: &str,
+    path: &Path,
+    places: Option<usize>,
+    convert: &dyn Fn(f64) -> Result<f64, ConvertError>,
+) -> Result<(String, usize), String> {
+    let mut parser = Parser::new(input);
+    let mut documents = Vec::new();
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            break;
+        }
+        documents.push(parser.value()?);
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut copied = 0;
+    let mut count = 0;
+    for document in &documents {
+        let mut found = Vec::new();
+        find(document, &path.0, String::new(), &mut found)?;
+        for (at, node) in found {
+            let at = &location(&at);
+            let span = match node {
+                Node::Number(span) => span,
+                Node::Null => continue,
+                node => return Err(format!("{} is {}, not a number", at, node.kind())),
+            };
+            let value: f64 = input[span.clone()]
+                .parse()
+                .map_err(|_| format!("{}: {} is not a number", at, &input[span.clone()]))?;
+            let converted = convert(value).map_err(|e| format!("{}: {}", at, e))?;
+            out.push_str(&input[copied..span.start]);
+            match places {
+                Some(places) => out.push_str(&format!("{:.*}", places, converted)),
+                None => out.push_str(&converted.to_string()),
+            }
+            copied = span.end;
+            count += 1;
+        }
+    }
+    out.push_str(&input[copied..]);
+    Ok((out, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(value: f64) -> Result<f64, ConvertError> {
+        Ok(value * 2.0)
+    }
+
+    fn path(text: &str) -> Path {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_path() {
+        use Step::*;
+        assert_eq!(path("."), Path(vec![]));
+        assert_eq!(
+            path(".readings[].temp_f"),
+            Path(vec![Key("readings".into()), Each, Key("temp_f".into())])
+        );
+        assert_eq!(
+            path(".[2][\"a b\"].c"),
+            Path(vec![Index(2), Key("a b".into()), Key("c".into())])
+        );
+        assert_eq!(path("[0]"), Path(vec![Index(0)]));
+        assert!("readings".parse::<Path>().is_err());
+        assert!(".a..b".parse::<Path>().is_err());
+        assert!(".a[x]".parse::<Path>().is_err());
+        assert!(".a[0".parse::<Path>().is_err());
+        assert!(".a[0]b".parse::<Path>().is_err());
+    }
+
+    #[test]
+    fn test_parser() {
+        let input = r#" {"a": [1, -2.5e3, 0], "b\u00e9\n": "x\"\ud83d\ude00", "c": {}, "d": [], "e": [true, false, null]} "#;
+        let node = Parser::new(input).value().unwrap();
+        let Node::Object(fields) = node else {
+            panic!("an object");
+        };
+        assert_eq!(fields[1].0, "bé\n");
+        assert_eq!(
+            fields[0].1,
+            Node::Array(vec![
+                Node::Number(8..9),
+                Node::Number(11..17),
+                Node::Number(19..20)
+            ])
+        );
+        for (input, error) in [
+            ("{\"a\" 1}", "line 1, column 6: expected ':'"),
+            ("[1,\n 2", "line 2, column 3: expected ',' or ']'"),
+            ("[01]", "line 1, column 4: a number cannot start with 0"),
+            ("[1.]", "line 1, column 4: expected a digit after '.'"),
+            ("\"a", "line 1, column 3: a string is not closed"),
+            ("\"\\x\"", "line 1, column 3: invalid escape"),
+            ("[nul]", "line 1, column 2: expected a JSON value"),
+            ("", "line 1, column 1: unexpected end of input"),
+        ] {
+            assert_eq!(Parser::new(input).value(), Err(error.to_string()));
+        }
+        let deep = "[".repeat(MAX_DEPTH + 1);
+        assert!(Parser::new(&deep).value().unwrap_err().contains("nested"));
+    }
+
+    #[test]
+    fn test_convert() {
+        let input =
+            "{\"readings\": [\n  {\"t\": 1, \"id\": 7},\n  {\"t\": null},\n  {\"id\": 8}\n]}\n";
+        assert_eq!(
+            convert(input, &path(".readings[].t"), None, &double),
+            Ok((
+                "{\"readings\": [\n  {\"t\": 2, \"id\": 7},\n  {\"t\": null},\n  {\"id\": 8}\n]}\n"
+                    .to_string(),
+                1
+            ))
+        );
+        assert_eq!(
+            convert("[1, 2.5]\n[3]\n", &path(".[]"), Some(1), &double),
+            Ok(("[2.0, 5.0]\n[6.0]\n".to_string(), 3))
+        );
+        assert_eq!(
+            convert("{\"a\": \"5\"}", &path(".a"), None, &double),
+            Err(".a is a string, not a number".to_string())
+        );
+        assert_eq!(
+            convert("{\"a\": 5}", &path(".a[]"), None, &double),
+            Err(".a is a number, not an array".to_string())
+        );
+        assert_eq!(
+            convert("[{\"a\": 1}, [2]]", &path(".[].a"), None, &double),
+            Err(".[1] is an array, not an object".to_string())
+        );
+        assert_eq!(
+            convert("[{\"a\": [1]}]", &path(".[].a"), None, &double),
+            Err(".[0].a is an array, not a number".to_string())
+        );
+        assert_eq!(
+            convert("{\"a\": 5} x", &path(".a"), None, &double),
+            Err("line 1, column 10: expected a JSON value".to_string())
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! # convertx
+//!
+//! The conversion library behind the `convertx` command line tool: unit
+//! definitions and the registry that resolves them, quantity parsing, and the
+//! calculators behind the non-unit subcommands.
+//!
+//! Groups of subcommands sit behind cargo features, so an embedding crate or a
+//! small static binary only compiles what it uses:
+//!
+//! | Feature     | Modules                                        |
+//! |-------------|------------------------------------------------|
+//! | `media`     | [`screen`], [`video`]                          |
+//! | `dates`     | `date`, `meeting`, `workdays`, `calendar`      |
+//! | `data`      | `stats`, `series`, `csv`, `jsonpath`, `checkpoint`, `sheet` |
+//! | `serve`     | `cache`, `metrics`, `openapi`, `serve`         |
+//! | `grpc`      | `grpc` (off by default; implies `serve`)       |
+//! | `bot`       | `bot` (off by default; implies `serve`)        |
+//!
+//! Everything else, including [`units`], [`registry`] and [`quantity`], is
+//! always built. Use `default-features = false` to start from that core.
+//!
+//! The `serde` feature (off by default) implements `Serialize` and
+//! `Deserialize` for the unit enums in [`units`] and for
+//! [`conversion::ConversionResult`], for services that take conversion
+//! requests as JSON.
+
+pub mod alcohol;
+pub mod bake;
+#[cfg(feature = "bot")]
+pub mod bot;
+#[cfg(feature = "serve")]
+pub mod cache;
+#[cfg(feature = "calendars")]
+pub mod calendar;
+#[cfg(feature = "data")]
+pub mod checkpoint;
+pub mod clipboard;
+pub mod codes;
+pub mod color;
+pub mod compound;
+pub mod conversion;
+pub mod crop;
+#[cfg(feature = "data")]
+pub mod csv;
+#[cfg(feature = "dates")]
+pub mod date;
+pub mod drill;
+pub mod entropy;
+pub mod error;
+pub mod expr;
+pub mod fabric;
+pub mod gear;
+pub mod gravity;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod json;
+#[cfg(feature = "data")]
+pub mod jsonpath;
+pub mod material;
+#[cfg(feature = "dates")]
+pub mod meeting;
+#[cfg(feature = "serve")]
+pub mod metrics;
+pub mod money;
+#[cfg(feature = "serve")]
+pub mod openapi;
+pub mod pack;
+pub mod paint;
+pub mod portable;
+pub mod prefix;
+pub mod quantity;
+pub mod registry;
+pub mod rng;
+#[cfg(feature = "media")]
+pub mod screen;
+#[cfg(feature = "data")]
+pub mod series;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "arrow")]
+pub mod sheet;
+pub mod sql;
+#[cfg(feature = "data")]
+pub mod stats;
+pub mod table;
+pub mod template;
+pub mod text;
+pub mod tire;
+pub mod unit;
+pub mod units;
+#[cfg(feature = "media")]
+pub mod video;
+pub mod wire;
+#[cfg(feature = "dates")]
+pub mod workdays;
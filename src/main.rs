@@ -1,26 +1,30 @@
 //! # convertx
 //!
 //! A simple unit-conversion CLI supporting many unit types such as
-//! + bytes 
+//! + bytes
 //! + time
 //! + length
 //! + temperature
-//! + mass 
-//! + data rate, 
-//! + area, volume, 
+//! + mass
+//! + data rate,
+//! + area, volume,
 //! + speed
-//! + pressure 
+//! + pressure
+//! + counts (dozens, gross, ...)
+//! + water hardness
+//! + information (bits, nibbles, bytes, kibibits, ...)
+//! + hash rates (H/s to EH/s)
 //! + more
 //!
 //! ## Installation
 //! Simply put the following in your **Cargo.toml**.
-//! 
+//!
 //! ```toml
 //! [dependencies]
 //! convertx = "0.1.0"
 //! ```
 //! Or use `cargo add convertx`
-//! 
+//!
 //! ## Usage
 //!
 //! ```sh
@@ -33,7 +37,7 @@
 //! ```sh
 //! convertx bytes 1024 --megabytes
 //! # Output: 1024 bytes = 0.00 MB
-//! 
+//!
 //! convertx bytes 1024 -m
 //! # Output: 1024 bytes = 0.00 MB
 //! ```
@@ -42,7 +46,7 @@
 //! ```sh
 //! convertx time 3600 --human-readable
 //! # Output: 3600 seconds = 1h 0m 0s
-//! 
+//!
 //! convertx time 3600 -h
 //! # Output: 3600 seconds = 1h 0m 0s
 //! ```
@@ -50,10 +54,10 @@
 //! Convert 1 kilometer to feet:
 //! ```sh
 //! convertx length 1 --from kilometers --to feet
-//! # Output: 1.0000 kilometers = 3280.8400 feet
-//! 
+//! # Output: 1.0000 kilometer = 3280.8400 feet
+//!
 //! convertx length 10 -f kilometers -t feet
-//! # Output: 10.0000 kilometers = 32800.8400 feet
+//! # Output: 10.0000 kilometers = 32808.4000 feet
 //! ```
 //!
 //! Convert 100 Fahrenheit to Celsius:
@@ -64,138 +68,47 @@
 //!
 //! Run with `--help` to see all supported subcommands and options.
 //!
-use std::fmt;
+use std::ffi::{OsStr, OsString};
 use structopt::StructOpt;
 
-/// Constant: Number of feet in a meter.
-const FEET_IN_METER: f64 = 3.28084;
-/// Constant: Number of inches in a meter.
-const INCHES_IN_METER: f64 = 39.3701;
-/// Constant: Number of kilograms in one pound.
-const KG_IN_LB: f64 = 2.20462;
-/// Constant: Number of ounces in one kilogram.
-const OZ_IN_KG: f64 = 35.274;
-/// Constant: Number of bits per second in one megabit per second.
-const BPS_IN_MBPS: f64 = 1_000_000.0;
-/// Constant: Zero-offset for Kelvin scale.
-const KELVIN_OFFSET: f64 = 273.15;
-
-/// Command-line interface definition for convertx.
-/// Use `convertx <SUBCOMMAND> [OPTIONS]` for usage.
-#[derive(StructOpt, Debug)]
-#[structopt(name = "convertx", about = "Multi-purpose unit converter CLI")]
-enum Cli {
-    /// Convert byte values (e.g., bytes to MB or human readable).
-    Bytes {
-        /// Number of bytes to convert.
-        num: u64,
-        /// Convert bytes to megabytes.
-        #[structopt(short, long)]
-        megabytes: bool,
-        /// Convert bytes to a human-readable string (e.g., "1.00 MB").
-        #[structopt(short = "h", long = "human-readable")]
-        human_readable: bool,
-    },
-    /// Convert time (seconds) to a human-readable format.
-    Time {
-        /// Seconds to convert.
-        seconds: u64,
-        /// Convert to human-readable format (e.g., "1h 13m 5s")
-        #[structopt(short = "h", long = "human-readable")]
-        human_readable: bool,
-    },
-    /// Convert length units.
-    Length {
-        /// Value to convert.
-        value: f64,
-        /// Unit to convert from (default: meters).
-        #[structopt(short = "f", long, default_value = "meters", possible_values = &LengthUnit::variants(), case_insensitive = true)]
-        from: LengthUnit,
-        /// Unit to convert to (default: feet).
-        #[structopt(short = "t", long, default_value = "feet", possible_values = &LengthUnit::variants(), case_insensitive = true)]
-        to: LengthUnit,
-    },
-    /// Convert temperature units.
-    Temperature {
-        /// Value to convert.
-        value: f64,
-        /// Source temperature unit.
-        #[structopt(short = "f", long, possible_values = &TempUnit::variants(), case_insensitive = true)]
-        from: TempUnit,
-        /// Target temperature unit.
-        #[structopt(short = "t", long, possible_values = &TempUnit::variants(), case_insensitive = true)]
-        to: TempUnit,
-    },
-    /// Convert mass/weight units.
-    Mass {
-        /// Value to convert.
-        value: f64,
-        /// Source mass unit.
-        #[structopt(short = "f", long, possible_values = &MassUnit::variants(), case_insensitive = true)]
-        from: MassUnit,
-        /// Target mass unit.
-        #[structopt(short = "t", long, possible_values = &MassUnit::variants(), case_insensitive = true)]
-        to: MassUnit,
-    },
-    /// Convert data rate units.
-    Datarate {
-        /// Value to convert.
-        value: f64,
-        /// Source data rate unit.
-        #[structopt(short = "f", long, possible_values = &DataRateUnit::variants(), case_insensitive = true)]
-        from: DataRateUnit,
-        /// Target data rate unit.
-        #[structopt(short = "t", long, possible_values = &DataRateUnit::variants(), case_insensitive = true)]
-        to: DataRateUnit,
-    },
-    /// Convert area units.
-    Area {
-        /// Value to convert.
-        value: f64,
-        /// Source area unit.
-        #[structopt(short = "f", long, possible_values = &AreaUnit::variants(), case_insensitive = true)]
-        from: AreaUnit,
-        /// Target area unit.
-        #[structopt(short = "t", long, possible_values = &AreaUnit::variants(), case_insensitive = true)]
-        to: AreaUnit,
-    },
-    /// Convert volume units.
-    Volume {
-        /// Value to convert.
-        value: f64,
-        /// Source volume unit.
-        #[structopt(short = "f", long, possible_values = &VolumeUnit::variants(), case_insensitive = true)]
-        from: VolumeUnit,
-        /// Target volume unit.
-        #[structopt(short = "t", long, possible_values = &VolumeUnit::variants(), case_insensitive = true)]
-        to: VolumeUnit,
-    },
-    /// Convert speed units.
-    Speed {
-        /// Value to convert.
-        value: f64,
-        /// Source speed unit.
-        #[structopt(short = "f", long, possible_values = &SpeedUnit::variants(), case_insensitive = true)]
-        from: SpeedUnit,
-        /// Target speed unit.
-        #[structopt(short = "t", long, possible_values = &SpeedUnit::variants(), case_insensitive = true)]
-        to: SpeedUnit,
-    },
-    /// Convert pressure units.
-    Pressure {
-        /// Value to convert.
-        value: f64,
-        /// Source pressure unit.
-        #[structopt(short = "f", long, possible_values = &PressureUnit::variants(), case_insensitive = true)]
-        from: PressureUnit,
-        /// Target pressure unit.
-        #[structopt(short = "t", long, possible_values = &PressureUnit::variants(), case_insensitive = true)]
-        to: PressureUnit,
-    },
+use convertx::{color, conversion, error, portable, quantity};
+
+use commands::category::{self, UnitSystem};
+#[cfg(feature = "data")]
+use commands::data;
+#[cfg(feature = "dates")]
+use commands::dates;
+#[cfg(feature = "finance")]
+use commands::finance;
+#[cfg(feature = "media")]
+use commands::media;
+use commands::output::{self, Format, FORMAT, PRECISION};
+#[cfg(feature = "serve")]
+use commands::server;
+use commands::{computing, convert, farm, kitchen, workshop, writing};
+use conversion::Measurement;
+use error::ConvertError;
+
+/// Prints a line of a subcommand's result, `say!(value => result, ...)` or
+/// `say!(result, ...)` with the [`Figure`](commands::output::Figure)s in it
+/// and a `format!` of its text, as the global `--format` asks, see
+/// [`say`](commands::output::say).
+///
+/// Machine readable output, such as `--output json`, uses `println!`.
+macro_rules! say {
+    ($value:expr => $result:expr, $($arg:tt)*) => {
+        $crate::commands::output::say(Some($value), $result, &format!($($arg)*))
+    };
+    ($result:expr, $($arg:tt)*) => {
+        $crate::commands::output::say(None, $result, &format!($($arg)*))
+    };
 }
 
 /// Macro for quickly defining enums with string variants and utility implementations.
 ///
+/// The units of the conversion categories are defined with `unit_enum!` in
+/// [`units`](convertx::units) instead.
+///
 /// # Example
 ///
 /// ```rust
@@ -206,17 +119,8 @@ enum Cli {
 /// });
 /// ```
 macro_rules! enum_with_variants {
-    ($name:ident { $($variant:ident => $val:expr),* $(,)? }) => {
-        #[derive(Debug, Clone, PartialEq)]
-        enum $name {
-            $($variant,)*
-        }
-        impl $name {
-            /// Returns a static list of all variant names as strings.
-            fn variants() -> &'static [&'static str] {
-                &[$($val),*]
-            }
-        }
+    ($vis:vis $name:ident { $($variant:ident => $val:expr),* $(,)? }) => {
+        enum_with_variants!(@enum $vis $name { $($variant => $val),* });
         impl ::std::str::FromStr for $name {
             type Err = String;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -226,529 +130,352 @@ macro_rules! enum_with_variants {
                 }
             }
         }
-        impl fmt::Display for $name {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    };
+    (@enum $vis:vis $name:ident { $($variant:ident => $val:expr),* }) => {
+        #[derive(Debug, Clone, PartialEq)]
+        $vis enum $name {
+            $($variant,)*
+        }
+        impl $name {
+            /// Returns a static list of all variant names as strings.
+            $vis fn variants() -> &'static [&'static str] {
+                &[$($val),*]
+            }
+        }
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                 let s = match self {
                     $(Self::$variant => $val,)*
                 };
                 write!(f, "{}", s)
             }
         }
-    }
+    };
 }
 
-// Define enums for each category with macro.
-// Supported units for length.
-enum_with_variants!(LengthUnit {
-    Meters => "meters",
-    Feet => "feet",
-    Inches => "inches",
-    Kilometers => "kilometers",
-});
-
-// Supported units for temperature.
-enum_with_variants!(TempUnit {
-    C => "c",
-    F => "f",
-    K => "k",
-});
-
-// Supported units for mass/weight.
-enum_with_variants!(MassUnit {
-    Kg => "kg",
-    Lb => "lb",
-    Oz => "oz",
-});
-
-// Supported units for data rate.
-enum_with_variants!(DataRateUnit {
-    Bps => "bps",
-    Mbps => "mbps",
-});
-
-// Supported units for area.
-enum_with_variants!(AreaUnit {
-    SquareMeters => "sqm",
-    SquareFeet => "sqft",
-    Acres => "acres",
-    Hectares => "hectares",
-});
-
-// Supported units for volume.
-enum_with_variants!(VolumeUnit {
-    Liters => "liters",
-    Milliliters => "milliliters",
-    CubicMeters => "cubic_meters",
-    CubicInches => "cubic_inches",
-    Gallons => "gallons",
-});
+// Declared after the macros above, which its modules use.
+mod commands;
 
-// Supported units for speed.
-enum_with_variants!(SpeedUnit {
-    Mps => "mps",
-    Kph => "kph",
-    Mph => "mph",
-    Knots => "knots",
-});
+/// Command-line interface definition for convertx.
+/// Use `convertx <SUBCOMMAND> [OPTIONS]` for usage.
+#[derive(StructOpt, Debug)]
+#[structopt(name = "convertx", about = "Multi-purpose unit converter CLI")]
+enum Cli {
+    // Each family of subcommands is its own enum, whose subcommands are listed
+    // here as if declared directly.
+    #[structopt(flatten)]
+    Category(category::Command),
+    #[structopt(flatten)]
+    Convert(convert::Command),
+    #[structopt(flatten)]
+    Computing(computing::Command),
+    #[structopt(flatten)]
+    Writing(writing::Command),
+    #[cfg(feature = "media")]
+    #[structopt(flatten)]
+    Media(media::Command),
+    #[structopt(flatten)]
+    Workshop(workshop::Command),
+    #[structopt(flatten)]
+    Kitchen(kitchen::Command),
+    #[structopt(flatten)]
+    Farm(farm::Command),
+    #[cfg(feature = "finance")]
+    #[structopt(flatten)]
+    Finance(finance::Command),
+    #[cfg(feature = "dates")]
+    #[structopt(flatten)]
+    Dates(dates::Command),
+    #[cfg(feature = "data")]
+    #[structopt(flatten)]
+    Data(data::Command),
+    #[cfg(feature = "serve")]
+    #[structopt(flatten)]
+    Server(server::Command),
+}
 
-// Supported units for pressure.
-enum_with_variants!(PressureUnit {
-    Pascal => "pa",
-    Bar => "bar",
-    Atm => "atm",
-    Psi => "psi",
+// Supported values of `--color`.
+enum_with_variants!(ColorChoice {
+    Auto => "auto",
+    Always => "always",
+    Never => "never",
 });
 
-/// Convert bytes to megabytes.
-///
-/// # Example
-/// ```
-/// assert_eq!(bytes_to_mb(1048576), 1.0);
-/// ```
-fn bytes_to_mb(num_bytes: u64) -> f64 {
-    num_bytes as f64 / (1024.0 * 1024.0)
-}
-
-/// Convert a number of bytes to a human-readable string.
-///
-/// # Example
-/// ```
-/// assert_eq!(bytes_to_human_readable(1048576), "1.00 MB");
-/// ```
-fn bytes_to_human_readable(num_bytes: u64) -> String {
-    let units = ["B", "KB", "MB", "GB", "TB", "PB"];
-    let mut idx = 0;
-    let mut n = num_bytes as f64;
-    while n >= 1024.0 && idx < units.len() - 1 {
-        n /= 1024.0;
-        idx += 1;
-    }
-    format!("{:.2} {}", n, units[idx])
+/// Reports a failed conversion and exits with the error's exit code.
+fn fail(error: ConvertError) -> ! {
+    eprintln!("error: {}", error);
+    std::process::exit(error.exit_code());
 }
 
-/// Convert seconds to a human-readable string (e.g., days, hours, minutes, seconds).
-///
-/// # Example
-/// ```
-/// assert_eq!(seconds_to_human_readable(3661), "1h 1m 1s");
-/// ```
-fn seconds_to_human_readable(seconds: u64) -> String {
-    let (d, h, mut m, s);
-    m = seconds / 60;
-    s = seconds % 60;
-    h = m / 60;
-    m = m % 60;
-    d = h / 24;
-    let mut parts = vec![];
-    if d > 0 {
-        parts.push(format!("{}d", d));
+/// Reports a command line clap could not read and exits like [`fail`], with
+/// the code of the error of the value it rejected, else that of a parse error.
+/// Help and the version are printed as they are.
+fn reject(error: structopt::clap::Error) -> ! {
+    if !error.use_stderr() {
+        error.exit();
     }
-    if h % 24 > 0 {
-        parts.push(format!("{}h", h % 24));
-    }
-    if m > 0 {
-        parts.push(format!("{}m", m));
-    }
-    if s > 0 || parts.is_empty() {
-        parts.push(format!("{}s", s));
-    }
-    parts.join(" ")
-}
-
-/// Convert between length units.
-///
-/// Returns `Some(result)` if conversion is supported.
-///
-/// # Example
-/// ```
-/// use crate::LengthUnit::*;
-/// assert!((convert_length(1.0, Meters, Feet).unwrap() - 3.28084).abs() < 1e-5);
-/// ```
-fn convert_length(value: f64, from: LengthUnit, to: LengthUnit) -> Option<f64> {
-    use LengthUnit::*;
-    let in_meters = match from {
-        Meters => value,
-        Feet => value / FEET_IN_METER,
-        Inches => value / INCHES_IN_METER,
-        Kilometers => value * 1000.0,
-    };
-    let result = match to {
-        Meters => in_meters,
-        Feet => in_meters * FEET_IN_METER,
-        Inches => in_meters * INCHES_IN_METER,
-        Kilometers => in_meters / 1000.0,
-    };
-    Some(result)
-}
-
-/// Convert between temperature units (Celsius, Fahrenheit, Kelvin).
-///
-/// # Example
-/// ```
-/// use crate::TempUnit::*;
-/// assert!((convert_temp(0.0, C, F).unwrap() - 32.0).abs() < 1e-6);
-/// ```
-fn convert_temp(value: f64, from: TempUnit, to: TempUnit) -> Option<f64> {
-    use TempUnit::*;
-    let celsius = match from {
-        C => value,
-        F => (value - 32.0) * 5.0 / 9.0,
-        K => value - KELVIN_OFFSET,
-    };
-    let result = match to {
-        C => celsius,
-        F => celsius * 9.0 / 5.0 + 32.0,
-        K => celsius + KELVIN_OFFSET,
-    };
-    Some(result)
-}
-
-/// Convert between mass units.
-///
-/// # Example
-/// ```
-/// use crate::MassUnit::*;
-/// assert!((convert_mass(1.0, Kg, Lb).unwrap() - 2.20462).abs() < 1e-5);
-/// ```
-fn convert_mass(value: f64, from: MassUnit, to: MassUnit) -> Option<f64> {
-    use MassUnit::*;
-    let in_kg = match from {
-        Kg => value,
-        Lb => value / KG_IN_LB,
-        Oz => value / OZ_IN_KG,
-    };
-    let result = match to {
-        Kg => in_kg,
-        Lb => in_kg * KG_IN_LB,
-        Oz => in_kg * OZ_IN_KG,
+    let kind = match error.kind {
+        structopt::clap::ErrorKind::ValueValidation => quantity::take_number_error(),
+        _ => None,
     };
-    Some(result)
+    eprintln!("{}", error.message);
+    let kind = kind.unwrap_or(ConvertError::Parse(error.message));
+    std::process::exit(kind.exit_code());
 }
 
-/// Convert between data rate units (bps, Mbps).
-///
-/// # Example
-/// ```
-/// use crate::DataRateUnit::*;
-/// assert_eq!(convert_datarate(1_000_000.0, Bps, Mbps), Some(1.0));
-/// ```
-fn convert_datarate(value: f64, from: DataRateUnit, to: DataRateUnit) -> Option<f64> {
-    use DataRateUnit::*;
-    match (from, to) {
-        (Bps, Mbps) => Some(value / BPS_IN_MBPS),
-        (Mbps, Bps) => Some(value * BPS_IN_MBPS),
-        _ => Some(value),
+/// Fails with [`ConvertError::OutOfRange`] and `reason` if any of `values` is
+/// not greater than zero.
+fn check_positive<'a>(values: impl IntoIterator<Item = &'a f64>, reason: &str) {
+    if let Some(&value) = values
+        .into_iter()
+        .find(|value| !value.is_nan() && **value <= 0.0)
+    {
+        fail(ConvertError::OutOfRange {
+            value,
+            reason: reason.to_string(),
+        });
     }
 }
 
-/// Convert between area units.
-///
-/// # Example
-/// ```
-/// use crate::AreaUnit::*;
-/// assert!((convert_area(1.0, Acres, SquareMeters).unwrap() - 4046.85642).abs() < 1e-4);
-/// ```
-fn convert_area(value: f64, from: AreaUnit, to: AreaUnit) -> Option<f64> {
-    use AreaUnit::*;
-    let sqm = match from {
-        SquareMeters => value,
-        SquareFeet => value / 10.7639,
-        Acres => value * 4046.85642,
-        Hectares => value * 10000.0,
-    };
-    let result = match to {
-        SquareMeters => sqm,
-        SquareFeet => sqm * 10.7639,
-        Acres => sqm / 4046.85642,
-        Hectares => sqm / 10000.0,
-    };
-    Some(result)
-}
-
-/// Convert between volume units.
-///
-/// # Example
-/// ```
-/// use crate::VolumeUnit::*;
-/// assert!((convert_volume(1.0, Gallons, Liters).unwrap() - 3.78541).abs() < 1e-5);
-/// ```
-fn convert_volume(value: f64, from: VolumeUnit, to: VolumeUnit) -> Option<f64> {
-    use VolumeUnit::*;
-    let liters = match from {
-        Liters => value,
-        Milliliters => value / 1000.0,
-        CubicMeters => value * 1000.0,
-        CubicInches => value / 61.0237,
-        Gallons => value * 3.78541,
-    };
-    let result = match to {
-        Liters => liters,
-        Milliliters => liters * 1000.0,
-        CubicMeters => liters / 1000.0,
-        CubicInches => liters * 61.0237,
-        Gallons => liters / 3.78541,
-    };
-    Some(result)
-}
-
-/// Convert between speed units.
-///
-/// # Example
-/// ```
-/// use crate::SpeedUnit::*;
-/// assert!((convert_speed(1.0, Mps, Kph).unwrap() - 3.6).abs() < 1e-6);
-/// ```
-fn convert_speed(value: f64, from: SpeedUnit, to: SpeedUnit) -> Option<f64> {
-    use SpeedUnit::*;
-    let mps = match from {
-        Mps => value,
-        Kph => value / 3.6,
-        Mph => value * 0.44704,
-        Knots => value * 0.514444,
-    };
-    let result = match to {
-        Mps => mps,
-        Kph => mps * 3.6,
-        Mph => mps / 0.44704,
-        Knots => mps / 0.514444,
-    };
-    Some(result)
+/// Returns `result`, computed from `value`, or fails with
+/// [`ConvertError::OutOfRange`] if a finite value overflowed to infinity.
+fn finite_result(value: f64, result: f64) -> f64 {
+    if value.is_finite() && !result.is_finite() {
+        fail(ConvertError::OutOfRange {
+            value,
+            reason: "the result is too large to represent".to_string(),
+        });
+    }
+    result
 }
 
-/// Convert between pressure units.
-///
-/// # Example
-/// ```
-/// use crate::PressureUnit::*;
-/// assert!((convert_pressure(1.0, Atm, Pascal).unwrap() - 101325.0).abs() < 1e-3);
-/// ```
-fn convert_pressure(value: f64, from: PressureUnit, to: PressureUnit) -> Option<f64> {
-    use PressureUnit::*;
-    let pa = match from {
-        Pascal => value,
-        Bar => value * 100000.0,
-        Atm => value * 101325.0,
-        Psi => value * 6894.76,
-    };
-    let result = match to {
-        Pascal => pa,
-        Bar => pa / 100000.0,
-        Atm => pa / 101325.0,
-        Psi => pa / 6894.76,
-    };
-    Some(result)
+/// Lets a closed stdout end the process quietly, as it does other Unix tools,
+/// instead of panicking on the next `println!`, e.g. in `convertx units | head`.
+#[cfg(unix)]
+fn restore_sigpipe() {
+    extern "C" {
+        fn signal(signum: i32, handler: usize) -> usize;
+    }
+    const SIGPIPE: i32 = 13;
+    const SIG_DFL: usize = 0;
+    // SAFETY: `signal` only swaps the handler, and no other thread exists yet.
+    unsafe {
+        signal(SIGPIPE, SIG_DFL);
+    }
 }
 
-/// Entry point for the CLI application.
-///
-/// Parses CLI arguments, dispatches the appropriate conversion, and prints results.
-
-fn main() {
-    let cli = Cli::from_args();
-    match cli {
-        Cli::Bytes {
-            num,
-            megabytes,
-            human_readable,
-        } => {
-            if megabytes {
-                println!("{} bytes = {:.2} MB", num, bytes_to_mb(num));
-            } else if human_readable {
-                println!("{} bytes = {}", num, bytes_to_human_readable(num));
-            } else {
-                println!("Please specify --megabytes or --human-readable. See --help.");
-            }
-        }
-        Cli::Time {
-            seconds,
-            human_readable,
-        } => {
-            if human_readable {
-                println!(
-                    "{} seconds = {}",
-                    seconds,
-                    seconds_to_human_readable(seconds)
-                );
-            } else {
-                println!("Please specify --human-readable. See --help.");
-            }
-        }
-        Cli::Length { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_length(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-        Cli::Temperature { value, from, to } => {
-            if from == to {
-                println!(
-                    "{:.2}°{} = {:.2}°{}",
-                    value,
-                    format!("{}", from).to_uppercase(),
-                    to,
-                    format!("{}", to).to_uppercase()
-                );
-            } else if let Some(result) = convert_temp(value, from.clone(), to.clone()) {
-                println!(
-                    "{:.2}°{} = {:.2}°{}",
-                    value,
-                    format!("{}", from).to_uppercase(),
-                    result,
-                    format!("{}", to).to_uppercase()
-                );
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-
-        Cli::Mass { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_mass(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-        Cli::Datarate { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_datarate(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-        Cli::Area { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_area(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-        Cli::Volume { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_volume(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
-        }
-        Cli::Speed { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_speed(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
+/// The code page of the console before [`use_utf8_console`], restored at exit.
+#[cfg(windows)]
+static CONSOLE_CODE_PAGE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+/// Switches the Windows console to UTF-8 while convertx runs, so the unit
+/// symbols it prints, such as °C, µs and m², reach a program reading its
+/// output, e.g. `more` or PowerShell, intact rather than in the legacy code
+/// page. The console's own code page is put back at exit.
+#[cfg(windows)]
+fn use_utf8_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleOutputCP() -> u32;
+        fn SetConsoleOutputCP(code_page: u32) -> i32;
+    }
+    extern "C" {
+        fn atexit(callback: extern "C" fn()) -> i32;
+    }
+    extern "C" fn restore() {
+        let code_page = CONSOLE_CODE_PAGE.load(std::sync::atomic::Ordering::Relaxed);
+        // SAFETY: setting the code page only affects the attached console.
+        unsafe {
+            SetConsoleOutputCP(code_page);
         }
-        Cli::Pressure { value, from, to } => {
-            if from == to {
-                println!("{:.4} {} = {:.4} {}", value, from, value, to);
-            } else if let Some(result) = convert_pressure(value, from.clone(), to.clone()) {
-                println!("{:.4} {} = {:.4} {}", value, from, result, to);
-            } else {
-                println!("Conversion from {} to {} not supported.", from, to);
-            }
+    }
+    const CP_UTF8: u32 = 65001;
+    // SAFETY: these only read and set the code page of the attached console,
+    // returning 0 without one, and `restore` is safe to run at exit.
+    unsafe {
+        let code_page = GetConsoleOutputCP();
+        if code_page == 0 || code_page == CP_UTF8 || SetConsoleOutputCP(CP_UTF8) == 0 {
+            return;
         }
+        CONSOLE_CODE_PAGE.store(code_page, std::sync::atomic::Ordering::Relaxed);
+        atexit(restore);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_bytes_to_mb() {
-        assert_eq!(bytes_to_mb(1048576), 1.0);
-        assert!((bytes_to_mb(2097152) - 2.0).abs() < 1e-8);
+/// Lets the Windows console render the escape codes of `--color`. Returns
+/// false for a console that cannot, such as those before Windows 10.
+#[cfg(windows)]
+fn use_ansi_console() -> bool {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(handle: u32) -> isize;
+        fn GetConsoleMode(console: isize, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: isize, mode: u32) -> i32;
     }
-
-    #[test]
-    fn test_bytes_to_human_readable() {
-        assert_eq!(bytes_to_human_readable(1023), "1023.00 B");
-        assert_eq!(bytes_to_human_readable(1024), "1.00 KB");
-        assert_eq!(bytes_to_human_readable(1048576), "1.00 MB");
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+    // SAFETY: these only read and set the mode of the attached console, and
+    // fail without one, e.g. when stdout is a pipe the codes are written as is.
+    unsafe {
+        let console = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut mode = 0;
+        GetConsoleMode(console, &mut mode) == 0
+            || SetConsoleMode(console, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
     }
+}
 
-    #[test]
-    fn test_seconds_to_human_readable() {
-        assert_eq!(seconds_to_human_readable(59), "59s");
-        assert_eq!(seconds_to_human_readable(61), "1m 1s");
-        assert_eq!(seconds_to_human_readable(3661), "1h 1m 1s");
-        assert_eq!(seconds_to_human_readable(90061), "1d 1h 1m 1s");
+/// Drops a `CONVERTX_SYSTEM` default that is empty or not a unit system, with a
+/// warning for the latter, so a stray environment cannot stop every conversion.
+fn check_environment() {
+    let Some(value) = std::env::var_os("CONVERTX_SYSTEM") else {
+        return;
+    };
+    if value
+        .to_str()
+        .is_some_and(|v| v.parse::<UnitSystem>().is_ok())
+    {
+        return;
     }
-
-    #[test]
-    fn test_convert_length() {
-        use LengthUnit::*;
-        assert!((convert_length(1.0, Meters, Feet).unwrap() - 3.28084).abs() < 1e-5);
-        assert!((convert_length(3.28084, Feet, Meters).unwrap() - 1.0).abs() < 1e-5);
-        assert!((convert_length(1.0, Kilometers, Meters).unwrap() - 1000.0).abs() < 1e-5);
-        assert!((convert_length(12.0, Inches, Feet).unwrap() - 1.0).abs() < 1e-5);
+    if !value.is_empty() {
+        eprintln!(
+            "warning: ignoring CONVERTX_SYSTEM={:?}, which is not one of {}",
+            value,
+            UnitSystem::variants().join(", ")
+        );
     }
+    std::env::remove_var("CONVERTX_SYSTEM");
+}
 
-    #[test]
-    fn test_convert_temp() {
-        use TempUnit::*;
-        assert!((convert_temp(0.0, C, F).unwrap() - 32.0).abs() < 1e-6);
-        assert!((convert_temp(32.0, F, C).unwrap() - 0.0).abs() < 1e-6);
-        assert!((convert_temp(100.0, C, K).unwrap() - 373.15).abs() < 1e-2);
-        assert!((convert_temp(0.0, K, C).unwrap() - -273.15).abs() < 1e-2);
+/// Returns the command line, busybox style: run through a link named after a
+/// subcommand, e.g. `temperature -> convertx`, the link name is the subcommand.
+/// A value in place of a subcommand, e.g. `convertx 72kg --to lb`, runs `convert`.
+fn command_line() -> Vec<OsString> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    let applet = args
+        .first()
+        .and_then(|program| std::path::Path::new(program).file_stem())
+        .and_then(OsStr::to_str)
+        .filter(|&name| name != "convertx")
+        .map(str::to_string);
+    if let Some(applet) = applet {
+        let probe = Cli::clap().get_matches_from_safe(["convertx", &applet, "--help"]);
+        if probe.is_err_and(|e| e.kind == structopt::clap::ErrorKind::HelpDisplayed) {
+            args.insert(1, applet.into());
+        }
     }
-
-    #[test]
-    fn test_convert_mass() {
-        use MassUnit::*;
-        assert!((convert_mass(1.0, Kg, Lb).unwrap() - 2.20462).abs() < 1e-5);
-        assert!((convert_mass(2.20462, Lb, Kg).unwrap() - 1.0).abs() < 1e-5);
-        assert!((convert_mass(1.0, Kg, Oz).unwrap() - 35.274).abs() < 1e-3);
-        assert!((convert_mass(35.274, Oz, Kg).unwrap() - 1.0).abs() < 1e-3);
+    let value = args.get(1).and_then(|arg| arg.to_str());
+    if value.is_some_and(|value| value.parse::<Measurement>().is_ok()) {
+        args.insert(1, "convert".into());
     }
+    args
+}
 
-    #[test]
-    fn test_convert_datarate() {
-        use DataRateUnit::*;
-        assert!((convert_datarate(1_000_000.0, Bps, Mbps).unwrap() - 1.0).abs() < 1e-8);
-        assert!((convert_datarate(1.0, Mbps, Bps).unwrap() - 1_000_000.0).abs() < 1e-8);
-    }
+/// Returns the command line parser, with the flags every subcommand accepts.
+fn app() -> structopt::clap::App<'static, 'static> {
+    Cli::clap()
+        .arg(
+            structopt::clap::Arg::with_name("allow-non-finite")
+                .long("allow-non-finite")
+                .global(true)
+                .help("Accept NaN and infinite values and pass them through conversions"),
+        )
+        .arg(
+            structopt::clap::Arg::with_name("precision")
+                .long("precision")
+                .value_name("N")
+                .global(true)
+                .validator(|n| n.parse::<usize>().map(drop).map_err(|e| e.to_string()))
+                .help("Decimal places of the numbers shown [default: adaptive]"),
+        )
+        .arg(
+            structopt::clap::Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .global(true)
+                .validator(|format| format.parse::<Format>().map(drop))
+                .help(
+                    "Output format: an output mode, as for --output, or a template of a unit \
+                     conversion, e.g. \"{value} {from} is {result:.3} {to_symbol}\". \
+                     Placeholders: value, from, to, from_symbol, to_symbol, result, category; \
+                     {{ and }} escape braces",
+                ),
+        )
+        .arg(
+            structopt::clap::Arg::with_name("reproducible")
+                .long("reproducible")
+                .global(true)
+                .help("Use portable math that gives bit-identical results on every platform"),
+        )
+        .arg(
+            structopt::clap::Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .global(true)
+                .possible_values(ColorChoice::variants())
+                .default_value("auto")
+                .help("Highlight results in color: auto colors a terminal unless NO_COLOR is set"),
+        )
+}
 
-    #[test]
-    fn test_convert_area() {
-        use AreaUnit::*;
-        assert!((convert_area(1.0, Acres, SquareMeters).unwrap() - 4046.85642).abs() < 1e-4);
-        assert!((convert_area(1.0, SquareMeters, Acres).unwrap() - 0.000247105).abs() < 1e-7);
-        assert!((convert_area(1.0, Hectares, Acres).unwrap() - 2.47105).abs() < 1e-5);
+/// Entry point for the CLI application.
+///
+/// Parses CLI arguments, dispatches the appropriate conversion, and prints results.
+fn main() {
+    #[cfg(unix)]
+    restore_sigpipe();
+    #[cfg(windows)]
+    use_utf8_console();
+    check_environment();
+    let args = command_line();
+    // Values are checked as they are parsed, so the policy has to be set first.
+    quantity::set_allow_non_finite(args.iter().any(|arg| arg == "--allow-non-finite"));
+    portable::set_reproducible(args.iter().any(|arg| arg == "--reproducible"));
+    let matches = app()
+        .get_matches_from_safe(args)
+        .unwrap_or_else(|error| reject(error));
+    if let Some(precision) = matches.value_of("precision") {
+        let precision = precision.parse().expect("--precision is validated");
+        PRECISION.store(precision, std::sync::atomic::Ordering::Relaxed);
     }
-
-    #[test]
-    fn test_convert_volume() {
-        use VolumeUnit::*;
-        assert!((convert_volume(1.0, Gallons, Liters).unwrap() - 3.78541).abs() < 1e-5);
-        assert!((convert_volume(1.0, Liters, Gallons).unwrap() - 0.264172).abs() < 1e-6);
-        assert!((convert_volume(1000.0, Milliliters, Liters).unwrap() - 1.0).abs() < 1e-6);
+    let choice = matches.value_of("color").and_then(|c| c.parse().ok());
+    let colored = match choice {
+        Some(ColorChoice::Always) => true,
+        Some(ColorChoice::Never) => false,
+        _ => {
+            let terminal = std::io::IsTerminal::is_terminal(&std::io::stdout());
+            color::auto(terminal, std::env::var_os("NO_COLOR").as_deref())
+        }
+    };
+    #[cfg(windows)]
+    let colored = colored && use_ansi_console();
+    color::set_enabled(colored);
+    if let Some(format) = matches.value_of("format") {
+        let format = format.parse().expect("--format is validated");
+        FORMAT.set(format).expect("--format is read once");
     }
-
-    #[test]
-    fn test_convert_speed() {
-        use SpeedUnit::*;
-        assert!((convert_speed(1.0, Mps, Kph).unwrap() - 3.6).abs() < 1e-6);
-        assert!((convert_speed(3.6, Kph, Mps).unwrap() - 1.0).abs() < 1e-6);
-        assert!((convert_speed(1.0, Knots, Mph).unwrap() - 1.15078).abs() < 1e-5);
+    if let Some(subcommand) = matches.subcommand_name() {
+        output::SUBCOMMAND
+            .set(subcommand.to_string())
+            .expect("the subcommand is read once");
     }
-
-    #[test]
-    fn test_convert_pressure() {
-        use PressureUnit::*;
-        assert!((convert_pressure(1.0, Atm, Pascal).unwrap() - 101325.0).abs() < 1e-3);
-        assert!((convert_pressure(1.0, Psi, Bar).unwrap() - 0.0689476).abs() < 1e-6);
-        assert!((convert_pressure(1.0, Bar, Psi).unwrap() - 14.5038).abs() < 1e-4);
+    let cli = Cli::from_clap(&matches);
+    match cli {
+        Cli::Category(command) => command.run(),
+        Cli::Convert(command) => command.run(),
+        Cli::Computing(command) => command.run(),
+        Cli::Writing(command) => command.run(),
+        #[cfg(feature = "media")]
+        Cli::Media(command) => command.run(),
+        Cli::Workshop(command) => command.run(),
+        Cli::Kitchen(command) => command.run(),
+        Cli::Farm(command) => command.run(),
+        #[cfg(feature = "finance")]
+        Cli::Finance(command) => command.run(),
+        #[cfg(feature = "dates")]
+        Cli::Dates(command) => command.run(),
+        #[cfg(feature = "data")]
+        Cli::Data(command) => command.run(),
+        #[cfg(feature = "serve")]
+        Cli::Server(command) => command.run(),
     }
 }
@@ -0,0 +1,78 @@
+//! Bulk building materials for `convertx material`.
+//!
+//! Densities are typical in-place values in kg/m³; real loads vary with
+//! moisture and compaction, so the results are estimates for ordering.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Typical densities of bulk materials, in kilograms per cubic meter.
+const DENSITIES: &[(&str, f64)] = &[
+    ("concrete", 2400.0),
+    ("asphalt", 2320.0),
+    ("gravel", 1680.0),
+    ("sand", 1600.0),
+    ("topsoil", 1250.0),
+    ("mulch", 350.0),
+];
+
+/// A bulk material from the density table, e.g. `concrete` or `gravel`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    name: &'static str,
+    pub kg_per_m3: f64,
+}
+
+impl Material {
+    /// Returns the mass in kilograms of `m3` cubic meters of the material.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::material::*;
+    /// let concrete: Material = "concrete".parse().unwrap();
+    /// assert_eq!(concrete.mass_kg(2.5), 6000.0);
+    /// ```
+    pub fn mass_kg(&self, m3: f64) -> f64 {
+        m3 * self.kg_per_m3
+    }
+}
+
+impl FromStr for Material {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DENSITIES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+            .map(|(name, kg_per_m3)| Material {
+                name,
+                kg_per_m3: *kg_per_m3,
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = DENSITIES.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown material '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_material() {
+        let gravel: Material = "Gravel".parse().unwrap();
+        assert_eq!(gravel.to_string(), "gravel");
+        assert_eq!(gravel.mass_kg(0.5), 840.0);
+        assert!("granite".parse::<Material>().is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Time zone lookups for `convertx meeting`.
+//!
+//! Zones are IANA names from the tz database (`Europe/Berlin`), or just their
+//! city part (`berlin`, `new_york`), matched case-insensitively.
+
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+use chrono_tz::{Tz, TZ_VARIANTS};
+
+/// Date and time layouts accepted for the meeting time.
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"];
+
+/// Finds a time zone by IANA name or by its city, e.g. `tokyo` or `New York`.
+///
+/// # Example
+/// ```
+/// # use convertx::meeting::*;
+/// assert_eq!(find_zone("new_york"), Ok(chrono_tz::America::New_York));
+/// ```
+pub fn find_zone(name: &str) -> Result<Tz, String> {
+    let name = name.trim();
+    if let Ok(zone) = name.parse::<Tz>() {
+        return Ok(zone);
+    }
+    let city = name.replace(' ', "_");
+    TZ_VARIANTS
+        .iter()
+        .find(|zone| {
+            let zone = zone.name();
+            zone.eq_ignore_ascii_case(&city)
+                || zone
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|last| last.eq_ignore_ascii_case(&city))
+        })
+        .copied()
+        .ok_or_else(|| format!("unknown time zone '{}'", name))
+}
+
+/// Parses a time such as `2025-03-01 15:00 UTC` or `2025-03-01 09:30 berlin`.
+/// Without a zone, the time is taken as UTC.
+///
+/// # Example
+/// ```
+/// # use convertx::meeting::*;
+/// let time = parse_time("2025-03-01 15:00 tokyo").unwrap();
+/// assert_eq!(time.timezone(), chrono_tz::Asia::Tokyo);
+/// ```
+pub fn parse_time(s: &str) -> Result<DateTime<Tz>, String> {
+    let s = s.trim();
+    let invalid = || format!("'{}' is not a time like 2025-03-01 15:00 UTC", s);
+    let local = |text: &str| {
+        TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    };
+    let (naive, zone) = match local(s) {
+        Some(naive) => (naive, Tz::UTC),
+        None => {
+            let (text, zone) = s.rsplit_once(' ').ok_or_else(invalid)?;
+            (local(text.trim()).ok_or_else(invalid)?, find_zone(zone)?)
+        }
+    };
+    zone.from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{} does not exist in {}", naive, zone))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::{America, Asia, Europe};
+
+    #[test]
+    fn test_find_zone() {
+        assert_eq!(find_zone("Europe/Berlin"), Ok(Europe::Berlin));
+        assert_eq!(find_zone("berlin"), Ok(Europe::Berlin));
+        assert_eq!(find_zone("New York"), Ok(America::New_York));
+        assert_eq!(find_zone("UTC"), Ok(Tz::UTC));
+        assert!(find_zone("atlantis").is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let time = parse_time("2025-03-01 15:00 UTC").unwrap();
+        let tokyo = time.with_timezone(&Asia::Tokyo);
+        assert_eq!(
+            tokyo.format("%Y-%m-%d %H:%M").to_string(),
+            "2025-03-02 00:00"
+        );
+        assert_eq!(parse_time("2025-03-01 15:00").unwrap(), time);
+        assert!(parse_time("2025-03-01 02:30 new_york").is_ok());
+        // Clocks in New York skip from 02:00 to 03:00 on 2025-03-09.
+        assert!(parse_time("2025-03-09 02:30 new_york").is_err());
+        assert!(parse_time("tomorrow").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Counters and latency histograms for `GET /metrics` of `convertx serve`,
+//! in the Prometheus text format.
+//!
+//! Labels only take a few values, so the number of series stays bounded
+//! however the server is queried: endpoints other than the server's own are
+//! counted as `other`, and categories other than the registered ones as
+//! `compound`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+pub const BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// The observations of one histogram.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Histogram {
+    /// Observations at or below each of [`BUCKETS`], not cumulative.
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        self.buckets.resize(BUCKETS.len(), 0);
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    /// Responses by endpoint and status.
+    requests: BTreeMap<(&'static str, u16), u64>,
+    /// Latency by endpoint.
+    latency: BTreeMap<&'static str, Histogram>,
+    /// Values converted by category.
+    conversions: BTreeMap<&'static str, u64>,
+}
+
+/// What a server has done since it started.
+#[derive(Debug)]
+pub struct Metrics {
+    endpoints: &'static [&'static str],
+    categories: &'static [&'static str],
+    counts: Mutex<Counts>,
+}
+
+impl Metrics {
+    /// Creates metrics that label requests with `endpoints` and conversions
+    /// with `categories`.
+    pub fn new(endpoints: &'static [&'static str], categories: &'static [&'static str]) -> Self {
+        Metrics {
+            endpoints,
+            categories,
+            counts: Mutex::new(Counts::default()),
+        }
+    }
+
+    fn counts(&self) -> std::sync::MutexGuard<'_, Counts> {
+        self.counts.lock().expect("the metrics are not poisoned")
+    }
+
+    /// Records a response with `status` to a request for `path` that took
+    /// `elapsed`.
+    pub fn request(&self, path: &str, status: u16, elapsed: Duration) {
+        let endpoint = self
+            .endpoints
+            .iter()
+            .find(|endpoint| **endpoint == path)
+            .map_or("other", |endpoint| *endpoint);
+        let mut counts = self.counts();
+        *counts.requests.entry((endpoint, status)).or_default() += 1;
+        counts
+            .latency
+            .entry(endpoint)
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Records a value converted in `category`.
+    pub fn conversion(&self, category: &str) {
+        let category = self
+            .categories
+            .iter()
+            .find(|name| **name == category)
+            .map_or("compound", |name| *name);
+        *self.counts().conversions.entry(category).or_default() += 1;
+    }
+
+    /// Renders the metrics in the Prometheus text format, after `extra`
+    /// lines such as gauges kept elsewhere.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use convertx::metrics::*;
+    /// let metrics = Metrics::new(&["/convert"], &["length"]);
+    /// metrics.request("/convert", 200, Duration::from_millis(2));
+    /// assert!(metrics.render("").contains(r#"convertx_http_requests_total{endpoint="/convert",status="200"} 1"#));
+    /// ```
+    pub fn render(&self, extra: &str) -> String {
+        let counts = self.counts();
+        let mut out = extra.to_string();
+        let _ = writeln!(
+            out,
+            "# HELP convertx_http_requests_total HTTP responses by endpoint and status."
+        );
+        let _ = writeln!(out, "# TYPE convertx_http_requests_total counter");
+        for ((endpoint, status), count) in &counts.requests {
+            let _ = writeln!(
+                out,
+                "convertx_http_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                endpoint, status, count
+            );
+        }
+        let name = "convertx_http_request_duration_seconds";
+        let _ = writeln!(
+            out,
+            "# HELP {} Time to read, answer and write an HTTP request.",
+            name
+        );
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (endpoint, histogram) in &counts.latency {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{endpoint=\"{}\",le=\"{}\"}} {}",
+                    name, endpoint, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}",
+                name, endpoint, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_sum{{endpoint=\"{}\"}} {}",
+                name, endpoint, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{{endpoint=\"{}\"}} {}",
+                name, endpoint, histogram.count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP convertx_conversions_total Values converted by category."
+        );
+        let _ = writeln!(out, "# TYPE convertx_conversions_total counter");
+        for (category, count) in &counts.conversions {
+            let _ = writeln!(
+                out,
+                "convertx_conversions_total{{category=\"{}\"}} {}",
+                category, count
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let metrics = Metrics::new(&["/convert", "/info"], &["length", "mass"]);
+        metrics.request("/convert", 200, Duration::from_micros(300));
+        metrics.request("/convert", 200, Duration::from_millis(20));
+        metrics.request("/convert", 400, Duration::from_millis(1));
+        metrics.request("/admin", 404, Duration::from_secs(60));
+        metrics.conversion("length");
+        metrics.conversion("m·kg");
+        let text = metrics.render("# TYPE convertx_uptime_seconds gauge\n");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "# TYPE convertx_uptime_seconds gauge");
+        for line in [
+            r#"convertx_http_requests_total{endpoint="/convert",status="200"} 2"#,
+            r#"convertx_http_requests_total{endpoint="/convert",status="400"} 1"#,
+            r#"convertx_http_requests_total{endpoint="other",status="404"} 1"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="/convert",le="0.0005"} 1"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="/convert",le="0.001"} 2"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="/convert",le="0.025"} 3"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="/convert",le="+Inf"} 3"#,
+            r#"convertx_http_request_duration_seconds_count{endpoint="/convert"} 3"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="other",le="10"} 0"#,
+            r#"convertx_http_request_duration_seconds_bucket{endpoint="other",le="+Inf"} 1"#,
+            r#"convertx_conversions_total{category="compound"} 1"#,
+            r#"convertx_conversions_total{category="length"} 1"#,
+        ] {
+            assert!(lines.contains(&line), "missing {}", line);
+        }
+        assert!(!text.contains("mass"));
+    }
+}
@@ -0,0 +1,59 @@
+//! Money formatting for the price and finance subcommands.
+//!
+//! Amounts are shown with two decimal places, or as many as asked for, and
+//! comma thousands separators; no currency is assumed.
+
+/// Formats `amount` with two decimals and thousands separators.
+///
+/// # Example
+/// ```
+/// # use convertx::money::*;
+/// assert_eq!(format(1234567.891), "1,234,567.89");
+/// ```
+pub fn format(amount: f64) -> String {
+    format_places(amount, 2)
+}
+
+/// Formats `amount` with `places` decimals and thousands separators.
+///
+/// # Example
+/// ```
+/// # use convertx::money::*;
+/// assert_eq!(format_places(1234567.891, 0), "1,234,568");
+/// ```
+pub fn format_places(amount: f64, places: usize) -> String {
+    let fixed = format!("{:.*}", places, amount.abs());
+    let (whole, decimals) = fixed.split_at(fixed.find('.').unwrap_or(fixed.len()));
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    // Keep the sign only if something non-zero is displayed.
+    let sign = if amount < 0.0 && fixed.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{}{}", sign, grouped, decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(0.0), "0.00");
+        assert_eq!(format(4.995), "5.00");
+        assert_eq!(format(999.999), "1,000.00");
+        assert_eq!(format(1234567.891), "1,234,567.89");
+        assert_eq!(format(-1234.5), "-1,234.50");
+        assert_eq!(format(-0.001), "0.00");
+        assert_eq!(format_places(1234567.891, 0), "1,234,568");
+        assert_eq!(format_places(-0.4, 0), "0");
+        assert_eq!(format_places(1234.5678, 3), "1,234.568");
+    }
+}
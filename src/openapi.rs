@@ -0,0 +1,318 @@
+//! The OpenAPI 3 description of `convertx serve`, for `serve --openapi`.
+//!
+//! The document is built from the same tables the server answers from: the
+//! categories and units of the [`UnitRegistry`](crate::registry::UnitRegistry)
+//! and the fields of [`json::FIELDS`](crate::json::FIELDS), so a client SDK
+//! generated from it stays in step with the build that printed it.
+
+use crate::json::Value::{self, Array, Bool, Number, Object, Text};
+use crate::registry::UnitDef;
+
+/// The OpenAPI version the document follows.
+pub const VERSION: &str = "3.0.3";
+
+fn text(text: &str) -> Value {
+    Text(text.to_string())
+}
+
+fn texts<'a>(texts: impl IntoIterator<Item = &'a str>) -> Value {
+    Array(texts.into_iter().map(text).collect())
+}
+
+fn schema(name: &str) -> Value {
+    Object(vec![(
+        "$ref",
+        Text(format!("#/components/schemas/{}", name)),
+    )])
+}
+
+fn string() -> Value {
+    Object(vec![("type", text("string"))])
+}
+
+fn number() -> Value {
+    Object(vec![("type", text("number")), ("nullable", Bool(true))])
+}
+
+/// An object schema with every one of `properties` required.
+fn object(properties: Vec<(&'static str, Value)>) -> Value {
+    let required = texts(properties.iter().map(|(name, _)| *name));
+    Object(vec![
+        ("type", text("object")),
+        ("required", required),
+        ("properties", Object(properties)),
+    ])
+}
+
+/// A query parameter of `/convert`.
+fn parameter(name: &str, description: &str, required: bool, schema: Value) -> Value {
+    Object(vec![
+        ("name", text(name)),
+        ("in", text("query")),
+        ("description", text(description)),
+        ("required", Bool(required)),
+        ("schema", schema),
+    ])
+}
+
+/// A response whose body is the JSON of `schema`.
+fn response(description: &str, schema: Value) -> Value {
+    Object(vec![
+        ("description", text(description)),
+        (
+            "content",
+            Object(vec![("application/json", Object(vec![("schema", schema)]))]),
+        ),
+    ])
+}
+
+/// Returns the OpenAPI document of a server at `server`, e.g.
+/// `http://127.0.0.1:8080`, converting between `units` of `categories` and
+/// at most `max_batch` values a request.
+///
+/// # Example
+/// ```
+/// # use convertx::openapi::*;
+/// # use convertx::registry::UnitDef;
+/// let units = [UnitDef::new("kilometers", "kilometer", "km", "length")];
+/// let document = document("http://localhost:8080", "0.1.0", &["length"], &units, 1000);
+/// assert!(document.to_string().starts_with(r#"{"openapi":"3.0.3""#));
+/// ```
+pub fn document(
+    server: &str,
+    version: &str,
+    categories: &[&str],
+    units: &[UnitDef],
+    max_batch: usize,
+) -> Value {
+    let mut known: Vec<&str> = units
+        .iter()
+        .flat_map(|unit| [unit.name.as_str(), unit.symbol.as_str()])
+        .collect();
+    known.sort_unstable();
+    known.dedup();
+    // Prefixed metric and compound units are read too, so the list only
+    // suggests values.
+    let unit = Object(vec![
+        (
+            "description",
+            text("A unit by name or symbol, with an optional SI prefix, or a compound unit such as km/h."),
+        ),
+        (
+            "anyOf",
+            Array(vec![
+                Object(vec![("type", text("string")), ("enum", texts(known))]),
+                string(),
+            ]),
+        ),
+    ]);
+    let error = response("The request cannot be answered.", schema("Error"));
+    let convert = Object(vec![
+        ("summary", text("Convert values between two units")),
+        ("operationId", text("convert")),
+        (
+            "parameters",
+            Array(vec![
+                parameter(
+                    "value",
+                    "Values to convert, each a number, fraction, tolerance or range, optionally with its unit, e.g. 5km; several are converted to an array.",
+                    true,
+                    Object(vec![
+                        ("type", text("array")),
+                        ("items", string()),
+                        ("minItems", Number(1.0)),
+                        ("maxItems", Number(max_batch as f64)),
+                    ]),
+                ),
+                parameter(
+                    "from",
+                    "Unit to convert from, unless written with the values.",
+                    false,
+                    schema("Unit"),
+                ),
+                parameter("to", "Unit to convert to.", true, schema("Unit")),
+                parameter(
+                    "category",
+                    "Category to convert in when a unit could belong to several.",
+                    false,
+                    Object(vec![
+                        ("type", text("string")),
+                        ("enum", texts(categories.iter().copied())),
+                    ]),
+                ),
+            ]),
+        ),
+        (
+            "responses",
+            Object(vec![
+                (
+                    "200",
+                    response(
+                        "The conversion, or an array of them for several values.",
+                        Object(vec![(
+                            "oneOf",
+                            Array(vec![
+                                schema("Conversion"),
+                                Object(vec![
+                                    ("type", text("array")),
+                                    ("items", schema("Conversion")),
+                                ]),
+                            ]),
+                        )]),
+                    ),
+                ),
+                ("400", error.clone()),
+                ("408", error.clone()),
+                ("413", error.clone()),
+                ("429", error.clone()),
+            ]),
+        ),
+    ]);
+    let info = Object(vec![
+        (
+            "summary",
+            text("Report the version, uptime and cache counters"),
+        ),
+        ("operationId", text("info")),
+        (
+            "responses",
+            Object(vec![
+                ("200", response("The server's counters.", schema("Info"))),
+                ("429", error),
+            ]),
+        ),
+    ]);
+    let metrics = Object(vec![
+        (
+            "summary",
+            text("Report request, conversion and latency metrics for Prometheus"),
+        ),
+        ("operationId", text("metrics")),
+        (
+            "responses",
+            Object(vec![(
+                "200",
+                Object(vec![
+                    (
+                        "description",
+                        text("The metrics in the Prometheus text format."),
+                    ),
+                    (
+                        "content",
+                        Object(vec![("text/plain", Object(vec![("schema", string())]))]),
+                    ),
+                ]),
+            )]),
+        ),
+    ]);
+    let page = Object(vec![
+        ("summary", text("A form to convert values from a browser")),
+        ("operationId", text("page")),
+        (
+            "responses",
+            Object(vec![(
+                "200",
+                Object(vec![
+                    ("description", text("An HTML page that calls /convert.")),
+                    (
+                        "content",
+                        Object(vec![("text/html", Object(vec![("schema", string())]))]),
+                    ),
+                ]),
+            )]),
+        ),
+    ]);
+    let count = || Object(vec![("type", text("integer"))]);
+    let schemas = Object(vec![
+        ("Unit", unit),
+        (
+            "Amount",
+            Object(vec![(
+                "oneOf",
+                Array(vec![
+                    number(),
+                    object(vec![("nominal", number()), ("tolerance", number())]),
+                    object(vec![("min", number()), ("max", number())]),
+                ]),
+            )]),
+        ),
+        (
+            "Conversion",
+            object(vec![
+                // A compound unit's dimension, e.g. `m·kg`, if no category has it.
+                ("category", string()),
+                ("value", schema("Amount")),
+                ("from", string()),
+                ("to", string()),
+                ("from_symbol", string()),
+                ("to_symbol", string()),
+                ("result", schema("Amount")),
+            ]),
+        ),
+        (
+            "Info",
+            object(vec![
+                ("version", string()),
+                ("uptime_seconds", count()),
+                ("requests", count()),
+                (
+                    "cache",
+                    object(vec![
+                        ("capacity", count()),
+                        ("entries", count()),
+                        ("hits", count()),
+                        ("misses", count()),
+                        ("evictions", count()),
+                    ]),
+                ),
+            ]),
+        ),
+        ("Error", object(vec![("error", string())])),
+    ]);
+    Object(vec![
+        ("openapi", text(VERSION)),
+        (
+            "info",
+            Object(vec![
+                ("title", text("convertx")),
+                ("version", text(version)),
+            ]),
+        ),
+        ("servers", Array(vec![Object(vec![("url", text(server))])])),
+        (
+            "paths",
+            Object(vec![
+                ("/", Object(vec![("get", page)])),
+                ("/convert", Object(vec![("get", convert)])),
+                ("/info", Object(vec![("get", info)])),
+                ("/metrics", Object(vec![("get", metrics)])),
+            ]),
+        ),
+        ("components", Object(vec![("schemas", schemas)])),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::FIELDS;
+
+    #[test]
+    fn test_document() {
+        let units = [
+            UnitDef::new("kilometers", "kilometer", "km", "length"),
+            UnitDef::new("miles", "mile", "mi", "length"),
+        ];
+        let document = document("http://localhost:8080", "1.2.3", &["length"], &units, 50);
+        let json = document.to_string();
+        assert!(
+            json.starts_with(r#"{"openapi":"3.0.3","info":{"title":"convertx","version":"1.2.3"}"#)
+        );
+        assert!(json.contains(r#""servers":[{"url":"http://localhost:8080"}]"#));
+        assert!(json.contains(r#""enum":["kilometers","km","mi","miles"]"#));
+        assert!(json.contains(r#""maxItems":50"#));
+        // The conversion schema requires the fields of `--output json`, in order.
+        let required = Array(FIELDS.iter().map(|field| text(field)).collect());
+        assert!(json.contains(&format!(r#""required":{}"#, required)));
+    }
+}
@@ -0,0 +1,156 @@
+//! Optional unit packs.
+//!
+//! A pack is a table of extra units attached to existing categories. Packs are
+//! compiled in with a cargo feature (e.g. `--features historical`) and enabled per
+//! invocation with `--units-pack <name>`, so their names never clash with the
+//! built-in units unless asked for.
+
+#[cfg(feature = "historical")]
+mod historical;
+#[cfg(feature = "nautical")]
+mod nautical;
+
+/// A unit provided by a pack.
+///
+/// Values convert to the category's base unit (meters, kilograms, liters, ...)
+/// as `value * factor + offset`.
+#[derive(Debug, PartialEq)]
+pub struct PackUnit {
+    /// Name of the pack that provides the unit.
+    pub pack: &'static str,
+    /// Category the unit belongs to, e.g. "length".
+    pub category: &'static str,
+    /// Name accepted on the command line, e.g. "cubits".
+    pub name: &'static str,
+    pub singular: &'static str,
+    pub plural: &'static str,
+    pub symbol: &'static str,
+    /// Size of one unit in the category's base unit.
+    pub factor: f64,
+    /// Base-unit value of zero in this unit (zero for all but temperature scales).
+    pub offset: f64,
+}
+
+impl PackUnit {
+    /// Defines a unit that scales linearly from the category's base unit.
+    #[allow(dead_code)] // Unused when the build includes no packs.
+    pub const fn linear(
+        pack: &'static str,
+        category: &'static str,
+        name: &'static str,
+        singular: &'static str,
+        plural: &'static str,
+        symbol: &'static str,
+        factor: f64,
+    ) -> PackUnit {
+        PackUnit {
+            pack,
+            category,
+            name,
+            singular,
+            plural,
+            symbol,
+            factor,
+            offset: 0.0,
+        }
+    }
+
+    /// Returns true if one of the `enabled` packs provides this unit.
+    ///
+    /// Several packs may define the same unit (e.g. fathoms), so any of them will do.
+    pub fn is_enabled(&self, enabled: &[&str]) -> bool {
+        PACKS
+            .iter()
+            .filter(|pack| enabled.contains(&pack.name))
+            .flat_map(|pack| pack.units)
+            .any(|unit| unit.category == self.category && unit.name == self.name)
+    }
+
+    /// Converts a value in this unit to the category's base unit.
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    /// Converts a value in the category's base unit to this unit.
+    pub fn to_unit(&self, base: f64) -> f64 {
+        (base - self.offset) / self.factor
+    }
+}
+
+/// A named collection of units.
+pub struct Pack {
+    pub name: &'static str,
+    pub units: &'static [PackUnit],
+}
+
+/// Packs compiled into this build.
+const PACKS: &[&Pack] = &[
+    #[cfg(feature = "historical")]
+    &historical::PACK,
+    #[cfg(feature = "nautical")]
+    &nautical::PACK,
+];
+
+/// Finds a compiled-in pack unit of `category` by name or symbol.
+pub fn find(category: &str, token: &str) -> Option<&'static PackUnit> {
+    PACKS
+        .iter()
+        .flat_map(|pack| pack.units)
+        .filter(|unit| unit.category == category)
+        .find(|unit| {
+            unit.name.eq_ignore_ascii_case(token) || unit.symbol.eq_ignore_ascii_case(token)
+        })
+}
+
+/// Returns every compiled-in pack unit of `category`.
+pub fn units(category: &str) -> impl Iterator<Item = &'static PackUnit> + '_ {
+    PACKS
+        .iter()
+        .flat_map(|pack| pack.units)
+        .filter(move |unit| unit.category == category)
+}
+
+/// Returns the names and symbols of every compiled-in pack unit of `category`.
+pub fn accepted(category: &str) -> impl Iterator<Item = &'static str> + '_ {
+    units(category).flat_map(|unit| [unit.name, unit.symbol])
+}
+
+/// Parses a `--units-pack` argument into the name of a compiled-in pack.
+pub fn parse_name(name: &str) -> Result<&'static str, String> {
+    match PACKS
+        .iter()
+        .find(|pack| pack.name.eq_ignore_ascii_case(name))
+    {
+        Some(pack) => Ok(pack.name),
+        None if PACKS.is_empty() => Err(format!(
+            "unknown unit pack '{}', this build has no packs (enable them with cargo features)",
+            name
+        )),
+        None => {
+            let names: Vec<&str> = PACKS.iter().map(|pack| pack.name).collect();
+            Err(format!(
+                "unknown unit pack '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "historical", feature = "nautical"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_and_convert() {
+        let fathom = find("length", "FTM").unwrap();
+        assert_eq!(fathom.name, "fathoms");
+        assert!((fathom.to_base(1.0) - 1.8288).abs() < 1e-12);
+        assert!((fathom.to_unit(1.8288) - 1.0).abs() < 1e-12);
+        assert!(find("mass", "fathoms").is_none());
+        assert_eq!(parse_name("Historical"), Ok("historical"));
+        assert!(parse_name("bogus").is_err());
+        assert!(fathom.is_enabled(&["nautical"]));
+        assert!(!fathom.is_enabled(&[]));
+    }
+}
@@ -0,0 +1,44 @@
+//! Historical and archaic units (`--units-pack historical`).
+//!
+//! English definitions are used where several existed: the 18-inch cubit, the
+//! 3-mile league, the avoirdupois dram and the 9-gallon ale firkin.
+
+use super::{Pack, PackUnit};
+
+pub const PACK: Pack = Pack {
+    name: "historical",
+    units: &[
+        unit("length", "cubits", "cubit", "cubits", "cbt", 0.4572),
+        unit("length", "leagues", "league", "leagues", "lea", 4828.032),
+        unit("length", "fathoms", "fathom", "fathoms", "ftm", 1.8288),
+        unit("length", "chains", "chain", "chains", "ch", 20.1168),
+        unit(
+            "mass",
+            "drams",
+            "dram",
+            "drams",
+            "dr",
+            0.001_771_845_195_312_5,
+        ),
+        unit("volume", "firkins", "firkin", "firkins", "fir", 40.914_81),
+    ],
+};
+
+const fn unit(
+    category: &'static str,
+    name: &'static str,
+    singular: &'static str,
+    plural: &'static str,
+    symbol: &'static str,
+    factor: f64,
+) -> PackUnit {
+    PackUnit::linear(
+        "historical",
+        category,
+        name,
+        singular,
+        plural,
+        symbol,
+        factor,
+    )
+}
@@ -0,0 +1,49 @@
+//! Nautical and aviation units (`--units-pack nautical`).
+//!
+//! Knots are built in; this pack adds the distances and climb rates used
+//! alongside them, so e.g. `speed --from knots --to fpm` works.
+
+use super::{Pack, PackUnit};
+
+pub const PACK: Pack = Pack {
+    name: "nautical",
+    units: &[
+        unit(
+            "length",
+            "nautical_miles",
+            "nautical mile",
+            "nautical miles",
+            "nmi",
+            1852.0,
+        ),
+        unit("length", "cables", "cable", "cables", "cb", 185.2),
+        unit("length", "fathoms", "fathom", "fathoms", "ftm", 1.8288),
+        unit(
+            "length",
+            "flight_levels",
+            "flight level",
+            "flight levels",
+            "FL",
+            30.48,
+        ),
+        unit(
+            "speed",
+            "fpm",
+            "foot per minute",
+            "feet per minute",
+            "ft/min",
+            0.00508,
+        ),
+    ],
+};
+
+const fn unit(
+    category: &'static str,
+    name: &'static str,
+    singular: &'static str,
+    plural: &'static str,
+    symbol: &'static str,
+    factor: f64,
+) -> PackUnit {
+    PackUnit::linear("nautical", category, name, singular, plural, symbol, factor)
+}
@@ -0,0 +1,73 @@
+//! Paint coverage for `convertx paint`.
+//!
+//! Paint tins state a spread rate, the area one unit of paint covers in one
+//! coat; the paint needed is the area times the number of coats divided by it.
+
+use crate::quantity::{Area, Liquid};
+use std::fmt;
+use std::str::FromStr;
+
+/// A spread rate, e.g. `10m2/L` or `350ft2/gal`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    /// Square meters covered by one milliliter.
+    pub m2_per_ml: f64,
+    text: String,
+}
+
+impl Coverage {
+    /// Returns the milliliters of paint needed for `coats` coats over `area`.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::paint::*;
+    /// let coverage: Coverage = "10m2/L".parse().unwrap();
+    /// assert_eq!(coverage.ml_needed(&"45m2".parse().unwrap(), 2), 9000.0);
+    /// ```
+    pub fn ml_needed(&self, area: &Area, coats: u32) -> f64 {
+        area.m2 * f64::from(coats) / self.m2_per_ml
+    }
+}
+
+impl FromStr for Coverage {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (area, volume) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("'{}' is not a coverage like 10m2/L", s))?;
+        let area: Area = area.parse()?;
+        // A bare unit after the slash means one of it.
+        let volume: Liquid = format!("1{}", volume.trim()).parse()?;
+        if area.m2 <= 0.0 {
+            return Err("coverage must be greater than zero".to_string());
+        }
+        Ok(Coverage {
+            m2_per_ml: area.m2 / volume.ml,
+            text: s.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage() {
+        let metric: Coverage = "10m2/L".parse().unwrap();
+        assert_eq!(metric.m2_per_ml, 0.01);
+        let us: Coverage = "350ft2/gal".parse().unwrap();
+        assert!((us.m2_per_ml - 0.0085898).abs() < 1e-7);
+        assert_eq!(metric.ml_needed(&"45m2".parse().unwrap(), 2), 9000.0);
+        assert!("10m2".parse::<Coverage>().is_err());
+        assert!("0m2/L".parse::<Coverage>().is_err());
+        assert!("10m2/bucket".parse::<Coverage>().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Portable floating point math for `--reproducible`.
+//!
+//! Rust compiles `+`, `-`, `*`, `/` and `sqrt` to correctly rounded IEEE-754
+//! double precision operations and never fuses them into FMA instructions on
+//! its own, so results built only from those operations are the same on every
+//! platform. The functions in `std` that go beyond them (`powi`, `ln`, `sin`,
+//! ...) call into the platform's math library or LLVM intrinsics, whose last
+//! bit may differ between targets, compiler versions and optimization levels.
+//!
+//! Every function here uses `std` by default. After [`set_reproducible`] it
+//! switches to the algorithm documented on the function, written with those
+//! basic operations only and evaluated in a fixed order, so the results are
+//! accurate to a few units in the last place and identical everywhere. The
+//! conformance vectors in `tests/data/reproducible.tsv` pin them bit for bit.
+//!
+//! On 32-bit x86 this relies on SSE2, which every `i686` Rust target enables;
+//! the x87 unit of `i586` targets rounds intermediate results differently.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the portable algorithms are used, see [`set_reproducible`].
+static REPRODUCIBLE: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether the functions of this module use their portable algorithms
+/// instead of `std`. They use `std` by default.
+pub fn set_reproducible(reproducible: bool) {
+    REPRODUCIBLE.store(reproducible, Ordering::Relaxed);
+}
+
+fn reproducible() -> bool {
+    REPRODUCIBLE.load(Ordering::Relaxed)
+}
+
+/// The natural logarithm of 2, split so that `k * LN2_HI` is exact for any
+/// binary exponent `k` (fdlibm's constants).
+const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+/// π/2 in three parts, each exact when multiplied by a quadrant count below
+/// 2^20, and the remainder (fdlibm's constants).
+const PIO2_1: f64 = 1.570_796_326_734_125_6;
+const PIO2_2: f64 = 6.077_100_506_303_966e-11;
+const PIO2_3: f64 = 2.022_266_248_711_166_5e-21;
+const PIO2_3T: f64 = 8.478_427_660_368_9e-32;
+
+/// Returns `x * 2^k`, exactly unless the result overflows or is subnormal.
+fn scale(mut x: f64, mut k: i32) -> f64 {
+    // 2^k is only representable as a normal number for -1022 <= k <= 1023.
+    while k > 1023 {
+        x *= f64::from_bits(2046 << 52);
+        k -= 1023;
+    }
+    while k < -1022 {
+        x *= f64::from_bits(1 << 52);
+        k += 1022;
+    }
+    x * f64::from_bits(((k + 1023) as u64) << 52)
+}
+
+/// `x` raised to the integer power `n`.
+///
+/// Portable algorithm: binary exponentiation, squaring `x` for each bit of
+/// `|n|` from the lowest and multiplying the result by the squares of the set
+/// bits; a negative `n` takes the reciprocal at the end.
+///
+/// # Example
+/// ```
+/// # use convertx::portable::*;
+/// assert_eq!(powi(10.0, 3), 1000.0);
+/// ```
+pub fn powi(x: f64, n: i32) -> f64 {
+    if !reproducible() {
+        return x.powi(n);
+    }
+    let (mut base, mut bits, mut result) = (x, n.unsigned_abs(), 1.0);
+    while bits > 0 {
+        if bits & 1 == 1 {
+            result *= base;
+        }
+        base *= base;
+        bits >>= 1;
+    }
+    if n < 0 {
+        1.0 / result
+    } else {
+        result
+    }
+}
+
+/// The natural logarithm of `x`.
+///
+/// Portable algorithm: `x = m * 2^k` with `m` in [√½, √2), then
+/// `ln x = k ln 2 + 2 atanh(s)` with `s = (m - 1) / (m + 1)`, the series of
+/// `atanh` summed by Horner's rule to the `s^23` term.
+pub fn ln(x: f64) -> f64 {
+    if !reproducible() {
+        return x.ln();
+    }
+    log_parts(x).map_or_else(
+        |special| special,
+        |(k, ln_m)| k * LN2_HI + (k * LN2_LO + ln_m),
+    )
+}
+
+/// Splits the logarithm of `x` into `(k, ln m)` with `x = m * 2^k` as in
+/// [`ln`], or returns the logarithm of zero, infinity, NaN or a negative `x`.
+fn log_parts(x: f64) -> Result<(f64, f64), f64> {
+    if x.is_nan() || x < 0.0 {
+        return Err(f64::NAN);
+    }
+    if x == 0.0 {
+        return Err(f64::NEG_INFINITY);
+    }
+    if x == f64::INFINITY {
+        return Err(x);
+    }
+    let (mut x, mut k) = (x, 0);
+    if x < f64::MIN_POSITIVE {
+        x *= f64::from_bits((1023 + 54) << 52);
+        k -= 54;
+    }
+    let bits = x.to_bits();
+    k += ((bits >> 52) as i32) - 1023;
+    let mut m = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    if m > std::f64::consts::SQRT_2 {
+        m /= 2.0;
+        k += 1;
+    }
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut series = 0.0;
+    for n in (0..12).rev() {
+        series = 1.0 / f64::from(2 * n + 1) + s2 * series;
+    }
+    Ok((f64::from(k), 2.0 * s * series))
+}
+
+/// The base 2 logarithm of `x`.
+///
+/// Portable algorithm: `k + ln m / ln 2` with `k` and `ln m` as in [`ln`], so
+/// powers of two give exact results.
+pub fn log2(x: f64) -> f64 {
+    if !reproducible() {
+        return x.log2();
+    }
+    log_parts(x).map_or_else(
+        |special| special,
+        |(k, ln_m)| k + ln_m / std::f64::consts::LN_2,
+    )
+}
+
+/// The base 10 logarithm of `x`.
+///
+/// Portable algorithm: [`ln`] divided by ln 10, rounded to the exponent when
+/// `x` is a power of ten from 10^-22 to 10^22 as computed by [`powi`].
+pub fn log10(x: f64) -> f64 {
+    if !reproducible() {
+        return x.log10();
+    }
+    let log = ln(x) / std::f64::consts::LN_10;
+    let exponent = log.round();
+    if exponent.abs() <= 22.0 && powi(10.0, exponent as i32) == x {
+        exponent
+    } else {
+        log
+    }
+}
+
+/// `e` raised to the power `x`.
+///
+/// Portable algorithm: `x = k ln 2 + r` with `k` the nearest integer to
+/// `x / ln 2` and `|r| <= ln 2 / 2`, then `e^x = 2^k e^r`, with the Taylor
+/// series of `e^r` summed by Horner's rule to the `r^15` term.
+pub fn exp(x: f64) -> f64 {
+    if !reproducible() {
+        return x.exp();
+    }
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.8 {
+        return f64::INFINITY;
+    }
+    if x < -745.2 {
+        return 0.0;
+    }
+    let k = (x / std::f64::consts::LN_2).round();
+    let r = (x - k * LN2_HI) - k * LN2_LO;
+    scale(exp_reduced(r), k as i32)
+}
+
+/// `e^r` for `|r| <= ln 2 / 2`, see [`exp`].
+fn exp_reduced(r: f64) -> f64 {
+    let mut sum = 1.0;
+    for n in (1..=15).rev() {
+        sum = 1.0 + r * sum / f64::from(n);
+    }
+    sum
+}
+
+/// 2 raised to the power `x`.
+///
+/// Portable algorithm: `x = k + f` with `k` the nearest integer to `x`, then
+/// `2^x = 2^k e^(f ln 2)` as in [`exp`].
+pub fn exp2(x: f64) -> f64 {
+    if !reproducible() {
+        return x.exp2();
+    }
+    if x.is_nan() {
+        return x;
+    }
+    let k = x.round().clamp(-1100.0, 1100.0);
+    let f = x - k;
+    if f.abs() > 0.5 {
+        // `x` is beyond the clamp, so the result under- or overflows.
+        return if x > 0.0 { f64::INFINITY } else { 0.0 };
+    }
+    scale(exp_reduced(f * std::f64::consts::LN_2), k as i32)
+}
+
+/// `x` raised to the power `y`.
+///
+/// Portable algorithm: [`powi`] for an integer `y` within `i32`, otherwise
+/// `e^(y ln x)` with [`exp`] and [`ln`]; NaN for a negative `x`.
+pub fn powf(x: f64, y: f64) -> f64 {
+    if !reproducible() {
+        return x.powf(y);
+    }
+    if y == 0.0 || x == 1.0 {
+        return 1.0;
+    }
+    if y.fract() == 0.0 && y.abs() <= f64::from(i32::MAX) {
+        return powi(x, y as i32);
+    }
+    if x.is_nan() || y.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    match x {
+        0.0 if y > 0.0 => 0.0,
+        0.0 => f64::INFINITY,
+        _ => exp(y * ln(x)),
+    }
+}
+
+/// The sine of `x` radians.
+///
+/// Portable algorithm: `x = k π/2 + r` with `k` the nearest integer to
+/// `x / (π/2)`, using π/2 in four parts so that `|r| <= π/4`, then `±sin r` or
+/// `±cos r` by the quadrant `k mod 4`, each a Taylor series summed by Horner's
+/// rule to the `r^25` or `r^24` term. Above about 10^6 the reduction loses
+/// accuracy, but stays the same everywhere.
+pub fn sin(x: f64) -> f64 {
+    if !reproducible() {
+        return x.sin();
+    }
+    if !x.is_finite() {
+        return f64::NAN;
+    }
+    let k = (x / std::f64::consts::FRAC_PI_2).round();
+    let r = (((x - k * PIO2_1) - k * PIO2_2) - k * PIO2_3) - k * PIO2_3T;
+    let r2 = r * r;
+    let (mut sin, mut cos) = (1.0, 1.0);
+    for n in (1..=12).rev() {
+        let n = f64::from(n);
+        sin = 1.0 - r2 * sin / ((2.0 * n) * (2.0 * n + 1.0));
+        cos = 1.0 - r2 * cos / ((2.0 * n - 1.0) * (2.0 * n));
+    }
+    let sin = r * sin;
+    match (k % 4.0 + 4.0) % 4.0 {
+        0.0 => sin,
+        1.0 => cos,
+        2.0 => -sin,
+        _ => -cos,
+    }
+}
+
+/// The length of the hypotenuse of a right triangle with sides `a` and `b`.
+///
+/// Portable algorithm: `big * sqrt(1 + (small / big)^2)` for the larger and
+/// smaller of `|a|` and `|b|`, which avoids overflow; `sqrt` is correctly
+/// rounded by IEEE-754.
+pub fn hypot(a: f64, b: f64) -> f64 {
+    if !reproducible() {
+        return a.hypot(b);
+    }
+    let (a, b) = (a.abs(), b.abs());
+    if a.is_infinite() || b.is_infinite() {
+        return f64::INFINITY;
+    }
+    if a.is_nan() || b.is_nan() {
+        return f64::NAN;
+    }
+    let (big, small) = if a >= b { (a, b) } else { (b, a) };
+    if big == 0.0 {
+        return 0.0;
+    }
+    let ratio = small / big;
+    big * (1.0 + ratio * ratio).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The conformance vectors, as `function<TAB>arguments<TAB>result` lines.
+    const VECTORS: &str = include_str!("../tests/data/reproducible.tsv");
+
+    /// Evaluates the portable `function` on the comma separated `arguments`.
+    fn call(function: &str, arguments: &str) -> f64 {
+        let args: Vec<f64> = arguments
+            .split(',')
+            .map(|arg| arg.trim().parse().unwrap())
+            .collect();
+        match function {
+            "powi" => powi(args[0], args[1] as i32),
+            "ln" => ln(args[0]),
+            "log2" => log2(args[0]),
+            "log10" => log10(args[0]),
+            "exp" => exp(args[0]),
+            "exp2" => exp2(args[0]),
+            "powf" => powf(args[0], args[1]),
+            "sin" => sin(args[0]),
+            "hypot" => hypot(args[0], args[1]),
+            _ => panic!("unknown function '{}'", function),
+        }
+    }
+
+    // One test, as the mode is global and tests run on several threads.
+    #[test]
+    fn test_conformance_vectors() {
+        let vectors = || {
+            VECTORS
+                .lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let fields: Vec<&str> = line.split('\t').collect();
+                    let expected: f64 = fields[2].parse().unwrap();
+                    (fields[0], fields[1], expected)
+                })
+        };
+        set_reproducible(true);
+        for (function, arguments, expected) in vectors() {
+            let result = call(function, arguments);
+            assert!(
+                result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()),
+                "{}({}) = {:?}, expected {:?}",
+                function,
+                arguments,
+                result,
+                expected
+            );
+        }
+        set_reproducible(false);
+        // The portable algorithms stay within a few ulp of the platform's.
+        for (function, arguments, expected) in vectors() {
+            let platform = call(function, arguments);
+            let tolerance = 8.0 * f64::EPSILON * platform.abs().max(f64::MIN_POSITIVE);
+            let nan = platform.is_nan() && expected.is_nan();
+            assert!(
+                (platform - expected).abs() <= tolerance || platform == expected || nan,
+                "{}({}) = {:?} here, {:?} portably",
+                function,
+                arguments,
+                platform,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! SI prefixes for the metric units of every category.
+//!
+//! Rather than a unit per prefix, [`METRIC`] lists the units that take
+//! prefixes and [`PREFIXES`] the prefixes, and a token such as `kilopascals`,
+//! `nm` or `GB/s` is split into the two when it is read. The result scales a
+//! unit of its category by name, so it converts wherever that unit does: in
+//! the unit enums and in the [`UnitRegistry`](crate::registry::UnitRegistry).
+
+use std::sync::Mutex;
+
+use crate::registry::UnitDef;
+
+/// An SI prefix.
+#[derive(Debug, PartialEq)]
+pub struct Prefix {
+    pub name: &'static str,
+    /// Symbols, the first of which is shown, e.g. `µ` and `u` for micro.
+    pub symbols: &'static [&'static str],
+    pub factor: f64,
+}
+
+const fn prefix(name: &'static str, symbols: &'static [&'static str], factor: f64) -> Prefix {
+    Prefix {
+        name,
+        symbols,
+        factor,
+    }
+}
+
+/// The SI prefixes, from quecto to quetta.
+pub const PREFIXES: &[Prefix] = &[
+    prefix("quecto", &["q"], 1e-30),
+    prefix("ronto", &["r"], 1e-27),
+    prefix("yocto", &["y"], 1e-24),
+    prefix("zepto", &["z"], 1e-21),
+    prefix("atto", &["a"], 1e-18),
+    prefix("femto", &["f"], 1e-15),
+    prefix("pico", &["p"], 1e-12),
+    prefix("nano", &["n"], 1e-9),
+    prefix("micro", &["µ", "μ", "u"], 1e-6),
+    prefix("milli", &["m"], 1e-3),
+    prefix("centi", &["c"], 1e-2),
+    prefix("deci", &["d"], 1e-1),
+    prefix("deca", &["da"], 1e1),
+    prefix("hecto", &["h"], 1e2),
+    prefix("kilo", &["k"], 1e3),
+    prefix("mega", &["M"], 1e6),
+    prefix("giga", &["G"], 1e9),
+    prefix("tera", &["T"], 1e12),
+    prefix("peta", &["P"], 1e15),
+    prefix("exa", &["E"], 1e18),
+    prefix("zetta", &["Z"], 1e21),
+    prefix("yotta", &["Y"], 1e24),
+    prefix("ronna", &["R"], 1e27),
+    prefix("quetta", &["Q"], 1e30),
+];
+
+/// A unit that takes SI prefixes.
+#[derive(Debug, PartialEq)]
+pub struct Metric {
+    pub category: &'static str,
+    /// The unit of the category it is `factor` of, by name, e.g. "kg" for grams.
+    pub reference: &'static str,
+    pub singular: &'static str,
+    pub plural: &'static str,
+    /// Symbols, the first of which is shown.
+    pub symbols: &'static [&'static str],
+    pub factor: f64,
+}
+
+const fn metric(
+    category: &'static str,
+    reference: &'static str,
+    singular: &'static str,
+    plural: &'static str,
+    symbols: &'static [&'static str],
+    factor: f64,
+) -> Metric {
+    Metric {
+        category,
+        reference,
+        singular,
+        plural,
+        symbols,
+        factor,
+    }
+}
+
+/// Every unit that takes SI prefixes.
+pub const METRIC: &[Metric] = &[
+    metric("length", "meters", "meter", "meters", &["m"], 1.0),
+    metric("mass", "kg", "gram", "grams", &["g"], 1e-3),
+    metric("temperature", "k", "kelvin", "kelvins", &["K"], 1.0),
+    metric("volume", "liters", "liter", "liters", &["L", "l"], 1.0),
+    metric("pressure", "pa", "pascal", "pascals", &["Pa"], 1.0),
+    metric("pressure", "bar", "bar", "bars", &["bar"], 1.0),
+    metric("information", "bits", "bit", "bits", &["bit"], 1.0),
+    metric("information", "bytes", "byte", "bytes", &["B"], 1.0),
+    metric(
+        "datarate",
+        "bps",
+        "bit per second",
+        "bits per second",
+        &["bit/s", "bps"],
+        1.0,
+    ),
+    metric(
+        "speed",
+        "mps",
+        "meter per second",
+        "meters per second",
+        &["m/s"],
+        1.0,
+    ),
+    metric(
+        "hashrate",
+        "h/s",
+        "hash per second",
+        "hashes per second",
+        &["H/s"],
+        1.0,
+    ),
+];
+
+/// A metric unit with a prefix, or none, as read from a token.
+#[derive(Debug, PartialEq)]
+pub struct PrefixedUnit {
+    /// The unit as the registry shows it, e.g. "kilopascals" with symbol "kPa".
+    pub def: UnitDef,
+    /// The unit of the category it is `factor` of, by name.
+    pub reference: &'static str,
+    pub factor: f64,
+}
+
+/// Units read so far, so that each is only built once and lives as long as
+/// the built-in units.
+static READ: Mutex<Vec<&'static PrefixedUnit>> = Mutex::new(Vec::new());
+
+/// Reads `token` as a prefixed metric unit of `category`, e.g. `kPa`,
+/// `kilopascal` or `kilopascals` in "pressure". A bare metric unit such as
+/// `grams` is read too. Names ignore case, while symbols must match it, as
+/// `mm` and `Mm` differ.
+///
+/// # Example
+/// ```
+/// # use convertx::prefix::*;
+/// let unit = find("length", "nanometers").unwrap();
+/// assert_eq!((unit.def.symbol.as_str(), unit.reference, unit.factor), ("nm", "meters", 1e-9));
+/// ```
+pub fn find(category: &str, token: &str) -> Option<&'static PrefixedUnit> {
+    let lower = token.to_lowercase();
+    let (prefix, metric) = METRIC
+        .iter()
+        .filter(|metric| metric.category == category)
+        .find_map(|metric| {
+            let by_name = |rest: &str| rest == metric.singular || rest == metric.plural;
+            let by_symbol = |rest: &str| metric.symbols.contains(&rest);
+            if by_name(&lower) || by_symbol(token) {
+                return Some((None, metric));
+            }
+            PREFIXES
+                .iter()
+                .find(|prefix| {
+                    lower.strip_prefix(prefix.name).is_some_and(by_name)
+                        || prefix
+                            .symbols
+                            .iter()
+                            .any(|symbol| token.strip_prefix(symbol).is_some_and(by_symbol))
+                })
+                .map(|prefix| (Some(prefix), metric))
+        })?;
+    let (name, symbol, factor) = match prefix {
+        Some(prefix) => (prefix.name, prefix.symbols[0], prefix.factor),
+        None => ("", "", 1.0),
+    };
+    let def = UnitDef::new(
+        &format!("{}{}", name, metric.plural),
+        &format!("{}{}", name, metric.singular),
+        &format!("{}{}", symbol, metric.symbols[0]),
+        category,
+    );
+    let mut read = READ.lock().expect("the prefixed units are not poisoned");
+    if let Some(unit) = read.iter().find(|unit| unit.def == def) {
+        return Some(unit);
+    }
+    let unit: &'static PrefixedUnit = Box::leak(Box::new(PrefixedUnit {
+        def,
+        reference: metric.reference,
+        factor: factor * metric.factor,
+    }));
+    read.push(unit);
+    Some(unit)
+}
+
+/// Reads `token` like [`find`], but with its symbol in any case, e.g. `tb` as
+/// terabytes, as long as only one unit of `category` is spelled that way:
+/// `mb` is neither millibytes nor megabytes.
+///
+/// # Example
+/// ```
+/// # use convertx::prefix::*;
+/// assert_eq!(find_ignoring_case("information", "tb").unwrap().def.symbol, "TB");
+/// assert_eq!(find_ignoring_case("information", "mb"), None);
+/// ```
+pub fn find_ignoring_case(category: &str, token: &str) -> Option<&'static PrefixedUnit> {
+    let mut units: Vec<&PrefixedUnit> = METRIC
+        .iter()
+        .filter(|metric| metric.category == category)
+        .flat_map(|metric| {
+            let prefixes = PREFIXES.iter().flat_map(|prefix| prefix.symbols);
+            [""].iter().chain(prefixes).flat_map(|prefix| {
+                metric
+                    .symbols
+                    .iter()
+                    .map(move |symbol| format!("{}{}", prefix, symbol))
+            })
+        })
+        .filter(|spelling| spelling.eq_ignore_ascii_case(token))
+        .filter_map(|spelling| find(category, &spelling))
+        .collect();
+    units.dedup_by(|a, b| a.def.symbol == b.def.symbol);
+    match units[..] {
+        [unit] => Some(unit),
+        _ => find(category, token),
+    }
+}
+
+/// Reads `token` as a prefixed metric unit in every category it can be one of.
+pub fn find_all(token: &str) -> Vec<&'static PrefixedUnit> {
+    in_every_category(token, find)
+}
+
+/// Reads `token` like [`find_ignoring_case`] in every category it can be one of.
+pub fn find_all_ignoring_case(token: &str) -> Vec<&'static PrefixedUnit> {
+    in_every_category(token, find_ignoring_case)
+}
+
+fn in_every_category(
+    token: &str,
+    find: fn(&str, &str) -> Option<&'static PrefixedUnit>,
+) -> Vec<&'static PrefixedUnit> {
+    let mut categories: Vec<&str> = METRIC.iter().map(|metric| metric.category).collect();
+    categories.dedup();
+    categories
+        .into_iter()
+        .filter_map(|category| find(category, token))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(category: &str, token: &str) -> Option<(&'static str, f64)> {
+        find(category, token).map(|unit| (unit.def.symbol.as_str(), unit.factor))
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(read("pressure", "kilopascal"), Some(("kPa", 1e3)));
+        assert_eq!(read("pressure", "KiloPascals"), Some(("kPa", 1e3)));
+        assert_eq!(read("pressure", "mbar"), Some(("mbar", 1e-3)));
+        assert_eq!(read("length", "mm"), Some(("mm", 1e-3)));
+        assert_eq!(read("length", "Mm"), Some(("Mm", 1e6)));
+        assert_eq!(read("length", "um"), Some(("µm", 1e-6)));
+        assert_eq!(read("length", "dam"), Some(("dam", 10.0)));
+        assert_eq!(read("mass", "grams"), Some(("g", 1e-3)));
+        assert_eq!(read("mass", "mg"), Some(("mg", 1e-6)));
+        assert_eq!(read("datarate", "Gbps"), Some(("Gbit/s", 1e9)));
+        assert_eq!(read("information", "TB"), Some(("TB", 1e12)));
+        assert_eq!(read("length", "MM"), None);
+        assert_eq!(read("length", "kg"), None);
+        assert_eq!(read("area", "km"), None);
+        let ignoring_case = |category, token| {
+            find_ignoring_case(category, token).map(|unit| unit.def.symbol.as_str())
+        };
+        assert_eq!(ignoring_case("information", "tb"), Some("TB"));
+        assert_eq!(ignoring_case("volume", "KL"), Some("kL"));
+        assert_eq!(ignoring_case("information", "mB"), Some("mB"));
+        assert_eq!(ignoring_case("information", "mb"), None);
+        assert_eq!(ignoring_case("length", "MM"), None);
+        let unit = find("temperature", "millikelvin").unwrap();
+        assert_eq!(
+            (unit.def.name.as_str(), unit.reference),
+            ("millikelvins", "k")
+        );
+        assert!(std::ptr::eq(unit, find("temperature", "mK").unwrap()));
+    }
+
+    #[test]
+    fn test_find_all() {
+        let units = find_all("mm");
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].def.dimension, "length");
+        assert!(find_all("furlongs").is_empty());
+    }
+}
@@ -0,0 +1,763 @@
+//! Parsing of numbers written with a unit suffix, such as `4KiB` or `500MB/s`.
+//!
+//! Each quantity keeps the text it was parsed from, so output can echo the
+//! value the way the user wrote it.
+
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::ConvertError;
+
+/// Whether NaN and infinities are accepted, see [`set_allow_non_finite`].
+static ALLOW_NON_FINITE: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether [`parse_number`] and the unit conversions accept NaN and
+/// infinite values and pass them through, instead of rejecting them with
+/// [`ConvertError::NonFinite`]. They are rejected by default.
+pub fn set_allow_non_finite(allow: bool) {
+    ALLOW_NON_FINITE.store(allow, Ordering::Relaxed);
+}
+
+thread_local! {
+    /// The error of the last call to [`parse_number`], see [`take_number_error`].
+    static NUMBER_ERROR: Cell<Option<ConvertError>> = const { Cell::new(None) };
+}
+
+/// Takes the error of the last call to [`parse_number`] on this thread, or
+/// `None` if it succeeded. clap keeps only the message of a value it could
+/// not read, so this recovers its kind, and with it the exit code.
+pub fn take_number_error() -> Option<ConvertError> {
+    NUMBER_ERROR.take()
+}
+
+/// Returns `value`, or [`ConvertError::NonFinite`] if it is NaN or infinite
+/// and those are not allowed.
+pub fn check_finite(value: f64) -> Result<f64, ConvertError> {
+    if value.is_finite() || ALLOW_NON_FINITE.load(Ordering::Relaxed) {
+        Ok(value)
+    } else {
+        Err(ConvertError::NonFinite { value })
+    }
+}
+
+/// Returns `value` as the whole number it is, or [`ConvertError::NotWhole`]
+/// naming `unit` if it has a fractional part, for `--strict-int`. Rounding
+/// errors of the conversion factors, as in 2.0000000000000004, are not a
+/// fractional part.
+pub fn check_whole(value: f64, unit: &str) -> Result<f64, ConvertError> {
+    let whole = value.round();
+    if (value - whole).abs() <= 1e-9 * whole.abs().max(1.0) {
+        Ok(whole)
+    } else {
+        Err(ConvertError::NotWhole {
+            value,
+            unit: unit.to_string(),
+        })
+    }
+}
+
+/// Byte size suffixes and the number of bytes they stand for.
+const BYTE_UNITS: &[(&str, f64)] = &[
+    ("", 1.0),
+    ("B", 1.0),
+    ("kB", 1e3),
+    ("KB", 1e3),
+    ("MB", 1e6),
+    ("GB", 1e9),
+    ("TB", 1e12),
+    ("KiB", 1024.0),
+    ("MiB", 1024.0 * 1024.0),
+    ("GiB", 1024.0 * 1024.0 * 1024.0),
+    ("TiB", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+];
+
+/// Bit rate suffixes and the number of bytes per second they stand for.
+const BIT_RATE_UNITS: &[(&str, f64)] = &[
+    ("bps", 1.0 / 8.0),
+    ("Kbps", 1e3 / 8.0),
+    ("Mbps", 1e6 / 8.0),
+    ("Gbps", 1e9 / 8.0),
+];
+
+/// Duration suffixes and the number of seconds they stand for.
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("ns", 1e-9),
+    ("us", 1e-6),
+    ("µs", 1e-6),
+    ("ms", 1e-3),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("min", 60.0),
+    ("h", 3600.0),
+];
+
+/// Count suffixes for requests and events, e.g. the `M` in `1M`.
+const COUNT_UNITS: &[(&str, f64)] = &[("", 1.0), ("k", 1e3), ("K", 1e3), ("M", 1e6), ("B", 1e9)];
+
+/// Periods a request rate can be given per, and their length in seconds. A
+/// month is an average Gregorian month of 30.436875 days.
+const PERIOD_UNITS: &[(&str, f64)] = &[
+    ("s", 1.0),
+    ("sec", 1.0),
+    ("second", 1.0),
+    ("min", 60.0),
+    ("minute", 60.0),
+    ("h", 3600.0),
+    ("hour", 3600.0),
+    ("d", 86_400.0),
+    ("day", 86_400.0),
+    ("week", 604_800.0),
+    ("month", 2_629_746.0),
+    ("year", 31_556_952.0),
+];
+
+/// Distance suffixes and the number of kilometers they stand for.
+const DISTANCE_UNITS: &[(&str, f64)] = &[
+    ("mm", 1e-6),
+    ("cm", 1e-5),
+    ("m", 1e-3),
+    ("km", 1.0),
+    ("in", 2.54e-5),
+    ("ft", 3.048e-4),
+    ("yd", 9.144e-4),
+    ("mi", 1.609344),
+];
+
+/// Liquid volume suffixes and the number of milliliters they stand for.
+const LIQUID_UNITS: &[(&str, f64)] = &[
+    ("ml", 1.0),
+    ("cl", 10.0),
+    ("dl", 100.0),
+    ("l", 1000.0),
+    ("L", 1000.0),
+    ("floz", 29.573_529_562_5),
+    ("pt", 473.176_473),
+    ("gal", 3_785.411_784),
+];
+
+/// Bulk volume suffixes and the number of cubic meters they stand for.
+const VOLUME_UNITS: &[(&str, f64)] = &[
+    ("L", 1e-3),
+    ("m3", 1.0),
+    ("ft3", 0.028_316_846_592),
+    ("yd3", 0.764_554_857_984),
+];
+
+/// Area suffixes and the number of square meters they stand for.
+const AREA_UNITS: &[(&str, f64)] = &[
+    ("cm2", 1e-4),
+    ("m2", 1.0),
+    ("in2", 6.4516e-4),
+    ("ft2", 0.092_903_04),
+    ("yd2", 0.836_127_36),
+];
+
+/// Weight suffixes and the number of grams they stand for.
+const WEIGHT_UNITS: &[(&str, f64)] = &[
+    ("mg", 1e-3),
+    ("g", 1.0),
+    ("kg", 1e3),
+    ("oz", 28.349_523_125),
+    ("lb", 453.592_37),
+];
+
+/// Frequency suffixes and the number of hertz they stand for.
+const FREQUENCY_UNITS: &[(&str, f64)] = &[("Hz", 1.0), ("kHz", 1e3), ("MHz", 1e6), ("GHz", 1e9)];
+
+/// Parses `s` as a number followed by one of the suffixes in `units`, returning
+/// the number scaled by the suffix's factor.
+///
+/// # Example
+///
+/// Not compiled, as the function is private:
+///
+/// ```ignore
+/// assert_eq!(parse_with_units("4KiB", "byte size", BYTE_UNITS), Ok(4096.0));
+/// ```
+fn parse_with_units(s: &str, what: &str, units: &[(&str, f64)]) -> Result<f64, String> {
+    let s = s.trim();
+    // Units such as `m2` end in a digit, so look for them before splitting.
+    let powered = units
+        .iter()
+        .filter(|(unit, _)| unit.ends_with(|c: char| c.is_ascii_digit()) && s.ends_with(unit))
+        .find_map(|(unit, factor)| {
+            let number = parse_number(&s[..s.len() - unit.len()]).ok()?;
+            Some(number * factor)
+        });
+    if let Some(value) = powered {
+        return Ok(value);
+    }
+    let split = s
+        .rfind(|c: char| c.is_ascii_digit() || c == '.')
+        .map_or(0, |i| i + 1);
+    let (number, suffix) = s.split_at(split);
+    let number = parse_number(number).map_err(|error| match error {
+        ConvertError::NonFinite { .. } => error.to_string(),
+        _ => format!("'{}' is not a valid {}", s, what),
+    })?;
+    Ok(number * unit_factor(suffix.trim(), what, units)?)
+}
+
+/// Parses a decimal number, a fraction such as `1/250`, or a mixed number such
+/// as `1 1/2`.
+///
+/// # Example
+/// ```
+/// # use convertx::quantity::*;
+/// assert_eq!(parse_number("1/250"), Ok(0.004));
+/// assert_eq!(parse_number("-1 1/2"), Ok(-1.5));
+/// ```
+pub fn parse_number(s: &str) -> Result<f64, ConvertError> {
+    let parsed = parse_fraction(s.trim());
+    NUMBER_ERROR.set(parsed.as_ref().err().cloned());
+    parsed
+}
+
+/// Parses the trimmed `s` for [`parse_number`].
+fn parse_fraction(s: &str) -> Result<f64, ConvertError> {
+    let invalid =
+        || ConvertError::Parse(format!("'{}' is not a number or a fraction like 1/250", s));
+    let Some((numerator, denominator)) = s.split_once('/') else {
+        return check_finite(s.parse().map_err(|_| invalid())?);
+    };
+    let (whole, numerator) = match numerator.trim().rsplit_once(' ') {
+        Some((whole, numerator)) => (
+            whole.trim().parse::<f64>().map_err(|_| invalid())?,
+            numerator,
+        ),
+        None => (0.0, numerator),
+    };
+    let numerator: f64 = numerator.trim().parse().map_err(|_| invalid())?;
+    let denominator: f64 = denominator.trim().parse().map_err(|_| invalid())?;
+    if denominator == 0.0 {
+        return Err(ConvertError::Parse(format!("'{}' divides by zero", s)));
+    }
+    let fraction = numerator / denominator;
+    Ok(if whole.is_sign_negative() {
+        whole - fraction
+    } else {
+        whole + fraction
+    })
+}
+
+/// Returns true if `rest` starts with an exponent such as `e3` or `E-6`.
+pub fn is_exponent(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    matches!(chars.next(), Some('e' | 'E'))
+        && match chars.next() {
+            Some('+' | '-') => chars.next().is_some_and(|c| c.is_ascii_digit()),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+}
+
+/// Looks up the factor of the unit suffix `name` in `units`.
+fn unit_factor(name: &str, what: &str, units: &[(&str, f64)]) -> Result<f64, String> {
+    match units.iter().find(|(unit, _)| *unit == name) {
+        Some((_, factor)) => Ok(*factor),
+        None => {
+            let names: Vec<&str> = units
+                .iter()
+                .map(|(unit, _)| *unit)
+                .filter(|unit| !unit.is_empty())
+                .collect();
+            Err(format!(
+                "unknown {} unit '{}', expected one of: {}",
+                what,
+                name,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+/// Dimensions two quantities can be compared in, tried in order. A bare
+/// number is a plain count, and `m` is minutes before it is meters.
+const DIMENSIONS: &[(&str, &[(&str, f64)])] = &[
+    ("number", &[("", 1.0)]),
+    ("byte size", BYTE_UNITS),
+    ("duration", DURATION_UNITS),
+    ("distance", DISTANCE_UNITS),
+    ("volume", LIQUID_UNITS),
+    ("area", AREA_UNITS),
+    ("bulk volume", VOLUME_UNITS),
+    ("weight", WEIGHT_UNITS),
+    ("frequency", FREQUENCY_UNITS),
+];
+
+/// One of the `DIMENSIONS`, for reading many values of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dimension {
+    pub what: &'static str,
+    units: &'static [(&'static str, f64)],
+}
+
+impl Dimension {
+    /// Looks up a dimension by name, e.g. "duration" or "byte size".
+    pub fn named(what: &str) -> Option<Dimension> {
+        DIMENSIONS
+            .iter()
+            .find(|(name, _)| *name == what)
+            .map(|(what, units)| Dimension { what, units })
+    }
+
+    /// Parses a value such as `343ms` into the dimension's base unit.
+    pub fn parse(&self, s: &str) -> Result<f64, String> {
+        parse_with_units(s, self.what, self.units)
+    }
+
+    /// The number of base units in `unit`.
+    pub fn factor(&self, unit: &str) -> Result<f64, String> {
+        unit_factor(unit.trim(), self.what, self.units)
+    }
+
+    /// The suffix of the base unit, e.g. `s` for durations.
+    pub fn base_unit(&self) -> &'static str {
+        self.units
+            .iter()
+            .find(|(unit, factor)| !unit.is_empty() && *factor == 1.0)
+            .map_or("", |(unit, _)| unit)
+    }
+}
+
+/// The change between two quantities of the same dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The dimension both quantities were read in, e.g. "byte size".
+    pub what: &'static str,
+    /// The absolute change, in the unit of the first quantity.
+    pub delta: f64,
+    /// The unit suffix of the first quantity, e.g. `MB`.
+    pub unit: &'static str,
+    /// The second quantity divided by the first.
+    pub factor: f64,
+}
+
+/// Compares `from` and `to`, such as `45MB` and `62MB`, in the first dimension
+/// both parse in.
+///
+/// # Example
+/// ```
+/// # use convertx::quantity::*;
+/// let change = change("1GB", "1500MB").unwrap();
+/// assert_eq!((change.delta, change.unit, change.factor), (0.5, "GB", 1.5));
+/// ```
+pub fn change(from: &str, to: &str) -> Result<Change, String> {
+    let (from, to) = (from.trim(), to.trim());
+    DIMENSIONS
+        .iter()
+        .find_map(|(what, units)| {
+            let old = parse_with_units(from, what, units).ok()?;
+            let new = parse_with_units(to, what, units).ok()?;
+            // The longest suffix that leaves a number in front is the unit.
+            let (unit, factor) = units
+                .iter()
+                .filter(|(unit, _)| {
+                    from.ends_with(unit) && parse_number(&from[..from.len() - unit.len()]).is_ok()
+                })
+                .max_by_key(|(unit, _)| unit.len())?;
+            Some(Change {
+                what,
+                delta: (new - old) / factor,
+                unit,
+                factor: new / old,
+            })
+        })
+        .ok_or_else(|| {
+            format!(
+                "'{}' and '{}' are not quantities of the same kind",
+                from, to
+            )
+        })
+}
+
+/// Defines a quantity type parsed with [`parse_with_units`] from a suffix table.
+macro_rules! quantity {
+    ($(#[$doc:meta])* $name:ident($field:ident) as $what:literal in $units:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name {
+            pub $field: f64,
+            text: String,
+        }
+        impl FromStr for $name {
+            type Err = String;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name {
+                    $field: parse_with_units(s, $what, $units)?,
+                    text: s.trim().to_string(),
+                })
+            }
+        }
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.text)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// A number of bytes, e.g. `4KiB` or `1.5GB`. A bare number is bytes.
+    ByteSize(bytes) as "byte size" in BYTE_UNITS
+);
+quantity!(
+    /// A length of time, e.g. `20ms` or `3m` (minutes).
+    Duration(seconds) as "duration" in DURATION_UNITS
+);
+quantity!(
+    /// A distance in kilometers, e.g. `4000km`, `120mi` or `6.1in`.
+    Distance(km) as "distance" in DISTANCE_UNITS
+);
+quantity!(
+    /// A frequency, e.g. `3.2GHz` or `48kHz`.
+    Frequency(hertz) as "frequency" in FREQUENCY_UNITS
+);
+quantity!(
+    /// A liquid volume in milliliters, e.g. `500ml`, `33cl` or `12floz` (US).
+    Liquid(ml) as "volume" in LIQUID_UNITS
+);
+quantity!(
+    /// An area in square meters, e.g. `45m2` or `400ft2`.
+    Area(m2) as "area" in AREA_UNITS
+);
+quantity!(
+    /// A bulk volume in cubic meters, e.g. `2.5m3` or `3yd3`.
+    Volume(m3) as "volume" in VOLUME_UNITS
+);
+quantity!(
+    /// A weight in grams, e.g. `30g` or `1.5oz`.
+    Weight(grams) as "weight" in WEIGHT_UNITS
+);
+
+/// A throughput in bytes per second, written as a byte size per second
+/// (`500MB/s`) or as a bit rate (`4Gbps`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteRate {
+    pub bytes_per_second: f64,
+    text: String,
+}
+
+impl FromStr for ByteRate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes_per_second = match s.trim().strip_suffix("/s") {
+            Some(size) => parse_with_units(size, "throughput", BYTE_UNITS)?,
+            None => parse_with_units(s, "throughput", BIT_RATE_UNITS)?,
+        };
+        Ok(ByteRate {
+            bytes_per_second,
+            text: s.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ByteRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A flow rate in milliliters per second, written as a volume per time unit,
+/// e.g. `12L/min` or `2gal/h`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowRate {
+    pub ml_per_second: f64,
+    text: String,
+}
+
+impl FromStr for FlowRate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (volume, time) = s
+            .trim()
+            .rsplit_once('/')
+            .ok_or_else(|| format!("'{}' is not a flow rate like 12L/min", s))?;
+        let ml = parse_with_units(volume, "volume", LIQUID_UNITS)?;
+        let seconds = unit_factor(time.trim(), "time", DURATION_UNITS)?;
+        Ok(FlowRate {
+            ml_per_second: ml / seconds,
+            text: s.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for FlowRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A rate of requests or events, e.g. `250/s`, `1M requests/day` or
+/// `3k req/min`. A bare number is per second, and is shown with its unit,
+/// e.g. `250 requests/s`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestRate {
+    pub per_second: f64,
+    text: String,
+}
+
+impl FromStr for RequestRate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (written, period) = match s.trim().rsplit_once('/') {
+            Some((count, period)) => (count, Some(period)),
+            None => (s.trim(), None),
+        };
+        let written = written.trim();
+        let count = ["requests", "request", "reqs", "req"]
+            .iter()
+            .find_map(|word| written.strip_suffix(word))
+            .unwrap_or(written)
+            .trim();
+        let per_second = parse_with_units(count, "count", COUNT_UNITS)?
+            / unit_factor(period.unwrap_or("s").trim(), "period", PERIOD_UNITS)?;
+        Ok(RequestRate {
+            per_second,
+            text: match period {
+                Some(_) => s.trim().to_string(),
+                None => format!("{} requests/s", count),
+            },
+        })
+    }
+}
+
+impl fmt::Display for RequestRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A byte size unit on its own, e.g. `MiB`, used as a conversion target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteUnit {
+    pub bytes: f64,
+    name: String,
+}
+
+impl FromStr for ByteUnit {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err("missing byte unit".to_string());
+        }
+        Ok(ByteUnit {
+            bytes: unit_factor(name, "byte", BYTE_UNITS)?,
+            name: name.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ByteUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A ratio such as `1:16`, read as "1 part of one thing to 16 of another".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ratio {
+    pub left: f64,
+    pub right: f64,
+}
+
+impl Ratio {
+    /// Returns how many parts of the right-hand side go with one part of the left.
+    pub fn per_left(&self) -> f64 {
+        self.right / self.left
+    }
+}
+
+impl FromStr for Ratio {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a ratio like 1:16", s);
+        let (left, right) = s.split_once(':').ok_or_else(invalid)?;
+        let part = |part: &str| {
+            parse_number(part)
+                .ok()
+                .filter(|n| *n > 0.0 && n.is_finite())
+                .ok_or_else(invalid)
+        };
+        Ok(Ratio {
+            left: part(left)?,
+            right: part(right)?,
+        })
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.left, self.right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("2.5"), Ok(2.5));
+        assert_eq!(parse_number("1/250"), Ok(0.004));
+        assert_eq!(parse_number(" 7/32 "), Ok(0.21875));
+        assert_eq!(parse_number("1 1/2"), Ok(1.5));
+        assert_eq!(parse_number("-1 1/2"), Ok(-1.5));
+        assert_eq!(parse_number("-1/2"), Ok(-0.5));
+        assert!(parse_number("1/0").is_err());
+        assert!(parse_number("1/2/3").is_err());
+        assert!(parse_number("a/b").is_err());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        assert_eq!(check_finite(1.5), Ok(1.5));
+        assert_eq!(
+            parse_number("1e999"),
+            Err(ConvertError::NonFinite {
+                value: f64::INFINITY
+            })
+        );
+        assert!(matches!(
+            check_finite(f64::NAN),
+            Err(ConvertError::NonFinite { .. })
+        ));
+        // `--allow-non-finite` is process-wide, so it is tested through the CLI.
+        assert!(matches!(
+            parse_number("nan"),
+            Err(ConvertError::NonFinite { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_whole() {
+        assert_eq!(check_whole(3.0, "KiB"), Ok(3.0));
+        assert_eq!(check_whole(2.0000000000000004, "dozens"), Ok(2.0));
+        assert_eq!(check_whole(-1e12 + 1e-4, "B"), Ok(-1e12));
+        assert_eq!(
+            check_whole(1.5, "KiB"),
+            Err(ConvertError::NotWhole {
+                value: 1.5,
+                unit: "KiB".to_string()
+            })
+        );
+        assert!(check_whole(0.001, "MB").is_err());
+    }
+
+    #[test]
+    fn test_byte_size() {
+        assert_eq!("4KiB".parse::<ByteSize>().unwrap().bytes, 4096.0);
+        assert_eq!("1.5 GB".parse::<ByteSize>().unwrap().bytes, 1.5e9);
+        assert_eq!("512".parse::<ByteSize>().unwrap().bytes, 512.0);
+        assert!("4XB".parse::<ByteSize>().is_err());
+        assert!("KiB".parse::<ByteSize>().is_err());
+        assert_eq!("MiB".parse::<ByteUnit>().unwrap().bytes, 1048576.0);
+        assert!("".parse::<ByteUnit>().is_err());
+    }
+
+    #[test]
+    fn test_duration_and_distance() {
+        assert_eq!("20ms".parse::<Duration>().unwrap().seconds, 0.02);
+        assert_eq!("3m".parse::<Duration>().unwrap().seconds, 180.0);
+        assert_eq!("1/250s".parse::<Duration>().unwrap().seconds, 0.004);
+        assert!("20".parse::<Duration>().is_err());
+        assert_eq!("4000km".parse::<Distance>().unwrap().km, 4000.0);
+        assert_eq!("500m".parse::<Distance>().unwrap().km, 0.5);
+        assert_eq!("3.2GHz".parse::<Frequency>().unwrap().hertz, 3.2e9);
+        assert_eq!("33cl".parse::<Liquid>().unwrap().ml, 330.0);
+        assert_eq!("1.5kg".parse::<Weight>().unwrap().grams, 1500.0);
+        assert_eq!("45m2".parse::<Area>().unwrap().m2, 45.0);
+        assert_eq!("5000cm2".parse::<Area>().unwrap().m2, 0.5);
+        assert!("45".parse::<Area>().is_err());
+        assert_eq!("2.5m3".parse::<Volume>().unwrap().m3, 2.5);
+        assert_eq!("1e3m".parse::<Distance>().unwrap().km, 1.0);
+        assert_eq!("2.5E-3kg".parse::<Weight>().unwrap().grams, 2.5);
+        assert!("1e400m"
+            .parse::<Distance>()
+            .unwrap_err()
+            .contains("inf is not a finite number"));
+    }
+
+    #[test]
+    fn test_ratio() {
+        let ratio: Ratio = "1:16".parse().unwrap();
+        assert_eq!(ratio.per_left(), 16.0);
+        assert_eq!(ratio.to_string(), "1:16");
+        assert_eq!("2 : 31".parse::<Ratio>().unwrap().per_left(), 15.5);
+        assert!("16".parse::<Ratio>().is_err());
+        assert!("0:16".parse::<Ratio>().is_err());
+    }
+
+    #[test]
+    fn test_flow_rate() {
+        assert_eq!("12L/min".parse::<FlowRate>().unwrap().ml_per_second, 200.0);
+        assert_eq!("3.6L/h".parse::<FlowRate>().unwrap().ml_per_second, 1.0);
+        assert_eq!(
+            "12L/min".parse::<FlowRate>().unwrap().to_string(),
+            "12L/min"
+        );
+        assert!("12L".parse::<FlowRate>().is_err());
+        assert!("12L/week".parse::<FlowRate>().is_err());
+    }
+
+    #[test]
+    fn test_request_rate() {
+        assert_eq!("250".parse::<RequestRate>().unwrap().per_second, 250.0);
+        assert_eq!(
+            "250".parse::<RequestRate>().unwrap().to_string(),
+            "250 requests/s"
+        );
+        assert_eq!(
+            "2k req".parse::<RequestRate>().unwrap().to_string(),
+            "2k requests/s"
+        );
+        assert_eq!("120/min".parse::<RequestRate>().unwrap().per_second, 2.0);
+        assert_eq!(
+            "1M requests/day".parse::<RequestRate>().unwrap().per_second,
+            1e6 / 86_400.0
+        );
+        assert_eq!("3.6k req/h".parse::<RequestRate>().unwrap().per_second, 1.0);
+        assert!("250/fortnight".parse::<RequestRate>().is_err());
+        assert!("lots/s".parse::<RequestRate>().is_err());
+    }
+
+    #[test]
+    fn test_change() {
+        let grew = change("45MB", "62MB").unwrap();
+        assert_eq!(
+            (grew.what, grew.delta, grew.unit),
+            ("byte size", 17.0, "MB")
+        );
+        assert!((grew.factor - 62.0 / 45.0).abs() < 1e-12);
+        let faster = change("1.2s", "900ms").unwrap();
+        assert_eq!(
+            (faster.what, faster.unit, faster.factor),
+            ("duration", "s", 0.75)
+        );
+        assert!((faster.delta + 0.3).abs() < 1e-12);
+        assert_eq!(change("40", "50").unwrap().what, "number");
+        assert_eq!(change("2m2", "3m2").unwrap().unit, "m2");
+        assert!(change("45MB", "3kg").is_err());
+    }
+
+    #[test]
+    fn test_dimension() {
+        let time = Dimension::named("duration").unwrap();
+        assert_eq!(time.parse("343ms"), Ok(0.343));
+        assert_eq!(time.factor("min"), Ok(60.0));
+        assert_eq!(time.base_unit(), "s");
+        assert_eq!(Dimension::named("number").unwrap().base_unit(), "");
+        assert!(time.parse("3kg").is_err());
+        assert!(Dimension::named("color").is_none());
+    }
+
+    #[test]
+    fn test_byte_rate() {
+        assert_eq!("500MB/s".parse::<ByteRate>().unwrap().bytes_per_second, 5e8);
+        assert_eq!("8Gbps".parse::<ByteRate>().unwrap().bytes_per_second, 1e9);
+        assert!("500MB".parse::<ByteRate>().is_err());
+    }
+}
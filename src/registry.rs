@@ -0,0 +1,705 @@
+//! A runtime registry of every unit, for looking units up by text.
+//!
+//! Units are nodes and conversion factors are links between them, each saying
+//! that one unit is `factor` of another plus an `offset`. A conversion follows
+//! the shortest chain of links in either direction, so a unit only needs to be
+//! defined relative to one other unit of its dimension (e.g. furlong to yard,
+//! yard to foot) to convert to all of them.
+
+use std::collections::VecDeque;
+
+use crate::prefix;
+
+/// Units defined only relative to another unit, as
+/// `(name, singular, symbol, dimension, factor, of)`: one unit is `factor` of `of`.
+const RELATIVE_UNITS: &[(&str, &str, &str, &str, f64, &str)] = &[
+    ("yards", "yard", "yd", "length", 3.0, "feet"),
+    ("furlongs", "furlong", "fur", "length", 220.0, "yards"),
+    ("miles", "mile", "mi", "length", 8.0, "furlongs"),
+    (
+        "fluid_ounces",
+        "fluid ounce",
+        "fl oz",
+        "volume",
+        1.0 / 128.0,
+        "gallons",
+    ),
+    ("pints", "pint", "pt", "volume", 1.0 / 8.0, "gallons"),
+    (
+        "tebibytes",
+        "tebibyte",
+        "TiB",
+        "information",
+        1024.0,
+        "gibibytes",
+    ),
+];
+
+/// Informal spellings of units, as `(alias, name, dimension)`. They may clash
+/// with units of other dimensions, e.g. `oz` for ounces and fluid ounces.
+const ALIASES: &[(&str, &str, &str)] = &[("oz", "fluid_ounces", "volume")];
+
+/// Returns true if `token` is one of the informal spellings of `ALIASES`,
+/// ignoring case, which name units of several dimensions.
+pub fn is_informal(token: &str) -> bool {
+    ALIASES
+        .iter()
+        .any(|(alias, _, _)| alias.eq_ignore_ascii_case(token))
+}
+
+/// A unit name with several common definitions, such as the gallon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ambiguity {
+    /// Spellings that leave the definition open, e.g. "gal".
+    pub spellings: &'static [&'static str],
+    /// Dimension of every definition, e.g. "volume".
+    pub dimension: &'static str,
+    /// The definition the spellings are read as, or `None` if they are
+    /// refused and a qualified unit is required.
+    pub definition: Option<&'static str>,
+    /// Units naming each definition, with what sets it apart.
+    pub qualified: &'static [(&'static str, &'static str)],
+}
+
+/// Units with several common definitions. Those read as one definition
+/// convert with a warning naming it; the others need a qualified unit.
+pub const AMBIGUITIES: &[Ambiguity] = &[
+    Ambiguity {
+        spellings: &["calories", "calorie", "cal"],
+        dimension: "energy",
+        definition: Some("the thermochemical calorie of 4.184 J"),
+        qualified: &[
+            ("thermochemical_calories", "4.184 J"),
+            ("kilocalories", "the food Calorie, 4184 J"),
+        ],
+    },
+    Ambiguity {
+        spellings: &["tons", "ton"],
+        dimension: "mass",
+        definition: None,
+        qualified: &[
+            ("short_tons", "2000 lb, US"),
+            ("long_tons", "2240 lb, UK"),
+            ("tonnes", "1000 kg, metric"),
+        ],
+    },
+    Ambiguity {
+        spellings: &["gallons", "gallon", "gal"],
+        dimension: "volume",
+        definition: Some("the US liquid gallon of 3.785 L"),
+        qualified: &[("us_gallons", "3.785 L"), ("imperial_gallons", "4.546 L")],
+    },
+    Ambiguity {
+        spellings: &["horsepower", "hp"],
+        dimension: "power",
+        definition: Some("the mechanical horsepower of 745.7 W"),
+        qualified: &[
+            ("mechanical_horsepower", "745.7 W"),
+            ("metric_horsepower", "735.5 W"),
+        ],
+    },
+];
+
+impl Ambiguity {
+    /// Explains how `token`, one of the spellings, is read, e.g. "'gal' is
+    /// read as the US liquid gallon of 3.785 L; write us_gallons (3.785 L)
+    /// or imperial_gallons (4.546 L) to choose".
+    pub fn message(&self, token: &str) -> String {
+        let choices: Vec<String> = self
+            .qualified
+            .iter()
+            .map(|(name, note)| format!("{} ({})", name, note))
+            .collect();
+        let (last, rest) = choices.split_last().expect("an ambiguity has choices");
+        let choices = format!("{} or {}", rest.join(", "), last);
+        match self.definition {
+            Some(definition) => format!(
+                "'{}' is read as {}; write {} to choose",
+                token, definition, choices
+            ),
+            None => format!("'{}' has several definitions; write {}", token, choices),
+        }
+    }
+}
+
+/// Relative units read so far, so that each is only built once, as in
+/// [`prefix::find`].
+static RELATIVE_READ: std::sync::Mutex<Vec<&'static prefix::PrefixedUnit>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// Reads `token` as a unit of `category` that is defined relative to another,
+/// e.g. `mi` or `miles` in "length", ignoring case. It is given as a multiple
+/// of the first unit in its chain of definitions that is not relative, so
+/// miles are 5280 feet.
+///
+/// # Example
+/// ```
+/// # use convertx::registry::*;
+/// let unit = relative("length", "MI").unwrap();
+/// assert_eq!((unit.def.name.as_str(), unit.reference, unit.factor), ("miles", "feet", 5280.0));
+/// ```
+pub fn relative(category: &str, token: &str) -> Option<&'static prefix::PrefixedUnit> {
+    let find = |name: &str| {
+        RELATIVE_UNITS
+            .iter()
+            .find(|unit| unit.0 == name && unit.3 == category)
+    };
+    let &(name, singular, symbol, _, mut factor, mut reference) =
+        RELATIVE_UNITS.iter().find(|unit| {
+            unit.3 == category
+                && [unit.0, unit.1, unit.2, &unit.0.replace('_', " ")]
+                    .iter()
+                    .any(|spelling| spelling.eq_ignore_ascii_case(token))
+        })?;
+    while let Some(&(_, _, _, _, of_factor, of)) = find(reference) {
+        factor *= of_factor;
+        reference = of;
+    }
+    let mut read = RELATIVE_READ
+        .lock()
+        .expect("the relative units are not poisoned");
+    if let Some(unit) = read.iter().find(|unit| unit.def.name == name) {
+        return Some(unit);
+    }
+    let def = UnitDef {
+        plural: name.replace('_', " "),
+        ..UnitDef::new(name, singular, symbol, category)
+    };
+    let unit: &'static prefix::PrefixedUnit = Box::leak(Box::new(prefix::PrefixedUnit {
+        def,
+        reference,
+        factor,
+    }));
+    read.push(unit);
+    Some(unit)
+}
+
+/// Returns the ambiguity of `token`, ignoring case, if it is a unit with
+/// several common definitions.
+pub fn ambiguity(token: &str) -> Option<&'static Ambiguity> {
+    AMBIGUITIES.iter().find(|ambiguity| {
+        ambiguity
+            .spellings
+            .iter()
+            .any(|spelling| spelling.eq_ignore_ascii_case(token))
+    })
+}
+
+/// Returns the ambiguities of the units in `text`, e.g. a whole `eval`
+/// expression such as `5 gal + 2 L`, each with the word that names it.
+pub fn ambiguities(text: &str) -> Vec<(&str, &'static Ambiguity)> {
+    let mut found: Vec<(&str, &'static Ambiguity)> = Vec::new();
+    let words = text
+        .split(|c: char| !(c.is_alphabetic() || c == '_' || c == '-'))
+        .map(|word| word.trim_matches('-'));
+    for word in words {
+        if let Some(ambiguity) = ambiguity(word) {
+            if !found.iter().any(|(_, other)| *other == ambiguity) {
+                found.push((word, ambiguity));
+            }
+        }
+    }
+    found
+}
+
+/// The SI Brochure, which defines the SI units and prefixes.
+const SI_BROCHURE: &str =
+    "BIPM, The International System of Units (SI Brochure), 9th edition, 2019";
+
+/// Where the definitions of units are published, as `(source, units)` with
+/// the units by name, for `--cite`. Units with an SI prefix are cited like
+/// the unit they scale.
+const SOURCES: &[(&str, &[&str])] = &[
+    (
+        SI_BROCHURE,
+        &[
+            "meters",
+            "kilometers",
+            "kg",
+            "k",
+            "c",
+            "sqm",
+            "cubic_meters",
+            "mps",
+            "pa",
+        ],
+    ),
+    (
+        "BIPM, The International System of Units (SI Brochure), 9th edition, 2019, Table 8",
+        &["liters", "milliliters", "hectares", "tonnes", "kph"],
+    ),
+    (
+        "NIST Special Publication 811, 2008 edition, Appendix B.8",
+        &[
+            "feet",
+            "inches",
+            "yards",
+            "miles",
+            "f",
+            "lb",
+            "oz",
+            "troy_ounces",
+            "troy_pounds",
+            "grains",
+            "short_tons",
+            "long_tons",
+            "sqft",
+            "acres",
+            "cubic_inches",
+            "gallons",
+            "us_gallons",
+            "imperial_gallons",
+            "fluid_ounces",
+            "mph",
+            "knots",
+            "bar",
+            "atm",
+            "psi",
+        ],
+    ),
+    (
+        "NIST Handbook 44, 2024 edition, Appendix C",
+        &[
+            "furlongs",
+            "pennyweights",
+            "scruples",
+            "apothecaries_drams",
+            "apothecaries_ounces",
+            "apothecaries_pounds",
+        ],
+    ),
+    (
+        "IEC 80000-13:2008, Quantities and units, Part 13: Information science and technology",
+        &[
+            "bits",
+            "bytes",
+            "kilobits",
+            "megabits",
+            "gigabits",
+            "kibibits",
+            "mebibits",
+            "gibibits",
+            "kilobytes",
+            "megabytes",
+            "gigabytes",
+            "kibibytes",
+            "mebibytes",
+            "gibibytes",
+            "bps",
+            "mbps",
+        ],
+    ),
+];
+
+/// Returns where the definition of the unit `name` of `dimension` is
+/// published, if it is on record.
+///
+/// # Example
+/// ```
+/// # use convertx::registry::*;
+/// assert_eq!(source("feet", "length"), Some("NIST Special Publication 811, 2008 edition, Appendix B.8"));
+/// assert_eq!(source("dozens", "count"), None);
+/// ```
+pub fn source(name: &str, dimension: &str) -> Option<&'static str> {
+    let listed = SOURCES
+        .iter()
+        .find(|(_, units)| units.iter().any(|unit| unit.eq_ignore_ascii_case(name)));
+    if let Some((source, _)) = listed {
+        return Some(source);
+    }
+    prefix::find(dimension, name)
+        .filter(|unit| unit.reference != name)
+        .and_then(|unit| source(unit.reference, dimension))
+}
+
+/// A unit known to the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitDef {
+    /// Name accepted on the command line, e.g. "meters".
+    pub name: String,
+    pub singular: String,
+    pub plural: String,
+    pub symbol: String,
+    /// Other accepted spellings.
+    pub aliases: Vec<String>,
+    /// Dimension, which is also the category name, e.g. "length".
+    pub dimension: String,
+    /// Unit pack that provides the unit, if it is not built in.
+    pub pack: Option<&'static str>,
+}
+
+impl UnitDef {
+    /// A unit with a plural `name` and the given singular and symbol.
+    pub fn new(name: &str, singular: &str, symbol: &str, dimension: &str) -> UnitDef {
+        UnitDef {
+            name: name.to_string(),
+            singular: singular.to_string(),
+            plural: name.to_string(),
+            symbol: symbol.to_string(),
+            aliases: Vec::new(),
+            dimension: dimension.to_string(),
+            pack: None,
+        }
+    }
+
+    /// Returns true if `token` is the unit's name, symbol or an alias, ignoring case.
+    pub fn is_named(&self, token: &str) -> bool {
+        [&self.name, &self.singular, &self.plural, &self.symbol]
+            .into_iter()
+            .chain(&self.aliases)
+            .any(|name| name.eq_ignore_ascii_case(token))
+    }
+}
+
+/// A link saying `to = from * factor + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Link {
+    from: usize,
+    to: usize,
+    factor: f64,
+    offset: f64,
+}
+
+/// Every unit and the links between them.
+#[derive(Debug, Clone, Default)]
+pub struct UnitRegistry {
+    units: Vec<UnitDef>,
+    links: Vec<Link>,
+}
+
+impl UnitRegistry {
+    /// Adds `unit` and returns its index. A unit with the same name and
+    /// dimension is only added once.
+    pub fn add(&mut self, unit: UnitDef) -> usize {
+        match self
+            .units
+            .iter()
+            .position(|u| u.name == unit.name && u.dimension == unit.dimension)
+        {
+            Some(index) => index,
+            None => {
+                self.units.push(unit);
+                self.units.len() - 1
+            }
+        }
+    }
+
+    /// Records that one `from` is `factor` of `to` plus `offset`.
+    pub fn link(&mut self, from: usize, to: usize, factor: f64, offset: f64) {
+        self.links.push(Link {
+            from,
+            to,
+            factor,
+            offset,
+        });
+    }
+
+    /// Adds `unit` defined as `factor` of the unit named `of` in its dimension.
+    pub fn define(&mut self, unit: UnitDef, factor: f64, of: &str) -> Result<usize, String> {
+        let of = self.index(of, &unit.dimension)?;
+        let index = self.add(unit);
+        self.link(index, of, factor, 0.0);
+        Ok(index)
+    }
+
+    /// Adds the units defined relative to other units, and informal aliases.
+    pub fn with_relative_units(mut self) -> UnitRegistry {
+        for (name, singular, symbol, dimension, factor, of) in RELATIVE_UNITS {
+            let unit = UnitDef {
+                plural: name.replace('_', " "),
+                ..UnitDef::new(name, singular, symbol, dimension)
+            };
+            self.define(unit, *factor, of)
+                .expect("relative units refer to registered units");
+        }
+        for (alias, name, dimension) in ALIASES {
+            let index = self
+                .index(name, dimension)
+                .expect("aliases refer to registered units");
+            self.units[index].aliases.push(alias.to_string());
+        }
+        self
+    }
+
+    /// Every registered unit, in registration order.
+    pub fn units(&self) -> &[UnitDef] {
+        &self.units
+    }
+
+    /// Units whose name, symbol, alias or dimension contains `query`, ignoring case.
+    pub fn search(&self, query: &str) -> Vec<&UnitDef> {
+        let query = query.to_lowercase();
+        self.units
+            .iter()
+            .filter(|unit| {
+                [&unit.name, &unit.singular, &unit.symbol, &unit.dimension]
+                    .into_iter()
+                    .chain(&unit.aliases)
+                    .any(|name| name.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Units that `token` names, at most one per dimension, including units
+    /// with an SI prefix, see [`prefix::find`].
+    ///
+    /// Within a dimension the first registered unit wins, so built-in units
+    /// shadow pack units registered after them, and both shadow prefixed units.
+    pub fn lookup(&self, token: &str) -> Vec<&UnitDef> {
+        self.lookup_with(token, prefix::find_all)
+    }
+
+    /// Units that `token` names like [`lookup`](Self::lookup), reading
+    /// prefixed symbols in any case, see [`prefix::find_ignoring_case`].
+    pub fn lookup_ignoring_case(&self, token: &str) -> Vec<&UnitDef> {
+        self.lookup_with(token, prefix::find_all_ignoring_case)
+    }
+
+    fn lookup_with(
+        &self,
+        token: &str,
+        prefixed: fn(&str) -> Vec<&'static prefix::PrefixedUnit>,
+    ) -> Vec<&UnitDef> {
+        let mut found: Vec<&UnitDef> = Vec::new();
+        for unit in self.units.iter().filter(|unit| unit.is_named(token)) {
+            if !found.iter().any(|f| f.dimension == unit.dimension) {
+                found.push(unit);
+            }
+        }
+        // Prefixed units, e.g. "kPa", in dimensions where nothing else matched.
+        for unit in prefixed(token) {
+            let dimension = &unit.def.dimension;
+            if !found.iter().any(|f| &f.dimension == dimension)
+                && self.index(unit.reference, dimension).is_ok()
+            {
+                found.push(&unit.def);
+            }
+        }
+        found
+    }
+
+    /// Finds the unit of `dimension` that `token` names.
+    fn index(&self, token: &str, dimension: &str) -> Result<usize, String> {
+        self.units
+            .iter()
+            .position(|unit| unit.dimension == dimension && unit.is_named(token))
+            .ok_or_else(|| format!("unknown {} unit '{}'", dimension, token))
+    }
+
+    /// Returns `(factor, offset)` such that a value in unit `from` is
+    /// `value * factor + offset` in unit `to`, following the shortest chain of
+    /// links between them.
+    fn path(&self, from: usize, to: usize) -> Option<(f64, f64)> {
+        // Breadth-first search, remembering the affine map from `from` to each unit.
+        let mut maps: Vec<Option<(f64, f64)>> = vec![None; self.units.len()];
+        maps[from] = Some((1.0, 0.0));
+        let mut queue = VecDeque::from([from]);
+        while let Some(unit) = queue.pop_front() {
+            let (factor, offset) = maps[unit]?;
+            if unit == to {
+                return Some((factor, offset));
+            }
+            for link in &self.links {
+                // A link is followed forwards, or backwards through its inverse.
+                let (next, f, o) = if link.from == unit {
+                    (link.to, link.factor, link.offset)
+                } else if link.to == unit {
+                    (link.from, 1.0 / link.factor, -link.offset / link.factor)
+                } else {
+                    continue;
+                };
+                if maps[next].is_none() {
+                    maps[next] = Some((factor * f, offset * f + o));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a function converting values between the units of `dimension`
+    /// that `from` and `to` name.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::registry::*;
+    /// let mut registry = UnitRegistry::default();
+    /// let meters = registry.add(UnitDef::new("meters", "meter", "m", "length"));
+    /// let feet = registry.add(UnitDef::new("feet", "foot", "ft", "length"));
+    /// registry.link(feet, meters, 0.3048, 0.0);
+    /// let convert = registry.converter("feet", "meters", "length").unwrap();
+    /// assert!((convert(10.0) - 3.048).abs() < 1e-9);
+    /// ```
+    pub fn converter(
+        &self,
+        from: &str,
+        to: &str,
+        dimension: &str,
+    ) -> Result<impl Fn(f64) -> f64 + Send + Sync + 'static, String> {
+        let (factor, offset) = self.affine(from, to, dimension)?;
+        Ok(move |value| value * factor + offset)
+    }
+
+    /// Returns `(factor, offset)` such that a value in the unit of `dimension`
+    /// that `from` names is `value * factor + offset` in the one `to` names.
+    pub fn affine(&self, from: &str, to: &str, dimension: &str) -> Result<(f64, f64), String> {
+        let (from, from_size) = self.anchor(from, dimension)?;
+        let (to, to_size) = self.anchor(to, dimension)?;
+        let (factor, offset) = self.path(from, to).ok_or_else(|| {
+            format!(
+                "no conversion is defined from {} to {}",
+                self.units[from].name, self.units[to].name
+            )
+        })?;
+        Ok((from_size * factor / to_size, offset / to_size))
+    }
+
+    /// Finds the unit of `dimension` that `token` names, or that it is a
+    /// prefixed multiple of, with the size of `token` in that unit.
+    fn anchor(&self, token: &str, dimension: &str) -> Result<(usize, f64), String> {
+        match self.index(token, dimension) {
+            Ok(index) => Ok((index, 1.0)),
+            Err(error) => match prefix::find(dimension, token) {
+                Some(unit) => Ok((self.index(unit.reference, dimension)?, unit.factor)),
+                None => Err(error),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> UnitRegistry {
+        let mut registry = UnitRegistry::default();
+        let meters = registry.add(UnitDef::new("meters", "meter", "m", "length"));
+        let feet = registry.add(UnitDef::new("feet", "foot", "ft", "length"));
+        registry.link(feet, meters, 0.3048, 0.0);
+        let celsius = registry.add(UnitDef::new("C", "degree Celsius", "°C", "temperature"));
+        let fahrenheit = registry.add(UnitDef::new("F", "degree Fahrenheit", "°F", "temperature"));
+        registry.link(fahrenheit, celsius, 5.0 / 9.0, -32.0 * 5.0 / 9.0);
+        let mut minutes = UnitDef::new("minutes", "minute", "min", "time");
+        minutes.aliases.push("m".to_string());
+        registry.add(minutes);
+        registry.add(UnitDef::new("gallons", "gallon", "gal", "volume"));
+        registry.add(UnitDef::new("gibibytes", "gibibyte", "GiB", "information"));
+        registry.with_relative_units()
+    }
+
+    #[test]
+    fn test_converter_follows_links() {
+        let registry = registry();
+        let convert = registry.converter("furlongs", "m", "length").unwrap();
+        assert!((convert(1.0) - 201.168).abs() < 1e-9);
+        let convert = registry.converter("meters", "MI", "length").unwrap();
+        assert!((convert(1609.344) - 1.0).abs() < 1e-12);
+        let convert = registry.converter("c", "f", "temperature").unwrap();
+        assert!((convert(100.0) - 212.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_converter_errors() {
+        let registry = registry();
+        assert_eq!(
+            registry.converter("m", "c", "length").err(),
+            Some("unknown length unit 'c'".to_string())
+        );
+        assert!(registry.converter("furlongs", "parsecs", "length").is_err());
+        let mut registry = registry;
+        registry.add(UnitDef::new("leagues", "league", "lea", "length"));
+        assert_eq!(
+            registry.converter("leagues", "m", "length").err(),
+            Some("no conversion is defined from leagues to meters".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prefixed_units() {
+        let registry = registry();
+        let convert = registry.converter("kilometers", "mi", "length").unwrap();
+        assert!((convert(1.609344) - 1.0).abs() < 1e-12);
+        let convert = registry.converter("mm", "nanometers", "length").unwrap();
+        assert!((convert(1.0) - 1e6).abs() < 1e-6);
+        let found = registry.lookup("um");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "micrometers");
+        // Only prefixes of units the registry has are found.
+        assert!(registry.lookup("kPa").is_empty());
+    }
+
+    #[test]
+    fn test_lookup_and_search() {
+        let registry = registry();
+        let dimensions: Vec<&str> = registry
+            .lookup("m")
+            .iter()
+            .map(|unit| unit.dimension.as_str())
+            .collect();
+        assert_eq!(dimensions, vec!["length", "time"]);
+        let found: Vec<&str> = registry
+            .search("FUR")
+            .iter()
+            .map(|u| u.name.as_str())
+            .collect();
+        assert_eq!(found, vec!["furlongs"]);
+        assert_eq!(registry.search("temperature").len(), 2);
+        assert_eq!(registry.units().len(), 13);
+        assert_eq!(registry.lookup("OZ")[0].name, "fluid_ounces");
+    }
+
+    #[test]
+    fn test_is_informal() {
+        assert!(is_informal("OZ"));
+        assert!(!is_informal("fl oz"));
+        assert!(!is_informal("ounces"));
+    }
+
+    #[test]
+    fn test_relative() {
+        let furlong = relative("length", "furlong").unwrap();
+        assert_eq!((furlong.reference, furlong.factor), ("feet", 660.0));
+        assert!(std::ptr::eq(furlong, relative("length", "fur").unwrap()));
+        let ounce = relative("volume", "fluid ounces").unwrap();
+        assert_eq!(
+            (ounce.def.symbol.as_str(), ounce.reference),
+            ("fl oz", "gallons")
+        );
+        assert!(relative("mass", "miles").is_none());
+        assert!(relative("length", "feet").is_none());
+    }
+
+    #[test]
+    fn test_source() {
+        assert_eq!(source("kilometers", "length"), Some(SI_BROCHURE));
+        assert_eq!(source("nanometers", "length"), Some(SI_BROCHURE));
+        assert_eq!(source("grams", "mass"), Some(SI_BROCHURE));
+        assert!(source("megabars", "pressure").unwrap().starts_with("NIST"));
+        assert!(source("kibibytes", "information")
+            .unwrap()
+            .starts_with("IEC"));
+        assert_eq!(source("dozens", "count"), None);
+        assert_eq!(source("gh/s", "hashrate"), None);
+    }
+
+    #[test]
+    fn test_ambiguities() {
+        let gallon = ambiguity("GAL").unwrap();
+        assert_eq!(gallon.dimension, "volume");
+        assert_eq!(
+            gallon.message("gal"),
+            "'gal' is read as the US liquid gallon of 3.785 L; write us_gallons (3.785 L) or imperial_gallons (4.546 L) to choose"
+        );
+        assert_eq!(
+            ambiguity("tons").unwrap().message("tons"),
+            "'tons' has several definitions; write short_tons (2000 lb, US), long_tons (2240 lb, UK) or tonnes (1000 kg, metric)"
+        );
+        assert!(ambiguity("us_gallons").is_none());
+        let found: Vec<&str> = ambiguities("5gal + 3 gallons in L, 2 hp in metric-horsepower")
+            .iter()
+            .map(|(word, _)| *word)
+            .collect();
+        assert_eq!(found, vec!["gal", "hp"]);
+        assert!(ambiguities("10 kcal in kJ").is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+//! A small seeded random number generator for `convertx quiz`.
+//!
+//! The generator is SplitMix64, so the same seed gives the same sequence on
+//! every platform and build, which keeps printed worksheets and tests
+//! repeatable. It is not suitable for anything security related.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A reproducible stream of pseudo-random numbers.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    /// A generator that always produces the same sequence for `seed`.
+    pub fn seeded(seed: u64) -> Rng {
+        Rng { seed, state: seed }
+    }
+
+    /// A generator seeded from the clock, for runs without `--seed`.
+    pub fn from_clock() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Rng::seeded(nanos)
+    }
+
+    /// The seed this generator started from, to repeat the run with `--seed`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the next number of the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `low..=high`.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::rng::*;
+    /// let roll = Rng::seeded(7).between(1, 6);
+    /// assert!((1..=6).contains(&roll));
+    /// ```
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        // The modulo bias is negligible for the small ranges used here.
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// Returns a random element of `items`, or `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.between(0, items.len() as u64 - 1) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_sequence() {
+        // Reference values of SplitMix64 for seed 1234567.
+        let mut rng = Rng::seeded(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+        let (mut a, mut b) = (Rng::seeded(42), Rng::seeded(42));
+        let first: Vec<u64> = (0..5).map(|_| a.between(1, 100)).collect();
+        let second: Vec<u64> = (0..5).map(|_| b.between(1, 100)).collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|n| (1..=100).contains(n)));
+        assert_eq!(a.seed(), 42);
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut rng = Rng::seeded(3);
+        assert_eq!(rng.choose::<u8>(&[]), None);
+        assert_eq!(rng.choose(&["only"]), Some(&"only"));
+        assert!(["a", "b"].contains(rng.choose(&["a", "b"]).unwrap()));
+    }
+}
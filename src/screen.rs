@@ -0,0 +1,100 @@
+//! Display geometry and timing for `convertx ppi` and `convertx refresh`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::portable::hypot;
+
+/// Millimeters in one inch.
+const MM_IN_INCH: f64 = 25.4;
+
+/// A screen resolution in pixels, written as `WIDTHxHEIGHT`, e.g. `2556x1179`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    /// Returns the length of the diagonal in pixels.
+    pub fn diagonal(&self) -> f64 {
+        hypot(f64::from(self.width), f64::from(self.height))
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a resolution like 1920x1080", s);
+        let (width, height) = s.trim().split_once(['x', 'X', '×']).ok_or_else(invalid)?;
+        Ok(Resolution {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// Returns the pixel density of a screen with `diagonal_in` inches diagonal.
+///
+/// # Example
+/// ```
+/// # use convertx::screen::*;
+/// let resolution: Resolution = "1920x1080".parse().unwrap();
+/// assert!((ppi(resolution, 24.0) - 91.79).abs() < 0.01);
+/// ```
+pub fn ppi(resolution: Resolution, diagonal_in: f64) -> f64 {
+    resolution.diagonal() / diagonal_in
+}
+
+/// Converts a pixel density to a dot pitch in millimeters, or back; the
+/// conversion is its own inverse.
+pub fn dot_pitch_mm(ppi: f64) -> f64 {
+    MM_IN_INCH / ppi
+}
+
+/// Converts a refresh rate in hertz to a frame time in milliseconds, or back;
+/// the conversion is its own inverse.
+///
+/// # Example
+/// ```
+/// # use convertx::screen::*;
+/// assert_eq!(frame_time_ms(125.0), 8.0);
+/// ```
+pub fn frame_time_ms(hz: f64) -> f64 {
+    1000.0 / hz
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolution() {
+        let resolution: Resolution = "2556x1179".parse().unwrap();
+        assert_eq!((resolution.width, resolution.height), (2556, 1179));
+        assert_eq!("1920 × 1080".parse::<Resolution>().unwrap().height, 1080);
+        assert!("1920".parse::<Resolution>().is_err());
+        assert!("axb".parse::<Resolution>().is_err());
+    }
+
+    #[test]
+    fn test_density() {
+        let resolution: Resolution = "2556x1179".parse().unwrap();
+        assert!((ppi(resolution, 6.1) - 461.44).abs() < 0.01);
+        assert!((dot_pitch_mm(163.0) - 0.155828).abs() < 1e-6);
+        assert!((dot_pitch_mm(dot_pitch_mm(163.0)) - 163.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frame_time_ms() {
+        assert_eq!(frame_time_ms(125.0), 8.0);
+        assert!((frame_time_ms(144.0) - 6.944).abs() < 1e-3);
+        assert!((frame_time_ms(frame_time_ms(60.0)) - 60.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,246 @@
+//! Column conversion of CSV measurement series for `convertx series`.
+//!
+//! Rows are split on commas without quoting, which is enough for the numeric
+//! files plotting tools read. The first row is kept as a header if its y cell
+//! is not a number, and blank lines and `#` comments are copied through.
+//! Inputs can be memory-mapped, so the file is paged in as it is read rather
+//! than copied into memory up front, and converted in chunks from a byte
+//! offset so an interrupted conversion can resume.
+
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+use crate::error::ConvertError;
+
+/// The contents of an input file, read into memory or memory-mapped.
+pub enum Input {
+    Read(String),
+    Mapped(Mmap),
+}
+
+impl Input {
+    /// Opens `path`, memory-mapping it if `mmap` is set.
+    pub fn open(path: &Path, mmap: bool) -> Result<Input, String> {
+        let error = |e: std::io::Error| format!("could not read {}: {}", path.display(), e);
+        if !mmap {
+            return std::fs::read_to_string(path)
+                .map(Input::Read)
+                .map_err(error);
+        }
+        let file = File::open(path).map_err(error)?;
+        // SAFETY: the map is read-only; like any reader, the output is
+        // undefined if another process truncates the file while it is read.
+        let map = unsafe { Mmap::map(&file) }.map_err(error)?;
+        Ok(Input::Mapped(map))
+    }
+
+    /// Returns the contents as text.
+    pub fn text(&self) -> Result<&str, String> {
+        match self {
+            Input::Read(text) => Ok(text),
+            Input::Mapped(map) => {
+                std::str::from_utf8(map).map_err(|e| format!("input is not UTF-8: {}", e))
+            }
+        }
+    }
+}
+
+/// Inputs with more lines than this are converted on the rayon thread pool.
+const PARALLEL_LINES: usize = 10_000;
+
+/// Lines converted per chunk by [`convert_from`].
+pub const CHUNK_LINES: usize = 100_000;
+
+/// Returns true for lines holding a row, as opposed to blank lines and comments.
+fn is_data(line: &str) -> bool {
+    !line.trim().is_empty() && !line.trim().starts_with('#')
+}
+
+/// Picks the `x_col` and `y_col` cells of every row of `input` and converts the
+/// y values with `convert`, returning the two-column CSV text.
+///
+/// Large inputs are split across threads; rows keep their order.
+///
+/// # Example
+/// ```
+/// # use convertx::series::*;
+/// let out = convert("t,bytes\n0,2048\n", 0, 1, &|v| Ok(v / 1024.0)).unwrap();
+/// assert_eq!(out, "t,bytes\n0,2\n");
+/// ```
+pub fn convert(
+    input: &str,
+    x_col: usize,
+    y_col: usize,
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+) -> Result<String, String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let first_row = lines.iter().position(|line| is_data(line));
+    convert_lines(&lines, 0, first_row, x_col, y_col, convert)
+}
+
+/// Converts `input` from byte `offset`, the start of zero-based line `line`,
+/// in chunks of [`CHUNK_LINES`] lines.
+///
+/// Each converted chunk is passed to `on_chunk` with the offset and line number
+/// that follow it, which is where a later call can pick up.
+pub fn convert_from(
+    input: &str,
+    mut offset: usize,
+    mut line: usize,
+    x_col: usize,
+    y_col: usize,
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+    on_chunk: &mut dyn FnMut(&str, usize, usize) -> Result<(), String>,
+) -> Result<(), String> {
+    let first_row = input.lines().position(is_data);
+    while offset < input.len() {
+        let rest = input
+            .get(offset..)
+            .ok_or_else(|| format!("offset {} is not a line start of the input", offset))?;
+        let len: usize = rest
+            .split_inclusive('\n')
+            .take(CHUNK_LINES)
+            .map(str::len)
+            .sum();
+        let lines: Vec<&str> = rest[..len].lines().collect();
+        let converted = convert_lines(&lines, line, first_row, x_col, y_col, convert)?;
+        offset += len;
+        line += lines.len();
+        on_chunk(&converted, offset, line)?;
+    }
+    Ok(())
+}
+
+/// Converts `lines`, which start at zero-based line `first` of an input whose
+/// first data row is `first_row`.
+fn convert_lines(
+    lines: &[&str],
+    first: usize,
+    first_row: Option<usize>,
+    x_col: usize,
+    y_col: usize,
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+) -> Result<String, String> {
+    let convert_line = |(i, line): (usize, &&str)| {
+        let i = first + i;
+        if !is_data(line) {
+            return Ok(line.to_string());
+        }
+        let cells: Vec<&str> = line.trim().split(',').map(str::trim).collect();
+        let cell = |col: usize| {
+            cells
+                .get(col)
+                .copied()
+                .ok_or_else(|| format!("line {}: there is no column {}", i + 1, col))
+        };
+        let (x, y) = (cell(x_col)?, cell(y_col)?);
+        let header = Some(i) == first_row && y.parse::<f64>().is_err();
+        let y = if header {
+            y.to_string()
+        } else {
+            let value: f64 = y
+                .parse()
+                .map_err(|_| format!("line {}: '{}' is not a number", i + 1, y))?;
+            let converted = convert(value).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            converted.to_string()
+        };
+        Ok(format!("{},{}", x, y))
+    };
+    let rows: Vec<String> = if lines.len() > PARALLEL_LINES {
+        lines
+            .par_iter()
+            .enumerate()
+            .map(convert_line)
+            .collect::<Result<_, String>>()?
+    } else {
+        lines
+            .iter()
+            .enumerate()
+            .map(convert_line)
+            .collect::<Result<_, String>>()?
+    };
+    Ok(rows.iter().map(|row| format!("{}\n", row)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let input = "# bench\nt,x,bytes\n0, a, 1073741824\n\n1, b, 536870912\n";
+        let gib = &|v: f64| Ok(v / 1073741824.0);
+        assert_eq!(
+            convert(input, 0, 2, gib).unwrap(),
+            "# bench\nt,bytes\n0,1\n\n1,0.5\n"
+        );
+        assert_eq!(convert("1,2\n", 1, 0, &|v| Ok(v * 2.0)).unwrap(), "2,2\n");
+        assert_eq!(
+            convert("0,1\n1,x\n", 0, 1, gib),
+            Err("line 2: 'x' is not a number".to_string())
+        );
+        assert_eq!(
+            convert("0,1\n", 0, 3, gib),
+            Err("line 1: there is no column 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_input() {
+        let path = std::env::temp_dir().join("convertx_series_unit_input.csv");
+        std::fs::write(&path, "0,1\n").unwrap();
+        for mmap in [false, true] {
+            assert_eq!(Input::open(&path, mmap).unwrap().text(), Ok("0,1\n"));
+        }
+        std::fs::write(&path, b"0,\xff\n").unwrap();
+        assert!(Input::open(&path, true).unwrap().text().is_err());
+        assert!(Input::open(&path.with_extension("missing"), true).is_err());
+    }
+
+    #[test]
+    fn test_convert_in_parallel() {
+        let input: String = (0..PARALLEL_LINES * 3)
+            .map(|i| format!("{},{}\n", i, i * 1024))
+            .collect();
+        let expected: String = (0..PARALLEL_LINES * 3)
+            .map(|i| format!("{},{}\n", i, i))
+            .collect();
+        assert_eq!(
+            convert(&input, 0, 1, &|v| Ok(v / 1024.0)).unwrap(),
+            expected
+        );
+        let broken = input.replacen("7,7168", "7,x", 1);
+        assert_eq!(
+            convert(&broken, 0, 1, &|v| Ok(v)),
+            Err("line 8: 'x' is not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_from_resumes() {
+        let input: String = std::iter::once("t,v\n".to_string())
+            .chain((0..CHUNK_LINES * 2).map(|i| format!("{},{}\n", i, i * 2)))
+            .collect();
+        let half = &|v: f64| Ok(v / 2.0);
+        let mut output = String::new();
+        let mut stopped_at = (0, 0);
+        let interrupted = convert_from(&input, 0, 0, 0, 1, half, &mut |chunk, offset, line| {
+            output.push_str(chunk);
+            stopped_at = (offset, line);
+            Err("interrupted".to_string())
+        });
+        assert_eq!(interrupted, Err("interrupted".to_string()));
+        assert_eq!(stopped_at.1, CHUNK_LINES);
+        let (offset, line) = stopped_at;
+        convert_from(&input, offset, line, 0, 1, half, &mut |chunk, _, _| {
+            output.push_str(chunk);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(output, convert(&input, 0, 1, half).unwrap());
+        assert!(convert_from("é\n", 1, 0, 0, 1, half, &mut |_, _, _| Ok(())).is_err());
+    }
+}
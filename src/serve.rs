@@ -0,0 +1,480 @@
+//! A small HTTP/1.1 server for `convertx serve`.
+//!
+//! Only what a JSON API and a webhook need is implemented: the request line,
+//! headers, a query string and a body of known length. Each connection is handled on its own thread,
+//! up to a limit at a time, and closed after one response, so no HTTP library is needed. [`Limits`]
+//! keep a client from tying the server up, and every response is recorded in
+//! the server's [`Metrics`].
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::json::Value;
+use crate::metrics::Metrics;
+
+/// The page served at `/`: a form that converts through `/convert`, so the
+/// server can be used from a browser.
+pub const INDEX: &str = include_str!("serve/index.html");
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// The path without its query string, e.g. `/convert`.
+    pub path: String,
+    /// The decoded query parameters, in order.
+    pub query: Vec<(String, String)>,
+    /// The headers, with lowercase names, in order.
+    pub headers: Vec<(String, String)>,
+    /// The body of `Content-Length` bytes; empty if there is none.
+    pub body: String,
+}
+
+impl Request {
+    /// The first value of the query parameter `name`, if given.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every value of the query parameter `name`, in order.
+    pub fn params<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.query
+            .iter()
+            .filter(move |(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value of the header `name`, in lowercase, if sent.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The fields of a form sent as the body, decoded as a query string is.
+    pub fn form(&self) -> Vec<(String, String)> {
+        parse_query(&self.body)
+    }
+}
+
+/// An HTTP response with a text body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    /// Headers besides the content type and length, e.g. `Retry-After`.
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl Response {
+    /// A `200 OK` response carrying JSON.
+    pub fn json(body: String) -> Response {
+        Response::with_status(200, body)
+    }
+
+    /// A JSON response with `status`.
+    pub fn with_status(status: u16, body: String) -> Response {
+        Response {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// A JSON response with `status` whose body is `{"error": message}`.
+    pub fn error(status: u16, message: &str) -> Response {
+        let body = Value::Object(vec![("error", Value::Text(message.to_string()))]);
+        Response::with_status(status, body.to_string())
+    }
+
+    /// Writes the response, closing the connection after it.
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Content Too Large",
+            429 => "Too Many Requests",
+            _ => "Error",
+        };
+        write!(out, "HTTP/1.1 {} {}\r\n", self.status, reason)?;
+        write!(out, "Content-Type: {}\r\n", self.content_type)?;
+        write!(out, "Content-Length: {}\r\n", self.body.len())?;
+        for (name, value) in &self.headers {
+            write!(out, "{}: {}\r\n", name, value)?;
+        }
+        write!(out, "Connection: close\r\n\r\n{}", self.body)?;
+        out.flush()
+    }
+}
+
+/// Decodes `%XX` escapes and `+` for spaces, as in a query string.
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = match (byte, tail) {
+            (b'%', [high, low, ..]) => std::str::from_utf8(&[*high, *low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match (escaped, byte) {
+            (Some(decoded), _) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+                continue;
+            }
+            (None, b'+') => bytes.push(b' '),
+            (None, byte) => bytes.push(byte),
+        }
+        rest = tail;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Decodes the `key=value` pairs of a query string or form, in order.
+fn parse_query(text: &str) -> Vec<(String, String)> {
+    text.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Reads a request line, its headers and its body from `input`.
+///
+/// # Example
+/// ```
+/// # use convertx::serve::*;
+/// let request = read_request(&mut "GET /convert?value=5&from=km HTTP/1.1\r\n\r\n".as_bytes())?;
+/// assert_eq!(request.param("from"), Some("km"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn read_request(input: &mut impl BufRead) -> io::Result<Request> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "malformed request line",
+        ));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .map_or(Ok(0), |(_, length)| length.parse::<u64>())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "malformed Content-Length"))?;
+    // The body grows as it arrives rather than being allocated up front, so
+    // a client cannot make the server reserve whatever length it claims.
+    let mut body = Vec::new();
+    if input.by_ref().take(length).read_to_end(&mut body)? as u64 != length {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "body shorter than its Content-Length",
+        ));
+    }
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Ok(Request {
+        method,
+        path: percent_decode(path),
+        query: parse_query(query),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// What a client may ask of the server.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Requests each client address may make a minute; 0 for no limit.
+    pub requests_per_minute: u32,
+    /// The longest request line, headers and body, in bytes.
+    pub max_request_bytes: usize,
+    /// How long reading the request or writing the response may take.
+    pub timeout: Duration,
+    /// Connections handled at once, further ones waiting to be accepted; 0 for
+    /// no limit.
+    pub max_connections: usize,
+}
+
+/// A token bucket per client address, holding up to a minute's requests and
+/// refilled continuously.
+struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    /// Takes a token for a request from `client` at `now`, or returns how long
+    /// until one is available.
+    fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = f64::from(self.per_minute);
+        let per_second = capacity / 60.0;
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("the rate limiter is not poisoned");
+        if buckets.len() > 10_000 {
+            // Forget clients whose buckets have refilled, to bound the memory.
+            buckets.retain(|_, (tokens, at)| {
+                *tokens + now.duration_since(*at).as_secs_f64() * per_second < capacity
+            });
+        }
+        let (tokens, at) = buckets.entry(client).or_insert((capacity, now));
+        *tokens = (*tokens + now.duration_since(*at).as_secs_f64() * per_second).min(capacity);
+        *at = now;
+        if *tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - *tokens) / per_second));
+        }
+        *tokens -= 1.0;
+        Ok(())
+    }
+}
+
+/// The connections that may still be handled at once.
+struct Slots {
+    free: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl Slots {
+    /// Waits for a free slot and takes it until the returned [`Slot`] is dropped.
+    fn take(self: &Arc<Self>) -> Slot {
+        let free = self.free.lock().expect("the slots are not poisoned");
+        let mut free = self
+            .freed
+            .wait_while(free, |free| *free == 0)
+            .expect("the slots are not poisoned");
+        *free -= 1;
+        Slot(Arc::clone(self))
+    }
+}
+
+/// A slot taken from [`Slots`], freed when dropped.
+struct Slot(Arc<Slots>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        *self.0.free.lock().expect("the slots are not poisoned") += 1;
+        self.0.freed.notify_one();
+    }
+}
+
+/// Answers one request on `stream` with `handler`, within `limits`, and
+/// records the response in `metrics`. A client over its rate limit is turned
+/// away before its request is read.
+fn respond<H: Fn(&Request) -> Response>(
+    stream: TcpStream,
+    limits: &Limits,
+    limiter: &RateLimiter,
+    metrics: &Metrics,
+    handler: &H,
+) -> io::Result<()> {
+    let started = Instant::now();
+    stream.set_read_timeout(Some(limits.timeout))?;
+    stream.set_write_timeout(Some(limits.timeout))?;
+    let (path, response) = match limiter.check(stream.peer_addr()?.ip(), started) {
+        Err(wait) => (
+            String::new(),
+            Response {
+                headers: vec![(
+                    "Retry-After",
+                    wait.as_secs_f64().ceil().max(1.0).to_string(),
+                )],
+                ..Response::error(429, "too many requests, try again later")
+            },
+        ),
+        Ok(()) => {
+            let mut reader =
+                BufReader::new(stream.try_clone()?.take(limits.max_request_bytes as u64));
+            let request = read_request(&mut reader);
+            let path = request
+                .as_ref()
+                .map_or(String::new(), |request| request.path.clone());
+            let response = match request {
+                _ if reader.get_ref().limit() == 0 => Response::error(
+                    413,
+                    &format!("requests are limited to {} bytes", limits.max_request_bytes),
+                ),
+                Ok(request) => handler(&request),
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    Response::error(408, "timed out reading the request")
+                }
+                Err(error) => Response::error(400, &format!("cannot read the request: {}", error)),
+            };
+            (path, response)
+        }
+    };
+    let written = response.write_to(&mut &stream);
+    metrics.request(&path, response.status, started.elapsed());
+    written?;
+    // Closing with part of the request unread resets the connection, which
+    // can discard the response, so the rest is read and dropped first.
+    stream.shutdown(Shutdown::Write)?;
+    let _ = io::copy(&mut (&stream).take(1 << 16), &mut io::sink());
+    Ok(())
+}
+
+/// Accepts connections on `listener` until it fails, answering each request
+/// with `handler` on a thread of its own, within `limits`, and recording the
+/// responses in `metrics`. Once `limits.max_connections` are being handled,
+/// the next is only accepted when one of them closes.
+pub fn run<H>(
+    listener: TcpListener,
+    limits: Limits,
+    metrics: Arc<Metrics>,
+    handler: H,
+) -> io::Result<()>
+where
+    H: Fn(&Request) -> Response + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    let limiter = Arc::new(RateLimiter {
+        per_minute: limits.requests_per_minute,
+        buckets: Mutex::new(HashMap::new()),
+    });
+    let slots = Arc::new(Slots {
+        free: Mutex::new(match limits.max_connections {
+            0 => usize::MAX,
+            max => max,
+        }),
+        freed: Condvar::new(),
+    });
+    loop {
+        let slot = slots.take();
+        let (stream, _) = listener.accept()?;
+        let (handler, limiter) = (Arc::clone(&handler), Arc::clone(&limiter));
+        let metrics = Arc::clone(&metrics);
+        thread::spawn(move || {
+            let _slot = slot;
+            respond(stream, &limits, &limiter, &metrics, &*handler)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let mut input =
+            "GET /convert?value=3.5%20lb&to=kg&category= HTTP/1.1\r\nHost: x\r\n\r\n".as_bytes();
+        let request = read_request(&mut input).unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("GET", "/convert")
+        );
+        assert_eq!(request.param("value"), Some("3.5 lb"));
+        assert_eq!(request.param("to"), Some("kg"));
+        assert_eq!(request.param("category"), Some(""));
+        assert_eq!(request.param("from"), None);
+        assert_eq!(request.header("host"), Some("x"));
+        assert_eq!(request.body, "");
+        assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+        let mut input = "POST / HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: 22\r\n\r\ntext=5+km+to+mi&user=a".as_bytes();
+        let request = read_request(&mut input).unwrap();
+        assert_eq!(
+            request.header("content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(
+            request.form()[0],
+            ("text".to_string(), "5 km to mi".to_string())
+        );
+        // A body shorter than its Content-Length is an error.
+        let mut input = "POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n{}".as_bytes();
+        assert!(read_request(&mut input).is_err());
+        // So is one claiming more than could be allocated, without trying to.
+        let mut input = "POST / HTTP/1.1\r\nContent-Length: 100000000000000\r\n\r\n{}".as_bytes();
+        assert!(read_request(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("kg*m%2Fs%5E2"), "kg*m/s^2");
+        assert_eq!(percent_decode("100%C2%B0F+x"), "100°F x");
+        assert_eq!(percent_decode("50%"), "50%");
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter {
+            per_minute: 2,
+            buckets: Mutex::new(HashMap::new()),
+        };
+        let (client, other) = ([10, 0, 0, 1].into(), [10, 0, 0, 2].into());
+        let start = Instant::now();
+        assert_eq!(limiter.check(client, start), Ok(()));
+        assert_eq!(limiter.check(client, start), Ok(()));
+        assert_eq!(limiter.check(client, start), Err(Duration::from_secs(30)));
+        assert_eq!(limiter.check(other, start), Ok(()));
+        assert_eq!(
+            limiter.check(client, start + Duration::from_secs(30)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_slots() {
+        let slots = Arc::new(Slots {
+            free: Mutex::new(1),
+            freed: Condvar::new(),
+        });
+        let slot = slots.take();
+        let waiting = {
+            let slots = Arc::clone(&slots);
+            thread::spawn(move || drop(slots.take()))
+        };
+        assert!(!waiting.is_finished());
+        drop(slot);
+        waiting.join().unwrap();
+        assert_eq!(*slots.free.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        Response::json("{}".to_string()).write_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+        );
+    }
+}
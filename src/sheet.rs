@@ -0,0 +1,191 @@
+//! Parquet column conversion for `convertx sheet`.
+//!
+//! The file is streamed in record batches of a fixed number of rows, so only
+//! one batch is held in memory at a time. The converted column is written as
+//! nullable `Float64`; values that cannot be converted become nulls. Large
+//! batches are converted on the rayon thread pool.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use rayon::prelude::*;
+
+use crate::error::ConvertError;
+
+/// Batches with more rows than this are converted in parallel.
+const PARALLEL_ROWS: usize = 10_000;
+
+/// Converts `column` of the Parquet file `input` with `convert`, writing every
+/// column to `output`. Returns the number of rows written.
+///
+/// The rows are written next to `output` and renamed over it once all are
+/// converted, so `output` may be `input` itself, and a failure leaves it as
+/// it was.
+pub fn convert_file(
+    input: &Path,
+    output: &Path,
+    column: &str,
+    batch_rows: usize,
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+) -> Result<usize, String> {
+    let file =
+        File::open(input).map_err(|e| format!("could not read {}: {}", input.display(), e))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| format!("{}: {}", input.display(), e))?
+        .with_batch_size(batch_rows.max(1));
+    let schema = builder.schema().clone();
+    let index = schema
+        .index_of(column)
+        .map_err(|_| format!("{} has no column '{}'", input.display(), column))?;
+    let data_type = schema.field(index).data_type();
+    if !data_type.is_numeric() {
+        return Err(format!("column '{}' is {}, not numeric", column, data_type));
+    }
+    let schema = converted_schema(&schema, index);
+    let reader = builder.build().map_err(|e| e.to_string())?;
+    let partial = output.with_extension("partial");
+    let file = File::create(&partial)
+        .map_err(|e| format!("could not write {}: {}", output.display(), e))?;
+    let written = (|| {
+        let mut writer =
+            ArrowWriter::try_new(file, schema.clone(), None).map_err(|e| e.to_string())?;
+        let mut rows = 0;
+        for batch in reader {
+            let batch = batch.map_err(|e| e.to_string())?;
+            let batch = convert_batch(&batch, index, &schema, convert)?;
+            rows += batch.num_rows();
+            writer.write(&batch).map_err(|e| e.to_string())?;
+        }
+        writer.close().map_err(|e| e.to_string())?;
+        std::fs::rename(&partial, output)
+            .map_err(|e| format!("could not write {}: {}", output.display(), e))?;
+        Ok(rows)
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written
+}
+
+/// Returns `schema` with the field at `index` changed to nullable `Float64`.
+fn converted_schema(schema: &SchemaRef, index: usize) -> SchemaRef {
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[index] = Field::new(fields[index].name(), DataType::Float64, true);
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Converts the column at `index` of one record batch.
+fn convert_batch(
+    batch: &RecordBatch,
+    index: usize,
+    schema: &SchemaRef,
+    convert: &(dyn Fn(f64) -> Result<f64, ConvertError> + Sync),
+) -> Result<RecordBatch, String> {
+    let values =
+        arrow_cast::cast(batch.column(index), &DataType::Float64).map_err(|e| e.to_string())?;
+    let values = values
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .expect("cast to Float64 yields a Float64Array");
+    let converted: Float64Array = if values.len() > PARALLEL_ROWS {
+        let converted: Vec<Option<f64>> = (0..values.len())
+            .into_par_iter()
+            .map(|i| {
+                values
+                    .is_valid(i)
+                    .then(|| values.value(i))
+                    .and_then(|v| convert(v).ok())
+            })
+            .collect();
+        converted.into()
+    } else {
+        values
+            .iter()
+            .map(|v| v.and_then(|v| convert(v).ok()))
+            .collect()
+    };
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns[index] = Arc::new(converted);
+    RecordBatch::try_new(schema.clone(), columns).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int64Array, StringArray};
+
+    #[test]
+    fn test_convert_file() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("convertx_sheet_unit_input.parquet");
+        let output = dir.join("convertx_sheet_unit_output.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("city", DataType::Utf8, false),
+            Field::new("temp_f", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                Arc::new(Int64Array::from(vec![Some(32), None, Some(212)])),
+            ],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&input).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let to_c = |f: f64| Ok((f - 32.0) * 5.0 / 9.0);
+        assert_eq!(convert_file(&input, &output, "temp_f", 2, &to_c), Ok(3));
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&output).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let temps: Vec<Option<f64>> = reader
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                let column = batch
+                    .column(1)
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .unwrap();
+                column.iter().collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(temps, vec![Some(0.0), None, Some(100.0)]);
+        assert!(convert_file(&input, &output, "temp_k", 2, &to_c).is_err());
+        assert!(convert_file(&input, &output, "city", 2, &to_c).is_err());
+
+        // A file converted onto itself is read whole before it is replaced.
+        assert_eq!(convert_file(&output, &output, "temp_f", 2, &to_c), Ok(3));
+        assert!(!output.with_extension("partial").exists());
+    }
+
+    #[test]
+    fn test_convert_batch_in_parallel() {
+        let rows = PARALLEL_ROWS as i64 * 3;
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from_iter(
+                (0..rows).map(|n| (n != 7).then_some(n)),
+            ))],
+        )
+        .unwrap();
+        let schema = converted_schema(&schema, 0);
+        let converted = convert_batch(&batch, 0, &schema, &|n| Ok(n * 2.0)).unwrap();
+        let column = converted
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(column.len(), rows as usize);
+        assert_eq!(column.value(rows as usize - 1), (rows - 1) as f64 * 2.0);
+        assert!(column.is_null(7));
+    }
+}
@@ -0,0 +1,125 @@
+//! SQL statements for `--output sql`.
+//!
+//! Each conversion becomes one `INSERT` row. Table names are restricted to
+//! plain identifiers, optionally schema-qualified, so they never need quoting.
+//!
+//! Every numeric column holds a number or `NULL`: `value` and `result` are
+//! exact amounts or the nominal value of a tolerance, and the `_min` and
+//! `_max` columns the limits of tolerances and ranges.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Columns written for every conversion, in order.
+pub const COLUMNS: &[&str] = &[
+    "category",
+    "value",
+    "from_unit",
+    "to_unit",
+    "result",
+    "value_min",
+    "value_max",
+    "result_min",
+    "result_max",
+];
+
+/// A table name such as `conversions` or `metrics.conversions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table(String);
+
+impl FromStr for Table {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let identifier = |part: &str| {
+            part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        if s.split('.').all(identifier) {
+            Ok(Table(s.to_string()))
+        } else {
+            Err(format!(
+                "'{}' is not a table name; use letters, digits and underscores",
+                s
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A value in an `INSERT` row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A number, written unrounded; `NULL` if it is not finite.
+    Number(f64),
+    /// Text, written as a quoted string literal.
+    Text(String),
+    /// No value, e.g. for a number that is not finite.
+    Null,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) if number.is_finite() => write!(f, "{}", number),
+            Value::Number(_) => write!(f, "NULL"),
+            Value::Text(text) => write!(f, "'{}'", text.replace('\'', "''")),
+            Value::Null => write!(f, "NULL"),
+        }
+    }
+}
+
+/// Returns an `INSERT` statement adding one row of `values` for [`COLUMNS`].
+///
+/// # Example
+/// ```
+/// # use convertx::sql::*;
+/// let table: Table = "conversions".parse().unwrap();
+/// let row = [Value::Text("mass".into()), Value::Number(2.0)];
+/// assert!(insert(&table, &row).ends_with("VALUES ('mass', 2);"));
+/// ```
+pub fn insert(table: &Table, values: &[Value]) -> String {
+    let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({});",
+        table,
+        COLUMNS.join(", "),
+        values.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        assert!("conversions".parse::<Table>().is_ok());
+        assert!("metrics.unit_conversions_2".parse::<Table>().is_ok());
+        assert!("conversions; DROP TABLE x".parse::<Table>().is_err());
+        assert!("2fast".parse::<Table>().is_err());
+        assert!("".parse::<Table>().is_err());
+    }
+
+    #[test]
+    fn test_insert() {
+        let table: Table = "conversions".parse().unwrap();
+        let row = [
+            Value::Text("length".to_string()),
+            Value::Number(0.001),
+            Value::Text("o'clock".to_string()),
+            Value::Null,
+            Value::Number(f64::INFINITY),
+        ];
+        assert_eq!(
+            insert(&table, &row),
+            "INSERT INTO conversions (category, value, from_unit, to_unit, result, \
+             value_min, value_max, result_min, result_max) \
+             VALUES ('length', 0.001, 'o''clock', NULL, NULL);"
+        );
+    }
+}
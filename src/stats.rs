@@ -0,0 +1,127 @@
+//! Summary statistics for `convertx stats`.
+//!
+//! Percentiles use the nearest-rank method, so every reported value is one
+//! that was actually read; the median of an even count is the mean of the two
+//! middle values. Histograms split the range into equal-width buckets.
+
+/// The summary of a set of values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// Returns the `p`th percentile of `sorted` by the nearest-rank method.
+///
+/// # Example
+/// ```
+/// # use convertx::stats::*;
+/// assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0], 50.0), 2.0);
+/// ```
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Summarizes `values`, or returns `None` if there are none.
+pub fn summarize(values: &[f64]) -> Option<Summary> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    let median = if n.is_multiple_of(2) {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    } else {
+        sorted[n / 2]
+    };
+    Some(Summary {
+        count: n,
+        min: sorted[0],
+        median,
+        p95: percentile(&sorted, 95.0),
+        max: sorted[n - 1],
+    })
+}
+
+/// A histogram bucket covering `low..high`; the last one includes `high`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub low: f64,
+    pub high: f64,
+    pub count: usize,
+}
+
+/// Counts `values` into `buckets` equal-width buckets from the smallest value
+/// to the largest. Equal values all land in a single bucket.
+///
+/// # Example
+/// ```
+/// # use convertx::stats::*;
+/// let counts: Vec<usize> = histogram(&[1.0, 1.5, 4.0], 3).iter().map(|b| b.count).collect();
+/// assert_eq!(counts, vec![2, 0, 1]);
+/// ```
+pub fn histogram(values: &[f64], buckets: usize) -> Vec<Bucket> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if values.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    if min == max {
+        return vec![Bucket {
+            low: min,
+            high: max,
+            count: values.len(),
+        }];
+    }
+    let width = (max - min) / buckets as f64;
+    let mut result: Vec<Bucket> = (0..buckets)
+        .map(|i| Bucket {
+            low: min + width * i as f64,
+            high: min + width * (i + 1) as f64,
+            count: 0,
+        })
+        .collect();
+    for value in values {
+        let i = (((value - min) / width) as usize).min(buckets - 1);
+        result[i].count += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let values: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+        let summary = summarize(&values).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                count: 20,
+                min: 1.0,
+                median: 10.5,
+                p95: 19.0,
+                max: 20.0,
+            }
+        );
+        assert_eq!(summarize(&[3.0]).unwrap().p95, 3.0);
+        assert!(summarize(&[]).is_none());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let buckets = histogram(&[0.0, 1.0, 2.5, 9.0, 10.0], 4);
+        let counts: Vec<usize> = buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 1, 0, 2]);
+        assert_eq!((buckets[1].low, buckets[1].high), (2.5, 5.0));
+        assert_eq!(histogram(&[7.0, 7.0], 5).len(), 1);
+        assert!(histogram(&[], 5).is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+//! Tables of results for `--format markdown` and `--format html`.
+//!
+//! A [`Table`] holds rows of cells that are already formatted, so every
+//! subcommand that lists several results renders them the same way, ready to
+//! paste into documentation or a wiki.
+
+use std::fmt::Write;
+
+/// How the cells of a column are aligned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    /// For numbers, so their digits line up.
+    Right,
+}
+
+/// A table with a header row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    columns: Vec<(String, Align)>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Escapes `text` for HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Table {
+    /// Creates a table with `columns`, each a heading and its alignment.
+    pub fn new(columns: &[(&str, Align)]) -> Self {
+        Table {
+            columns: columns
+                .iter()
+                .map(|(heading, align)| (heading.to_string(), *align))
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row with one cell for each column; missing cells are empty
+    /// and extra ones dropped.
+    pub fn push(&mut self, mut row: Vec<String>) {
+        row.resize(self.columns.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Renders the table in Markdown, with its columns padded to line up.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::table::*;
+    /// let mut table = Table::new(&[("Value", Align::Right), ("Unit", Align::Left)]);
+    /// table.push(vec!["5".into(), "km".into()]);
+    /// assert_eq!(table.markdown(), "| Value | Unit |\n|------:|:-----|\n|     5 | km   |\n");
+    /// ```
+    pub fn markdown(&self) -> String {
+        let escape = |cell: &str| cell.replace('|', "\\|");
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(heading, _)| escape(heading))
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| escape(cell)).collect())
+            .collect();
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|i| {
+                std::iter::once(&header)
+                    .chain(&rows)
+                    .map(|row| row[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    // The separator needs room for its colon and a dash.
+                    .max(2)
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            let mut line = String::from("|");
+            for ((cell, width), (_, align)) in cells.iter().zip(&widths).zip(&self.columns) {
+                let _ = match align {
+                    Align::Left => write!(line, " {:<1$} |", cell, width),
+                    Align::Right => write!(line, " {:>1$} |", cell, width),
+                };
+            }
+            line + "\n"
+        };
+        let mut out = line(&header);
+        out.push('|');
+        for (width, (_, align)) in widths.iter().zip(&self.columns) {
+            let dashes = "-".repeat(*width);
+            let _ = match align {
+                Align::Left => write!(out, ":{}-|", dashes),
+                Align::Right => write!(out, "-{}:|", dashes),
+            };
+        }
+        out.push('\n');
+        for row in &rows {
+            out.push_str(&line(row));
+        }
+        out
+    }
+
+    /// Renders the table as an HTML `<table>`.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::table::*;
+    /// let mut table = Table::new(&[("Unit", Align::Left)]);
+    /// table.push(vec!["°C".into()]);
+    /// assert!(table.html().contains("<td>°C</td>"));
+    /// ```
+    pub fn html(&self) -> String {
+        let cell = |tag: &str, text: &str, align: Align| match align {
+            Align::Left => format!("<{}>{}</{}>", tag, escape_html(text), tag),
+            Align::Right => format!(
+                "<{} style=\"text-align: right\">{}</{}>",
+                tag,
+                escape_html(text),
+                tag
+            ),
+        };
+        let mut out = String::from("<table>\n  <thead>\n    <tr>");
+        for (heading, align) in &self.columns {
+            out.push_str(&cell("th", heading, *align));
+        }
+        out.push_str("</tr>\n  </thead>\n  <tbody>\n");
+        for row in &self.rows {
+            out.push_str("    <tr>");
+            for (text, (_, align)) in row.iter().zip(&self.columns) {
+                out.push_str(&cell("td", text, *align));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("  </tbody>\n</table>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(&[
+            ("Value", Align::Right),
+            ("From", Align::Left),
+            ("Result", Align::Right),
+            ("To", Align::Left),
+        ]);
+        table.push(vec![
+            "5.0000".into(),
+            "kilometers".into(),
+            "16404.2000".into(),
+            "feet".into(),
+        ]);
+        table.push(vec!["1".into(), "a|b".into()]);
+        table
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            table().markdown(),
+            "\
+|  Value | From       |     Result | To   |
+|-------:|:-----------|-----------:|:-----|
+| 5.0000 | kilometers | 16404.2000 | feet |
+|      1 | a\\|b       |            |      |
+"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let html = table().html();
+        assert!(html.starts_with(
+            "<table>\n  <thead>\n    <tr><th style=\"text-align: right\">Value</th><th>From</th>"
+        ));
+        assert!(html.contains(
+            "    <tr><td style=\"text-align: right\">1</td><td>a|b</td><td style=\"text-align: right\"></td><td></td></tr>\n"
+        ));
+        assert!(html.ends_with("  </tbody>\n</table>\n"));
+        let mut table = Table::new(&[("Unit", Align::Left)]);
+        table.push(vec!["<b>&".into()]);
+        assert!(table.html().contains("<td>&lt;b&gt;&amp;</td>"));
+    }
+}
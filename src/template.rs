@@ -0,0 +1,158 @@
+//! A tiny template engine for `--format` output strings.
+//!
+//! Templates mix literal text with `{field}` or `{field:.N}` placeholders, where
+//! `N` overrides the number of decimal places. `{{` and `}}` produce literal braces.
+
+use std::str::FromStr;
+
+/// Placeholder names accepted in templates.
+pub const FIELDS: &[&str] = &[
+    "value",
+    "from",
+    "to",
+    "from_symbol",
+    "to_symbol",
+    "result",
+    "category",
+];
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field {
+        name: String,
+        precision: Option<usize>,
+    },
+}
+
+/// A parsed output template.
+///
+/// # Example
+/// ```
+/// # use convertx::template::*;
+/// let template: Template = "{value} {from} is {result:.3} {to}".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Renders the template, resolving each placeholder with `field(name, precision)`.
+    pub fn render(&self, mut field: impl FnMut(&str, Option<usize>) -> String) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field { name, precision } => out.push_str(&field(name, *precision)),
+            }
+        }
+        out
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        spec.push(c);
+                    }
+                    if !closed {
+                        return Err(format!("unclosed placeholder '{{{}'", spec));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(parse_field(&spec)?);
+                }
+                '}' => return Err("unmatched '}' (use '}}' for a literal brace)".to_string()),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template { segments })
+    }
+}
+
+/// Parses the inside of a placeholder, e.g. `result:.3`.
+fn parse_field(spec: &str) -> Result<Segment, String> {
+    let (name, format) = match spec.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (spec.trim(), None),
+    };
+    if !FIELDS.contains(&name) {
+        return Err(format!(
+            "unknown placeholder '{{{}}}', expected one of: {}",
+            name,
+            FIELDS.join(", ")
+        ));
+    }
+    let precision = match format {
+        None => None,
+        Some(format) => Some(
+            format
+                .strip_prefix('.')
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| {
+                    format!(
+                        "invalid format '{}' for '{}', expected e.g. '.3'",
+                        format, name
+                    )
+                })?,
+        ),
+    };
+    Ok(Segment::Field {
+        name: name.to_string(),
+        precision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str) -> String {
+        let template: Template = template.parse().unwrap();
+        template.render(|name, precision| format!("<{}:{:?}>", name, precision))
+    }
+
+    #[test]
+    fn test_render_fields_and_literals() {
+        assert_eq!(
+            render("{value} {from} is {result:.3}!"),
+            "<value:None> <from:None> is <result:Some(3)>!"
+        );
+        assert_eq!(render("{{literal}} {to}"), "{literal} <to:None>");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("{missing".parse::<Template>().is_err());
+        assert!("stray }".parse::<Template>().is_err());
+        assert!("{bogus}".parse::<Template>().is_err());
+        assert!("{result:3}".parse::<Template>().is_err());
+    }
+}
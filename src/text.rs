@@ -0,0 +1,54 @@
+//! Typing speed and text metrics for `convertx text`.
+//!
+//! Typing speeds use the standard five characters per word, and reading time
+//! assumes an average silent reading speed unless one is given.
+
+/// Characters in a standard typing word.
+pub const CHARS_PER_WORD: f64 = 5.0;
+/// Average silent reading speed of adults, in words per minute.
+pub const READING_WPM: f64 = 238.0;
+
+/// Converts a typing speed in words per minute to characters per minute.
+///
+/// # Example
+/// ```
+/// # use convertx::text::*;
+/// assert_eq!(chars_per_minute(80.0), 400.0);
+/// ```
+pub fn chars_per_minute(wpm: f64) -> f64 {
+    wpm * CHARS_PER_WORD
+}
+
+/// Converts a typing speed in words per minute to words per hour.
+pub fn words_per_hour(wpm: f64) -> f64 {
+    wpm * 60.0
+}
+
+/// Returns the whole seconds needed to read `words` at `wpm` words per minute.
+///
+/// # Example
+/// ```
+/// # use convertx::text::*;
+/// assert_eq!(reading_seconds(500, 250.0), 120);
+/// ```
+pub fn reading_seconds(words: u64, wpm: f64) -> u64 {
+    (words as f64 / wpm * 60.0).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typing_speeds() {
+        assert_eq!(chars_per_minute(80.0), 400.0);
+        assert_eq!(words_per_hour(80.0), 4800.0);
+    }
+
+    #[test]
+    fn test_reading_seconds() {
+        assert_eq!(reading_seconds(500, 250.0), 120);
+        assert_eq!(reading_seconds(5000, READING_WPM), 1261);
+        assert_eq!(reading_seconds(0, READING_WPM), 0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Metric tire sizes for `convertx tire`.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A metric tire size such as `225/45R17`: section width in millimeters,
+/// aspect ratio in percent of the width, and rim diameter in inches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TireSize {
+    pub width_mm: f64,
+    pub aspect: f64,
+    pub rim_in: f64,
+}
+
+impl TireSize {
+    /// Returns the overall diameter in millimeters.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::tire::*;
+    /// let tire: TireSize = "225/45R17".parse().unwrap();
+    /// assert!((tire.diameter_mm() - 634.3).abs() < 1e-9);
+    /// ```
+    pub fn diameter_mm(&self) -> f64 {
+        2.0 * self.width_mm * self.aspect / 100.0 + self.rim_in * 25.4
+    }
+
+    /// Returns the rolling circumference in millimeters.
+    pub fn circumference_mm(&self) -> f64 {
+        std::f64::consts::PI * self.diameter_mm()
+    }
+
+    /// Returns how much faster than indicated a car calibrated for `self`
+    /// travels on `other`, in percent.
+    pub fn speedometer_error(&self, other: &TireSize) -> f64 {
+        (other.diameter_mm() / self.diameter_mm() - 1.0) * 100.0
+    }
+}
+
+impl FromStr for TireSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a tire size like 225/45R17", s);
+        let upper = s.trim().to_ascii_uppercase();
+        // Drop a passenger/light-truck prefix such as the "P" in P225/45R17.
+        let size = upper.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let (width, rest) = size.split_once('/').ok_or_else(invalid)?;
+        // The construction letters (R, ZR, D, B) separate aspect ratio and rim.
+        let (aspect, rim) = rest
+            .split_once(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(invalid)?;
+        let rim = rim.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let number = |part: &str| {
+            part.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|n| *n > 0.0)
+                .ok_or_else(invalid)
+        };
+        Ok(TireSize {
+            width_mm: number(width)?,
+            aspect: number(aspect)?,
+            rim_in: number(rim)?,
+        })
+    }
+}
+
+impl fmt::Display for TireSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}R{}", self.width_mm, self.aspect, self.rim_in)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tire_size() {
+        let tire: TireSize = "225/45R17".parse().unwrap();
+        assert_eq!(
+            (tire.width_mm, tire.aspect, tire.rim_in),
+            (225.0, 45.0, 17.0)
+        );
+        assert_eq!("P215/55ZR17".parse::<TireSize>().unwrap().aspect, 55.0);
+        assert_eq!(tire.to_string(), "225/45R17");
+        assert!("225-45-17".parse::<TireSize>().is_err());
+        assert!("225/R17".parse::<TireSize>().is_err());
+    }
+
+    #[test]
+    fn test_speedometer_error() {
+        let stock: TireSize = "225/45R17".parse().unwrap();
+        let other: TireSize = "215/55R17".parse().unwrap();
+        assert!((other.diameter_mm() - 668.3).abs() < 1e-9);
+        assert!((stock.speedometer_error(&other) - 5.3602).abs() < 1e-4);
+        assert_eq!(stock.speedometer_error(&stock), 0.0);
+    }
+}
@@ -0,0 +1,148 @@
+//! The [`Unit`] trait behind the unit-to-unit conversion categories.
+//!
+//! Every unit converts values to and from the base unit of its [`dimension`],
+//! so any two units of one dimension convert through the base. Unit enums
+//! implement the trait with the `unit_conversions!` macro.
+
+use crate::error::ConvertError;
+use crate::quantity::check_finite;
+
+/// A unit of measurement that converts through its dimension's base unit.
+pub trait Unit: Clone {
+    /// The dimension measured, which fixes the base unit.
+    type Dimension;
+    /// Converts `value` in this unit to the base unit.
+    fn to_base(&self, value: f64) -> f64;
+    /// Converts `value` in the base unit to this unit.
+    // Named to pair with `to_base`; it converts a value, not `self`.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_base(&self, value: f64) -> f64;
+}
+
+/// Marker types for the dimensions units measure, with their base units.
+pub mod dimension {
+    /// Base unit: meters.
+    pub struct Length;
+    /// Base unit: degrees Celsius.
+    pub struct Temperature;
+    /// Base unit: kilograms.
+    pub struct Mass;
+    /// Base unit: bits per second.
+    pub struct DataRate;
+    /// Base unit: square meters.
+    pub struct Area;
+    /// Base unit: liters.
+    pub struct Volume;
+    /// Base unit: meters per second.
+    pub struct Speed;
+    /// Base unit: pascals.
+    pub struct Pressure;
+    /// Base unit: items.
+    pub struct Count;
+    /// Base unit: ppm of calcium carbonate.
+    pub struct Hardness;
+    /// Base unit: bits.
+    pub struct Information;
+    /// Base unit: hashes per second.
+    pub struct Hashrate;
+}
+
+/// A value together with its unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity<U> {
+    pub value: f64,
+    pub unit: U,
+}
+
+impl<U: Unit> Quantity<U> {
+    pub fn new(value: f64, unit: U) -> Self {
+        Quantity { value, unit }
+    }
+
+    /// Converts to `unit`, which may be of any unit type of the same dimension.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::unit::*;
+    /// # use convertx::units::LengthUnit;
+    /// let km = Quantity::new(1500.0, LengthUnit::Meters).to(LengthUnit::Kilometers);
+    /// assert_eq!(km.value, 1.5);
+    /// ```
+    pub fn to<V: Unit<Dimension = U::Dimension>>(&self, unit: V) -> Quantity<V> {
+        let value = unit.from_base(self.unit.to_base(self.value));
+        Quantity { value, unit }
+    }
+}
+
+/// Converts `value` from one unit to another of the same type.
+///
+/// Fails with [`ConvertError::OutOfRange`] if a finite value overflows, and
+/// with [`ConvertError::NonFinite`] for NaN or infinities unless allowed.
+pub fn convert_units<U: Unit>(value: f64, from: U, to: U) -> Result<f64, ConvertError> {
+    let result = Quantity::new(check_finite(value)?, from).to(to).value;
+    if value.is_finite() && !result.is_finite() {
+        return Err(ConvertError::OutOfRange {
+            value,
+            reason: "the result is too large to represent".to_string(),
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Metric {
+        Grams,
+        Kilograms,
+    }
+
+    impl Unit for Metric {
+        type Dimension = dimension::Mass;
+        fn to_base(&self, value: f64) -> f64 {
+            match self {
+                Metric::Grams => value / 1000.0,
+                Metric::Kilograms => value,
+            }
+        }
+        fn from_base(&self, value: f64) -> f64 {
+            match self {
+                Metric::Grams => value * 1000.0,
+                Metric::Kilograms => value,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tonnes;
+
+    impl Unit for Tonnes {
+        type Dimension = dimension::Mass;
+        fn to_base(&self, value: f64) -> f64 {
+            value * 1000.0
+        }
+        fn from_base(&self, value: f64) -> f64 {
+            value / 1000.0
+        }
+    }
+
+    #[test]
+    fn test_quantity() {
+        let grams = Quantity::new(2500.0, Metric::Grams);
+        assert_eq!(
+            grams.to(Metric::Kilograms),
+            Quantity::new(2.5, Metric::Kilograms)
+        );
+        assert_eq!(grams.to(Tonnes).value, 0.0025);
+        assert_eq!(
+            convert_units(3.0, Metric::Kilograms, Metric::Grams),
+            Ok(3000.0)
+        );
+        assert!(matches!(
+            convert_units(1e306, Metric::Kilograms, Metric::Grams),
+            Err(ConvertError::OutOfRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,543 @@
+//! The units of the unit-to-unit conversion categories.
+//!
+//! Each category has a unit enum with a `Pack` variant for units from the
+//! optional unit packs, and implements [`Unit`] through its dimension's base
+//! unit. With the `serde` feature every unit enum serializes as its name, e.g.
+//! `"kilometers"`, and deserializes from any name or symbol it accepts.
+
+use std::fmt;
+
+use crate::pack::{self, PackUnit};
+use crate::prefix;
+use crate::registry;
+use crate::unit::{dimension, Unit};
+
+/// Constant: Number of feet in a meter.
+pub const FEET_IN_METER: f64 = 3.28084;
+/// Constant: Number of inches in a meter.
+pub const INCHES_IN_METER: f64 = 39.3701;
+/// Constant: Number of kilograms in one pound.
+pub const KG_IN_LB: f64 = 2.20462;
+/// Constant: Number of ounces in one kilogram.
+pub const OZ_IN_KG: f64 = 35.274;
+/// Constant: Kilograms in one grain, the base of the troy and apothecaries systems.
+pub const KG_IN_GRAIN: f64 = 0.000_064_798_91;
+/// Constant: Number of bits per second in one megabit per second.
+pub const BPS_IN_MBPS: f64 = 1_000_000.0;
+/// Constant: The binary prefix multiplier, e.g. bits in a kibibit.
+pub const KIBI: f64 = 1024.0;
+/// Constant: Zero-offset for Kelvin scale.
+pub const KELVIN_OFFSET: f64 = 273.15;
+
+/// Display names and symbols for a unit, used by the output formatters.
+pub trait UnitNames {
+    /// Returns the singular or plural name of the unit, e.g. "foot" or "feet".
+    fn name(&self, singular: bool) -> &'static str;
+    /// Returns the unit symbol, e.g. "ft".
+    fn symbol(&self) -> &'static str;
+    /// Returns the canonical name of the unit, e.g. "feet", as the registry
+    /// and `convertx units` list it.
+    fn id(&self) -> &'static str;
+    /// Returns the pack unit, if the unit is not built in.
+    fn pack_unit(&self) -> Option<&'static PackUnit>;
+    /// Returns every built-in unit followed by the compiled-in pack units.
+    fn units() -> Vec<Self>
+    where
+        Self: Sized;
+}
+
+/// Defines a unit enum of the category `$category`, with the
+/// `(singular, plural, symbol)` display names of each unit.
+///
+/// The enum parses from its variant names, the unit symbols and the names of
+/// compiled-in pack units, which it holds in a `Pack` variant. With the
+/// `serde` feature it serializes as its name and deserializes from any
+/// spelling it parses from.
+///
+/// # Example
+///
+/// Not compiled, as the macro is only exported within the crate:
+///
+/// ```ignore
+/// unit_enum!(LengthUnit in "length" {
+///     Feet => "feet" ("foot", "feet", "ft"),
+/// });
+/// ```
+macro_rules! unit_enum {
+    ($(#[$doc:meta])* $name:ident in $category:literal { $($variant:ident => $val:literal ($singular:literal, $plural:literal, $symbol:literal)),* $(,)? }) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $($variant,)*
+            /// A unit from an optional unit pack.
+            Pack(&'static pack::PackUnit),
+            /// A metric unit with an SI prefix, e.g. kilopascals, or a unit the
+            /// registry defines relative to a built-in one, e.g. miles.
+            Prefixed(&'static prefix::PrefixedUnit),
+        }
+        impl $name {
+            /// Returns a static list of all built-in variant names as strings.
+            pub fn variants() -> &'static [&'static str] {
+                &[$($val),*]
+            }
+            /// Returns every accepted spelling: the variant names followed by unit
+            /// symbols, the names output shows and the names of compiled-in pack
+            /// units.
+            pub fn accepted() -> Vec<&'static str> {
+                let mut names = Self::variants().to_vec();
+                let shown = [$($symbol),*].into_iter().chain([$($singular, $plural),*]);
+                for symbol in shown.chain(pack::accepted($category)) {
+                    if !names.iter().any(|name| name.eq_ignore_ascii_case(symbol)) {
+                        names.push(symbol);
+                    }
+                }
+                names
+            }
+        }
+        impl ::std::str::FromStr for $name {
+            type Err = String;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                // The names output shows are read back too, e.g. "1 foot".
+                $(if [$val, $symbol, $singular, $plural].iter().any(|name| s.eq_ignore_ascii_case(name)) {
+                    return Ok($name::$variant);
+                })*
+                let found = pack::find($category, s)
+                    .map($name::Pack)
+                    .or_else(|| prefix::find($category, s).map($name::Prefixed))
+                    .or_else(|| registry::relative($category, s).map($name::Prefixed));
+                if let Some(unit) = found {
+                    return Ok(unit);
+                }
+                match registry::ambiguity(s) {
+                    Some(ambiguity) if ambiguity.dimension == $category => {
+                        // A unit read as its usual definition, which the CLI
+                        // warns about; the others need a qualified unit.
+                        if ambiguity.definition.is_some() {
+                            $(if ambiguity.spellings.iter().any(|spelling| spelling.eq_ignore_ascii_case($val)) {
+                                return Ok($name::$variant);
+                            })*
+                        }
+                        Err(ambiguity.message(s))
+                    }
+                    _ => Err(format!("unknown unit '{}'", s)),
+                }
+            }
+        }
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.id())
+            }
+        }
+        impl UnitNames for $name {
+            fn name(&self, singular: bool) -> &'static str {
+                match (self, singular) {
+                    $((Self::$variant, true) => $singular,
+                    (Self::$variant, false) => $plural,)*
+                    (Self::Pack(unit), true) => unit.singular,
+                    (Self::Pack(unit), false) => unit.plural,
+                    (Self::Prefixed(unit), true) => &unit.def.singular,
+                    (Self::Prefixed(unit), false) => &unit.def.plural,
+                }
+            }
+            fn symbol(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $symbol,)*
+                    Self::Pack(unit) => unit.symbol,
+                    Self::Prefixed(unit) => &unit.def.symbol,
+                }
+            }
+            fn id(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $val,)*
+                    Self::Pack(unit) => unit.name,
+                    Self::Prefixed(unit) => &unit.def.name,
+                }
+            }
+            fn pack_unit(&self) -> Option<&'static pack::PackUnit> {
+                match self {
+                    Self::Pack(unit) => Some(unit),
+                    _ => None,
+                }
+            }
+            fn units() -> Vec<Self> {
+                let mut units = vec![$(Self::$variant),*];
+                units.extend(pack::units($category).map(Self::Pack));
+                units
+            }
+        }
+            #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(::serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+/// Implements [`Unit`] for a unit enum declared with
+/// [`unit_enum!`], from `(to_base, from_base)` expressions of `$v`
+/// per variant. `Pack` units convert with their pack definition, and
+/// `Prefixed` units through the unit they scale.
+///
+/// # Example
+///
+/// Not compiled, as the macro is only exported within the crate:
+///
+/// ```ignore
+/// unit_conversions!(LengthUnit as Length, |v| {
+///     Meters => (v, v),
+///     Kilometers => (v * 1000.0, v / 1000.0),
+/// });
+/// ```
+macro_rules! unit_conversions {
+    ($name:ident as $dimension:ident, |$v:ident| { $($pattern:pat => ($to_base:expr, $from_base:expr)),* $(,)? }) => {
+        impl Unit for $name {
+            type Dimension = dimension::$dimension;
+            fn to_base(&self, $v: f64) -> f64 {
+                use $name::*;
+                match self {
+                    $($pattern => $to_base,)*
+                    Pack(unit) => unit.to_base($v),
+                    Prefixed(unit) => Self::reference(unit).to_base($v * unit.factor),
+                }
+            }
+            fn from_base(&self, $v: f64) -> f64 {
+                use $name::*;
+                match self {
+                    $($pattern => $from_base,)*
+                    Pack(unit) => unit.to_unit($v),
+                    Prefixed(unit) => Self::reference(unit).from_base($v) / unit.factor,
+                }
+            }
+        }
+        impl $name {
+            /// The unit that a prefixed `unit` scales.
+            fn reference(unit: &prefix::PrefixedUnit) -> Self {
+                unit.reference
+                    .parse()
+                    .expect("prefixed units scale a built-in unit of their category")
+            }
+        }
+    };
+}
+
+unit_enum!(
+    /// Supported units for length.
+    LengthUnit in "length" {
+        Meters => "meters" ("meter", "meters", "m"),
+        Feet => "feet" ("foot", "feet", "ft"),
+        Inches => "inches" ("inch", "inches", "in"),
+        Kilometers => "kilometers" ("kilometer", "kilometers", "km"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for temperature.
+    TempUnit in "temperature" {
+        C => "c" ("degree Celsius", "degrees Celsius", "°C"),
+        F => "f" ("degree Fahrenheit", "degrees Fahrenheit", "°F"),
+        K => "k" ("kelvin", "kelvins", "K"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for mass/weight.
+    MassUnit in "mass" {
+        Kg => "kg" ("kilogram", "kilograms", "kg"),
+        Lb => "lb" ("pound", "pounds", "lb"),
+        Oz => "oz" ("ounce", "ounces", "oz"),
+        TroyOunces => "troy_ounces" ("troy ounce", "troy ounces", "oz t"),
+        TroyPounds => "troy_pounds" ("troy pound", "troy pounds", "lb t"),
+        Pennyweights => "pennyweights" ("pennyweight", "pennyweights", "dwt"),
+        Grains => "grains" ("grain", "grains", "gr"),
+        Scruples => "scruples" ("apothecaries' scruple", "apothecaries' scruples", "s ap"),
+        ApothecariesDrams => "apothecaries_drams" ("apothecaries' dram", "apothecaries' drams", "dr ap"),
+        ApothecariesOunces => "apothecaries_ounces" ("apothecaries' ounce", "apothecaries' ounces", "oz ap"),
+        ApothecariesPounds => "apothecaries_pounds" ("apothecaries' pound", "apothecaries' pounds", "lb ap"),
+        Tonnes => "tonnes" ("tonne", "tonnes", "t"),
+        ShortTons => "short_tons" ("short ton", "short tons", "sh tn"),
+        LongTons => "long_tons" ("long ton", "long tons", "long tn"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for data rate.
+    DataRateUnit in "datarate" {
+        Bps => "bps" ("bit per second", "bits per second", "bit/s"),
+        Mbps => "mbps" ("megabit per second", "megabits per second", "Mbit/s"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for area.
+    AreaUnit in "area" {
+        SquareMeters => "sqm" ("square meter", "square meters", "m²"),
+        SquareFeet => "sqft" ("square foot", "square feet", "ft²"),
+        Acres => "acres" ("acre", "acres", "ac"),
+        Hectares => "hectares" ("hectare", "hectares", "ha"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for volume.
+    VolumeUnit in "volume" {
+        Liters => "liters" ("liter", "liters", "L"),
+        Milliliters => "milliliters" ("milliliter", "milliliters", "mL"),
+        CubicMeters => "cubic_meters" ("cubic meter", "cubic meters", "m³"),
+        CubicInches => "cubic_inches" ("cubic inch", "cubic inches", "in³"),
+        Gallons => "gallons" ("gallon", "gallons", "gal"),
+        ImperialGallons => "imperial_gallons" ("imperial gallon", "imperial gallons", "imp gal"),
+        UsGallons => "us_gallons" ("US gallon", "US gallons", "US gal"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for speed.
+    SpeedUnit in "speed" {
+        Mps => "mps" ("meter per second", "meters per second", "m/s"),
+        Kph => "kph" ("kilometer per hour", "kilometers per hour", "km/h"),
+        Mph => "mph" ("mile per hour", "miles per hour", "mph"),
+        Knots => "knots" ("knot", "knots", "kn"),
+    }
+);
+
+unit_enum!(
+    /// Supported units for pressure.
+    PressureUnit in "pressure" {
+        Pascal => "pa" ("pascal", "pascals", "Pa"),
+        Bar => "bar" ("bar", "bars", "bar"),
+        Atm => "atm" ("atmosphere", "atmospheres", "atm"),
+        Psi => "psi" ("pound per square inch", "pounds per square inch", "psi"),
+    }
+);
+
+unit_enum!(
+    /// Supported counting units.
+    CountUnit in "count" {
+        Items => "items" ("item", "items", "ea"),
+        Dozens => "dozens" ("dozen", "dozen", "doz"),
+        Gross => "gross" ("gross", "gross", "gro"),
+        Scores => "scores" ("score", "score", "sc"),
+        Reams => "reams" ("ream", "reams", "rm"),
+    }
+);
+
+unit_enum!(
+    /// Supported water hardness units, all expressed as calcium carbonate.
+    HardnessUnit in "hardness" {
+        Ppm => "ppm" ("ppm CaCO₃", "ppm CaCO₃", "mg/L"),
+        Dgh => "dgh" ("degree German hardness", "degrees German hardness", "°dH"),
+        Clark => "clark" ("degree Clark", "degrees Clark", "°e"),
+        French => "french" ("degree French", "degrees French", "°fH"),
+        Gpg => "gpg" ("grain per gallon", "grains per gallon", "gr/gal"),
+        Mmol => "mmol" ("millimole per liter", "millimoles per liter", "mmol/L"),
+    }
+);
+
+unit_enum!(
+    /// Supported units of information, with decimal (SI) and binary (IEC) prefixes.
+    InformationUnit in "information" {
+        Bits => "bits" ("bit", "bits", "bit"),
+        Nibbles => "nibbles" ("nibble", "nibbles", "nibble"),
+        Bytes => "bytes" ("byte", "bytes", "B"),
+        Kilobits => "kilobits" ("kilobit", "kilobits", "kbit"),
+        Megabits => "megabits" ("megabit", "megabits", "Mbit"),
+        Gigabits => "gigabits" ("gigabit", "gigabits", "Gbit"),
+        Kibibits => "kibibits" ("kibibit", "kibibits", "Kibit"),
+        Mebibits => "mebibits" ("mebibit", "mebibits", "Mibit"),
+        Gibibits => "gibibits" ("gibibit", "gibibits", "Gibit"),
+        Kilobytes => "kilobytes" ("kilobyte", "kilobytes", "kB"),
+        Megabytes => "megabytes" ("megabyte", "megabytes", "MB"),
+        Gigabytes => "gigabytes" ("gigabyte", "gigabytes", "GB"),
+        Kibibytes => "kibibytes" ("kibibyte", "kibibytes", "KiB"),
+        Mebibytes => "mebibytes" ("mebibyte", "mebibytes", "MiB"),
+        Gibibytes => "gibibytes" ("gibibyte", "gibibytes", "GiB"),
+    }
+);
+
+unit_enum!(
+    /// Supported hash rate units, in hashes per second.
+    HashrateUnit in "hashrate" {
+        Hashes => "h/s" ("hash per second", "hashes per second", "H/s"),
+        Kilohashes => "kh/s" ("kilohash per second", "kilohashes per second", "kH/s"),
+        Megahashes => "mh/s" ("megahash per second", "megahashes per second", "MH/s"),
+        Gigahashes => "gh/s" ("gigahash per second", "gigahashes per second", "GH/s"),
+        Terahashes => "th/s" ("terahash per second", "terahashes per second", "TH/s"),
+        Petahashes => "ph/s" ("petahash per second", "petahashes per second", "PH/s"),
+        Exahashes => "eh/s" ("exahash per second", "exahashes per second", "EH/s"),
+    }
+);
+
+// Length units, converted through meters.
+unit_conversions!(LengthUnit as Length, |v| {
+    Meters => (v, v),
+    Feet => (v / FEET_IN_METER, v * FEET_IN_METER),
+    Inches => (v / INCHES_IN_METER, v * INCHES_IN_METER),
+    Kilometers => (v * 1000.0, v / 1000.0),
+});
+
+// Temperature units (Celsius, Fahrenheit, Kelvin), converted through Celsius.
+unit_conversions!(TempUnit as Temperature, |v| {
+    C => (v, v),
+    F => ((v - 32.0) * 5.0 / 9.0, v * 9.0 / 5.0 + 32.0),
+    K => (v - KELVIN_OFFSET, v + KELVIN_OFFSET),
+});
+
+// Mass units, converted through kilograms.
+//
+// Plain ounces and pounds are avoirdupois; the troy and apothecaries units are
+// whole numbers of grains.
+unit_conversions!(MassUnit as Mass, |v| {
+    Kg => (v, v),
+    Lb => (v / KG_IN_LB, v * KG_IN_LB),
+    Oz => (v / OZ_IN_KG, v * OZ_IN_KG),
+    TroyOunces | ApothecariesOunces => (v * 480.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 480.0),
+    TroyPounds | ApothecariesPounds => (v * 5760.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 5760.0),
+    Pennyweights => (v * 24.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 24.0),
+    Grains => (v * KG_IN_GRAIN, v / KG_IN_GRAIN),
+    Scruples => (v * 20.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 20.0),
+    ApothecariesDrams => (v * 60.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 60.0),
+    Tonnes => (v * 1000.0, v / 1000.0),
+    // A pound is 7000 grains.
+    ShortTons => (v * 2000.0 * 7000.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 7000.0 / 2000.0),
+    LongTons => (v * 2240.0 * 7000.0 * KG_IN_GRAIN, v / KG_IN_GRAIN / 7000.0 / 2240.0),
+});
+
+// Data rate units, converted through bits per second.
+unit_conversions!(DataRateUnit as DataRate, |v| {
+    Bps => (v, v),
+    Mbps => (v * BPS_IN_MBPS, v / BPS_IN_MBPS),
+});
+
+// Area units, converted through square meters.
+unit_conversions!(AreaUnit as Area, |v| {
+    SquareMeters => (v, v),
+    SquareFeet => (v / 10.7639, v * 10.7639),
+    Acres => (v * 4046.85642, v / 4046.85642),
+    Hectares => (v * 10000.0, v / 10000.0),
+});
+
+// Volume units, converted through liters.
+unit_conversions!(VolumeUnit as Volume, |v| {
+    Liters => (v, v),
+    Milliliters => (v / 1000.0, v * 1000.0),
+    CubicMeters => (v * 1000.0, v / 1000.0),
+    CubicInches => (v / 61.0237, v * 61.0237),
+    Gallons | UsGallons => (v * 3.78541, v / 3.78541),
+    ImperialGallons => (v * 4.54609, v / 4.54609),
+});
+
+// Speed units, converted through meters per second.
+unit_conversions!(SpeedUnit as Speed, |v| {
+    Mps => (v, v),
+    Kph => (v / 3.6, v * 3.6),
+    Mph => (v * 0.44704, v / 0.44704),
+    Knots => (v * 0.514444, v / 0.514444),
+});
+
+// Pressure units, converted through pascals.
+unit_conversions!(PressureUnit as Pressure, |v| {
+    Pascal => (v, v),
+    Bar => (v * 100000.0, v / 100000.0),
+    Atm => (v * 101325.0, v / 101325.0),
+    Psi => (v * 6894.76, v / 6894.76),
+});
+
+// Counting units (items, dozens, gross, scores, reams), converted through items.
+unit_conversions!(CountUnit as Count, |v| {
+    Items => (v, v),
+    Dozens => (v * 12.0, v / 12.0),
+    Gross => (v * 144.0, v / 144.0),
+    Scores => (v * 20.0, v / 20.0),
+    Reams => (v * 500.0, v / 500.0),
+});
+
+// Water hardness units, converted through ppm of calcium carbonate.
+unit_conversions!(HardnessUnit as Hardness, |v| {
+    Ppm => (v, v),
+    Dgh => (v * 17.848, v / 17.848),
+    Clark => (v * 14.254, v / 14.254),
+    French => (v * 10.0, v / 10.0),
+    Gpg => (v * 17.118, v / 17.118),
+    Mmol => (v * 100.09, v / 100.09),
+});
+
+// Units of information, converted through bits.
+unit_conversions!(InformationUnit as Information, |v| {
+    Bits => (v, v),
+    Nibbles => (v * 4.0, v / 4.0),
+    Bytes => (v * 8.0, v / 8.0),
+    Kilobits => (v * 1e3, v / 1e3),
+    Megabits => (v * 1e6, v / 1e6),
+    Gigabits => (v * 1e9, v / 1e9),
+    Kibibits => (v * KIBI, v / KIBI),
+    Mebibits => (v * KIBI * KIBI, v / (KIBI * KIBI)),
+    Gibibits => (v * KIBI * KIBI * KIBI, v / (KIBI * KIBI * KIBI)),
+    Kilobytes => (v * 8e3, v / 8e3),
+    Megabytes => (v * 8e6, v / 8e6),
+    Gigabytes => (v * 8e9, v / 8e9),
+    Kibibytes => (v * 8.0 * KIBI, v / (8.0 * KIBI)),
+    Mebibytes => (v * 8.0 * KIBI * KIBI, v / (8.0 * KIBI * KIBI)),
+    Gibibytes => (v * 8.0 * KIBI * KIBI * KIBI, v / (8.0 * KIBI * KIBI * KIBI)),
+});
+
+// Hash rate units, converted through hashes per second.
+unit_conversions!(HashrateUnit as Hashrate, |v| {
+    Hashes => (v, v),
+    Kilohashes => (v * 1e3, v / 1e3),
+    Megahashes => (v * 1e6, v / 1e6),
+    Gigahashes => (v * 1e9, v / 1e9),
+    Terahashes => (v * 1e12, v / 1e12),
+    Petahashes => (v * 1e15, v / 1e15),
+    Exahashes => (v * 1e18, v / 1e18),
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_names() {
+        assert_eq!("KM".parse(), Ok(LengthUnit::Kilometers));
+        assert_eq!("°F".parse(), Ok(TempUnit::F));
+        assert_eq!(MassUnit::TroyOunces.to_string(), "troy_ounces");
+        assert_eq!(CountUnit::Dozens.name(true), "dozen");
+        assert!(SpeedUnit::accepted().contains(&"km/h"));
+        // Tons need a qualifier; the error names the choices.
+        let error = "tons".parse::<MassUnit>().unwrap_err();
+        assert!(error.contains("short_tons (2000 lb, US)"), "{}", error);
+        assert_eq!(
+            "tons".parse::<VolumeUnit>().err().unwrap(),
+            "unknown unit 'tons'"
+        );
+        assert_eq!("sh tn".parse(), Ok(MassUnit::ShortTons));
+        // Other ambiguous units are read as their usual definition.
+        assert_eq!("Gallon".parse(), Ok(VolumeUnit::Gallons));
+        // The names output shows are read back.
+        assert_eq!("foot".parse(), Ok(LengthUnit::Feet));
+        assert_eq!("Inch".parse(), Ok(LengthUnit::Inches));
+        assert_eq!("degrees Celsius".parse(), Ok(TempUnit::C));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&[LengthUnit::Kilometers, LengthUnit::Feet]).unwrap();
+        assert_eq!(json, r#"["kilometers","feet"]"#);
+        let units: Vec<PressureUnit> = serde_json::from_str(r#"["psi", "Pa", "ATM"]"#).unwrap();
+        assert_eq!(
+            units,
+            [PressureUnit::Psi, PressureUnit::Pascal, PressureUnit::Atm]
+        );
+        let error = serde_json::from_str::<TempUnit>(r#""furlongs""#).unwrap_err();
+        assert!(error.to_string().contains("unknown unit 'furlongs'"));
+    }
+}
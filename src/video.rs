@@ -0,0 +1,103 @@
+//! Video resolution presets and typical bitrates for `convertx video`.
+//!
+//! Bitrates follow common streaming recommendations for H.264 at 30 frames per
+//! second; higher frame rates need about half as much again.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Resolution names and their typical H.264 bitrate at 30 fps, in Mbps.
+const RESOLUTIONS: &[(&str, f64)] = &[
+    ("480p", 2.5),
+    ("720p", 5.0),
+    ("1080p", 8.0),
+    ("1440p", 16.0),
+    ("2160p", 35.0),
+    ("4k", 35.0),
+    ("8k", 100.0),
+];
+
+/// Frame rate assumed when a preset does not name one.
+const DEFAULT_FPS: u32 = 30;
+
+/// A resolution and frame rate, e.g. `4k60` or `1080p`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    resolution: &'static str,
+    base_mbps: f64,
+    pub fps: u32,
+}
+
+impl Preset {
+    /// Returns the typical H.264 bitrate of the preset at `fps`, in Mbps.
+    ///
+    /// # Example
+    /// ```
+    /// # use convertx::video::*;
+    /// let preset: Preset = "1080p60".parse().unwrap();
+    /// assert_eq!(preset.h264_mbps(preset.fps), 12.0);
+    /// ```
+    pub fn h264_mbps(&self, fps: u32) -> f64 {
+        if fps > DEFAULT_FPS {
+            self.base_mbps * 1.5
+        } else {
+            self.base_mbps
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let unknown = || {
+            let names: Vec<&str> = RESOLUTIONS.iter().map(|(name, _)| *name).collect();
+            format!(
+                "unknown video preset '{}', expected a resolution ({}) optionally followed by a frame rate, e.g. 4k60",
+                s,
+                names.join(", ")
+            )
+        };
+        let (resolution, base_mbps) = RESOLUTIONS
+            .iter()
+            .find(|(name, _)| lower.starts_with(name))
+            .ok_or_else(unknown)?;
+        let fps = match &lower[resolution.len()..] {
+            "" => DEFAULT_FPS,
+            rate => rate.parse().map_err(|_| unknown())?,
+        };
+        Ok(Preset {
+            resolution,
+            base_mbps: *base_mbps,
+            fps,
+        })
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.resolution, self.fps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preset() {
+        let preset: Preset = "4K60".parse().unwrap();
+        assert_eq!((preset.resolution, preset.fps), ("4k", 60));
+        assert_eq!(preset.to_string(), "4k60");
+        assert_eq!("1080p".parse::<Preset>().unwrap().fps, 30);
+        assert!("1080i".parse::<Preset>().is_err());
+        assert!("900p".parse::<Preset>().is_err());
+    }
+
+    #[test]
+    fn test_h264_mbps() {
+        let preset: Preset = "1080p".parse().unwrap();
+        assert_eq!(preset.h264_mbps(30), 8.0);
+        assert_eq!(preset.h264_mbps(60), 12.0);
+    }
+}
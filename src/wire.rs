@@ -0,0 +1,108 @@
+//! American Wire Gauge (AWG) sizes for `convertx awg`.
+//!
+//! Gauges follow the standard formula `d = 0.127 mm × 92^((36 − n) / 39)`, where
+//! the aught sizes 0, 00 (2/0), 000 (3/0) and 0000 (4/0) are n = 0, −1, −2, −3.
+
+use crate::portable::{ln, powf, powi};
+
+/// Smallest gauge number supported, i.e. 4/0.
+const MIN_GAUGE: i32 = -3;
+/// Largest gauge number supported.
+const MAX_GAUGE: i32 = 40;
+
+/// Parses a gauge such as `12`, `0`, `00`, `000`, `0000` or `4/0`.
+///
+/// # Example
+/// ```
+/// # use convertx::wire::*;
+/// assert_eq!(parse_gauge("4/0"), Ok(-3));
+/// ```
+pub fn parse_gauge(s: &str) -> Result<i32, String> {
+    let s = s.trim();
+    let gauge = match s.split_once('/') {
+        Some((aughts, "0")) => aughts
+            .parse::<i32>()
+            .ok()
+            .filter(|n| *n >= 1)
+            .map(|n| 1 - n),
+        Some(_) => None,
+        None if s.len() > 1 && s.chars().all(|c| c == '0') => Some(1 - s.len() as i32),
+        None => s.parse::<i32>().ok(),
+    };
+    gauge
+        .filter(|n| (MIN_GAUGE..=MAX_GAUGE).contains(n))
+        .ok_or_else(|| format!("'{}' is not a wire gauge between 4/0 and {}", s, MAX_GAUGE))
+}
+
+/// Returns the usual name of a gauge, e.g. "12" or "4/0".
+pub fn gauge_name(gauge: i32) -> String {
+    if gauge < 0 {
+        format!("{}/0", 1 - gauge)
+    } else {
+        gauge.to_string()
+    }
+}
+
+/// Returns the diameter of a solid wire of `gauge` in millimeters.
+///
+/// # Example
+/// ```
+/// # use convertx::wire::*;
+/// assert!((diameter_mm(12) - 2.0525).abs() < 1e-4);
+/// ```
+pub fn diameter_mm(gauge: i32) -> f64 {
+    0.127 * powf(92.0, f64::from(36 - gauge) / 39.0)
+}
+
+/// Returns the cross-sectional area in mm² of a round wire `diameter_mm` thick.
+pub fn area_mm2(diameter_mm: f64) -> f64 {
+    std::f64::consts::PI * powi(diameter_mm / 2.0, 2)
+}
+
+/// Returns the diameter in millimeters of a round wire of `area_mm2`.
+pub fn diameter_from_area(area_mm2: f64) -> f64 {
+    (area_mm2 / std::f64::consts::PI).sqrt() * 2.0
+}
+
+/// Returns the gauge whose diameter is closest to `diameter_mm`, limited to the
+/// supported range.
+pub fn nearest_gauge(diameter_mm: f64) -> i32 {
+    let exact = 36.0 - 39.0 * ln(diameter_mm / 0.127) / ln(92.0);
+    (exact.round() as i32).clamp(MIN_GAUGE, MAX_GAUGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gauge() {
+        assert_eq!(parse_gauge("12"), Ok(12));
+        assert_eq!(parse_gauge("0"), Ok(0));
+        assert_eq!(parse_gauge("00"), Ok(-1));
+        assert_eq!(parse_gauge("0000"), Ok(-3));
+        assert_eq!(parse_gauge("3/0"), Ok(-2));
+        assert!(parse_gauge("5/0").is_err());
+        assert!(parse_gauge("0/0").is_err());
+        assert!(parse_gauge("41").is_err());
+        assert!(parse_gauge("twelve").is_err());
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert!((diameter_mm(36) - 0.127).abs() < 1e-9);
+        assert!((diameter_mm(-3) - 11.684).abs() < 1e-3);
+        assert!((area_mm2(diameter_mm(12)) - 3.3088).abs() < 1e-4);
+        assert!((diameter_from_area(area_mm2(2.0)) - 2.0).abs() < 1e-12);
+        assert_eq!(gauge_name(-3), "4/0");
+        assert_eq!(gauge_name(14), "14");
+    }
+
+    #[test]
+    fn test_nearest_gauge() {
+        assert_eq!(nearest_gauge(2.05), 12);
+        assert_eq!(nearest_gauge(diameter_mm(-2)), -2);
+        assert_eq!(nearest_gauge(100.0), MIN_GAUGE);
+        assert_eq!(nearest_gauge(0.001), MAX_GAUGE);
+    }
+}
@@ -0,0 +1,86 @@
+//! Business calendar rules for `convertx workdays`.
+//!
+//! A holiday file lists one `YYYY-MM-DD` date per line; blank lines and lines
+//! starting with `#` are ignored.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::fs;
+
+/// Reads the holidays listed in the file at `path`.
+pub fn load_holidays(path: &str) -> Result<Vec<NaiveDate>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    parse_holidays(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Parses holiday lines, reporting the first line that is not a date.
+fn parse_holidays(text: &str) -> Result<Vec<NaiveDate>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            NaiveDate::parse_from_str(line, "%Y-%m-%d")
+                .map_err(|_| format!("line {}: '{}' is not a YYYY-MM-DD date", number, line))
+        })
+        .collect()
+}
+
+/// Returns the last day of `days` working days starting on `start`, skipping
+/// weekends if asked and any of `holidays`.
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveDate;
+/// # use convertx::workdays::*;
+/// let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+/// let friday = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+/// assert_eq!(last_working_day(monday, 10, true, &[]), friday);
+/// ```
+pub fn last_working_day(
+    start: NaiveDate,
+    days: u64,
+    skip_weekends: bool,
+    holidays: &[NaiveDate],
+) -> NaiveDate {
+    let is_working = |date: &NaiveDate| {
+        let weekend = skip_weekends && matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        !weekend && !holidays.contains(date)
+    };
+    start
+        .iter_days()
+        .filter(is_working)
+        .nth(days.saturating_sub(1) as usize)
+        .unwrap_or(NaiveDate::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_holidays() {
+        let holidays = parse_holidays("# 2025\n2025-12-25\n\n2025-12-26\n").unwrap();
+        assert_eq!(holidays, vec![date(2025, 12, 25), date(2025, 12, 26)]);
+        assert_eq!(
+            parse_holidays("2025-12-25\nChristmas"),
+            Err("line 2: 'Christmas' is not a YYYY-MM-DD date".to_string())
+        );
+    }
+
+    #[test]
+    fn test_last_working_day() {
+        let monday = date(2025, 3, 3);
+        assert_eq!(last_working_day(monday, 15, true, &[]), date(2025, 3, 21));
+        assert_eq!(last_working_day(monday, 15, false, &[]), date(2025, 3, 17));
+        assert_eq!(
+            last_working_day(monday, 1, true, &[monday]),
+            date(2025, 3, 4)
+        );
+        // Starting on a weekend moves to the next working day.
+        assert_eq!(last_working_day(date(2025, 3, 1), 1, true, &[]), monday);
+    }
+}
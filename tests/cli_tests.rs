@@ -1,3 +1,6 @@
+// The first tests pass their arguments as `&[..]`, which are left as written.
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::Command;
 use predicates::str::contains;
 
@@ -8,6 +11,37 @@ fn bytes_megabytes() {
     cmd.assert().success().stdout(contains("1.00 MB"));
 }
 
+#[test]
+fn strict_int_fails_instead_of_rounding() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1536", "--human-readable", "--strict-int"]);
+    cmd.assert()
+        .code(7)
+        .stderr("error: 1.5 KB is not a whole number; --strict-int does not round it\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1048576", "-m", "--strict-int"]);
+    cmd.assert().success().stdout("1048576 bytes = 1 MB\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["time", "3661", "--human-readable", "--strict-int"]);
+    cmd.assert().success().stdout("3661 seconds = 1h 1m 1s\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["information", "3072", "--from", "bytes", "--to", "KiB"]);
+    cmd.args(["--strict-int", "--symbols"]);
+    cmd.assert().success().stdout("3072 B = 3 KiB\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["information", "1500", "--from", "bytes", "--to", "KiB"]);
+    cmd.args(["--strict-int", "--both"]);
+    cmd.assert().code(7).stdout("");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["count", "48", "--from", "items", "--all", "--strict-int"]);
+    cmd.assert().success().stdout("48 items = 4 dozen\n");
+}
+
 #[test]
 fn time_human_readable() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
@@ -16,71 +50,3058 @@ fn time_human_readable() {
 }
 
 #[test]
-fn length_kilometers_to_meters() {
+fn text_metrics() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["length", "1", "--from", "kilometers", "--to", "meters"]);
+    cmd.args(["text", "--wpm", "80", "--to", "cpm"]);
+    cmd.assert().success().stdout(contains("80 wpm = 400 cpm"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["text", "--words", "5000", "--to", "reading-time"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 kilometers = 1000.0000 meters"));
+        .stdout(contains("5000 words = 21m 1s reading time"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["text", "--wpm", "80", "--to", "reading-time"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("--words with --to reading-time"));
 }
 
 #[test]
-fn temperature_f_to_c() {
+fn iops_solves_missing_value() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["temperature", "32", "--from", "f", "--to", "c"]);
-    cmd.assert().success().stdout(contains("32.00°F = 0.00°C"));
+    cmd.args(["iops", "--throughput", "500MB/s", "--block", "4KiB"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("500MB/s at 4KiB per I/O = 122070.31 IOPS"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["iops", "--iops", "10000", "--block", "4KiB"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("10000 IOPS at 4KiB per I/O = 39.06 MB/s"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["iops", "--block", "4KiB"]);
+    cmd.assert().code(2).stderr(contains(
+        "pass exactly two of --throughput, --block and --iops",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["iops", "--block", "4KiB", "--iops", "0"]);
+    cmd.assert()
+        .code(5)
+        .stderr(contains("--block and --iops must be greater than zero"));
 }
 
 #[test]
-fn mass_kg_to_lb() {
+fn latency_light_distance() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["mass", "1", "--from", "kg", "--to", "lb"]);
+    cmd.args(["latency", "20ms", "--to", "km-in-fiber"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("20ms = 3997.2 km in fiber"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["latency", "4000km", "--to", "ms-in-vacuum", "--rtt"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 kg = 2.2046 lb"));
+        .stdout(contains("4000km = 26.685 ms round trip in vacuum"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["latency", "20ms", "--to", "ms-in-fiber"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("unknown distance unit 'ms'"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["latency", "1e308s", "--to", "km-in-vacuum"]);
+    cmd.assert()
+        .code(5)
+        .stdout("")
+        .stderr("error: 1e308 is out of range: the result is too large to represent\n");
 }
 
 #[test]
-fn datarate_mbps_to_bps() {
+fn cycles_and_time() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["datarate", "1", "--from", "mbps", "--to", "bps"]);
+    cmd.args(["cycles", "2500", "--freq", "3.2GHz", "--to", "ns"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2500 cycles at 3.2GHz = 781.25 ns"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["cycles", "1us", "--freq", "3.2GHz", "--to", "cycles"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 mbps = 1000000.0000 bps"));
+        .stdout(contains("1us at 3.2GHz = 3200 cycles"));
 }
 
+#[cfg(feature = "media")]
 #[test]
-fn area_acres_to_sqm() {
+fn audio_size() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["area", "1", "--from", "acres", "--to", "sqm"]);
+    cmd.args(["audio-size", "--rate", "48kHz", "--bit-depth", "24"]);
+    cmd.args(["--channels", "2", "--duration", "3m", "--to", "MiB"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 acres = 4046.8564 sqm"));
+        .stdout(contains("3m at 48kHz, 24-bit, 2 channels = 49.44 MiB"));
 }
 
+#[cfg(feature = "media")]
 #[test]
-fn volume_gallons_to_liters() {
+fn audio_size_rejects_zero() {
+    for (rate, bit_depth, channels) in [
+        ("0Hz", "16", "2"),
+        ("48kHz", "0", "2"),
+        ("48kHz", "16", "0"),
+    ] {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["audio-size", "--rate", rate, "--bit-depth", bit_depth]);
+        cmd.args(["--channels", channels, "--duration", "3m", "--to", "MiB"]);
+        cmd.assert()
+            .failure()
+            .stderr(contains("must be greater than zero"));
+    }
+}
+
+#[cfg(feature = "media")]
+#[test]
+fn ppi_density() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["volume", "1", "--from", "gallons", "--to", "liters"]);
+    cmd.args(["ppi", "163", "--to", "dot-pitch-mm"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("163 ppi = 0.1558 mm dot pitch"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["ppi", "--resolution", "2556x1179", "--diagonal", "6.1in"]);
+    cmd.args(["--to", "ppi"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 gallons = 3.7854 liters"));
+        .stdout(contains("2556x1179 at 6.1in = 461.44 ppi"));
 }
 
+#[cfg(feature = "media")]
 #[test]
-fn speed_mph_to_kph() {
+fn refresh_frame_time() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["speed", "60", "--from", "mph", "--to", "kph"]);
+    cmd.args(["refresh", "144", "--to", "frame-time-ms"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("144 Hz = 6.944 ms per frame"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["refresh", "16.667", "--to", "hz"]);
     cmd.assert()
         .success()
-        .stdout(contains("60.0000 mph = 96.5606 kph"));
+        .stdout(contains("16.667 ms per frame = 60.00 Hz"));
 }
 
 #[test]
-fn pressure_atm_to_psi() {
+fn fractions_are_accepted() {
     let mut cmd = Command::cargo_bin("convertx").unwrap();
-    cmd.args(&["pressure", "1", "--from", "atm", "--to", "psi"]);
+    cmd.args(["length", "1 1/2", "--from", "feet", "--to", "inches"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.5000 feet = 18.0000 inches"));
+
+    if cfg!(feature = "media") {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["shutter", "1/250", "--to", "ms"]);
+        cmd.assert()
+            .success()
+            .stdout(contains("1/250 s = 4.000 ms"));
+    }
+}
+
+#[test]
+fn awg_sizes() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["awg", "12", "--to", "mm2"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("12 AWG = 3.3088 mm²"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["awg", "3.31", "--from", "mm2", "--to", "awg"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("3.3100 mm² ≈ 12 AWG (3.3088 mm²)"));
+}
+
+#[test]
+fn drill_sizes() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["drill", "7/32in", "--to", "mm"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("7/32in = 5.5563 mm (nearest metric bit: 5.5 mm)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["drill", "6mm", "--to", "in"]);
+    cmd.assert().success().stdout(contains(
+        "6mm = 0.2362 in (nearest fractional bit: 15/64 in)",
+    ));
+}
+
+#[test]
+fn alcohol_proof_and_standard_drinks() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["alcohol", "8", "--from", "percent", "--to", "proof"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("8% ABV = 16.0 proof"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["alcohol", "--volume", "500ml", "--abv", "5"]);
+    cmd.args(["--to", "standard-drinks", "--country", "uk"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("500ml at 5% ABV = 2.50 standard drinks (UK)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["alcohol", "150", "--from", "percent", "--to", "proof"]);
+    cmd.assert()
+        .code(5)
+        .stderr("error: 150 is out of range: percent must be between 0 and 100\n");
+}
+
+#[test]
+fn gravity_scales_and_abv() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["gravity", "1.050", "--from", "sg", "--to", "plato"]);
+    cmd.assert().success().stdout(contains("1.050 SG = 12.4°P"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["gravity", "0.5", "--from", "sg", "--to", "plato"]);
+    cmd.assert()
+        .code(5)
+        .stderr("error: 0.5 is out of range: sg must be between 0.98 and 1.2\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["gravity", "--og", "1.050", "--fg", "1.010"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("OG 1.050 SG and FG 1.010 SG = 5.25% ABV"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["gravity", "--og", "1.010", "--fg", "1.050"]);
+    cmd.assert().failure().stdout("").stderr(
+        "error: the final gravity 1.050 is above the original gravity 1.010; \
+         fermentation only lowers it\n",
+    );
+}
+
+#[test]
+fn bake_adjustments() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bake", "--temp", "180C", "--convection", "--time", "40m"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("180°C for 40m = 166°C for 40m (convection -25°F)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "bake",
+        "--temp",
+        "350F",
+        "--altitude",
+        "2000m",
+        "--time",
+        "40m",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "350°F for 40m = 365°F for 32m (altitude 2000m +15°F and 20% less time)",
+    ));
+}
+
+#[test]
+fn brew_ratio() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "brew",
+        "--ratio",
+        "1:16",
+        "--water",
+        "500ml",
+        "--to",
+        "coffee-grams",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("500ml water at 1:16 = 31.2 g coffee"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["brew", "--coffee", "30g", "--to", "water-ml"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("30g coffee at 1:16 = 480 ml water"));
+}
+
+#[test]
+fn fabric_to_metric_width() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["fabric", "--pattern", "2.5yd@45in", "--to", "m@140cm"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2.5yd@45in = 1.87 m@140cm (2.61 m²)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["fabric", "--pattern", "2.5yd@0in", "--to", "m@140cm"]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(contains("the width '0in' must be greater than zero"));
+}
+
+#[test]
+fn paint_coverage() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "paint",
+        "--area",
+        "45m2",
+        "--coverage",
+        "10m2/L",
+        "--coats",
+        "2",
+        "--to",
+        "liters",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("45m2 at 10m2/L, 2 coats = 9.00 L"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "paint",
+        "--area",
+        "400ft2",
+        "--coverage",
+        "350ft2/gal",
+        "--to",
+        "gallons",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("400ft2 at 350ft2/gal, 1 coat = 1.14 gal"));
+}
+
+#[test]
+fn material_weight_and_bags() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "material",
+        "--volume",
+        "2.5m3",
+        "--material",
+        "concrete",
+        "--to",
+        "tons",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2.5m3 of concrete = 6.00 t"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "material",
+        "--volume",
+        "0.1m3",
+        "--material",
+        "concrete",
+        "--to",
+        "bags",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("0.1m3 of concrete = 10 bags of 25kg"));
+}
+
+#[test]
+fn rain_depth_to_volume() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["rain", "25mm", "--area", "120m2", "--to", "liters"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("25mm over 120m2 = 3000.00 L"));
+}
+
+#[test]
+fn irrigation_solves_flow_triangle() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "irrigation",
+        "--flow",
+        "12L/min",
+        "--target",
+        "500L",
+        "--to",
+        "duration",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("500L at 12L/min = 41m 40s"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["irrigation", "--flow", "12L/min", "--to", "volume"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("--to volume needs --flow and --time"));
+}
+
+#[test]
+fn yield_to_bushels() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "yield", "3.5", "--from", "t/ha", "--to", "bu/acre", "--crop", "wheat",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("3.5 t/ha = 52.04 bu/acre (wheat)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["yield", "3.5", "--from", "t/ha", "--to", "bu/acre"]);
+    cmd.assert().failure().stderr(contains("needs --crop"));
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn price_per_unit() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["price", "4.99", "--per", "lb", "--to", "per-kg"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("4.99 per lb = 11.00 per kg"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["price", "2", "--per", "liter", "--to", "per-us_gallons"]);
+    cmd.assert()
+        .success()
+        .stdout("2.00 per liter = 7.57 per us_gallons\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "price",
+        "3",
+        "--per",
+        "oz",
+        "--to",
+        "per-lb",
+        "--category",
+        "mass",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("3.00 per oz = 48.00 per lb\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["price", "4.99", "--per", "lb", "--to", "per-GB"]);
+    cmd.assert()
+        .code(4)
+        .stderr(contains("cannot convert 'lb' to 'GB'"));
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn price_reads_units_in_any_case() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["price", "20", "--per", "tb", "--to", "per-gb"]);
+    cmd.assert()
+        .success()
+        .stdout("20.00 per tb = 0.02 per gb\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["price", "3000", "--per", "KG", "--to", "per-G"]);
+    cmd.assert()
+        .success()
+        .stdout("3,000.00 per KG = 3.00 per G\n");
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn vat_net_and_gross() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "vat", "100", "--rate", "19", "--from", "net", "--to", "gross",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("100.00 net at 19% = 119.00 gross"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "vat", "119", "--rate", "19", "--from", "gross", "--to", "net",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("119.00 gross at 19% = 100.00 net"));
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn tip_split() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["tip", "86.40", "--percent", "18", "--split", "4"]);
+    cmd.assert().success().stdout(contains(
+        "18% tip on 86.40 = 15.55\nTotal = 101.95\nPer person (4) = 25.49",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "tip",
+        "86.40",
+        "--percent",
+        "18",
+        "--split",
+        "4",
+        "--round",
+        "ceil",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Per person (4) = 25.49"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "tip",
+        "100",
+        "--percent",
+        "10",
+        "--split",
+        "3",
+        "--round",
+        "ceil",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Per person (3) = 36.67"));
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn salary_hourly_to_annual() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "salary",
+        "35",
+        "--from",
+        "hourly",
+        "--to",
+        "annual",
+        "--hours-per-week",
+        "40",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "35.00 hourly = 72,800.00 annual (40h/week, 52 weeks/year)",
+    ));
+}
+
+#[cfg(feature = "dates")]
+#[test]
+fn meeting_time_zones() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "meeting",
+        "2025-03-01 15:00 UTC",
+        "--in",
+        "berlin,tokyo,new_york",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "Europe/Berlin     Sat 2025-03-01 16:00 (UTC+01:00)",
+        ))
+        .stdout(contains(
+            "Asia/Tokyo        Sun 2025-03-02 00:00 (UTC+09:00)",
+        ))
+        .stdout(contains(
+            "America/New_York  Sat 2025-03-01 10:00 (UTC-05:00)",
+        ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["meeting", "2025-03-01 15:00", "--in", "atlantis"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("unknown time zone 'atlantis'"));
+}
+
+#[cfg(feature = "dates")]
+#[test]
+fn workdays_business_calendar() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "workdays",
+        "120h",
+        "--to",
+        "business-days",
+        "--hours-per-day",
+        "8",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("120h = 15.00 business days (8h/day)"));
+
+    let holidays = std::env::temp_dir().join("convertx_workdays_holidays.txt");
+    std::fs::write(&holidays, "# Spring break\n2025-03-14\n").unwrap();
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "workdays",
+        "120h",
+        "--to",
+        "end-date",
+        "--start",
+        "2025-03-03",
+    ]);
+    cmd.args(["--skip-weekends", "--holidays", holidays.to_str().unwrap()]);
+    cmd.assert().success().stdout(contains(
+        "120h = 15.00 business days from 2025-03-03, ending Mon 2025-03-24 (22 calendar days)",
+    ));
+
+    // Weekends are skipped by default, and counted on request.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "workdays",
+        "120h",
+        "--to",
+        "end-date",
+        "--start",
+        "2025-03-03",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "120h = 15.00 business days from 2025-03-03, ending Fri 2025-03-21 (19 calendar days)",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "workdays",
+        "120h",
+        "--to",
+        "end-date",
+        "--start",
+        "2025-03-03",
+    ]);
+    cmd.args(["--count-weekends"]);
+    cmd.assert().success().stdout(contains(
+        "120h = 15.00 business days from 2025-03-03, ending Mon 2025-03-17 (15 calendar days)",
+    ));
+
+    for flag in [&["--count-weekends"][..], &["--start", "2025-03-03"]] {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["workdays", "120h", "--to", "business-weeks"])
+            .args(flag);
+        cmd.assert()
+            .code(2)
+            .stderr(contains("only apply to --to end-date, not business-weeks"));
+    }
+}
+
+#[cfg(feature = "dates")]
+#[test]
+fn date_formats() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["date", "03/04/2025", "--from", "mdY", "--to", "iso"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("03/04/2025 (mdY) = 2025-03-04 (iso)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["date", "03/04/2025", "--from", "dmY", "--to", "unix"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("03/04/2025 (dmY) = 1743638400 (unix)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["date", "13/04/2025", "--from", "mdY", "--to", "iso"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("'13/04/2025' is not a mdY date"));
+}
+
+#[cfg(feature = "dates")]
+#[test]
+fn week_and_ordinal_dates() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["week", "2025-W14-3", "--to", "date"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2025-04-02 (Wednesday)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["week", "2024-12-30", "--to", "week"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2025-W01-1 (Monday)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["week", "2025-04-02", "--to", "ordinal"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2025-092 (Wednesday)"));
+}
+
+#[test]
+fn morse_and_phonetic() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["morse", "SOS"]);
+    cmd.assert().success().stdout("... --- ...\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["morse", "... --- ...", "--to", "text"]);
+    cmd.assert().success().stdout("SOS\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["phonetic", "R2D4"]);
+    cmd.assert().success().stdout("Romeo Two Delta Four\n");
+}
+
+#[test]
+fn entropy_bits_and_crack_time() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "entropy",
+        "--charset",
+        "94",
+        "--length",
+        "16",
+        "--to",
+        "bits",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("16 symbols from 94 = 104.87 bits"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "entropy",
+        "--charset",
+        "digits",
+        "--length",
+        "12",
+        "--to",
+        "time-to-crack",
+    ]);
+    cmd.args(["--rate", "1e9/s"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("39.86 bits at 1e9 guesses/s = 8m 20s on average"));
+}
+
+#[test]
+fn rps_to_per_day_and_back() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["rps", "250", "--to", "per-day"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("250 requests/s = 21,600,000.00 requests/day"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["rps", "1M requests/day", "--to", "rps"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1M requests/day = 11.57 requests/s"));
+}
+
+#[test]
+fn littles_law_solves_each_variable() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "littles-law",
+        "--arrival",
+        "120/s",
+        "--latency",
+        "250ms",
+        "--to",
+        "in-flight",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("120/s × 250ms = 30.00 requests in flight"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "littles-law",
+        "--arrival",
+        "120/s",
+        "--in-flight",
+        "30",
+        "--to",
+        "latency",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("30 in flight / 120/s = 250.00 ms latency"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["littles-law", "--latency", "250ms", "--to", "arrival"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("--to arrival needs --latency and --in-flight"));
+}
+
+#[test]
+fn change_between_quantities() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["change", "45MB", "62MB", "--to", "percent"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("45MB -> 62MB = +17.00MB (+37.78%)"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["change", "1.2s", "900ms", "--to", "factor"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.2s -> 900ms = -0.30s (0.750x)"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["change", "45MB", "3kg", "--to", "percent"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("are not quantities of the same kind"));
+    for target in ["percent", "factor"] {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["change", "0MB", "62MB", "--to", target]);
+        cmd.assert()
+            .code(5)
+            .stdout("")
+            .stderr(contains("change from zero is undefined"));
+    }
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn stats_summarizes_stdin() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["stats", "--category", "time", "--to", "ms"])
+        .write_stdin("343ms\n1.2s\n\n90ms\n0.5s\n");
+    cmd.assert()
+        .success()
+        .stdout(contains("count  4"))
+        .stdout(contains("min    90.00 ms"))
+        .stdout(contains("median 421.50 ms"))
+        .stdout(contains("p95    1200.00 ms"))
+        .stdout(contains("max    1200.00 ms"));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["stats", "--category", "time"])
+        .write_stdin("343ms\n3kg\n");
+    cmd.assert().failure().stderr(contains("line 2:"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn stats_histogram() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "stats",
+        "--category",
+        "time",
+        "--to",
+        "ms",
+        "--histogram",
+        "--buckets",
+        "2",
+    ])
+    .write_stdin("100ms\n150ms\n200ms\n0.3s\n");
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "100.00-200.00 ms | ######################################## 2",
+        ))
+        .stdout(contains(
+            "200.00-300.00 ms | ######################################## 2",
+        ));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn stats_rejects_zero_buckets() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "stats",
+        "--category",
+        "time",
+        "--histogram",
+        "--buckets",
+        "0",
+    ])
+    .write_stdin("100ms\n");
+    cmd.assert()
+        .failure()
+        .stderr(contains("--buckets"))
+        .stderr(contains("at least 1"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn series_converts_y_column() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("convertx_series_input.csv");
+    let output = dir.join("convertx_series_output.csv");
+    std::fs::write(&input, "t,host,bytes\n0,a,1073741824\n10,a,1610612736\n").unwrap();
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "series",
+        input.to_str().unwrap(),
+        "--x-col",
+        "0",
+        "--y-col",
+        "2",
+    ]);
+    cmd.args([
+        "--y-from",
+        "bytes",
+        "--y-to",
+        "gib",
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "t,bytes\n0,1\n10,1.5\n"
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "series",
+        input.to_str().unwrap(),
+        "--y-from",
+        "km",
+        "--y-to",
+        "kg",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("cannot convert 'km' to 'kg'"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn csv_converts_columns_in_place() {
+    let input = std::env::temp_dir().join("convertx_csv_input.csv");
+    std::fs::write(
+        &input,
+        "item;\"weight; lb\";note\r\nbox;2.5;\"fragile; \"\"glass\"\"\"\r\ncrate;;\r\n",
+    )
+    .unwrap();
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["--precision", "3", "csv", input.to_str().unwrap()])
+        .args([
+            "--column",
+            "weight; lb",
+            "--delimiter",
+            ";",
+            "-f",
+            "lb",
+            "-t",
+            "kg",
+        ])
+        .assert()
+        .success()
+        .stdout("item;\"weight; lb\";note\r\nbox;1.134;\"fragile; \"\"glass\"\"\"\r\ncrate;;\r\n");
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["csv", "-", "--column", "1", "--column", "2"])
+        .args(["--category", "volume", "--from", "oz", "--to", "fl oz"])
+        .write_stdin("a,1,2\nb,x,3\n")
+        .assert()
+        .failure()
+        .stderr(contains("error: line 2: 'x' is not a number"));
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["csv", "-", "--column", "kg", "--from", "lb", "--to", "kg"])
+        .write_stdin("lb\n1\n")
+        .assert()
+        .failure()
+        .stderr(contains("there is no column named 'kg'"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn json_converts_values_at_a_path() {
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["--precision", "1", "json", "--path", ".readings[].temp_f"])
+        .args(["--category", "temperature"])
+        .args(["--from", "f", "--to", "c"])
+        .write_stdin("{\"site\": 4,\n \"readings\": [{\"temp_f\": 212}, {\"temp_f\": null}, {\"temp_f\": 32.0}]}\n")
+        .assert()
+        .success()
+        .stdout("{\"site\": 4,\n \"readings\": [{\"temp_f\": 100.0}, {\"temp_f\": null}, {\"temp_f\": 0.0}]}\n");
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args([
+            "--precision",
+            "1",
+            "json",
+            "--path",
+            ".",
+            "-f",
+            "km",
+            "-t",
+            "m",
+        ])
+        .write_stdin("1.5\n2\n")
+        .assert()
+        .success()
+        .stdout("1500.0\n2000.0\n");
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args([
+            "json",
+            "--path",
+            ".[]",
+            "--category",
+            "temperature",
+            "-f",
+            "f",
+            "-t",
+            "c",
+        ])
+        .write_stdin("[212, 32.5, 32]\n")
+        .assert()
+        .success()
+        .stdout("[100, 0.2777777777777778, 0]\n");
+
+    // Without --precision every digit is kept, e.g. of a 15-digit total.
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["json", "--path", ".total", "-f", "m", "-t", "m"])
+        .write_stdin("{\"total\": 123456789012345}\n")
+        .assert()
+        .success()
+        .stdout("{\"total\": 123456789012345}\n");
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["json", "--path", ".readings[].temp", "-f", "f", "-t", "c"])
+        .write_stdin("{\"readings\": [{\"temp\": \"hot\"}]}")
+        .assert()
+        .failure()
+        .stderr(contains(
+            "error: .readings[0].temp is a string, not a number",
+        ));
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["json", "--path", ".missing", "-f", "f", "-t", "c"])
+        .write_stdin("{}")
+        .assert()
+        .success()
+        .stdout("{}")
+        .stderr(contains("warning: no numbers found"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn series_resumes_from_checkpoint() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("convertx_checkpoint_input.csv");
+    let output = dir.join("convertx_checkpoint_output.csv");
+    let checkpoint = dir.join("convertx_checkpoint.txt");
+    let text = "t,bytes\n0,1024\n1,2048\n";
+    std::fs::write(&input, text).unwrap();
+    let fnv = |bytes: &[u8]| {
+        bytes.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+    };
+    // A run interrupted after the first two lines, with a partial third one.
+    std::fs::write(&output, "t,bytes\n0,1\n1,").unwrap();
+    std::fs::write(
+        &checkpoint,
+        format!(
+            "input-hash={:016x}\nconversion={}\noffset=15\nline=2\noutput-bytes=12\n",
+            fnv(text.as_bytes()),
+            "x-col=0 y-col=1 y-from=bytes y-to=kib"
+        ),
+    )
+    .unwrap();
+    let run = || {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["series", input.to_str().unwrap()]);
+        cmd.args(["--y-from", "bytes", "--y-to", "kib"]);
+        cmd.args(["-o", output.to_str().unwrap()]);
+        cmd.args(["--checkpoint", checkpoint.to_str().unwrap()]);
+        cmd
+    };
+    run().assert().success();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "t,bytes\n0,1\n1,2\n"
+    );
+    assert!(!checkpoint.exists());
+
+    std::fs::write(
+        &checkpoint,
+        "input-hash=0000000000000001\nconversion=x-col=0 y-col=1 y-from=bytes y-to=kib\n\
+         offset=15\nline=2\noutput-bytes=12\n",
+    )
+    .unwrap();
+    run()
+        .assert()
+        .failure()
+        .stderr(contains("the input changed since"));
+
+    // A checkpoint of a conversion to other units is not resumed.
+    std::fs::write(&output, "t,bytes\n0,1\n").unwrap();
+    std::fs::write(
+        &checkpoint,
+        format!(
+            "input-hash={:016x}\nconversion={}\noffset=15\nline=2\noutput-bytes=12\n",
+            fnv(text.as_bytes()),
+            "x-col=0 y-col=1 y-from=bytes y-to=mib"
+        ),
+    )
+    .unwrap();
+    run().assert().failure().stderr(contains(
+        "was written converting x-col=0 y-col=1 y-from=bytes y-to=mib, \
+         not x-col=0 y-col=1 y-from=bytes y-to=kib",
+    ));
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "t,bytes\n0,1\n");
+    std::fs::remove_file(&checkpoint).unwrap();
+}
+
+#[test]
+fn quiz_is_repeatable_with_seed() {
+    let quiz = |answers: bool| {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args([
+            "quiz",
+            "--seed",
+            "42",
+            "--count",
+            "3",
+            "--category",
+            "length",
+        ]);
+        if answers {
+            cmd.arg("--answers");
+        }
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let worksheet = quiz(false);
+    assert_eq!(worksheet, quiz(false));
+    assert!(worksheet.starts_with("Unit conversion quiz (seed 42)\n"));
+    assert_eq!(worksheet.lines().count(), 4);
+    assert_eq!(worksheet.matches("= ?").count(), 3);
+    assert!(quiz(true).contains("59 furlongs = 38940.0000 feet"));
+}
+
+#[test]
+fn conversion_errors_exit_with_their_code() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1e307", "--from", "km", "--to", "inches"]);
+    cmd.assert()
+        .code(5)
+        .stderr(contains("error: 1e307 is out of range"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["alcohol", "2", "--from", "standard-drinks", "--to", "proof"]);
+    cmd.assert()
+        .code(3)
+        .stderr(contains("standard drinks also depend on the volume"));
+
+    if cfg!(feature = "data") {
+        let input = std::env::temp_dir().join("convertx_mismatch_input.csv");
+        std::fs::write(&input, "0,1\n").unwrap();
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args([
+            "series",
+            input.to_str().unwrap(),
+            "--y-from",
+            "km",
+            "--y-to",
+            "kg",
+        ]);
+        cmd.assert()
+            .code(4)
+            .stderr(contains("cannot convert 'km' to 'kg'"));
+    }
+}
+
+#[test]
+fn startup_ignores_a_bad_environment() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "km", "--to", "m"]);
+    cmd.env("CONVERTX_SYSTEM", "bogus")
+        .env("LC_ALL", "xx_XX.bogus");
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 kilometer = 1000.0000 meters"))
+        .stderr(contains("warning: ignoring CONVERTX_SYSTEM=\"bogus\""));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.env_clear()
+        .args(["mass", "1", "--from", "kg", "--to", "lb"]);
+    cmd.assert().success().stdout(contains("2.2046 pounds"));
+}
+
+#[test]
+fn busybox_style_links_run_their_subcommand() {
+    let dir = std::env::temp_dir().join("convertx_cli_busybox");
+    std::fs::create_dir_all(&dir).unwrap();
+    let link = dir.join(format!("temperature{}", std::env::consts::EXE_SUFFIX));
+    let _ = std::fs::remove_file(&link);
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(assert_cmd::cargo::cargo_bin("convertx"), &link).unwrap();
+    // Symbolic links need extra privileges on Windows, so a copy stands in.
+    #[cfg(not(unix))]
+    std::fs::copy(assert_cmd::cargo::cargo_bin("convertx"), &link).unwrap();
+    let mut cmd = Command::new(&link);
+    cmd.args(["100", "--from", "c", "--to", "f"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("100.00°C = 212.00°F"));
+}
+
+#[cfg(unix)]
+#[test]
+fn closed_stdout_does_not_panic() {
+    use std::process::Stdio;
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("convertx"))
+        .args(["quiz", "--count", "200000", "--seed", "1"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+}
+
+#[test]
+fn gear_speed() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "gear",
+        "--chainring",
+        "50",
+        "--cog",
+        "12",
+        "--wheel",
+        "700x25c",
+    ]);
+    cmd.args(["--cadence", "90", "--to", "speed"]);
+    cmd.assert().success().stdout(contains(
+        "50x12 on 700x25c at 90 rpm = 47.4 km/h (29.4 mph)",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["gear", "--chainring", "50", "--cog", "12", "--to", "speed"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("--to speed needs --cadence"));
+}
+
+#[test]
+fn tire_speedometer_error() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["tire", "225/45R17", "--to", "diameter"]);
+    cmd.args(["--compare", "215/55R17"]);
+    cmd.assert().success().stdout(contains(
+        "225/45R17 = 634.3 mm (24.97 in) diameter\n\
+         215/55R17 = 668.3 mm (26.31 in) diameter\n\
+         215/55R17 is 5.36% larger than 225/45R17; an indicated 100 km/h is 105.36 km/h",
+    ));
+}
+
+#[cfg(feature = "media")]
+#[test]
+fn video_estimates() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "video",
+        "4k60",
+        "--codec",
+        "h264",
+        "--to",
+        "bitrate-estimate",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("4k60 h264 = 52.50 Mbps"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "video",
+        "1080p",
+        "--bitrate",
+        "10Mbps",
+        "--to",
+        "hourly-size",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1080p30 at 10Mbps = 4.19 GB per hour"));
+}
+
+#[test]
+fn nines_downtime() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["nines", "99.95", "--to", "downtime-per-year"]);
+    cmd.assert().success().stdout(contains(
+        "99.95% availability = 4h 22m 59s downtime per year",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["nines", "120", "--to", "downtime-per-day"]);
+    cmd.assert().failure().stderr(contains("between 0 and 100"));
+}
+
+#[test]
+fn length_kilometers_to_meters() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["length", "1", "--from", "kilometers", "--to", "meters"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 kilometer = 1000.0000 meters"));
+}
+
+#[test]
+fn temperature_f_to_c() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["temperature", "32", "--from", "f", "--to", "c"]);
+    cmd.assert().success().stdout(contains("32.00°F = 0.00°C"));
+}
+
+#[test]
+fn mass_kg_to_lb() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["mass", "1", "--from", "kg", "--to", "lb"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 kilogram = 2.2046 pounds"));
+}
+
+#[test]
+fn mass_troy_units() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "1", "--from", "troy_ounces", "--to", "oz"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 troy ounce = 1.0971 ounces"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "1", "--from", "oz t", "--to", "dwt"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 troy ounce = 20.0000 pennyweights"));
+}
+
+#[test]
+fn datarate_mbps_to_bps() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["datarate", "1", "--from", "mbps", "--to", "bps"]);
+    cmd.assert().success().stdout(contains(
+        "1.0000 megabit per second = 1000000.0000 bits per second",
+    ));
+}
+
+#[test]
+fn area_acres_to_sqm() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["area", "1", "--from", "acres", "--to", "sqm"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 acre = 4046.8564 square meters"));
+}
+
+#[test]
+fn volume_gallons_to_liters() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["volume", "1", "--from", "gallons", "--to", "liters"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 gallon = 3.7854 liters"));
+}
+
+#[test]
+fn speed_mph_to_kph() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["speed", "60", "--from", "mph", "--to", "kph"]);
+    cmd.assert().success().stdout(contains(
+        "60.0000 miles per hour = 96.5606 kilometers per hour",
+    ));
+}
+
+#[test]
+fn pressure_atm_to_psi() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(&["pressure", "1", "--from", "atm", "--to", "psi"]);
+    cmd.assert().success().stdout(contains(
+        "1.0000 atmosphere = 14.6959 pounds per square inch",
+    ));
+}
+
+#[test]
+fn count_gross_to_items() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["count", "2", "--from", "gross", "--to", "items"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2 gross = 288 items"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["count", "100", "--from", "items", "--to", "dozens"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("100 items = 8.3333 dozen"));
+}
+
+#[test]
+fn hardness_ppm_to_dgh() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["hardness", "120", "--from", "ppm", "--to", "dgh"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("120.00 ppm CaCO₃ = 6.72 degrees German hardness"));
+}
+
+#[test]
+fn information_bits_to_bytes() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["information", "64", "--from", "Kibit", "--to", "bytes"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("64.0000 kibibits = 8192.0000 bytes"));
+}
+
+#[test]
+fn hashrate_with_total_work() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "hashrate",
+        "110",
+        "--from",
+        "TH/s",
+        "--to",
+        "PH/s",
+        "--duration",
+        "1h",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "110.0000 terahashes per second = 0.1100 petahashes per second",
+        ))
+        .stdout(contains("over 1h = 3.9600e17 hashes"));
+}
+
+#[test]
+fn length_via_show_steps() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "1",
+        "--from",
+        "kilometers",
+        "--via",
+        "meters",
+        "--to",
+        "feet",
+        "--show-steps",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "1.0000 kilometer = 1000.0000 meters\n1000.0000 meters = 3280.8400 feet",
+    ));
+}
+
+#[test]
+fn temperature_solve_for_input() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["temperature", "--to", "f", "--result", "100", "--from", "c"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("37.78°C = 100.00°F"));
+}
+
+#[test]
+fn length_tolerance_and_range() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "10±0.2", "--from", "meters", "--to", "feet"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("10.0000±0.2000 meters = 32.8084±0.6562 feet"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5..7", "--from", "feet", "--to", "meters"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("5.0000..7.0000 feet = 1.5240..2.1336 meters"));
+}
+
+#[test]
+fn length_round_and_engineering() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "1", "--from", "meters", "--to", "inches", "--round", "ceil",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 meter = 39.3701 inches"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "1",
+        "--from",
+        "kilometers",
+        "--to",
+        "feet",
+        "--engineering",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000e0 kilometer = 3.2808e3 feet"));
+}
+
+#[test]
+fn length_format_template() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "1",
+        "--from",
+        "kilometers",
+        "--to",
+        "feet",
+        "--format",
+        "{value:.0} {from} is {result:.1} {to} ({category})",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1 kilometer is 3280.8 feet (length)"));
+}
+
+#[test]
+fn precision_is_adaptive_and_global() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1000", "J", "kWh"]);
+    cmd.assert()
+        .success()
+        .stdout("1000.0000 J = 0.000278 kWh\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "--precision",
+        "1",
+        "length",
+        "5",
+        "--from",
+        "km",
+        "--to",
+        "ft",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("5.0 kilometers = 16404.2 feet\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1572864", "--megabytes", "--precision", "1"]);
+    cmd.assert().success().stdout("1572864 bytes = 1.5 MB\n");
+
+    if cfg!(feature = "finance") {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["--precision", "0", "tip", "1234.6"]);
+        cmd.assert()
+            .success()
+            .stdout("15% tip on 1,235 = 185\nTotal = 1,420\n");
+    }
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "1",
+        "--from",
+        "km",
+        "--to",
+        "ft",
+        "--precision",
+        "x",
+    ]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn length_sql_output() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft", "--both"]);
+    cmd.args(["--output", "sql", "--table", "metrics.conversions"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "INSERT INTO metrics.conversions (category, value, from_unit, to_unit, result, \
+             value_min, value_max, result_min, result_max) \
+             VALUES ('length', 5, 'kilometers', 'feet', 16404.2, NULL, NULL, NULL, NULL);",
+        ))
+        .stdout(contains(
+            "VALUES ('length', 5, 'feet', 'kilometers', 0.0015239999512320014, NULL, NULL, NULL, NULL);",
+        ));
+
+    // One unit keeps its canonical name, and limits get their own columns.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "1±0.5", "--from", "km", "--to", "m", "--output", "sql",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "VALUES ('length', 1, 'kilometers', 'meters', 1000, 0.5, 1.5, 500, 1500);",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "1..2", "--from", "km", "--to", "m", "--output", "sql",
+    ]);
+    cmd.assert().success().stdout(contains(
+        "VALUES ('length', NULL, 'kilometers', 'meters', NULL, 1, 2, 1000, 2000);",
+    ));
+}
+
+#[test]
+fn length_json_output_is_stable() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "1", "--from", "km", "--to", "ft", "--output", "json",
+    ]);
+    cmd.assert().success().stdout(
+        "{\"category\":\"length\",\"value\":1,\"from\":\"kilometers\",\"to\":\"feet\",\
+         \"from_symbol\":\"km\",\"to_symbol\":\"ft\",\"result\":3280.84}\n",
+    );
+
+    // The unit names are the canonical ones, singular or plural alike.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1000", "m", "km", "--output", "json"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("\"from\":\"meters\",\"to\":\"kilometers\""))
+        .stdout(contains("\"result\":1}"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["temperature", "20..25", "--from", "c", "--to", "f"]);
+    cmd.args(["--output", "json"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("\"value\":{\"min\":20,\"max\":25}"))
+        .stdout(contains("\"result\":{\"min\":68,\"max\":77}"));
+}
+
+#[test]
+fn format_json_matches_output_json() {
+    let json = "{\"category\":\"length\",\"value\":1,\"from\":\"kilometers\",\"to\":\"feet\",\
+                \"from_symbol\":\"km\",\"to_symbol\":\"ft\",\"result\":3280.84}\n";
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "1", "--from", "km", "--to", "ft", "--format", "json",
+    ]);
+    cmd.assert().success().stdout(json);
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "km", "ft", "--format", "JSON"]);
+    cmd.assert().success().stdout(json);
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "--format", "json", "length", "1", "--from", "km", "--to", "ft",
+    ]);
+    cmd.assert().success().stdout(json);
+}
+
+#[test]
+fn format_is_global() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1048576", "--megabytes", "--format", "json"]);
+    cmd.assert()
+        .success()
+        .stdout("{\"category\":\"bytes\",\"value\":1048576,\"from\":\"bytes\",\"to\":\"MB\",\"result\":1}\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["--format", "raw", "time", "3661", "--human-readable"]);
+    cmd.assert().success().stdout("1h 1m 1s\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "bytes",
+        "1048576",
+        "--megabytes",
+        "--format",
+        "{value} {from} = {result:.1} {to}",
+    ]);
+    cmd.assert().success().stdout("1048576 bytes = 1.0 MB\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1048576", "--megabytes", "--format", "markdown"]);
+    cmd.assert().code(2).stdout("");
+}
+
+#[cfg(feature = "finance")]
+#[test]
+fn tip_format_json_and_raw() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "tip",
+        "100",
+        "--percent",
+        "15",
+        "--split",
+        "2",
+        "--format",
+        "json",
+    ]);
+    cmd.assert().success().stdout(concat!(
+        "{\"category\":\"tip\",\"value\":100,\"from\":\"bill\",\"to\":\"tip\",\"result\":15}\n",
+        "{\"category\":\"tip\",\"to\":\"total\",\"result\":115}\n",
+        "{\"category\":\"tip\",\"value\":115,\"from\":\"total\",\"to\":\"per person\",\"result\":57.5}\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["--format", "raw", "tip", "100", "--percent", "15"]);
+    cmd.assert().success().stdout("15.00\n115.00\n");
+}
+
+#[cfg(feature = "dates")]
+#[test]
+fn week_and_meeting_format_json_and_raw() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["week", "2024-03-05", "--to", "week", "--format", "json"]);
+    cmd.assert().success().stdout(
+        "{\"category\":\"week\",\"value\":\"2024-03-05\",\"from\":\"date\",\"to\":\"week\",\"result\":\"2024-W10-2\"}\n",
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["week", "2024-03-05", "--to", "ordinal", "--format", "raw"]);
+    cmd.assert().success().stdout("2024-065\n");
+
+    // The heading of the table is left out.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "meeting",
+        "2024-03-05 15:00 Europe/London",
+        "--in",
+        "America/New_York",
+    ]);
+    cmd.args(["--format", "raw"]);
+    cmd.assert()
+        .success()
+        .stdout("2024-03-05T15:00+00:00\n2024-03-05T10:00-05:00\n");
+}
+
+#[test]
+fn quiet_prints_only_the_number() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "-f", "km", "-t", "ft", "-q"]);
+    cmd.assert().success().stdout("16404.2000\n");
+
+    // Units the registry defines relative to others, such as miles.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "-f", "km", "-t", "mi", "-q"]);
+    cmd.assert().success().stdout("3.1069\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "-f", "furlongs", "-t", "yd"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 furlong = 220.0000 yards\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--raw"]);
+    cmd.assert().success().stdout("3.1069\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["count", "2", "--from", "gross", "--to", "items"]);
+    cmd.args(["--output", "raw"]);
+    cmd.assert().success().stdout("288\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "5", "-f", "km", "-t", "ft", "-q", "--output", "json",
+    ]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn cite_names_the_source_of_each_unit() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft", "--cite"]);
+    cmd.assert().success().stdout(
+        "5.0000 kilometers = 16404.2000 feet\n\
+         Source for kilometers: BIPM, The International System of Units (SI Brochure), 9th edition, 2019\n\
+         Source for feet: NIST Special Publication 811, 2008 edition, Appendix B.8\n",
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "2", "kPa", "dozen", "--cite"]);
+    cmd.assert().code(4);
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "2", "gross", "items", "--cite"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Source for gross: none on record"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "KiB", "bytes", "--cite"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Source for kibibytes: IEC 80000-13:2008"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "-f", "m", "-t", "ft", "--cite", "--quiet"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn spoken_spells_out_the_conversion() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "1",
+        "--from",
+        "km",
+        "--to",
+        "ft",
+        "--spoken",
+        "--symbols",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("1 kilometer equals 3280.84 feet\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "temperature",
+        "--from",
+        "c",
+        "--to",
+        "f",
+        "--output",
+        "spoken",
+    ]);
+    cmd.args(["--", "-40"]);
+    cmd.assert()
+        .success()
+        .stdout("minus 40 degrees Celsius equals minus 40 degrees Fahrenheit\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5..7", "km", "mi", "--spoken"]);
+    cmd.assert()
+        .success()
+        .stdout("5 to 7 kilometers equals 3.1069 to 4.3496 miles\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["hardness", "10", "--from", "ppm", "--to", "gpg", "--spoken"]);
+    cmd.assert()
+        .success()
+        .stdout("10 ppm CaCO3 equals 0.584 grains per gallon\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--spoken", "-q"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn script_filter_lists_launcher_items() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--output", "script-filter"]);
+    cmd.assert().success().stdout(
+        r#"{"items":[{"title":"3.1069 miles","subtitle":"5.0000 kilometers = 3.1069 miles","arg":"3.1069"}]}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "2", "--from", "kg", "--to", "lb", "--both"]);
+    cmd.args(["--format", "script-filter", "--symbols"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let items = document["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["title"], "4.4092 lb");
+    assert_eq!(items[0]["subtitle"], "2.0000 kg = 4.4092 lb");
+    assert_eq!(items[1]["arg"], "0.9072");
+}
+
+#[test]
+fn tables_in_markdown_and_html() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft", "--both"]);
+    cmd.args(["--format", "markdown"]);
+    cmd.assert().success().stdout(
+        "\
+|  Value | From       |     Result | To         |
+|-------:|:-----------|-----------:|:-----------|
+| 5.0000 | kilometers | 16404.2000 | feet       |
+| 5.0000 | feet       |    0.00152 | kilometers |
+",
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--output", "html", "--symbols"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("<table>\n  <thead>\n"))
+        .stdout(contains(
+            r#"<tr><td style="text-align: right">5.0000</td><td>km</td><td style="text-align: right">3.1069</td><td>mi</td></tr>"#,
+        ))
+        .stdout(contains("</table>\n"));
+}
+
+#[test]
+fn every_subcommand_takes_h_for_help() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    let output = cmd
+        .arg("help")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let help = String::from_utf8(output).unwrap();
+    let subcommands: Vec<&str> = help
+        .split("SUBCOMMANDS:")
+        .nth(1)
+        .unwrap()
+        .lines()
+        // Long descriptions wrap onto lines indented further.
+        .filter_map(|line| line.strip_prefix("    "))
+        .filter(|line| !line.starts_with(' '))
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| *name != "help")
+        .collect();
+    assert!(subcommands.contains(&"bytes") && subcommands.contains(&"time"));
+    for subcommand in subcommands {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args([subcommand, "-h"]);
+        cmd.assert()
+            .success()
+            .stdout(contains(format!("convertx {}", subcommand)))
+            .stdout(contains("-h, --help"));
+    }
+}
+
+#[test]
+fn help_describes_each_conversion_subcommand() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    let output = cmd
+        .arg("--help")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let help = String::from_utf8(output).unwrap();
+    for line in [
+        "    length         Convert length units\n",
+        "    mass           Convert mass/weight units\n",
+        "    temperature    Convert temperature units\n",
+        "    convert        Convert between any two units",
+        "    eval           Evaluate arithmetic on quantities",
+    ] {
+        assert!(help.contains(line), "{:?} is not in\n{}", line, help);
+    }
+    // The comments of the option structs flattened into these subcommands.
+    for comment in [
+        "Options shared",
+        "How numbers are rounded",
+        "Where the results",
+    ] {
+        assert!(!help.contains(comment), "{:?} is in\n{}", comment, help);
+    }
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["speed", "--help"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("\nConvert speed units\n"));
+}
+
+#[test]
+fn human_readable_takes_capital_h_and_human() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bytes", "1048576", "-H"]);
+    cmd.assert().success().stdout("1048576 bytes = 1.00 MB\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["time", "4000", "--human"]);
+    cmd.assert().success().stdout("4000 seconds = 1h 6m 40s\n");
+}
+
+#[test]
+fn unit_symbols_are_written_as_utf8() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["temperature", "100", "--from", "c", "--to", "f"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    assert_eq!(output, "100.00°C = 212.00°F\n".as_bytes());
+}
+
+#[test]
+fn length_pluralizes_unit_names() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "3", "--from", "feet", "--to", "inches"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("3.0000 feet = 36.0000 inches"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "feet", "--to", "inches"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 foot = 12.0000 inches"));
+}
+
+#[test]
+fn symbols_output() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["speed", "10", "--from", "mps", "--to", "kph", "--symbols"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("10.0000 m/s = 36.0000 km/h"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["temperature", "0", "--from", "c", "--to", "k", "--symbols"]);
+    cmd.assert().success().stdout(contains("0.00°C = 273.15 K"));
+}
+
+#[test]
+fn mass_both_directions() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "10", "--from", "kg", "--to", "lb", "--both"]);
+    cmd.assert().success().stdout(contains(
+        "10.0000 kilograms = 22.0462 pounds\n10.0000 pounds = 4.5359 kilograms",
+    ));
+}
+
+#[test]
+fn symbols_accepted_as_units() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("5.0000 kilometers = 16404.2000 feet"));
+}
+
+#[test]
+fn convert_detects_the_category() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi"]);
+    cmd.assert()
+        .success()
+        .stdout("5.0000 kilometers = 3.1069 miles\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "-40", "c", "f", "--symbols"]);
+    cmd.assert().success().stdout("-40.00 °C = -40.00 °F\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "2", "gross", "items"]);
+    cmd.assert().success().stdout("2 gross = 288 items\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "8", "fl oz", "ml"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("8.0000 fluid ounces = 236.5881 milliliters"));
+}
+
+#[test]
+fn convert_agrees_with_the_category_subcommands() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "32", "f", "c"]);
+    cmd.assert()
+        .success()
+        .stdout("32.00 degrees Fahrenheit = 0.00 degrees Celsius\n");
+
+    // The subcommands label some units differently, so only the results are
+    // compared.
+    for (category, from, to) in [("length", "km", "mi"), ("temperature", "f", "c")] {
+        let output = |args: &[&str]| {
+            let mut cmd = Command::cargo_bin("convertx").unwrap();
+            cmd.args(["--precision", "16"]).args(args);
+            let stdout = cmd.assert().success().get_output().stdout.clone();
+            let stdout = String::from_utf8(stdout).unwrap();
+            let (_, result) = stdout.split_once(" = ").unwrap();
+            result
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+                .to_string()
+        };
+        assert_eq!(
+            output(&["convert", "5", from, to]),
+            output(&[category, "5", "--from", from, "--to", to])
+        );
+    }
+}
+
+#[test]
+fn convert_reports_ambiguous_and_mismatched_units() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "8", "oz", "oz"]);
+    cmd.assert().code(2).stderr(contains(
+        "'oz' is ambiguous, it could be: oz (mass), fluid_ounces (volume). Pass --category to choose.",
+    ));
+
+    // The other unit does not settle `oz`, as either reading is plausible.
+    for other in ["g", "ml"] {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["convert", "1", "oz", other]);
+        cmd.assert()
+            .code(2)
+            .stderr(contains("oz (mass), fluid_ounces (volume)"));
+    }
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "oz", "lb", "--category", "mass"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 ounce = 0.0625 pounds"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "kg"]);
+    cmd.assert()
+        .code(4)
+        .stderr(contains("cannot convert 'km' to 'kg'"));
+}
+
+#[test]
+fn si_prefixes_apply_to_metric_units() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["pressure", "3", "-f", "kilopascal", "-t", "psi"]);
+    cmd.assert()
+        .success()
+        .stdout("3.0000 kilopascals = 0.4351 pounds per square inch\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "2.5um", "--to", "nanometers"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2.5000 micrometers = 2500.0000 nanometers"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "kN*m", "kJ"]);
+    cmd.assert().success().stdout(contains("= 1.0000 kJ"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "-f", "MM", "-t", "feet"]);
+    cmd.assert().failure().stderr(contains("unknown unit 'MM'"));
+}
+
+#[test]
+fn convert_handles_compound_units() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "kg*m/s^2", "N"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 kg*m/s^2 = 1.0000 N\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "2", "kW*h", "MJ"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2.0000 kW*h = 7.2000 MJ"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "kg/m^3", "g/L"]);
+    cmd.assert().success().stdout(contains("= 1.0000 g/L"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "kg*m/s^2", "W"]);
+    cmd.assert()
+        .code(4)
+        .stderr(contains("cannot convert 'kg*m/s^2' to 'W'"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--category", "mass"]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(contains("unknown mass unit 'km'"));
+}
+
+#[test]
+fn batch_runs_every_line_of_a_file() {
+    let jobs = std::env::temp_dir().join("convertx_batch_jobs.txt");
+    std::fs::write(
+        &jobs,
+        "# Mixed jobs\nlength 5 km ft\ntemperature 100 f c\n\n72kg lb\n3 km kg\n",
+    )
+    .unwrap();
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["batch", "--file", jobs.to_str().unwrap()])
+        .assert()
+        .code(4)
+        .stdout(
+            "5.0000 kilometers = 16404.2000 feet\n\
+             100.00 degrees Fahrenheit = 37.78 degrees Celsius\n\
+             72.0000 kilograms = 158.7326 pounds\n",
+        )
+        .stderr(contains("error: line 6: cannot convert 'km' to 'kg'"));
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["batch", "-f", "-", "--symbols"])
+        .write_stdin("1 in mm\nlength 2\n")
+        .assert()
+        .code(2)
+        .stdout("1.0000 in = 25.4000 mm\n")
+        .stderr(contains("error: line 2: expected a value"));
+
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["batch", "-f", "-"])
+        .write_stdin("length 1 km m\nlenght 5 km ft\n")
+        .assert()
+        .code(2)
+        .stderr(contains(
+            "error: line 2: unknown category 'lenght', expected one of length,",
+        ));
+}
+
+#[test]
+fn eval_does_unit_aware_arithmetic() {
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["eval", "(3 km + 250 m) / 2 in feet"])
+        .assert()
+        .success()
+        .stdout("5331.3650 feet\n");
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["eval", "2", "dozen", "+", "1", "gross"])
+        .assert()
+        .success()
+        .stdout("14 dozen\n");
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["eval", "8 oz + 100 ml to ml", "--category", "volume"])
+        .assert()
+        .success()
+        .stdout(contains("336.5881 milliliters"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["eval", "1 km + 1 kg"])
+        .assert()
+        .code(4)
+        .stderr(contains("cannot convert 'kg' to 'km'"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["eval", "(1 km"])
+        .assert()
+        .code(2)
+        .stderr(contains("missing ')'"));
+}
+
+#[test]
+fn values_can_carry_their_unit() {
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["length", "5km", "--to", "feet"])
+        .assert()
+        .success()
+        .stdout(contains("5.0000 kilometers = 16404.2000 feet"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["72kg", "--to", "lb"])
+        .assert()
+        .success()
+        .stdout(contains("72.0000 kilograms = 158.7326 pounds"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["convert", "3.5 lb", "kg"])
+        .assert()
+        .success()
+        .stdout(contains("3.5000 pounds = 1.5876 kilograms"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["mass", "5km", "--to", "lb"])
+        .assert()
+        .failure()
+        .stderr(contains("unknown mass unit 'km'"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["length", "5km", "--from", "meters"])
+        .assert()
+        .failure()
+        .stderr(contains("the value is in kilometers but --from is meters"));
+}
+
+#[test]
+fn printed_unit_names_are_read_back() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "-f", "ft", "-t", "in"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 foot = 12.0000 inches\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "12", "-f", "inches", "-t", "foot"]);
+    cmd.assert()
+        .success()
+        .stdout("12.0000 inches = 1.0000 foot\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "1 pound", "-t", "kg"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 pound = 0.4536 kilograms\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["volume", "1", "-f", "US gallon", "-t", "L"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 US gallon = 3.7854 liters\n")
+        .stderr("");
+}
+
+#[test]
+fn values_with_an_exponent_keep_it_before_the_unit() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1e3m", "--to", "km"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1000.0000 meters = 1.0000 kilometer"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "2.5E-3kg", "g"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("0.00250 kilograms = 2.5000 grams"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1e400", "--from", "km", "--to", "m"]);
+    cmd.assert()
+        .code(6)
+        .stderr(contains("inf is not a finite number"));
+}
+
+#[test]
+fn lookup_unit() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["lookup", "psi"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("psi = psi (pressure, symbol psi)"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["lookup", "km", "--category", "mass"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("unknown mass unit 'km'"));
+}
+
+#[test]
+fn units_lists_and_searches() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["units", "--category", "temperature"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("°C"))
+        .stdout(contains("°F"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["units", "--search", "furl"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("furlongs"))
+        .stdout(contains("length"));
+}
+
+#[cfg(feature = "data")]
+#[test]
+fn series_converts_units_defined_relative_to_others() {
+    let input = std::env::temp_dir().join("convertx_series_furlongs.csv");
+    std::fs::write(&input, "race,distance\nderby,10\n").unwrap();
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["series", input.to_str().unwrap()]);
+    cmd.args(["--y-from", "furlongs", "--y-to", "km"]);
+    cmd.assert().success().stdout(contains("derby,2.0116"));
+}
+
+#[test]
+fn system_presets() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["volume", "10", "--from", "liters", "--system", "imperial"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("10.0000 liters = 2.1997 imperial gallons"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["temperature", "212", "--from", "f"]);
+    cmd.env("CONVERTX_SYSTEM", "metric");
+    cmd.assert()
+        .success()
+        .stdout(contains("212.00°F = 100.00°C"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["datarate", "1", "--from", "mbps", "--system", "metric"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("the metric system has no datarate preset"));
+}
+
+#[cfg(feature = "historical")]
+#[test]
+fn historical_units_pack() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "2", "--from", "cubits", "--to", "feet"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("enable it with --units-pack historical"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length",
+        "2",
+        "--from",
+        "cubits",
+        "--to",
+        "feet",
+        "--units-pack",
+        "historical",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2.0000 cubits = 3.0000 feet"));
+}
+
+#[cfg(feature = "nautical")]
+#[test]
+fn nautical_units_pack() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "speed",
+        "1",
+        "--from",
+        "knots",
+        "--to",
+        "fpm",
+        "--units-pack",
+        "nautical",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1.0000 knot = 101.2685 feet per minute"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "350", "--from", "FL", "--to", "meters"]);
+    cmd.args(["--units-pack", "nautical"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("350.0000 flight levels = 10668.0000 meters"));
+}
+
+#[cfg(feature = "calendars")]
+#[test]
+fn calendar_conversions() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["calendar", "2025-03-01", "--to", "hebrew"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("2025-03-01 = 1 Adar 5785"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["calendar", "2025-03-01", "--to", "chinese"]);
+    cmd.assert().success().stdout(contains(
+        "2025-03-01 = month 2, day 2 of the Wood Snake year (乙巳, year 42 of cycle 78)",
+    ));
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn sheet_reports_missing_input() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["sheet", "missing.parquet", "--column", "temp_f"]);
+    cmd.args(["--from", "f", "--to", "c", "--output", "out.parquet"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("could not read missing.parquet"));
+}
+
+#[test]
+fn non_finite_values_are_rejected_unless_allowed() {
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["length", "nan", "--from", "kilometers", "--to", "meters"])
+        .assert()
+        .code(6)
+        .stderr(contains("NaN is not a finite number"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["temperature", "inf", "--from", "c", "--to", "f"])
+        .assert()
+        .code(6)
+        .stderr(contains("--allow-non-finite"));
+    if cfg!(feature = "finance") {
+        Command::cargo_bin("convertx")
+            .unwrap()
+            .args(["tip", "inf"])
+            .assert()
+            .code(6)
+            .stderr(contains("--allow-non-finite"));
+        Command::cargo_bin("convertx")
+            .unwrap()
+            .args(["--allow-non-finite", "vat", "inf", "--rate", "20"])
+            .args(["--from", "net", "--to", "gross"])
+            .assert()
+            .success()
+            .stdout(contains("inf net at 20% = inf gross"));
+    }
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args([
+            "--allow-non-finite",
+            "length",
+            "inf",
+            "--from",
+            "kilometers",
+        ])
+        .args(["--to", "meters"])
+        .assert()
+        .success()
+        .stdout(contains("inf kilometers = inf meters"));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["length", "nan", "--from", "kilometers", "--to", "meters"])
+        .args(["--output", "json", "--allow-non-finite"])
+        .assert()
+        .success()
+        .stdout(contains(r#""value":null"#));
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args([
+            "--allow-non-finite",
+            "length",
+            "nan",
+            "--from",
+            "kilometers",
+        ])
+        .args(["--to", "meters", "--output", "sql"])
+        .assert()
+        .success()
+        .stdout(contains("'meters', NULL, NULL, NULL, NULL, NULL);"));
+}
+
+#[test]
+fn values_clap_rejects_exit_with_their_code() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "five", "--from", "km", "--to", "m"]);
+    cmd.assert()
+        .code(2)
+        .stderr(contains("'five' is not a number"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["drill", "5xx", "--to", "mm"]);
+    cmd.assert()
+        .code(2)
+        .stderr(contains("unknown distance unit 'xx'"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["drill", "5mm"]);
+    cmd.assert().code(2).stderr(contains("--to <to>"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "nan", "--from", "km", "--to", "m"]);
+    cmd.assert()
+        .code(6)
+        .stderr(contains("NaN is not a finite number"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["drill", "1e999mm", "--to", "in"]);
+    cmd.assert()
+        .code(6)
+        .stderr(contains("inf is not a finite number"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["--version"]);
+    cmd.assert().success();
+}
+
+#[test]
+fn reproducible_mode_uses_portable_math() {
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["--reproducible", "awg", "12", "--to", "mm2"])
+        .assert()
+        .success()
+        .stdout("12 AWG = 3.3088 mm²\n");
+    Command::cargo_bin("convertx")
+        .unwrap()
+        .args(["length", "1234567", "--from", "meters", "--to", "feet"])
+        .args(["--engineering", "--reproducible"])
+        .assert()
+        .success()
+        .stdout("1.2346e6 meters = 4.0504e6 feet\n");
+}
+
+/// A `convertx serve` process, stopped when dropped so that a failed test
+/// does not leave it running.
+#[cfg(feature = "serve")]
+struct Server {
+    process: std::process::Child,
+    address: String,
+}
+
+#[cfg(feature = "serve")]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// Runs `convertx serve` on a free port with `args`.
+#[cfg(feature = "serve")]
+fn start_server(args: &[&str]) -> Server {
+    start_listening(&[&["serve", "--listen", "127.0.0.1:0"], args].concat())
+}
+
+/// Runs `convertx` with `args`, which make it listen on a free port.
+#[cfg(feature = "serve")]
+fn start_listening(args: &[&str]) -> Server {
+    use std::io::BufRead;
+    use std::process::Stdio;
+    let mut process = std::process::Command::new(assert_cmd::cargo::cargo_bin("convertx"))
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    std::io::BufReader::new(process.stderr.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line.trim().trim_start_matches("listening on http://");
+    Server {
+        address: address.to_string(),
+        process,
+    }
+}
+
+/// Sends `GET path` to `address` and returns the whole response.
+#[cfg(feature = "serve")]
+fn http_get(address: &str, path: &str) -> String {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, address).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// Sends `POST path` with `body` and `headers` to `address` and returns the
+/// whole response.
+#[cfg(feature = "bot")]
+fn http_post(address: &str, path: &str, headers: &[&str], body: &str) -> String {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    write!(stream, "POST {} HTTP/1.1\r\nHost: {}\r\n", path, address).unwrap();
+    for header in headers {
+        write!(stream, "{}\r\n", header).unwrap();
+    }
+    write!(stream, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_converts_and_caches_unit_pairs() {
+    let server = start_server(&["--cache-size", "8"]);
+    let address = &server.address;
+    let response = http_get(address, "/convert?value=5&from=km&to=mi");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains(r#""from":"kilometers","to":"miles""#));
+    http_get(address, "/convert?value=12&from=km&to=mi");
+    let response = http_get(address, "/convert?value=3.5%20lb&to=kg");
+    assert!(response.contains(r#""result":1.5875"#), "{}", response);
+    let response = http_get(address, "/convert?value=5&from=km&to=kg");
+    assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+    assert!(response.contains("cannot convert 'km' to 'kg'"));
+    let response = http_get(address, "/info");
+    assert!(
+        response.contains(r#""cache":{"capacity":8,"entries":3,"hits":1,"misses":3"#),
+        "{}",
+        response
+    );
+    let response = http_get(address, "/convert?value=5&from=km&to=mi&category=bogus");
+    assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+    let response = http_get(address, "/convert?value=5&from=oz&to=oz");
+    assert!(
+        response.contains("Pass the category parameter to choose."),
+        "{}",
+        response
+    );
+    assert!(http_get(address, "/nowhere").starts_with("HTTP/1.1 404"));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_answers_a_page_at_the_root() {
+    let server = start_server(&[]);
+    let response = http_get(&server.address, "/");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("Content-Type: text/html; charset=utf-8"));
+    assert!(response.contains(r#"<form id="convert">"#));
+    assert!(response.contains(r#"fetch("convert?" + query)"#));
+    let response = http_get(&server.address, "/metrics");
+    assert!(response.contains(r#"convertx_http_requests_total{endpoint="/",status="200"} 1"#));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_exposes_prometheus_metrics() {
+    let server = start_server(&[]);
+    let address = &server.address;
+    http_get(address, "/convert?value=5&value=6&from=km&to=mi");
+    http_get(address, "/convert?value=5&from=km&to=kg");
+    http_get(address, "/convert?value=1&from=kg*m&to=g*cm");
+    http_get(address, "/missing");
+    let response = http_get(address, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+    for line in [
+        r#"convertx_http_requests_total{endpoint="/convert",status="200"} 2"#,
+        r#"convertx_http_requests_total{endpoint="/convert",status="400"} 1"#,
+        r#"convertx_http_requests_total{endpoint="other",status="404"} 1"#,
+        r#"convertx_http_request_duration_seconds_count{endpoint="/convert"} 3"#,
+        r#"convertx_http_request_duration_seconds_bucket{endpoint="/convert",le="+Inf"} 3"#,
+        r#"convertx_conversions_total{category="length"} 2"#,
+        r#"convertx_conversions_total{category="compound"} 1"#,
+        "# TYPE convertx_http_request_duration_seconds histogram",
+        "convertx_cache_misses_total 3",
+    ] {
+        assert!(response.contains(line), "missing {} in {}", line, response);
+    }
+}
+
+#[cfg(feature = "bot")]
+#[test]
+fn bot_answers_slash_commands() {
+    let form = ["Content-Type: application/x-www-form-urlencoded"];
+    let slack = start_listening(&["bot", "--slack", "--no-verify", "--listen", "127.0.0.1:0"]);
+    let body = "command=%2Fconvert&text=What+is+5+km+to+mi%3F";
+    let response = http_post(&slack.address, "/", &form, body);
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(
+        response.contains(r#"{"response_type":"in_channel","text":"5 km to mi = 3.1069 miles"}"#),
+        "{}",
+        response
+    );
+    let response = http_post(&slack.address, "/", &form, "text=5+km+to+kg");
+    assert!(response.contains(r#"{"response_type":"ephemeral","text":"#));
+    assert!(http_get(&slack.address, "/").starts_with("HTTP/1.1 405"));
+
+    let discord = start_listening(&["bot", "--discord", "--no-verify", "--listen", "127.0.0.1:0"]);
+    let json = ["Content-Type: application/json"];
+    let response = http_post(&discord.address, "/", &json, r#"{"type":1}"#);
+    assert!(response.ends_with(r#"{"type":1}"#), "{}", response);
+    let body = r#"{"type":2,"data":{"name":"convert","options":[{"name":"question","type":3,"value":"72kg in lb"}]}}"#;
+    let response = http_post(&discord.address, "/", &json, body);
+    assert!(
+        response.contains(r#"{"type":4,"data":{"content":"72kg in lb = 158.7326 pounds"}}"#),
+        "{}",
+        response
+    );
+}
+
+#[cfg(feature = "bot")]
+#[test]
+fn bot_requires_a_key_to_verify_requests() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bot", "--slack"])
+        .env_remove("SLACK_SIGNING_SECRET");
+    cmd.assert().failure().stderr(contains(
+        "--signing-secret (or SLACK_SIGNING_SECRET) is needed",
+    ));
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["bot", "--discord", "--public-key", "abc"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("a public key is 64 hexadecimal digits"));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_openapi_describes_endpoints() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["serve", "--openapi", "--listen", "localhost:9000"]);
+    cmd.args(["--max-batch", "20"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("{\"openapi\":\"3.0.3\""))
+        .stdout(contains(
+            "\"servers\":[{\"url\":\"http://localhost:9000\"}]",
+        ))
+        .stdout(contains("\"/convert\":{\"get\""))
+        .stdout(contains("\"/info\":{\"get\""))
+        .stdout(contains("\"/metrics\":{\"get\""))
+        .stdout(contains("\"maxItems\":20"))
+        .stdout(contains("\"kilometers\""))
+        .stdout(contains("\"information\""));
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_limits_clients() {
+    let server = start_server(&[
+        "--rate-limit",
+        "3",
+        "--max-batch",
+        "2",
+        "--max-request-bytes",
+        "100",
+    ]);
+    let address = &server.address;
+    let response = http_get(address, "/convert?value=1&value=2km&from=km&to=m");
+    assert!(response.ends_with(r#""result":2000}]"#), "{}", response);
+    let response = http_get(address, "/convert?value=1&value=2&value=3&from=km&to=m");
+    assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+    let long = format!("/convert?value=1&from=km&to=m&pad={}", "x".repeat(100));
+    assert!(http_get(address, &long).contains("requests are limited to 100 bytes"));
+    let response = http_get(address, "/info");
+    assert!(response.starts_with("HTTP/1.1 429"), "{}", response);
+    assert!(response.contains("Retry-After: 20"), "{}", response);
+    // A client over its limit is answered without the server waiting for its
+    // request, which would take the whole --timeout here.
+    use std::io::Read;
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 429"), "{}", response);
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_refuses_an_oversized_content_length() {
+    use std::io::{Read, Write};
+    let server = start_server(&["--max-request-bytes", "1000"]);
+    let mut stream = std::net::TcpStream::connect(&server.address).unwrap();
+    write!(
+        stream,
+        "POST /convert HTTP/1.1\r\nContent-Length: 100000000000000\r\n\r\n{}",
+        "x".repeat(1000)
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+    // The server is still up.
+    let response = http_get(&server.address, "/convert?value=1&from=km&to=m");
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+}
+
+#[test]
+fn color_highlights_results_when_asked() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft"]);
+    cmd.env_remove("NO_COLOR");
+    // Output to a pipe is not colored by default.
+    cmd.assert()
+        .success()
+        .stdout("5.0000 kilometers = 16404.2000 feet\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "length", "5", "--from", "km", "--to", "ft", "--color", "always",
+    ]);
+    cmd.env("NO_COLOR", "1");
+    cmd.assert()
+        .success()
+        .stdout("5.0000 kilometers = \x1b[1;32m16404.2000 feet\x1b[0m\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["--color", "always", "latency", "1ms", "--to", "km-in-fiber"]);
+    cmd.assert()
+        .success()
+        .stdout("1ms = \x1b[1;32m199.9 km in fiber\x1b[0m\n");
+
+    // Machine readable output stays plain.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "-q", "--color", "always"]);
+    cmd.assert().success().stdout("3.1069\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--color", "sometimes"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn all_converts_into_every_unit() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "meters", "--all"]);
+    cmd.assert().success().stdout(
+        "1.0000 meter = 3.2808 feet\n\
+         1.0000 meter = 39.3701 inches\n\
+         1.0000 meter = 0.00100 kilometers\n\
+         1.0000 meter = 1.0936 yards\n\
+         1.0000 meter = 0.00497 furlongs\n\
+         1.0000 meter = 0.000621 miles\n",
+    );
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args([
+        "temperature",
+        "100",
+        "--from",
+        "c",
+        "--all",
+        "--output",
+        "json",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with(r#"{"category":"temperature""#))
+        .stdout(predicates::str::contains("}\n{"))
+        .stdout(predicates::str::contains(r#""to_symbol":"°F""#));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "m", "--all", "--to", "ft"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn emit_sends_results_to_several_places() {
+    let dir = std::env::temp_dir();
+    let json_file = dir.join("convertx_emit.json");
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--emit", "plain,json"]);
+    cmd.arg("--json-file").arg(&json_file);
+    cmd.assert()
+        .success()
+        .stdout("5.0000 kilometers = 3.1069 miles\n");
+    let json = std::fs::read_to_string(&json_file).unwrap();
+    assert!(json.starts_with(r#"{"category":"length","value":5,"from":"kilometers""#));
+
+    // `--json-file` alone also prints the sentence.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "m", "--all"]);
+    cmd.arg("--json-file").arg(&json_file);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("1.0000 meter = 3.2808 feet\n"));
+    let json = std::fs::read_to_string(&json_file).unwrap();
+    assert!(json.starts_with(r#"{"category":"length""#) && json.lines().count() == 6);
+    std::fs::remove_file(&json_file).unwrap();
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "1", "--from", "m", "--to", "ft", "--emit", "json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with(r#"{"category":"length""#));
+}
+
+#[cfg(unix)]
+#[test]
+fn emit_copies_the_number_to_the_clipboard() {
+    let clip = std::env::temp_dir().join("convertx_emit_clipboard.txt");
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["length", "5", "--from", "km", "--to", "ft", "--both"]);
+    cmd.args(["--emit", "clipboard"]);
+    cmd.env("CONVERTX_CLIPBOARD", format!("tee {}", clip.display()));
+    cmd.assert().success().stdout("");
+    assert_eq!(
+        std::fs::read_to_string(&clip).unwrap(),
+        "16404.2000\n0.00152"
+    );
+    std::fs::remove_file(&clip).unwrap();
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "km", "mi", "--emit", "plain,clipboard"]);
+    cmd.env("CONVERTX_CLIPBOARD", "false");
+    cmd.assert()
+        .failure()
+        .stdout("5.0000 kilometers = 3.1069 miles\n")
+        .stderr(predicates::str::contains("false failed"));
+}
+
+#[test]
+fn ambiguous_units_warn_or_need_a_qualifier() {
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "gal", "L"]);
+    cmd.assert()
+        .success()
+        .stdout("5.0000 gallons = 18.9271 liters\n")
+        .stderr(contains(
+            "warning: 'gal' is read as the US liquid gallon of 3.785 L; write us_gallons",
+        ));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "5", "us_gallons", "L"]);
+    cmd.assert()
+        .success()
+        .stdout("5.0000 US gallons = 18.9271 liters\n")
+        .stderr("");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["eval", "2 gallons + 1 imperial_gallons in L"]);
+    cmd.assert()
+        .success()
+        .stdout("12.1169 liters\n")
+        .stderr(contains("warning: 'gallons' is read as"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "hp", "PS"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 hp = 1.0139 PS\n")
+        .stderr(contains("the mechanical horsepower of 745.7 W"));
+
+    for to in ["metric_horsepower", "metric-horsepower"] {
+        let mut cmd = Command::cargo_bin("convertx").unwrap();
+        cmd.args(["convert", "1", "mechanical_horsepower", to]);
+        cmd.assert()
+            .success()
+            .stdout("1.0000 mechanical horsepower = 1.0139 metric horsepower\n");
+    }
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "1", "kilocalories", "thermochemical_calories"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 kilocalorie = 1000.0000 thermochemical calories\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["units", "--search", "horsepower"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("metric_horsepower        PS       power"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["convert", "3", "tons", "kg"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("'tons' has several definitions; write short_tons"));
+
+    // The category subcommands read them the same way, and name the unit as
+    // it was written.
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["volume", "1", "-f", "gallon", "-t", "liters"]);
+    cmd.assert()
+        .success()
+        .stdout("1.0000 gallon = 3.7854 liters\n")
+        .stderr("warning: 'gallon' is read as the US liquid gallon of 3.785 L; write us_gallons (3.785 L) or imperial_gallons (4.546 L) to choose\n");
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["volume", "2gal", "-t", "liters"]);
+    cmd.assert()
+        .success()
+        .stderr(contains("warning: 'gal' is read as"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "1", "--from", "ton", "--to", "kg"]);
+    cmd.assert()
+        .code(2)
+        .stderr(contains("'ton' has several definitions; write short_tons"));
+
+    let mut cmd = Command::cargo_bin("convertx").unwrap();
+    cmd.args(["mass", "1", "--from", "long_tons", "--to", "t"]);
     cmd.assert()
         .success()
-        .stdout(contains("1.0000 atm = 14.6959 psi"));
+        .stdout("1.0000 long ton = 1.0160 tonnes\n");
 }